@@ -0,0 +1,446 @@
+//! Terminal UI for playing a board with help from the solver, drawn with plain
+//! ANSI escape codes so it needs no extra dependencies.
+//!
+//! Run with `cargo run --example tui [BOARD]`, where `BOARD` is 81 characters
+//! in row-major order using `1`-`9` for filled cells and `.` or `0` for empty
+//! ones. Without a board a built-in puzzle is used.
+//!
+//! Keys: arrows move the cursor, `1`-`9` fill the cell, `0`, space, or
+//! backspace clear it, `h` shows a hint, `s` fills the hinted cell, `S` solves
+//! the rest of the board, and `q` quits.
+use std::env;
+use std::io::{self, Read, Write};
+use std::process::{self, Command, Stdio};
+
+use sudoku_solver::analysis::Analysis;
+use sudoku_solver::trace::DeductionReason;
+use sudoku_solver::{Board, Col, Coord, Row, Sector, Val, Zone};
+
+/// Puzzle used when none is given on the command line.
+const DEFAULT_BOARD: &str =
+    "...1.........586.18.136..9.5.....4.3..36.18..6.4.....7.3..845.61.572.........3...";
+
+/// Number of log lines shown below the board.
+const LOG_LINES: usize = 5;
+
+/// Input understood by the UI.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Digit(Val),
+    Clear,
+    Hint,
+    Step,
+    Solve,
+    Quit,
+}
+
+/// Decode raw terminal input into keys, ignoring anything unrecognized.
+fn parse_keys(input: &[u8]) -> Vec<Key> {
+    let mut keys = Vec::new();
+    let mut rest = input;
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        let key = match byte {
+            0x1b => match rest {
+                [b'[', dir @ b'A'..=b'D', tail @ ..] => {
+                    rest = tail;
+                    match dir {
+                        b'A' => Key::Up,
+                        b'B' => Key::Down,
+                        b'C' => Key::Right,
+                        _ => Key::Left,
+                    }
+                }
+                _ => continue,
+            },
+            b'1'..=b'9' => Key::Digit(Val::new(byte - b'0')),
+            b'0' | b' ' | 0x7f | 0x08 => Key::Clear,
+            b'h' => Key::Hint,
+            b's' => Key::Step,
+            b'S' => Key::Solve,
+            b'q' | 0x03 => Key::Quit,
+            _ => continue,
+        };
+        keys.push(key);
+    }
+    keys
+}
+
+/// Parse a board from 81 characters in row-major order. Whitespace is ignored.
+fn parse_board(line: &str) -> Option<Board> {
+    let vals = line
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '1'..='9' => Some(Some(Val::new(c as u8 - b'0'))),
+            '.' | '0' => Some(None),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Board::try_from(vals).ok()
+}
+
+/// State of the UI.
+struct App {
+    /// The puzzle as given, which cannot be edited.
+    givens: Board,
+    /// The board being played.
+    board: Board,
+    /// The selected cell.
+    cursor: Coord,
+    /// Cells justifying the last hint.
+    highlight: Vec<Coord>,
+    /// Messages shown to the user, oldest first.
+    log: Vec<String>,
+    /// Whether the user asked to quit.
+    quit: bool,
+}
+
+impl App {
+    fn new(givens: Board) -> Self {
+        App {
+            board: givens.clone(),
+            givens,
+            cursor: Coord::new(Row::new(0), Col::new(0)),
+            highlight: Vec::new(),
+            log: Vec::new(),
+            quit: false,
+        }
+    }
+
+    /// Apply a single key to the state.
+    fn update(&mut self, key: Key) {
+        match key {
+            Key::Up => self.move_cursor(Col::HEIGHT - 1, 0),
+            Key::Down => self.move_cursor(1, 0),
+            Key::Left => self.move_cursor(0, Row::WIDTH - 1),
+            Key::Right => self.move_cursor(0, 1),
+            Key::Digit(val) => self.set(Some(val)),
+            Key::Clear => self.set(None),
+            Key::Hint => {
+                if let Some((pos, val, reason)) = self.next_step() {
+                    self.log.push(format!(
+                        "Hint: {} is {}, {}",
+                        pos,
+                        val.val(),
+                        describe(&reason)
+                    ));
+                }
+            }
+            Key::Step => {
+                if let Some((pos, val, reason)) = self.next_step() {
+                    self.board[pos] = Some(val);
+                    self.cursor = pos;
+                    self.log.push(format!(
+                        "Filled {} with {}, {}",
+                        pos,
+                        val.val(),
+                        describe(&reason)
+                    ));
+                }
+            }
+            Key::Solve => {
+                self.highlight.clear();
+                match self.board.solve() {
+                    Some(solution) => {
+                        self.board = solution;
+                        self.log.push("Solved".to_string());
+                    }
+                    None => self.log.push("No solution from here".to_string()),
+                }
+            }
+            Key::Quit => self.quit = true,
+        }
+    }
+
+    /// Move the cursor by the given number of rows and columns, wrapping
+    /// around the edges.
+    fn move_cursor(&mut self, rows: u8, cols: u8) {
+        let row = (self.cursor.row().inner() + rows) % Col::HEIGHT;
+        let col = (self.cursor.col().inner() + cols) % Row::WIDTH;
+        self.cursor = Coord::new(Row::new(row), Col::new(col));
+    }
+
+    /// Set the cell under the cursor, unless it is a given.
+    fn set(&mut self, val: Option<Val>) {
+        if self.givens[self.cursor].is_some() {
+            self.log.push(format!("{} is a given", self.cursor));
+            return;
+        }
+        self.board[self.cursor] = val;
+        self.highlight.clear();
+    }
+
+    /// Find the next cell which can be filled by logic and highlight the cells
+    /// justifying it, logging why if there is none.
+    fn next_step(&mut self) -> Option<(Coord, Val, DeductionReason)> {
+        self.highlight.clear();
+        match self.board.analysis() {
+            Analysis::Solvable { hint } => {
+                self.highlight = justification(&hint.reason);
+                if !self.highlight.contains(&hint.pos) {
+                    self.highlight.push(hint.pos);
+                }
+                Some((hint.pos, hint.val, hint.reason))
+            }
+            Analysis::Solved => {
+                self.log.push("The board is already solved".to_string());
+                None
+            }
+            Analysis::Stuck { requires_guess } => {
+                self.log.push(if requires_guess {
+                    "No logical step left, a guess is required".to_string()
+                } else {
+                    "No logical step left".to_string()
+                });
+                None
+            }
+            Analysis::Contradictory(reason) => {
+                self.log.push(format!("Contradiction: {:?}", reason));
+                None
+            }
+        }
+    }
+
+    /// Whether the cell holds a value which is also in one of its peers.
+    fn conflicts(&self, coord: Coord) -> bool {
+        self.board[coord].is_some_and(|val| self.board.peer_values(coord).contains(val))
+    }
+
+    /// Draw the full screen, including the board, help, and recent log.
+    fn render(&self) -> String {
+        let mut out = String::from("\x1b[2J\x1b[H");
+        for row in (0..Col::HEIGHT).map(Row::new) {
+            if row.inner() != 0 && row.inner() % Sector::HEIGHT == 0 {
+                out.push_str("------+-------+------\r\n");
+            }
+            for coord in row.coords() {
+                let col = coord.col().inner();
+                if col != 0 {
+                    out.push_str(if col % Sector::WIDTH == 0 { " | " } else { " " });
+                }
+                let mut style = String::new();
+                if self.givens[coord].is_some() {
+                    style.push_str("\x1b[1m");
+                }
+                if self.conflicts(coord) {
+                    style.push_str("\x1b[31m");
+                }
+                if self.highlight.contains(&coord) {
+                    style.push_str("\x1b[43m");
+                }
+                if coord == self.cursor {
+                    style.push_str("\x1b[7m");
+                }
+                let cell = match self.board[coord] {
+                    Some(val) => char::from(b'0' + val.val()),
+                    None => '.',
+                };
+                if style.is_empty() {
+                    out.push(cell);
+                } else {
+                    out.push_str(&style);
+                    out.push(cell);
+                    out.push_str("\x1b[0m");
+                }
+            }
+            out.push_str("\r\n");
+        }
+        out.push_str("\r\narrows move, 1-9 fill, 0 clear, h hint, s step, S solve, q quit\r\n");
+        let start = self.log.len().saturating_sub(LOG_LINES);
+        for line in &self.log[start..] {
+            out.push_str(line);
+            out.push_str("\r\n");
+        }
+        out
+    }
+}
+
+/// Short description of a deduction for the log.
+fn describe(reason: &DeductionReason) -> String {
+    match reason {
+        DeductionReason::InitialState => "from the initial state".to_string(),
+        DeductionReason::CoordNeighbors { pos, .. } => format!("naked single at {}", pos),
+        DeductionReason::UniqueInRow { pos, .. } => format!("hidden single in {}", pos),
+        DeductionReason::UniqueInCol { pos, .. } => format!("hidden single in {}", pos),
+        DeductionReason::UniqueInSector { pos, .. } => format!(
+            "hidden single in the sector at {}",
+            Coord::new(pos.base_row(), pos.base_col())
+        ),
+        DeductionReason::SecRowTriple { .. } | DeductionReason::SecColTriple { .. } => {
+            "locked triple".to_string()
+        }
+        DeductionReason::SecOnlyRow { .. } | DeductionReason::SecOnlyCol { .. } => {
+            "pointing".to_string()
+        }
+        DeductionReason::RowOnlySec { .. } | DeductionReason::ColOnlySec { .. } => {
+            "claiming".to_string()
+        }
+        DeductionReason::HiddenPair { .. } => "hidden pair".to_string(),
+        DeductionReason::HiddenTriple { .. } => "hidden triple".to_string(),
+        DeductionReason::XWing { val, .. } => format!("x-wing on {}", val.val()),
+        DeductionReason::Unsolveable(reason) => format!("contradiction {:?}", reason),
+    }
+}
+
+/// Cells which justify a deduction.
+fn justification(reason: &DeductionReason) -> Vec<Coord> {
+    match *reason {
+        DeductionReason::CoordNeighbors { pos, .. } => pos.neighbors().collect(),
+        DeductionReason::UniqueInRow { pos, .. } => pos.coords().collect(),
+        DeductionReason::UniqueInCol { pos, .. } => pos.coords().collect(),
+        DeductionReason::UniqueInSector { pos, .. } => pos.coords().collect(),
+        DeductionReason::SecRowTriple { pos, .. }
+        | DeductionReason::SecOnlyRow { pos, .. }
+        | DeductionReason::RowOnlySec { pos, .. } => pos.coords().collect(),
+        DeductionReason::SecColTriple { pos, .. }
+        | DeductionReason::SecOnlyCol { pos, .. }
+        | DeductionReason::ColOnlySec { pos, .. } => pos.coords().collect(),
+        DeductionReason::HiddenPair { pos, .. } | DeductionReason::HiddenTriple { pos, .. } => {
+            pos.coords().collect()
+        }
+        DeductionReason::XWing { rows, cols, .. } => rows
+            .iter()
+            .flat_map(|row| row.coords())
+            .chain(cols.iter().flat_map(|col| col.coords()))
+            .collect(),
+        DeductionReason::InitialState | DeductionReason::Unsolveable(_) => Vec::new(),
+    }
+}
+
+/// Puts the terminal into raw mode for as long as it is alive.
+struct RawMode;
+
+impl RawMode {
+    fn enable() -> io::Result<Self> {
+        stty(&["raw", "-echo"])?;
+        Ok(RawMode)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = stty(&["-raw", "echo"]);
+    }
+}
+
+fn stty(args: &[&str]) -> io::Result<()> {
+    let status = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other("stty failed"))
+    }
+}
+
+fn main() -> io::Result<()> {
+    let givens = match env::args().nth(1) {
+        Some(arg) => parse_board(&arg).unwrap_or_else(|| {
+            eprintln!("board must be 81 characters of 1-9, '.', or '0'");
+            process::exit(2);
+        }),
+        None => parse_board(DEFAULT_BOARD).expect("default board is valid"),
+    };
+    let mut app = App::new(givens);
+
+    let raw = RawMode::enable()?;
+    let mut stdin = io::stdin();
+    let mut stdout = io::stdout();
+    let mut buf = [0; 16];
+    while !app.quit {
+        write!(stdout, "{}", app.render())?;
+        stdout.flush()?;
+        let n = stdin.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        for key in parse_keys(&buf[..n]) {
+            app.update(key);
+        }
+    }
+    drop(raw);
+    println!();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_input() {
+        let keys = parse_keys(b"\x1b[A\x1b[B\x1b[C\x1b[D5 hsSqx\x1b");
+        let expected = vec![
+            Key::Up,
+            Key::Down,
+            Key::Right,
+            Key::Left,
+            Key::Digit(Val::new(5)),
+            Key::Clear,
+            Key::Hint,
+            Key::Step,
+            Key::Solve,
+            Key::Quit,
+        ];
+        assert_eq!(keys, expected);
+        assert!(parse_board(DEFAULT_BOARD).is_some());
+        assert!(parse_board("123").is_none());
+    }
+
+    #[test]
+    fn scripted_session() {
+        let givens = parse_board(DEFAULT_BOARD).unwrap();
+        let solution = givens.solve().unwrap();
+        let mut app = App::new(givens.clone());
+
+        // Put a 1 in the empty top left cell, which conflicts with the 1 in the
+        // same row, so asking for a hint finds a contradiction. Clearing it
+        // and asking again gives a real hint.
+        let mut script = b"1h0h".to_vec();
+        // Wrap around to the last row, then step through several deductions.
+        script.extend_from_slice(b"\x1b[A\x1b[D");
+        script.extend_from_slice(b"ssssss");
+        script.extend_from_slice(b"S");
+
+        let mut frames = vec![app.render()];
+        let mut saw_conflict = false;
+        for key in parse_keys(&script) {
+            app.update(key);
+            let frame = app.render();
+            saw_conflict |= frame.contains("\x1b[31m");
+            frames.push(frame);
+        }
+        assert!(saw_conflict);
+        assert_eq!(app.board, solution);
+        assert!(app.board.is_solved());
+        assert_eq!(app.givens, givens);
+        assert!(frames.iter().all(|frame| frame.contains("h hint")));
+        assert!(app.log[0].starts_with("Contradiction:"));
+        assert!(app.log[1].starts_with("Hint:"));
+        assert_eq!(
+            app.log
+                .iter()
+                .filter(|line| line.starts_with("Filled"))
+                .count(),
+            6
+        );
+        assert_eq!(app.log.last().map(String::as_str), Some("Solved"));
+        assert!(!app.quit);
+
+        app.update(Key::Quit);
+        assert!(app.quit);
+
+        let mut app = App::new(givens.clone());
+        app.cursor = Coord::new(Row::new(0), Col::new(3));
+        app.update(Key::Digit(Val::new(9)));
+        assert_eq!(app.board, givens);
+        assert_eq!(app.log.last().unwrap(), "row 0 column 3 is a given");
+    }
+}
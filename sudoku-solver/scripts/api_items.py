@@ -0,0 +1,220 @@
+#!/usr/bin/env python3
+"""List every public item of the crate, for the API surface snapshot.
+
+Walks `src/lib.rs` and the public modules it declares, collecting top-level
+`pub` items, `pub use` re-exports, and exported macros. Each item is printed on
+its own line as `<kind> <path>`, followed by ` (<feature>)` when it only exists
+with an optional feature enabled.
+
+    scripts/api_items.py              # print the snapshot
+    scripts/api_items.py --write      # rewrite tests/snapshots/api.txt
+    scripts/api_items.py --registry   # print entries for tests/api_surface.rs
+
+This only understands the subset of Rust syntax the crate uses: items must
+start at the indentation of their module, and re-exports must not use globs.
+Methods, fields, and enum variants are not listed.
+"""
+import os
+import re
+import sys
+
+ROOT = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+SRC = os.path.join(ROOT, "src")
+SNAPSHOT = os.path.join(ROOT, "tests", "snapshots", "api.txt")
+CRATE = "sudoku_solver"
+
+ITEM = re.compile(
+    r"pub\s+(?:const\s+|unsafe\s+)*(struct|enum|trait|fn|type|const|static|union|mod)\s+(\w+)"
+)
+USE = re.compile(r"pub\s+use\s+")
+MACRO = re.compile(r"macro_rules!\s*(\w+)")
+FEATURE = re.compile(r'#\[cfg\((?:any\()?[^\]]*feature\s*=\s*"(\w+)"')
+
+
+def module_file(directory, name):
+    """Find the file holding the out-of-line module `name`."""
+    for candidate in (
+        os.path.join(directory, name + ".rs"),
+        os.path.join(directory, name, "mod.rs"),
+    ):
+        if os.path.exists(candidate):
+            return candidate
+    raise FileNotFoundError(f"no file for module {name} in {directory}")
+
+
+def child_dir(path):
+    """Directory holding the children of the module in the given file."""
+    base = os.path.basename(path)
+    if base in ("lib.rs", "mod.rs"):
+        return os.path.dirname(path)
+    return os.path.splitext(path)[0]
+
+
+def split_use_tree(tree):
+    """Flatten a use tree like `a::{b, c::{d as e}}` into (path, name) pairs."""
+    tree = tree.strip()
+    brace = tree.find("{")
+    if brace < 0:
+        if " as " in tree:
+            path, alias = (part.strip() for part in tree.split(" as "))
+        else:
+            path, alias = tree, None
+        segments = path.split("::")
+        if segments[-1] == "self":
+            segments = segments[:-1]
+        return [("::".join(segments), alias or segments[-1])]
+    prefix = tree[:brace].rstrip(":").strip()
+    inner = tree[brace + 1 : tree.rindex("}")]
+    parts, depth, start = [], 0, 0
+    for i, ch in enumerate(inner):
+        if ch == "{":
+            depth += 1
+        elif ch == "}":
+            depth -= 1
+        elif ch == "," and depth == 0:
+            parts.append(inner[start:i])
+            start = i + 1
+    parts.append(inner[start:])
+    result = []
+    for part in parts:
+        if part.strip():
+            sub = part.strip() if not prefix else prefix + "::" + part.strip()
+            result.extend(split_use_tree(sub))
+    return result
+
+
+def block_end(lines, start):
+    """Index of the line which closes the brace opened on line `start`."""
+    depth = 0
+    for i in range(start, len(lines)):
+        depth += lines[i].count("{") - lines[i].count("}")
+        if depth == 0:
+            return i
+    raise ValueError("unbalanced braces")
+
+
+def parse(lines, indent, module, directory, feature, items, definitions):
+    """Collect the public items of one module body."""
+    prefix = " " * indent
+    pending_feature = None
+    macro_export = False
+    i = 0
+    while i < len(lines):
+        line = lines[i]
+        if not line.startswith(prefix) or line[indent:indent + 1].isspace():
+            i += 1
+            continue
+        text = line[indent:]
+        if text.startswith("#[") or text.startswith(")]"):
+            match = FEATURE.match(text)
+            if match:
+                pending_feature = match.group(1)
+            if text.startswith("#[macro_export]"):
+                macro_export = True
+            i += 1
+            continue
+        if text.startswith("//"):
+            i += 1
+            continue
+        item_feature = pending_feature or feature
+        pending_feature = None
+        if macro_export:
+            macro_export = False
+            match = MACRO.match(text)
+            if match:
+                items.append(("macro", [CRATE, match.group(1)], item_feature))
+        if USE.match(text):
+            end = i
+            while not lines[end].rstrip().endswith(";"):
+                end += 1
+            tree = " ".join(l.strip() for l in lines[i:end + 1])
+            tree = USE.sub("", tree, count=1).rstrip(";")
+            for path, name in split_use_tree(tree):
+                items.append((("use", path), module + [name], item_feature))
+            i = end + 1
+            continue
+        match = ITEM.match(text)
+        if match:
+            kind, name = match.groups()
+            if kind == "mod":
+                if text.rstrip().endswith(";"):
+                    path = module_file(directory, name)
+                    with open(path) as f:
+                        body = f.read().splitlines()
+                    parse(body, 0, module + [name], child_dir(path), item_feature, items, definitions)
+                else:
+                    end = block_end(lines, i)
+                    parse(
+                        lines[i + 1:end],
+                        indent + 4,
+                        module + [name],
+                        os.path.join(directory, name),
+                        item_feature,
+                        items,
+                        definitions,
+                    )
+                    i = end
+            else:
+                items.append((kind, module + [name], item_feature))
+                definitions[name] = kind
+        i += 1
+
+
+def private_definitions(definitions):
+    """Record the kinds of items defined anywhere in the crate, so re-exports
+    of items from private modules can be described."""
+    for directory, _, files in os.walk(SRC):
+        for name in files:
+            if not name.endswith(".rs"):
+                continue
+            with open(os.path.join(directory, name)) as f:
+                for line in f:
+                    match = re.match(r"\s*pub(?:\([\w:]+\))?\s+(?:const\s+|unsafe\s+)*"
+                                     r"(struct|enum|trait|fn|type|const|static|union)\s+(\w+)", line)
+                    if match:
+                        definitions.setdefault(match.group(2), match.group(1))
+
+
+def collect():
+    items, definitions = [], {}
+    with open(os.path.join(SRC, "lib.rs")) as f:
+        lines = f.read().splitlines()
+    parse(lines, 0, [CRATE], SRC, None, items, definitions)
+    private_definitions(definitions)
+    result = []
+    for kind, path, feature in items:
+        if isinstance(kind, tuple):
+            kind = definitions.get(kind[1].split("::")[-1], "item")
+        result.append((kind, "::".join(path), feature))
+    return sorted(set(result), key=lambda item: (item[1], item[0]))
+
+
+def snapshot(items):
+    return "".join(
+        f"{kind} {path}" + (f" ({feature})" if feature else "") + "\n"
+        for kind, path, feature in items
+    )
+
+
+def registry(items):
+    lines = []
+    for kind, path, feature in items:
+        if feature:
+            lines.append(f'    #[cfg(feature = "{feature}")]')
+        lines.append(f"    {kind} {path};")
+    return "\n".join(lines) + "\n"
+
+
+def main(args):
+    items = collect()
+    if "--registry" in args:
+        sys.stdout.write(registry(items))
+    elif "--write" in args:
+        with open(SNAPSHOT, "w") as f:
+            f.write(snapshot(items))
+    else:
+        sys.stdout.write(snapshot(items))
+
+
+if __name__ == "__main__":
+    main(sys.argv[1:])
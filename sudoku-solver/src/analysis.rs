@@ -0,0 +1,734 @@
+//! Tools for analyzing boards and how well the solver handles them.
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::solve::deductive;
+use crate::solve::remaining::RemainingTracker;
+use crate::trace::{Deduction, DeductionReason, NopDeductiveTracer, UnsolveableReason};
+use crate::{
+    AvailSet, Board, Budget, BudgetExceeded, Coord, Exclusions, ExhaustionKind, ReduceOptions,
+    RuleKind, RulePriority, SolveOutcome, Solver, Val, Zone,
+};
+
+mod disclosure;
+mod graph;
+
+pub use disclosure::{
+    CellHint, DisclosureLevel, HintEscalation, HouseHint, PartialHint, ValueHint,
+};
+pub use graph::{
+    constraint_graph, implication_graph, CellNode, ConstraintGraph, ImplicationGraph, PeerEdge,
+};
+
+/// Fewest clues any classic puzzle with a unique solution can have.
+const MIN_UNIQUE_CLUES: usize = 17;
+
+/// Candidate entropy left after deduction above which a board counts as
+/// underdetermined. The hardest puzzles in the corpus keep about 123 bits,
+/// while boards with thousands of solutions keep 170 or more.
+pub const UNDERDETERMINED_ENTROPY: Log2Millibits = Log2Millibits(150_000);
+
+/// How constrained a board is, as a cheap check before running analysis whose
+/// answers only make sense for a puzzle with few solutions.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Regime {
+    /// Too few clues to have a unique solution, or so many candidates left
+    /// after deduction that the board almost certainly has a great many
+    /// solutions.
+    Underdetermined,
+    /// Anything else, including boards which deduction shows have no
+    /// solution.
+    Normal,
+    /// Every cell is filled, whether or not the values conflict.
+    Filled,
+}
+
+/// Classify the given board, as described on `Board::analysis_regime`.
+pub(crate) fn regime(board: &Board) -> Regime {
+    let clues = board
+        .row_major()
+        .iter()
+        .filter(|cell| cell.is_some())
+        .count();
+    if clues == Board::SIZE {
+        return Regime::Filled;
+    }
+    if clues < MIN_UNIQUE_CLUES {
+        return Regime::Underdetermined;
+    }
+    let reduced = deductive::reduce(
+        RemainingTracker::new(board),
+        &Default::default(),
+        NopDeductiveTracer,
+    )
+    .0;
+    match reduced {
+        Some(reduced)
+            if Log2Millibits::of_candidates(reduced.remaining().as_ref())
+                > UNDERDETERMINED_ENTROPY =>
+        {
+            Regime::Underdetermined
+        }
+        _ => Regime::Normal,
+    }
+}
+
+/// A cell that can be filled by logic alone, along with the deduction that
+/// determined it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Hint {
+    /// The cell which can be filled.
+    pub pos: Coord,
+    /// The value which belongs in the cell.
+    pub val: Val,
+    /// The deduction which left only this value in the cell.
+    pub reason: DeductionReason,
+}
+
+/// Overall state of a board, as determined by logic alone.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(tag = "status"),
+    serde(rename_all = "snake_case")
+)]
+pub enum Analysis {
+    /// Every cell is filled and no zone repeats a value.
+    Solved,
+    /// Logic can make progress. The hint is the next cell it can fill.
+    Solvable { hint: Hint },
+    /// No cell can be filled by logic alone. If `requires_guess` is true, the
+    /// board still has a solution and a guess is needed to reach it. If false,
+    /// the board has no solution even though logic cannot show where it breaks.
+    Stuck { requires_guess: bool },
+    /// Logic proved the board has no solution, for the given reason.
+    Contradictory(UnsolveableReason),
+}
+
+/// Analyze the given board.
+pub(crate) fn analyze(board: &Board) -> Analysis {
+    analyze_with_exclusions(board, &Exclusions::new())
+}
+
+/// Analyze the board as `analyze` does, with the given values forbidden from
+/// their cells.
+pub(crate) fn analyze_with_exclusions(board: &Board, exclusions: &Exclusions) -> Analysis {
+    if board.is_solved() {
+        return Analysis::Solved;
+    }
+    let (reduced, deductions) = deductive::reduce(
+        RemainingTracker::with_exclusions(board, exclusions),
+        &Default::default(),
+        Vec::new(),
+    );
+    if reduced.is_none() {
+        return match deductions.into_iter().last() {
+            Some(Deduction {
+                reason: DeductionReason::Unsolveable(reason),
+                ..
+            }) => Analysis::Contradictory(reason),
+            other => unreachable!("unsolveable reduction ended with {:?}", other),
+        };
+    }
+    match first_placement(board, deductions) {
+        Some(hint) => Analysis::Solvable { hint },
+        None => Analysis::Stuck {
+            requires_guess: board.solve_with_exclusions(exclusions).is_some(),
+        },
+    }
+}
+
+/// Find the first cell which is empty on the board but was left with a single
+/// value by the given deductions.
+pub(crate) fn first_placement(
+    board: &Board,
+    deductions: impl IntoIterator<Item = Deduction>,
+) -> Option<Hint> {
+    deductions.into_iter().find_map(|deduction| {
+        Coord::all()
+            .filter(|&coord| board[coord].is_none())
+            .find_map(|coord| {
+                deduction.remaining[coord]
+                    .get_single()
+                    .map(|val| (coord, val))
+            })
+            .map(|(pos, val)| Hint {
+                pos,
+                val,
+                reason: deduction.reason.clone(),
+            })
+    })
+}
+
+/// Whether a board has exactly one solution, with evidence.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Uniqueness {
+    /// The board has exactly one solution.
+    Unique,
+    /// The board has more than one solution. Holds two distinct solutions as
+    /// proof.
+    Multiple(Box<Board>, Box<Board>),
+    /// The board has no solution.
+    NoSolution,
+}
+
+/// Check whether the given board has a unique solution. Any second solution
+/// must differ from the first in some empty cell, so the board is solved again
+/// with each empty cell's first value excluded in turn.
+pub(crate) fn uniqueness(board: &Board) -> Uniqueness {
+    uniqueness_within(board, &mut Budget::new()).expect("unlimited budget was exceeded")
+}
+
+/// Check whether the given board has a unique solution, as `uniqueness` does,
+/// within the given budget.
+pub(crate) fn uniqueness_within(
+    board: &Board,
+    budget: &mut Budget,
+) -> Result<Uniqueness, BudgetExceeded> {
+    Ok(match two_solutions_within(board, budget)? {
+        None => Uniqueness::NoSolution,
+        Some((_, None)) => Uniqueness::Unique,
+        Some((first, Some(second))) => Uniqueness::Multiple(Box::new(first), Box::new(second)),
+    })
+}
+
+/// Find a solution to the board and, if there is one, a second distinct
+/// solution, as `uniqueness` does.
+pub(crate) fn two_solutions(board: &Board) -> Option<(Board, Option<Board>)> {
+    two_solutions_within(board, &mut Budget::new()).expect("unlimited budget was exceeded")
+}
+
+/// Find up to two solutions within the budget. The first solve gets half of
+/// the budget, and each solve looking for a second solution gets whatever is
+/// left.
+fn two_solutions_within(
+    board: &Board,
+    budget: &mut Budget,
+) -> Result<Option<(Board, Option<Board>)>, BudgetExceeded> {
+    let first = match solve_within(board, &Exclusions::new(), budget, 0.5)? {
+        Some(first) => first,
+        None => return Ok(None),
+    };
+    for coord in Coord::all().filter(|&coord| board[coord].is_none()) {
+        let mut exclusions = Exclusions::new();
+        exclusions.exclude(coord, AvailSet::only(first[coord].unwrap()));
+        if let Some(second) = solve_within(board, &exclusions, budget, 1.0)? {
+            return Ok(Some((first, Some(second))));
+        }
+    }
+    Ok(Some((first, None)))
+}
+
+/// Solve the board with the given exclusions within a fraction of what is left
+/// of the budget, charging the budget with the work done.
+fn solve_within(
+    board: &Board,
+    exclusions: &Exclusions,
+    budget: &mut Budget,
+    fraction: f64,
+) -> Result<Option<Board>, BudgetExceeded> {
+    let solver = Solver {
+        exclusions: exclusions.clone(),
+        ..Default::default()
+    };
+    let mut part = budget.split(fraction);
+    let outcome = solver.run_within(board, &mut part);
+    budget.charge(&part);
+    match outcome {
+        SolveOutcome::Solved(solution) => Ok(Some(solution)),
+        SolveOutcome::Unsolveable(_) => Ok(None),
+        SolveOutcome::Exhausted {
+            reason: ExhaustionKind::Budget(exceeded),
+            ..
+        } => Err(exceeded),
+        SolveOutcome::Exhausted {
+            reason: ExhaustionKind::VerificationFailed,
+            ..
+        } => unreachable!("verdicts are only checked with high_assurance"),
+    }
+}
+
+/// How well the solver handled a single puzzle.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PuzzleStrength {
+    /// Whether a solution was found.
+    pub solved: bool,
+    /// Whether the puzzle was solved by deduction alone, without guessing.
+    pub solved_without_guess: bool,
+    /// Number of guesses tried, including ones that turned out wrong.
+    pub guesses: usize,
+    /// Number of deductions made, keyed by the rule which made them.
+    pub deductions: BTreeMap<RuleKind, usize>,
+    /// Total number of candidates eliminated by deductions.
+    pub eliminations: usize,
+}
+
+impl PuzzleStrength {
+    /// Solve the given board and measure how much work it took.
+    #[must_use]
+    pub fn measure(board: &Board) -> Self {
+        Self::measure_with(board, &Default::default())
+    }
+
+    /// Solve the given board with the given reduce options and measure how
+    /// much work it took.
+    #[must_use]
+    pub fn measure_with(board: &Board, options: &ReduceOptions) -> Self {
+        let (solution, trace) = board.solve_with_options(options, Vec::new());
+        Self::from_trace(solution.is_some(), &trace)
+    }
+
+    /// Measure the work recorded in the trace of a solve, which found a
+    /// solution if `solved` is true.
+    pub(crate) fn from_trace(solved: bool, trace: &[Deduction]) -> Self {
+        let mut strength = PuzzleStrength {
+            solved,
+            ..Default::default()
+        };
+        let mut reductions: usize = 0;
+        for deduction in trace {
+            match deduction.reason.rule_kind() {
+                Some(kind) => {
+                    *strength.deductions.entry(kind).or_default() += 1;
+                    strength.eliminations += deduction.eliminated_count;
+                }
+                None if deduction.reason == DeductionReason::InitialState => reductions += 1,
+                None => {}
+            }
+        }
+        // Every guess starts a new reduction after the first.
+        strength.guesses = reductions.saturating_sub(1);
+        strength.solved_without_guess = strength.solved && strength.guesses == 0;
+        strength
+    }
+
+    /// The hardest rule which made a deduction, according to the given
+    /// priority. With `RulePriority::human` this is the technique which
+    /// decides how hard the puzzle feels.
+    #[must_use]
+    pub fn hardest(&self, priority: &RulePriority) -> Option<RuleKind> {
+        priority.hardest(self.deductions.keys().copied())
+    }
+}
+
+/// `log2(n)` for candidate counts `n` from 0 to 9, in thousandths of a bit,
+/// rounded to nearest. An empty cell has no candidates and contributes
+/// nothing.
+const LOG2_MILLIBITS: [u64; 10] = [0, 0, 1000, 1585, 2000, 2322, 2585, 2807, 3000, 3170];
+
+/// An amount of information in thousandths of a bit, kept as an integer so it
+/// is the same on every platform. Displays as a decimal number of bits.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct Log2Millibits(pub u64);
+
+impl Log2Millibits {
+    /// The information in choosing among the candidates of every cell, which
+    /// is the log2 of the product of their candidate counts.
+    #[must_use]
+    pub fn of_candidates(cells: &[AvailSet]) -> Self {
+        Log2Millibits(cells.iter().map(|avail| LOG2_MILLIBITS[avail.len()]).sum())
+    }
+}
+
+impl fmt::Display for Log2Millibits {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{:03} bits", self.0 / 1000, self.0 % 1000)
+    }
+}
+
+/// Lossy conversion to bits as a float, for display or plotting.
+impl From<Log2Millibits> for f64 {
+    fn from(bits: Log2Millibits) -> Self {
+        bits.0 as f64 / 1000.0
+    }
+}
+
+/// Solver strength over a corpus of puzzles, per puzzle and in aggregate.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StrengthReport {
+    /// Strength for each puzzle, in corpus order.
+    pub puzzles: Vec<PuzzleStrength>,
+    /// Number of puzzles solved without guessing.
+    pub solved_without_guess: usize,
+    /// Total guesses over all puzzles.
+    pub guesses: usize,
+    /// Total deductions over all puzzles, keyed by rule.
+    pub deductions: BTreeMap<RuleKind, usize>,
+    /// Total candidates eliminated over all puzzles.
+    pub eliminations: usize,
+}
+
+impl StrengthReport {
+    /// Percentage of the corpus solved without guessing, in thousandths of a
+    /// percent from 0 to 100,000, rounded down. An empty corpus counts as
+    /// fully solved. This is computed with integers only, so it is the same on
+    /// every platform and safe to compare exactly or store.
+    #[must_use]
+    pub fn no_guess_millipercent(&self) -> u32 {
+        if self.puzzles.is_empty() {
+            100_000
+        } else {
+            (self.solved_without_guess as u64 * 100_000 / self.puzzles.len() as u64) as u32
+        }
+    }
+
+    /// Percentage of the corpus solved without guessing, from 0 to 100, as a
+    /// float for display. Prefer `no_guess_millipercent` for anything compared
+    /// or stored.
+    #[must_use]
+    pub fn no_guess_percent(&self) -> f64 {
+        if self.puzzles.is_empty() {
+            100.0
+        } else {
+            self.solved_without_guess as f64 * 100.0 / self.puzzles.len() as f64
+        }
+    }
+
+    /// Render the report as CSV, with one row per puzzle followed by a `total`
+    /// row.
+    #[must_use]
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("puzzle,solved,no_guess,guesses,deductions,eliminations\n");
+        for (i, puzzle) in self.puzzles.iter().enumerate() {
+            writeln!(
+                out,
+                "{},{},{},{},{},{}",
+                i,
+                puzzle.solved as u8,
+                puzzle.solved_without_guess as u8,
+                puzzle.guesses,
+                puzzle.deductions.values().sum::<usize>(),
+                puzzle.eliminations,
+            )
+            .unwrap();
+        }
+        writeln!(
+            out,
+            "total,{},{},{},{},{}",
+            self.puzzles.iter().filter(|p| p.solved).count(),
+            self.solved_without_guess,
+            self.guesses,
+            self.deductions.values().sum::<usize>(),
+            self.eliminations,
+        )
+        .unwrap();
+        out
+    }
+}
+
+/// Measure how well the solver handles each puzzle of the corpus.
+#[must_use]
+pub fn strength_report(corpus: &[Board]) -> StrengthReport {
+    let mut report = StrengthReport::default();
+    for board in corpus {
+        let puzzle = PuzzleStrength::measure(board);
+        report.solved_without_guess += puzzle.solved_without_guess as usize;
+        report.guesses += puzzle.guesses;
+        for (rule, count) in &puzzle.deductions {
+            *report.deductions.entry(*rule).or_default() += count;
+        }
+        report.eliminations += puzzle.eliminations;
+        report.puzzles.push(puzzle);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board_art;
+    use crate::fixtures::{contradictory, inkala, puzzle1};
+    use crate::{Col, Row};
+
+    #[test]
+    fn analysis_solved() {
+        crate::setup();
+
+        let board = board_art!(
+            "467|192|385",
+            "329|458|671",
+            "851|367|294",
+            "---+---+---",
+            "518|279|463",
+            "273|641|859",
+            "694|835|127",
+            "---+---+---",
+            "732|984|516",
+            "145|726|938",
+            "986|513|742",
+        );
+        assert_eq!(board.analysis(), Analysis::Solved);
+        assert_eq!(board.hint(), None);
+    }
+
+    #[test]
+    fn analysis_solvable() {
+        crate::setup();
+
+        // Solved except for one cell.
+        let board = board_art!(
+            "467|192|385",
+            "329|458|671",
+            "851|367|294",
+            "---+---+---",
+            "518|279|463",
+            "273|6 1|859",
+            "694|835|127",
+            "---+---+---",
+            "732|984|516",
+            "145|726|938",
+            "986|513|742",
+        );
+        let pos = Coord::new(Row::new(4), Col::new(4));
+        match board.analysis() {
+            Analysis::Solvable { hint } => {
+                assert_eq!(hint.pos, pos);
+                assert_eq!(hint.val, Val::new(4));
+                assert_eq!(board.hint(), Some(hint));
+            }
+            other => panic!("expected solvable, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn analysis_stuck() {
+        crate::setup();
+
+        assert_eq!(
+            Board::new().analysis(),
+            Analysis::Stuck {
+                requires_guess: true
+            }
+        );
+        assert_eq!(Board::new().hint(), None);
+    }
+
+    #[test]
+    fn analysis_contradictory() {
+        crate::setup();
+
+        let board = contradictory();
+        assert!(matches!(board.analysis(), Analysis::Contradictory(_)));
+        assert_eq!(board.hint(), None);
+    }
+
+    #[test]
+    fn uniqueness_outcomes() {
+        crate::setup();
+
+        let unique = puzzle1();
+        assert_eq!(unique.uniqueness_certificate(), Uniqueness::Unique);
+
+        // A solved board with a deadly rectangle removed.
+        let ambiguous = board_art!(
+            "467|192|385",
+            "329|458|671",
+            "851|367|294",
+            "---+---+---",
+            "518|279|463",
+            "27 |6 1|859",
+            "69 |8 5|127",
+            "---+---+---",
+            "732|984|516",
+            "145|726|938",
+            "986|513|742",
+        );
+        match ambiguous.uniqueness_certificate() {
+            Uniqueness::Multiple(a, b) => {
+                assert_ne!(a, b);
+                for solution in [&a, &b] {
+                    assert!(solution.is_solved());
+                    assert!(Coord::all()
+                        .filter(|&c| ambiguous[c].is_some())
+                        .all(|c| solution[c] == ambiguous[c]));
+                }
+            }
+            other => panic!("expected multiple solutions, got {:?}", other),
+        }
+
+        let contradictory = contradictory();
+        assert_eq!(
+            contradictory.uniqueness_certificate(),
+            Uniqueness::NoSolution
+        );
+    }
+
+    #[test]
+    fn uniqueness_within_budget() {
+        crate::setup();
+
+        let unique = puzzle1();
+        for board in [unique, Board::new()] {
+            let mut unlimited = Budget::new();
+            let expected = board.uniqueness_within(&mut unlimited).unwrap();
+            assert_eq!(expected, board.uniqueness_certificate());
+            let total = unlimited.consumed().steps;
+
+            // However the budget is split between the solves, the total stays
+            // within the parent's limits.
+            for limit in [0, 1, 2, total / 4, total / 2, total - 1, total] {
+                let mut budget = Budget::new().with_steps(limit).with_guesses(limit / 2);
+                let result = board.uniqueness_within(&mut budget);
+                let used = budget.consumed();
+                assert!(used.steps <= limit, "{} steps of {}", used.steps, limit);
+                assert!(used.guesses <= limit / 2);
+                if let Ok(uniqueness) = result {
+                    assert_eq!(uniqueness, expected);
+                }
+            }
+            let mut budget = Budget::new().with_steps(total * 2);
+            assert_eq!(board.uniqueness_within(&mut budget), Ok(expected));
+            assert_eq!(budget.consumed().steps, total);
+        }
+    }
+
+    #[test]
+    fn candidate_entropy() {
+        let puzzle = puzzle1();
+        let empty = Board::new().candidate_entropy();
+        assert_eq!(empty, Log2Millibits(81 * 3170));
+        assert_eq!(empty.to_string(), "256.770 bits");
+        assert_eq!(
+            puzzle.solve().unwrap().candidate_entropy(),
+            Log2Millibits(0)
+        );
+        let entropy = puzzle.candidate_entropy();
+        assert_eq!(entropy, puzzle.candidate_entropy());
+        assert_eq!(entropy, Log2Millibits(85650));
+        assert_eq!(entropy.to_string(), "85.650 bits");
+
+        // The table matches the float log2 closely enough that totals order
+        // the same way.
+        let float = |board: &Board| -> f64 {
+            Coord::all()
+                .filter(|&coord| board[coord].is_none())
+                .map(|coord| (!board.peer_values(coord)).len())
+                .filter(|&len| len > 0)
+                .map(|len| (len as f64).log2())
+                .sum()
+        };
+        for (n, &bits) in LOG2_MILLIBITS.iter().enumerate().skip(1) {
+            assert_eq!(bits, ((n as f64).log2() * 1000.0).round() as u64);
+        }
+        let mut boards = vec![Board::new(), puzzle.clone(), puzzle.solve().unwrap()];
+        let mut partial = puzzle.solve().unwrap();
+        for coord in Coord::all().step_by(2) {
+            partial[coord] = None;
+        }
+        boards.push(partial);
+        for a in &boards {
+            assert!((f64::from(a.candidate_entropy()) - float(a)).abs() < 0.05);
+            for b in &boards {
+                assert_eq!(
+                    a.candidate_entropy().cmp(&b.candidate_entropy()),
+                    float(a).partial_cmp(&float(b)).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn analysis_regime() {
+        crate::setup();
+
+        let solved = board_art!(
+            "467|192|385",
+            "329|458|671",
+            "851|367|294",
+            "---+---+---",
+            "518|279|463",
+            "273|641|859",
+            "694|835|127",
+            "---+---+---",
+            "732|984|516",
+            "145|726|938",
+            "986|513|742",
+        );
+        assert_eq!(solved.analysis_regime(), Regime::Filled);
+        let mut conflicting = solved.clone();
+        conflicting.row_major_mut().swap(0, 1);
+        assert_eq!(conflicting.analysis_regime(), Regime::Filled);
+
+        let few = board_art!(
+            "1  |   |   ",
+            "  6|   |   ",
+            "7  |   |   ",
+            "---+---+---",
+            "   |   |   ",
+            "   |   |   ",
+            "   |   |  1",
+            "---+---+---",
+            "   |   |   ",
+            "   |   |   ",
+            "   |   |  5",
+        );
+        assert_eq!(few.analysis_regime(), Regime::Underdetermined);
+        assert_eq!(Board::new().analysis_regime(), Regime::Underdetermined);
+
+        // Enough clues, but all in two rows, so deduction gets nowhere.
+        let mut two_rows = solved.clone();
+        two_rows.row_major_mut()[18..].fill(None);
+        assert_eq!(two_rows.analysis_regime(), Regime::Underdetermined);
+
+        let unique = puzzle1();
+        let inkala = inkala();
+        let contradictory = contradictory();
+        for board in [unique, inkala, contradictory] {
+            assert_eq!(board.analysis_regime(), Regime::Normal);
+        }
+    }
+
+    #[test]
+    fn hardest_technique_human() {
+        crate::setup();
+
+        let human = RulePriority::human();
+        // Only needs singles, though the default order reaches for a triple.
+        let hidden_single = puzzle1();
+        let strength = PuzzleStrength::measure(&hidden_single);
+        assert_eq!(strength.hardest(&human), Some(RuleKind::SecRowTriple));
+        let strength = PuzzleStrength::measure_with(&hidden_single, &ReduceOptions::human());
+        assert!(strength.solved_without_guess);
+        assert_eq!(strength.hardest(&human), Some(RuleKind::UniqueInRow));
+
+        // Every cell follows from a cell with one value left.
+        let naked_single = board_art!(
+            "   |8  | 14",
+            "1 6|4  |75 ",
+            " 47|53 |   ",
+            "---+---+---",
+            "9  | 5 | 62",
+            "   |7 9|   ",
+            "63 | 4 |  5",
+            "---+---+---",
+            "   | 87|34 ",
+            " 14|  5|6 9",
+            "89 |  4|   ",
+        );
+        let strength = PuzzleStrength::measure_with(&naked_single, &ReduceOptions::human());
+        assert!(strength.solved_without_guess);
+        assert_eq!(strength.hardest(&human), Some(RuleKind::CoordNeighbors));
+        assert_eq!(
+            strength.deductions.keys().copied().collect::<Vec<_>>(),
+            [RuleKind::CoordNeighbors]
+        );
+    }
+}
@@ -0,0 +1,293 @@
+//! Hints revealed a little at a time, so a player asking for help isn't handed
+//! the answer outright.
+//!
+//! Each level has its own type holding only what that level reveals, so a
+//! serialized low-level hint carries no trace of the cell or value it points
+//! towards.
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::Hint;
+use crate::{lesson, Board, Coord, House, Val};
+
+/// How much of a hint to reveal. Levels are ordered from least to most
+/// revealing.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum DisclosureLevel {
+    /// Only the house to look at.
+    House,
+    /// The house and the value which can be placed in it.
+    Value,
+    /// The cell and the value which belongs there.
+    Cell,
+    /// The cell, the value, and the deduction which proves it.
+    Full,
+}
+
+impl DisclosureLevel {
+    /// The next more revealing level. `Full` is followed by itself.
+    #[must_use]
+    pub fn escalate(self) -> Self {
+        match self {
+            DisclosureLevel::House => DisclosureLevel::Value,
+            DisclosureLevel::Value => DisclosureLevel::Cell,
+            DisclosureLevel::Cell | DisclosureLevel::Full => DisclosureLevel::Full,
+        }
+    }
+}
+
+/// A hint revealed up to some `DisclosureLevel`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(tag = "level"),
+    serde(rename_all = "snake_case")
+)]
+pub enum PartialHint {
+    House(HouseHint),
+    Value(ValueHint),
+    Cell(CellHint),
+    Full(Hint),
+}
+
+/// A hint which only says where to look.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HouseHint {
+    /// The house containing the cell which can be filled.
+    pub house: House,
+}
+
+/// A hint which says where to look and what value to look for.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ValueHint {
+    /// The house containing the cell which can be filled.
+    pub house: House,
+    /// The value which can be placed somewhere in the house.
+    pub val: Val,
+}
+
+/// A hint which gives the answer for a cell without explaining it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CellHint {
+    /// The cell which can be filled.
+    pub pos: Coord,
+    /// The value which belongs in the cell.
+    pub val: Val,
+}
+
+impl Hint {
+    /// Reveal this hint only up to the given level. The house revealed is the
+    /// one the deduction focused on when it contains the cell, and otherwise
+    /// the cell's row.
+    #[must_use]
+    pub fn at_level(&self, level: DisclosureLevel) -> PartialHint {
+        match level {
+            DisclosureLevel::House => PartialHint::House(HouseHint {
+                house: self.house(),
+            }),
+            DisclosureLevel::Value => PartialHint::Value(ValueHint {
+                house: self.house(),
+                val: self.val,
+            }),
+            DisclosureLevel::Cell => PartialHint::Cell(CellHint {
+                pos: self.pos,
+                val: self.val,
+            }),
+            DisclosureLevel::Full => PartialHint::Full(self.clone()),
+        }
+    }
+
+    /// The house to point the player towards.
+    fn house(&self) -> House {
+        lesson::focus(&self.reason)
+            .filter(|house| House::containing(self.pos).contains(house))
+            .unwrap_or(House::Row(self.pos.row()))
+    }
+}
+
+/// Tracks how many times a hint has been asked for on each board, revealing
+/// more of it each time.
+///
+/// Boards are tracked by their exact contents, so filling a cell moves on to a
+/// new board whose hint starts again from the least revealing level.
+#[derive(Clone, Debug, Default)]
+pub struct HintEscalation {
+    levels: HashMap<Board, DisclosureLevel>,
+}
+
+impl HintEscalation {
+    /// Create an escalation tracker which hasn't given any hints.
+    #[must_use]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Get the hint for the given board, one level more revealing than the
+    /// last time it was asked for, starting from `DisclosureLevel::House`.
+    /// Returns None without counting the request if the board has no hint.
+    pub fn next(&mut self, board: &Board) -> Option<PartialHint> {
+        let hint = board.hint()?;
+        let level = match self.levels.get_mut(board) {
+            Some(level) => {
+                *level = level.escalate();
+                *level
+            }
+            None => {
+                self.levels.insert(board.clone(), DisclosureLevel::House);
+                DisclosureLevel::House
+            }
+        };
+        Some(hint.at_level(level))
+    }
+
+    /// The level of the last hint given for the given board, if any.
+    #[must_use]
+    pub fn level(&self, board: &Board) -> Option<DisclosureLevel> {
+        self.levels.get(board).copied()
+    }
+
+    /// Start the given board over from the least revealing level. Returns
+    /// whether any hints had been given for it.
+    pub fn reset(&mut self, board: &Board) -> bool {
+        self.levels.remove(board).is_some()
+    }
+
+    /// Start every board over from the least revealing level.
+    pub fn clear(&mut self) {
+        self.levels.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board_art;
+    use crate::{Col, Row};
+
+    /// Solved except for one cell, r4c4, which must be 4.
+    fn one_left() -> Board {
+        board_art!(
+            "467|192|385",
+            "329|458|671",
+            "851|367|294",
+            "---+---+---",
+            "518|279|463",
+            "273|6 1|859",
+            "694|835|127",
+            "---+---+---",
+            "732|984|516",
+            "145|726|938",
+            "986|513|742",
+        )
+    }
+
+    #[test]
+    fn levels() {
+        crate::setup();
+        let board = one_left();
+        let hint = board.hint().unwrap();
+        let pos = Coord::new(Row::new(4), Col::new(4));
+        let house = match board.hint_at(DisclosureLevel::House) {
+            Some(PartialHint::House(HouseHint { house })) => house,
+            other => panic!("expected house hint, got {:?}", other),
+        };
+        assert!(house.coords().any(|coord| coord == pos));
+        assert_eq!(
+            board.hint_at(DisclosureLevel::Value),
+            Some(PartialHint::Value(ValueHint {
+                house,
+                val: Val::new(4)
+            }))
+        );
+        assert_eq!(
+            board.hint_at(DisclosureLevel::Cell),
+            Some(PartialHint::Cell(CellHint {
+                pos,
+                val: Val::new(4)
+            }))
+        );
+        assert_eq!(
+            board.hint_at(DisclosureLevel::Full),
+            Some(PartialHint::Full(hint))
+        );
+        assert_eq!(Board::new().hint_at(DisclosureLevel::House), None);
+    }
+
+    #[test]
+    fn escalation() {
+        crate::setup();
+        let board = one_left();
+        let hint = board.hint().unwrap();
+        let mut escalation = HintEscalation::new();
+        assert_eq!(escalation.level(&board), None);
+        let levels = [
+            DisclosureLevel::House,
+            DisclosureLevel::Value,
+            DisclosureLevel::Cell,
+            DisclosureLevel::Full,
+            DisclosureLevel::Full,
+        ];
+        for level in levels {
+            assert_eq!(escalation.next(&board), Some(hint.at_level(level)));
+            assert_eq!(escalation.level(&board), Some(level));
+        }
+
+        // Other boards escalate separately, and boards with no hint aren't
+        // counted.
+        let mut other = board.clone();
+        other[Coord::new(Row::new(0), Col::new(0))] = None;
+        assert!(matches!(
+            escalation.next(&other),
+            Some(PartialHint::House(_))
+        ));
+        assert_eq!(escalation.next(&Board::new()), None);
+        assert_eq!(escalation.level(&Board::new()), None);
+
+        assert!(escalation.reset(&board));
+        assert!(!escalation.reset(&board));
+        assert_eq!(
+            escalation.next(&board),
+            Some(hint.at_level(DisclosureLevel::House))
+        );
+        assert_eq!(escalation.level(&other), Some(DisclosureLevel::House));
+        escalation.clear();
+        assert_eq!(escalation.level(&other), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_without_spoilers() {
+        crate::setup();
+        let board = one_left();
+        let house = serde_json::to_value(board.hint_at(DisclosureLevel::House).unwrap()).unwrap();
+        let fields: Vec<_> = house.as_object().unwrap().keys().cloned().collect();
+        assert_eq!(fields, ["house", "level"]);
+        assert_eq!(house["level"], "house");
+        let value = serde_json::to_value(board.hint_at(DisclosureLevel::Value).unwrap()).unwrap();
+        let fields: Vec<_> = value.as_object().unwrap().keys().cloned().collect();
+        assert_eq!(fields, ["house", "level", "val"]);
+
+        for level in [
+            DisclosureLevel::House,
+            DisclosureLevel::Value,
+            DisclosureLevel::Cell,
+            DisclosureLevel::Full,
+        ] {
+            let partial = board.hint_at(level).unwrap();
+            let ser = serde_json::to_string(&partial).unwrap();
+            let de: PartialHint = serde_json::from_str(&ser).unwrap();
+            assert_eq!(de, partial);
+        }
+    }
+}
@@ -0,0 +1,408 @@
+//! Graphs of how cells constrain each other, for analysis in external tools.
+use std::fmt::Write;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::solve::deductive;
+use crate::solve::remaining::RemainingTracker;
+use crate::solve::techniques::{self, Link, LinkStrength};
+use crate::trace::Remaining;
+use crate::{Board, Coord, House, Val, Zone};
+
+/// A cell of the constraint graph.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CellNode {
+    /// The cell.
+    pub pos: Coord,
+    /// The value given in the cell, if any.
+    pub clue: Option<Val>,
+    /// Number of values left for the cell after deductive reduction. If the
+    /// reduction proves the board has no solution, this is the count before
+    /// reduction instead.
+    pub candidates: usize,
+}
+
+/// An edge between two cells which share at least one house.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PeerEdge {
+    /// The first cell, which sorts before `b`.
+    pub a: Coord,
+    /// The second cell.
+    pub b: Coord,
+    /// The houses the cells share, rows first, then columns, then sectors.
+    pub houses: Vec<House>,
+}
+
+/// Every cell of a board and the peers each one constrains.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ConstraintGraph {
+    /// The cells, in row-major order.
+    pub nodes: Vec<CellNode>,
+    /// Edges between every pair of peers, ordered by their first cell, then
+    /// their second.
+    pub edges: Vec<PeerEdge>,
+}
+
+/// Build the constraint graph of the board.
+#[must_use]
+pub fn constraint_graph(board: &Board) -> ConstraintGraph {
+    let tracker = RemainingTracker::new(board);
+    let remaining = match deductive::reduce(tracker.clone(), &Default::default(), Vec::new()).0 {
+        Some(reduced) => reduced.remaining(),
+        None => tracker.remaining(),
+    };
+    let nodes = Coord::all()
+        .map(|pos| CellNode {
+            pos,
+            clue: board[pos],
+            candidates: remaining[pos].len(),
+        })
+        .collect();
+    let edges = Coord::all()
+        .flat_map(|a| {
+            a.neighbors()
+                .filter(move |&b| a < b)
+                .map(move |b| PeerEdge {
+                    a,
+                    b,
+                    houses: House::containing(a)
+                        .into_iter()
+                        .filter(|house| house.contains(b))
+                        .collect(),
+                })
+        })
+        .collect();
+    ConstraintGraph { nodes, edges }
+}
+
+impl ConstraintGraph {
+    /// Render the graph in the graphviz DOT language. Nodes are named by cell,
+    /// as in `r0c0`, and labeled with their clue or candidate count. Edges
+    /// are labeled with the kinds of house they share.
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("graph constraints {\n");
+        for node in &self.nodes {
+            let label = match node.clue {
+                Some(val) => val.val().to_string(),
+                None => format!("{} left", node.candidates),
+            };
+            writeln!(out, "  {} [label=\"{}\"];", node_id(node.pos), label).unwrap();
+        }
+        for edge in &self.edges {
+            let kinds: Vec<_> = edge.houses.iter().map(|&house| house_kind(house)).collect();
+            writeln!(
+                out,
+                "  {} -- {} [label=\"{}\"];",
+                node_id(edge.a),
+                node_id(edge.b),
+                kinds.join(",")
+            )
+            .unwrap();
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Render the graph as json in the adjacency format networkx reads with
+    /// `adjacency_graph`.
+    #[cfg(feature = "serde")]
+    #[must_use]
+    pub fn to_adjacency_json(&self) -> String {
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|node| {
+                serde_json::json!({
+                    "id": node_id(node.pos),
+                    "clue": node.clue,
+                    "candidates": node.candidates,
+                })
+            })
+            .collect();
+        let adjacency = self
+            .nodes
+            .iter()
+            .map(|node| {
+                self.edges
+                    .iter()
+                    .filter_map(|edge| {
+                        let other = other_end(node.pos, edge.a, edge.b)?;
+                        let houses: Vec<_> = edge.houses.iter().map(|&h| house_kind(h)).collect();
+                        Some(serde_json::json!({ "id": node_id(other), "houses": houses }))
+                    })
+                    .collect()
+            })
+            .collect();
+        adjacency_json(false, nodes, adjacency)
+    }
+}
+
+/// Links between the candidates for one value, strong where two cells are the
+/// only ones left for the value in a house and weak otherwise.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ImplicationGraph {
+    /// The value the graph is for.
+    pub value: Val,
+    /// Cells which allow the value, in row-major order.
+    pub nodes: Vec<Coord>,
+    /// The links, as found by `techniques::find_links`.
+    pub links: Vec<Link>,
+}
+
+/// Build the implication graph for the value in the candidate state.
+#[must_use]
+pub fn implication_graph(state: &Remaining, value: Val) -> ImplicationGraph {
+    ImplicationGraph {
+        value,
+        nodes: Coord::all()
+            .filter(|&pos| state[pos].contains(value))
+            .collect(),
+        links: techniques::find_links(state, value),
+    }
+}
+
+impl ImplicationGraph {
+    /// Render the graph in the graphviz DOT language. Strong links are solid
+    /// and weak links are dashed, and each is labeled with its house.
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        let mut out = format!("graph implications_{} {{\n", self.value.val());
+        for &pos in &self.nodes {
+            writeln!(out, "  {};", node_id(pos)).unwrap();
+        }
+        for link in &self.links {
+            let style = match link.strength {
+                LinkStrength::Strong => "solid",
+                LinkStrength::Weak => "dashed",
+            };
+            writeln!(
+                out,
+                "  {} -- {} [label=\"{}\", style={}];",
+                node_id(link.a),
+                node_id(link.b),
+                house_kind(link.house),
+                style
+            )
+            .unwrap();
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Render the graph as json in the adjacency format networkx reads with
+    /// `adjacency_graph(data, multigraph=True)`, since cells can be linked in
+    /// more than one house.
+    #[cfg(feature = "serde")]
+    #[must_use]
+    pub fn to_adjacency_json(&self) -> String {
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|&pos| serde_json::json!({ "id": node_id(pos) }))
+            .collect();
+        let adjacency = self
+            .nodes
+            .iter()
+            .map(|&pos| {
+                self.links
+                    .iter()
+                    .filter_map(|link| {
+                        let other = other_end(pos, link.a, link.b)?;
+                        Some(serde_json::json!({
+                            "id": node_id(other),
+                            "house": house_kind(link.house),
+                            "strength": link.strength,
+                        }))
+                    })
+                    .collect()
+            })
+            .collect();
+        adjacency_json(true, nodes, adjacency)
+    }
+}
+
+/// Name of the node for a cell in exported graphs.
+fn node_id(pos: Coord) -> String {
+    format!("r{}c{}", pos.row().inner(), pos.col().inner())
+}
+
+/// Short name of the kind of house.
+fn house_kind(house: House) -> &'static str {
+    match house {
+        House::Row(_) => "row",
+        House::Col(_) => "col",
+        House::Sector(_) => "sector",
+    }
+}
+
+/// The other end of the edge from `pos`, or None if the edge doesn't touch
+/// `pos`.
+#[cfg(feature = "serde")]
+fn other_end(pos: Coord, a: Coord, b: Coord) -> Option<Coord> {
+    if pos == a {
+        Some(b)
+    } else if pos == b {
+        Some(a)
+    } else {
+        None
+    }
+}
+
+/// Assemble a networkx adjacency document from the nodes and the neighbor
+/// list of each node, in the same order.
+#[cfg(feature = "serde")]
+fn adjacency_json(
+    multigraph: bool,
+    nodes: Vec<serde_json::Value>,
+    adjacency: Vec<Vec<serde_json::Value>>,
+) -> String {
+    serde_json::json!({
+        "directed": false,
+        "multigraph": multigraph,
+        "graph": {},
+        "nodes": nodes,
+        "adjacency": adjacency,
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::indexed::IndexMap;
+    use crate::fixtures::puzzle1;
+    use crate::{AvailSet, Col, Row};
+
+    /// Count the node and edge statements of DOT output written by this module.
+    fn dot_counts(dot: &str) -> (usize, usize) {
+        let mut lines = dot.lines();
+        assert!(lines.next().unwrap().ends_with('{'));
+        assert_eq!(lines.next_back(), Some("}"));
+        let (edges, nodes): (Vec<_>, Vec<_>) = lines.partition(|line| line.contains(" -- "));
+        assert!(nodes
+            .iter()
+            .chain(&edges)
+            .all(|line| line.starts_with("  r") && line.ends_with(';')));
+        (nodes.len(), edges.len())
+    }
+
+    #[test]
+    fn constraint_graph_shape() {
+        crate::setup();
+
+        let board = puzzle1();
+        let graph = constraint_graph(&board);
+        assert_eq!(graph.nodes.len(), 81);
+        assert_eq!(graph.edges.len(), 810);
+        assert!(graph.edges.iter().all(|edge| edge.a < edge.b));
+        // Peers share a row or column, and possibly a sector too.
+        let shared: Vec<_> = (1..=2)
+            .map(|n| graph.edges.iter().filter(|e| e.houses.len() == n).count())
+            .collect();
+        assert_eq!(shared, [81 * 16 / 2, 81 * 4 / 2]);
+
+        let given = Coord::new(Row::new(0), Col::new(3));
+        assert_eq!(graph.nodes[3].pos, given);
+        assert_eq!(graph.nodes[3].clue, Some(Val::new(1)));
+        // Puzzle 1 is solved by reduction alone.
+        assert!(graph.nodes.iter().all(|node| node.candidates == 1));
+
+        assert_eq!(dot_counts(&graph.to_dot()), (81, 810));
+        assert!(graph.to_dot().contains("  r0c3 [label=\"1\"];"));
+        assert!(graph
+            .to_dot()
+            .contains("  r0c0 -- r0c1 [label=\"row,sector\"];"));
+
+        let empty = constraint_graph(&Board::new());
+        assert!(empty.nodes.iter().all(|node| node.candidates == 9));
+        assert_eq!(empty.edges, graph.edges);
+    }
+
+    #[test]
+    fn implication_graph_links() {
+        let five = Val::new(5);
+        let pos = |row, col| Coord::new(Row::new(row), Col::new(col));
+        let mut state: Remaining = IndexMap::with_value(AvailSet::all()).into();
+        for cell in Coord::all() {
+            state[cell] -= five;
+        }
+        // Row 2 has 5 only at columns 1 and 7, and column 1 only at rows 2 and
+        // 6. Sector 6 has it in three cells.
+        let cells = [pos(2, 1), pos(2, 7), pos(6, 1), pos(7, 2), pos(8, 0)];
+        for cell in cells {
+            state[cell] |= five;
+        }
+        let graph = implication_graph(&state, five);
+        assert_eq!(graph.nodes, cells);
+        let strong: Vec<_> = graph
+            .links
+            .iter()
+            .filter(|link| link.strength == LinkStrength::Strong)
+            .map(|link| (link.house, link.a, link.b))
+            .collect();
+        assert_eq!(
+            strong,
+            [
+                (House::Row(Row::new(2)), pos(2, 1), pos(2, 7)),
+                (House::Col(Col::new(1)), pos(2, 1), pos(6, 1)),
+            ]
+        );
+        let weak: Vec<_> = graph
+            .links
+            .iter()
+            .filter(|link| link.strength == LinkStrength::Weak)
+            .map(|link| (link.a, link.b))
+            .collect();
+        assert_eq!(
+            weak,
+            [
+                (pos(6, 1), pos(7, 2)),
+                (pos(6, 1), pos(8, 0)),
+                (pos(7, 2), pos(8, 0)),
+            ]
+        );
+        assert_eq!(graph.links, techniques::find_links(&state, five));
+
+        let dot = graph.to_dot();
+        assert_eq!(dot_counts(&dot), (5, 5));
+        assert!(dot.contains("  r2c1 -- r2c7 [label=\"row\", style=solid];"));
+        assert!(dot.contains("  r6c1 -- r7c2 [label=\"sector\", style=dashed];"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn adjacency_json() {
+        crate::setup();
+
+        let graph = constraint_graph(&puzzle1());
+        let json: serde_json::Value = serde_json::from_str(&graph.to_adjacency_json()).unwrap();
+        assert_eq!(json["multigraph"], false);
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 81);
+        assert_eq!(json["nodes"][3]["clue"], 1);
+        let adjacency = json["adjacency"].as_array().unwrap();
+        assert_eq!(adjacency.len(), 81);
+        assert!(adjacency
+            .iter()
+            .all(|neighbors| neighbors.as_array().unwrap().len() == 20));
+        assert_eq!(adjacency[0][0]["id"], "r0c1");
+        assert_eq!(
+            adjacency[0][0]["houses"],
+            serde_json::json!(["row", "sector"])
+        );
+
+        let state = crate::solve::remaining::RemainingTracker::new(&Board::new()).remaining();
+        let graph = implication_graph(&state, Val::new(1));
+        let json: serde_json::Value = serde_json::from_str(&graph.to_adjacency_json()).unwrap();
+        assert_eq!(json["multigraph"], true);
+        // Each cell is linked to its 20 peers, and twice to the 4 peers which
+        // share both a line and its sector.
+        assert_eq!(json["adjacency"][0].as_array().unwrap().len(), 24);
+        assert_eq!(json["adjacency"][0][0]["strength"], "weak");
+    }
+}
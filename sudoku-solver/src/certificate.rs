@@ -0,0 +1,701 @@
+//! Evidence for solver verdicts which can be checked without trusting the
+//! solver.
+//!
+//! A certificate proves a board has no solution as a list of eliminations, each
+//! of which must follow from the candidates left at the time by one of the
+//! basic techniques: naked and hidden singles, a sector-row or sector-col with
+//! three values left, pointing and claiming, hidden pairs and triples, and
+//! X-Wings. The proof ends in a contradiction, or in a guess where every value
+//! for the guessed cell is refuted in turn. The checker here shares no code
+//! with the deduction engine, so a bug in the engine cannot make a false proof
+//! pass.
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::trace::{DeductionReason, TraceTree, UnsolveableReason};
+use crate::{
+    AvailSet, Board, Col, Coord, Exclusions, House, Row, Sector, SectorCol, SectorRow, Val, Zone,
+};
+
+/// Candidates of every cell, in row-major order.
+type Cands = [AvailSet; Board::SIZE];
+
+/// Proof that a board has no solution.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UnsolveableCertificate {
+    /// Values excluded from cells in addition to the board's own rules, which
+    /// the proof is allowed to rely on.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Exclusions::is_empty")
+    )]
+    pub exclusions: Exclusions,
+    /// The proof, starting from every value allowed in every empty cell.
+    pub proof: Proof,
+}
+
+/// A proof that the candidates it starts from have no solution.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(tag = "type"),
+    serde(rename_all = "snake_case")
+)]
+pub enum Proof {
+    /// Eliminations which lead to the given contradiction.
+    Refutation {
+        steps: Vec<CertificateStep>,
+        contradiction: UnsolveableReason,
+    },
+    /// Eliminations, then a guess at the given cell, where every value left in
+    /// the cell is refuted by its own branch.
+    Split {
+        steps: Vec<CertificateStep>,
+        pos: Coord,
+        branches: Vec<Branch>,
+    },
+}
+
+/// One value tried at the cell guessed by a `Proof::Split`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Branch {
+    /// The value tried.
+    pub val: Val,
+    /// Proof that the value leads to a contradiction.
+    pub proof: Proof,
+}
+
+/// Candidates eliminated together, each following from the candidates left
+/// before the step.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CertificateStep {
+    /// The deduction the solver made, for people reading the proof. None for
+    /// the eliminations made by the step which found the contradiction. Not
+    /// trusted by the checker.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub reason: Option<DeductionReason>,
+    /// The candidates eliminated, in row-major order.
+    pub eliminations: Vec<(Coord, Val)>,
+}
+
+/// Check that the solution solves the board: every cell is filled, every given
+/// is kept, no cell holds a value the exclusions forbid, and no row, column, or
+/// sector repeats a value.
+#[must_use]
+pub fn verify_solution(board: &Board, solution: &Board, exclusions: &Exclusions) -> bool {
+    let keeps_givens = Coord::all().all(|coord| match (board[coord], solution[coord]) {
+        (_, None) => false,
+        (Some(given), Some(val)) => given == val && !exclusions.is_excluded(coord, val),
+        (None, Some(val)) => !exclusions.is_excluded(coord, val),
+    });
+    keeps_givens
+        && House::all().all(|house| {
+            let vals = house
+                .coords()
+                .filter_map(|coord| solution[coord])
+                .fold(AvailSet::none(), |vals, val| vals | val);
+            vals == AvailSet::all()
+        })
+}
+
+/// Check that the certificate proves the board has no solution.
+#[must_use]
+pub fn verify_certificate(board: &Board, certificate: &UnsolveableCertificate) -> bool {
+    let mut start = [AvailSet::all(); Board::SIZE];
+    for coord in Coord::all() {
+        if let Some(val) = board[coord] {
+            start[coord.rowmajor_idx()] = AvailSet::only(val);
+        }
+        start[coord.rowmajor_idx()] -= certificate.exclusions.excluded(coord);
+    }
+    verify_proof(start, &certificate.proof)
+}
+
+/// Check the proof starting from the given candidates.
+fn verify_proof(mut state: Cands, proof: &Proof) -> bool {
+    let steps = match proof {
+        Proof::Refutation { steps, .. } | Proof::Split { steps, .. } => steps,
+    };
+    for step in steps {
+        let before = state;
+        for &(coord, val) in &step.eliminations {
+            let cell = &mut state[coord.rowmajor_idx()];
+            if !cell.remove(val) || justify(&before, coord, val).is_none() {
+                return false;
+            }
+        }
+    }
+    match proof {
+        Proof::Refutation { contradiction, .. } => {
+            contradiction_deps(&state, contradiction).is_some()
+        }
+        Proof::Split { pos, branches, .. } => {
+            let avail = state[pos.rowmajor_idx()];
+            let tried = branches
+                .iter()
+                .fold(AvailSet::none(), |tried, branch| tried | branch.val);
+            tried == avail
+                && branches.len() == avail.len()
+                && branches.iter().all(|branch| {
+                    let mut guessed = state;
+                    guessed[pos.rowmajor_idx()] = AvailSet::only(branch.val);
+                    verify_proof(guessed, &branch.proof)
+                })
+        }
+    }
+}
+
+/// A row or column segment within a sector.
+#[derive(Copy, Clone)]
+struct Segment {
+    cells: [Coord; 3],
+    line: House,
+    sector: Sector,
+}
+
+fn segments() -> impl Iterator<Item = Segment> {
+    let rows = SectorRow::all().map(|secrow| Segment {
+        cells: to_cells(secrow.coords()),
+        line: House::Row(secrow.row()),
+        sector: secrow.sector(),
+    });
+    let cols = SectorCol::all().map(|seccol| Segment {
+        cells: to_cells(seccol.coords()),
+        line: House::Col(seccol.col()),
+        sector: seccol.sector(),
+    });
+    rows.chain(cols)
+}
+
+fn to_cells(coords: impl Iterator<Item = Coord>) -> [Coord; 3] {
+    let coords: Vec<_> = coords.collect();
+    [coords[0], coords[1], coords[2]]
+}
+
+fn has(state: &Cands, coord: Coord, val: Val) -> bool {
+    state[coord.rowmajor_idx()].contains(val)
+}
+
+/// Every `(coord, val)` in the zone, for the given values, which is already
+/// eliminated.
+fn absent(
+    state: &Cands,
+    cells: impl IntoIterator<Item = Coord>,
+    vals: AvailSet,
+) -> Vec<(Coord, Val)> {
+    cells
+        .into_iter()
+        .flat_map(|coord| vals.iter().map(move |val| (coord, val)))
+        .filter(|&(coord, val)| !has(state, coord, val))
+        .collect()
+}
+
+/// Find a basic technique which shows the value cannot go in the cell, given
+/// the candidates. Returns the eliminated candidates the technique relies on,
+/// or None if no technique applies.
+fn justify(state: &Cands, coord: Coord, val: Val) -> Option<Vec<(Coord, Val)>> {
+    // Naked single: a peer can only hold the value.
+    if let Some(peer) = coord
+        .neighbors()
+        .find(|&peer| state[peer.rowmajor_idx()] == AvailSet::only(val))
+    {
+        return Some(absent(state, [peer], AvailSet::all()));
+    }
+    // Hidden single: some other value has only this cell left in a house.
+    for house in House::containing(coord) {
+        for other in state[coord.rowmajor_idx()] - val {
+            let elsewhere: Vec<_> = house.coords().filter(|&c| c != coord).collect();
+            if elsewhere.iter().all(|&c| !has(state, c, other)) {
+                return Some(absent(state, elsewhere, AvailSet::only(other)));
+            }
+        }
+    }
+    for seg in segments() {
+        if seg.cells.contains(&coord) {
+            continue;
+        }
+        let in_line = seg.line.contains(coord);
+        let in_sector = seg.sector.contains(coord);
+        if !in_line && !in_sector {
+            continue;
+        }
+        // Three cells of a segment with only three values between them.
+        let vals = seg
+            .cells
+            .iter()
+            .fold(AvailSet::none(), |vals, &c| vals | state[c.rowmajor_idx()]);
+        if vals.len() == 3 && vals.contains(val) {
+            return Some(absent(state, seg.cells, !vals));
+        }
+        // Pointing: the value is only in this segment within its sector, so it
+        // is in the segment, and so not elsewhere in the line.
+        if in_line {
+            let rest: Vec<_> = seg
+                .sector
+                .coords()
+                .filter(|c| !seg.cells.contains(c))
+                .collect();
+            if rest.iter().all(|&c| !has(state, c, val)) {
+                return Some(absent(state, rest, AvailSet::only(val)));
+            }
+        }
+        // Claiming: the same the other way around.
+        if in_sector {
+            let rest: Vec<_> = seg
+                .line
+                .coords()
+                .filter(|c| !seg.cells.contains(c))
+                .collect();
+            if rest.iter().all(|&c| !has(state, c, val)) {
+                return Some(absent(state, rest, AvailSet::only(val)));
+            }
+        }
+    }
+    // Hidden pair or triple: two or three other values of a house are confined
+    // to as many cells, this one among them, so this cell holds one of them.
+    let others: Vec<Val> = (AvailSet::all() - val).into_iter().collect();
+    for house in House::containing(coord) {
+        for chosen in 0u16..1 << others.len() {
+            let size = chosen.count_ones() as usize;
+            if size != 2 && size != 3 {
+                continue;
+            }
+            let vals = others
+                .iter()
+                .enumerate()
+                .filter(|&(idx, _)| chosen & 1 << idx != 0)
+                .fold(AvailSet::none(), |vals, (_, &other)| vals | other);
+            let (inside, outside): (Vec<_>, Vec<_>) = house
+                .coords()
+                .partition(|&c| state[c.rowmajor_idx()].intersects(vals));
+            if inside.len() == size && inside.contains(&coord) {
+                return Some(absent(state, outside, vals));
+            }
+        }
+    }
+    // X-Wing: in two other rows the value is confined to this cell's column
+    // and one other, so those two columns hold it in those rows. The same
+    // goes with rows and columns swapped.
+    for by_rows in [true, false] {
+        let line = |idx: usize| {
+            if by_rows {
+                House::Row(Row::new(idx as u8))
+            } else {
+                House::Col(Col::new(idx as u8))
+            }
+        };
+        let across = |c: Coord| {
+            if by_rows {
+                c.col().inner() as usize
+            } else {
+                c.row().inner() as usize
+            }
+        };
+        let own = if by_rows {
+            coord.row().inner() as usize
+        } else {
+            coord.col().inner() as usize
+        };
+        // Positions across each other line which still allow the value.
+        let places: Vec<(usize, u16)> = (0..9)
+            .filter(|&idx| idx != own)
+            .map(|idx| {
+                let mask = line(idx)
+                    .coords()
+                    .filter(|&c| has(state, c, val))
+                    .fold(0u16, |mask, c| mask | 1 << across(c));
+                (idx, mask)
+            })
+            .collect();
+        for (i, &(first, first_mask)) in places.iter().enumerate() {
+            for &(second, second_mask) in &places[i + 1..] {
+                let cover = first_mask | second_mask;
+                if first_mask != 0
+                    && second_mask != 0
+                    && cover.count_ones() == 2
+                    && cover & 1 << across(coord) != 0
+                {
+                    let rest = line(first)
+                        .coords()
+                        .chain(line(second).coords())
+                        .filter(|&c| cover & 1 << across(c) == 0);
+                    return Some(absent(state, rest, AvailSet::only(val)));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Check that the candidates show the given contradiction. Returns the
+/// eliminated candidates the contradiction relies on, or None if it does not
+/// hold.
+fn contradiction_deps(state: &Cands, reason: &UnsolveableReason) -> Option<Vec<(Coord, Val)>> {
+    let must_share = |house: House, vals: AvailSet| {
+        let cells = house
+            .coords()
+            .filter(|&c| state[c.rowmajor_idx()].intersects(vals))
+            .count();
+        (vals.len() >= 2 && cells <= 1).then(|| absent(state, house.coords(), vals))
+    };
+    let missing = |house: House, val: Val| {
+        let vals = AvailSet::only(val);
+        house
+            .coords()
+            .all(|c| !has(state, c, val))
+            .then(|| absent(state, house.coords(), vals))
+    };
+    let too_few = |cells: Vec<Coord>| {
+        let vals = cells
+            .iter()
+            .fold(AvailSet::none(), |vals, &c| vals | state[c.rowmajor_idx()]);
+        (vals.len() < cells.len()).then(|| absent(state, cells, !vals))
+    };
+    match *reason {
+        UnsolveableReason::Empty { pos } => state[pos.rowmajor_idx()]
+            .is_empty()
+            .then(|| absent(state, [pos], AvailSet::all())),
+        UnsolveableReason::RowValsMustShare { pos, vals } => must_share(House::Row(pos), vals),
+        UnsolveableReason::ColValsMustShare { pos, vals } => must_share(House::Col(pos), vals),
+        UnsolveableReason::SecValsMustShare { pos, vals } => must_share(House::Sector(pos), vals),
+        UnsolveableReason::RowMissingVal { pos, val } => missing(House::Row(pos), val),
+        UnsolveableReason::ColMissingVal { pos, val } => missing(House::Col(pos), val),
+        UnsolveableReason::SecMissingVal { pos, val } => missing(House::Sector(pos), val),
+        UnsolveableReason::SecRowTooFewVals { pos } => too_few(pos.coords().collect()),
+        UnsolveableReason::SecColTooFewVals { pos } => too_few(pos.coords().collect()),
+    }
+}
+
+/// A contradiction already visible in the candidates, without eliminating
+/// anything: an empty cell, or a house missing a value.
+fn initial_contradiction(state: &Cands) -> Option<UnsolveableReason> {
+    if let Some(pos) = Coord::all().find(|c| state[c.rowmajor_idx()].is_empty()) {
+        return Some(UnsolveableReason::Empty { pos });
+    }
+    House::all().find_map(|house| {
+        let val = AvailSet::all()
+            .into_iter()
+            .find(|&val| house.coords().all(|c| !has(state, c, val)))?;
+        Some(match house {
+            House::Row(pos) => UnsolveableReason::RowMissingVal { pos, val },
+            House::Col(pos) => UnsolveableReason::ColMissingVal { pos, val },
+            House::Sector(pos) => UnsolveableReason::SecMissingVal { pos, val },
+        })
+    })
+}
+
+/// Build a certificate for the given starting candidates, from the trace of
+/// a search which found no solution. `search` is only run if the candidates
+/// are not already contradictory, and returns None if it found a solution.
+/// Each proof keeps only the eliminations its contradiction relies on.
+/// Returns None if there is a solution, or if an elimination in the trace
+/// does not follow from a basic technique.
+pub(crate) fn certify(
+    start: &[AvailSet],
+    exclusions: &Exclusions,
+    search: impl FnOnce() -> Option<TraceTree>,
+) -> Option<UnsolveableCertificate> {
+    let start: Cands = start.try_into().ok()?;
+    let proof = match initial_contradiction(&start) {
+        Some(contradiction) => Proof::Refutation {
+            steps: Vec::new(),
+            contradiction,
+        },
+        None => prune(&search()?)?.0,
+    };
+    Some(UnsolveableCertificate {
+        exclusions: exclusions.clone(),
+        proof,
+    })
+}
+
+/// Build the proof for a node of the trace, along with the candidates which
+/// must already be eliminated when the node starts.
+fn prune(node: &TraceTree) -> Option<(Proof, Vec<(Coord, Val)>)> {
+    let deduction = match node {
+        TraceTree::Solution { .. } => return None,
+        TraceTree::Unsolveable { deduction } | TraceTree::Guess { deduction, .. } => deduction,
+    };
+    let states: Vec<Cands> = deduction
+        .iter()
+        .map(|d| d.remaining.as_ref().try_into().ok())
+        .collect::<Option<_>>()?;
+    let end = *states.last()?;
+    let (mut needed, split) = match node {
+        TraceTree::Unsolveable { .. } => match &deduction.last()?.reason {
+            DeductionReason::Unsolveable(contradiction) => (
+                contradiction_deps(&end, contradiction)?,
+                Err(contradiction.clone()),
+            ),
+            _ => return None,
+        },
+        TraceTree::Guess { guesses, .. } => {
+            let (pos, branches, needed) = prune_split(&end, guesses)?;
+            (needed, Ok((pos, branches)))
+        }
+        TraceTree::Solution { .. } => unreachable!(),
+    };
+    // Walk back from the end, keeping eliminations something later relies on,
+    // and adding what each kept elimination relies on in turn.
+    let mut steps = Vec::new();
+    for (i, d) in deduction.iter().enumerate().skip(1).rev() {
+        let (before, after) = (&states[i - 1], &states[i]);
+        let mut eliminations = Vec::new();
+        for coord in Coord::all() {
+            let gone = before[coord.rowmajor_idx()] - after[coord.rowmajor_idx()];
+            for val in gone {
+                if let Some(idx) = needed.iter().position(|&n| n == (coord, val)) {
+                    needed.swap_remove(idx);
+                    eliminations.push((coord, val));
+                }
+            }
+        }
+        for &(coord, val) in &eliminations {
+            for dep in justify(before, coord, val)? {
+                if !needed.contains(&dep) {
+                    needed.push(dep);
+                }
+            }
+        }
+        if !eliminations.is_empty() {
+            let reason = match d.reason {
+                DeductionReason::Unsolveable(_) | DeductionReason::InitialState => None,
+                ref reason => Some(reason.clone()),
+            };
+            steps.push(CertificateStep {
+                reason,
+                eliminations,
+            });
+        }
+    }
+    steps.reverse();
+    let proof = match split {
+        Err(contradiction) => Proof::Refutation {
+            steps,
+            contradiction,
+        },
+        Ok((pos, branches)) => Proof::Split {
+            steps,
+            pos,
+            branches,
+        },
+    };
+    Some((proof, needed))
+}
+
+/// The guessed cell, the branches for its values, and the candidates which
+/// must be eliminated before the guess.
+type PrunedSplit = (Coord, Vec<Branch>, Vec<(Coord, Val)>);
+
+/// Build the branches of a guess made from the given candidates.
+fn prune_split(end: &Cands, guesses: &[TraceTree]) -> Option<PrunedSplit> {
+    let mut pos = None;
+    let mut branches = Vec::new();
+    let mut needed = Vec::new();
+    for guess in guesses {
+        let first = match guess {
+            TraceTree::Solution { deduction }
+            | TraceTree::Unsolveable { deduction }
+            | TraceTree::Guess { deduction, .. } => deduction.first()?,
+        };
+        let start = first.remaining.as_ref();
+        let guessed = Coord::all().find(|c| start[c.rowmajor_idx()] != end[c.rowmajor_idx()])?;
+        if *pos.get_or_insert(guessed) != guessed {
+            return None;
+        }
+        let val = start[guessed.rowmajor_idx()].get_single()?;
+        let (proof, branch_needed) = prune(guess)?;
+        // The guess itself eliminates everything else from the cell.
+        needed.extend(
+            branch_needed
+                .into_iter()
+                .filter(|&(coord, _)| coord != guessed),
+        );
+        branches.push(Branch { val, proof });
+    }
+    let pos = pos?;
+    // Values already gone from the guessed cell need no branch, as long as
+    // they stay gone.
+    needed.extend(absent(end, [pos], AvailSet::all()));
+    needed.sort_unstable();
+    needed.dedup();
+    Some((pos, branches, needed))
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{contradictory, inkala};
+    use crate::{Col, Row, SolveOutcome, Solver};
+
+    fn high_assurance() -> Solver {
+        Solver {
+            high_assurance: true,
+            ..Solver::new()
+        }
+    }
+
+    fn certificate(board: &Board) -> UnsolveableCertificate {
+        match high_assurance().run(board) {
+            SolveOutcome::Unsolveable(unsolveable) => unsolveable.certificate.unwrap(),
+            outcome => panic!("expected no solution, got {:?}", outcome),
+        }
+    }
+
+    /// Inkala's puzzle with an extra given that breaks it, which takes guessing
+    /// to find out.
+    fn bad_inkala() -> Board {
+        let mut board = inkala();
+        board[Coord::new(Row::new(0), Col::new(1))] = Some(Val::new(2));
+        board
+    }
+
+    #[test]
+    fn deduction_certificate() {
+        crate::setup();
+
+        let board = contradictory();
+        let certificate = certificate(&board);
+        assert!(verify_certificate(&board, &certificate));
+        match &certificate.proof {
+            Proof::Refutation { steps, .. } => {
+                let (_, trace) = board.solve_with(Vec::new());
+                let kept: usize = steps.iter().map(|step| step.eliminations.len()).sum();
+                let total: usize = trace.iter().map(|d| d.eliminated_count).sum();
+                assert!(kept > 0);
+                assert!(kept < total, "kept {} of {}", kept, total);
+            }
+            proof => panic!("expected a refutation, got {:?}", proof),
+        }
+    }
+
+    #[test]
+    fn search_certificate() {
+        crate::setup();
+
+        let board = bad_inkala();
+        let certificate = certificate(&board);
+        assert!(matches!(certificate.proof, Proof::Split { .. }));
+        assert!(verify_certificate(&board, &certificate));
+        // A proof for one board says nothing about another.
+        let mut inkala = board.clone();
+        inkala[Coord::new(Row::new(0), Col::new(1))] = None;
+        assert!(!verify_certificate(&inkala, &certificate));
+    }
+
+    #[test]
+    fn invalid_givens_certificates() {
+        crate::setup();
+
+        let mut repeated = Board::new();
+        repeated[Coord::new(Row::new(0), Col::new(0))] = Some(Val::new(5));
+        repeated[Coord::new(Row::new(0), Col::new(8))] = Some(Val::new(5));
+        assert!(verify_certificate(&repeated, &certificate(&repeated)));
+
+        // Excluding a given empties its cell before any deduction.
+        let mut exclusions = Exclusions::new();
+        let corner = Coord::new(Row::new(0), Col::new(0));
+        exclusions.exclude(corner, AvailSet::only(Val::new(8)));
+        let solver = Solver {
+            exclusions,
+            ..high_assurance()
+        };
+        let board = bad_inkala();
+        let certificate = match solver.run(&board) {
+            SolveOutcome::Unsolveable(unsolveable) => unsolveable.certificate.unwrap(),
+            outcome => panic!("expected no solution, got {:?}", outcome),
+        };
+        assert_eq!(
+            certificate.proof,
+            Proof::Refutation {
+                steps: Vec::new(),
+                contradiction: UnsolveableReason::Empty { pos: corner },
+            }
+        );
+        assert!(verify_certificate(&board, &certificate));
+        // The exclusion is part of what the certificate proves.
+        let without = UnsolveableCertificate {
+            exclusions: Exclusions::new(),
+            ..certificate
+        };
+        assert!(!verify_certificate(&board, &without));
+    }
+
+    #[test]
+    fn tampered_certificate() {
+        crate::setup();
+
+        for board in [contradictory(), bad_inkala()] {
+            let certificate = certificate(&board);
+
+            // Claim a different value was eliminated by the first step.
+            let mut changed = certificate.clone();
+            let steps = match &mut changed.proof {
+                Proof::Refutation { steps, .. } | Proof::Split { steps, .. } => steps,
+            };
+            let (coord, val) = steps[0].eliminations[0];
+            let other = Val::new(val.val() % 9 + 1);
+            steps[0].eliminations[0] = (coord, other);
+            assert!(!verify_certificate(&board, &changed));
+
+            // Leave out the step the rest relies on.
+            let mut skipped = certificate.clone();
+            match &mut skipped.proof {
+                Proof::Refutation { steps, .. } | Proof::Split { steps, .. } => steps.remove(0),
+            };
+            assert!(!verify_certificate(&board, &skipped));
+        }
+
+        // Claim a different contradiction.
+        let mut wrong = certificate(&contradictory());
+        if let Proof::Refutation { contradiction, .. } = &mut wrong.proof {
+            *contradiction = UnsolveableReason::Empty {
+                pos: Coord::new(Row::new(8), Col::new(8)),
+            };
+        }
+        assert!(!verify_certificate(&contradictory(), &wrong));
+
+        // Drop a branch of a guess.
+        let mut pruned = certificate(&bad_inkala());
+        if let Proof::Split { branches, .. } = &mut pruned.proof {
+            branches.pop();
+        }
+        assert!(!verify_certificate(&bad_inkala(), &pruned));
+    }
+
+    #[test]
+    fn verified_solutions() {
+        crate::setup();
+
+        let mut inkala = bad_inkala();
+        inkala[Coord::new(Row::new(0), Col::new(1))] = None;
+        let solution = match high_assurance().run(&inkala) {
+            SolveOutcome::Solved(solution) => solution,
+            outcome => panic!("expected a solution, got {:?}", outcome),
+        };
+        let none = Exclusions::new();
+        assert!(verify_solution(&inkala, &solution, &none));
+        assert!(!verify_solution(&bad_inkala(), &solution, &none));
+        let mut partial = solution.clone();
+        partial[Coord::new(Row::new(4), Col::new(4))] = None;
+        assert!(!verify_solution(&inkala, &partial, &none));
+        let mut swapped = solution.clone();
+        swapped.row_major_mut().swap(0, 1);
+        assert!(!verify_solution(&Board::new(), &swapped, &none));
+        // The solution must also keep to the exclusions, givens included.
+        for coord in [
+            Coord::new(Row::new(0), Col::new(0)),
+            Coord::new(Row::new(0), Col::new(1)),
+        ] {
+            let mut exclusions = Exclusions::new();
+            exclusions.exclude(coord, AvailSet::only(solution[coord].unwrap()));
+            assert!(!verify_solution(&inkala, &solution, &exclusions));
+        }
+    }
+}
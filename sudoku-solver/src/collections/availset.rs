@@ -15,35 +15,41 @@ pub struct AvailSet(u16);
 impl AvailSet {
     /// Create a new AvailSet with all values available.
     #[inline]
+    #[must_use]
     pub const fn all() -> Self {
         AvailSet(0x1ff)
     }
 
     /// Create an AvailSet with no values available.
     #[inline]
+    #[must_use]
     pub const fn none() -> Self {
         AvailSet(0)
     }
 
     /// Create an AvailSet containing only the given value.
     #[inline]
+    #[must_use]
     pub fn only(val: Val) -> Self {
         AvailSet(AvailSet::to_mask(val))
     }
 
     /// Returns true if there are no more values available.
     #[inline]
+    #[must_use]
     pub fn is_empty(&self) -> bool {
         self.0 == 0
     }
 
     /// Returns true if this set contains a single element.
     #[inline]
+    #[must_use]
     pub fn is_single(&self) -> bool {
         self.len() == 1
     }
 
     /// If there is only a single entry, returns that entry.
+    #[must_use]
     pub fn get_single(&self) -> Option<Val> {
         if self.is_single() {
             let v = (self.0.trailing_zeros() + 1) as u8;
@@ -70,21 +76,68 @@ impl AvailSet {
     }
 
     /// Returns true if the set contains the given value.
+    #[must_use]
     pub fn contains(&self, val: Val) -> bool {
         self.0 & Self::to_mask(val) != 0
     }
 
     /// Return true if these two AvailSets overlap for at least one value.
+    #[must_use]
     pub fn intersects(&self, other: Self) -> bool {
         !(*self & other).is_empty()
     }
 
     /// Counts the number of values in this set.
     #[inline]
+    #[must_use]
     pub fn len(&self) -> usize {
         self.0.count_ones() as usize
     }
 
+    /// Counts the number of values in this set which are not in the other set,
+    /// without constructing the difference.
+    #[inline]
+    #[must_use]
+    pub fn difference_len(&self, other: Self) -> usize {
+        (self.0 & !other.0).count_ones() as usize
+    }
+
+    /// Counts the number of values in both this set and the other set, without
+    /// constructing the intersection.
+    #[inline]
+    #[must_use]
+    pub fn intersection_len(&self, other: Self) -> usize {
+        (self.0 & other.0).count_ones() as usize
+    }
+
+    /// Return true if every value in this set is also in the other set.
+    #[inline]
+    #[must_use]
+    pub fn is_subset(&self, other: Self) -> bool {
+        self.0 & !other.0 == 0
+    }
+
+    /// Return true if every value in the other set is also in this set.
+    #[inline]
+    #[must_use]
+    pub fn is_superset(&self, other: Self) -> bool {
+        other.is_subset(*self)
+    }
+
+    /// Return true if these two AvailSets have no values in common.
+    #[inline]
+    #[must_use]
+    pub fn is_disjoint(&self, other: Self) -> bool {
+        self.0 & other.0 == 0
+    }
+
+    /// Get the values which are in exactly one of the two sets.
+    #[inline]
+    #[must_use]
+    pub fn symmetric_difference(&self, other: Self) -> Self {
+        AvailSet(self.0 ^ other.0)
+    }
+
     /// Remove any value that don't match the given function.
     pub fn retain(&mut self, mut f: impl FnMut(Val) -> bool) {
         for val in self.iter() {
@@ -94,6 +147,27 @@ impl AvailSet {
         }
     }
 
+    /// The set as a bit mask, where bit `i` is set if `Val::new(i + 1)` is in
+    /// the set. Only the low 9 bits are ever set.
+    #[inline]
+    #[must_use]
+    pub const fn as_bits(self) -> u16 {
+        self.0
+    }
+
+    /// Build a set from a bit mask laid out as in `as_bits`, where bit `i`
+    /// stands for `Val::new(i + 1)`. Returns None if any bit above the low 9
+    /// is set.
+    #[inline]
+    #[must_use]
+    pub const fn from_bits(bits: u16) -> Option<Self> {
+        if bits & !AvailSet::all().0 == 0 {
+            Some(AvailSet(bits))
+        } else {
+            None
+        }
+    }
+
     /// Convert a single value to a bitmask.
     fn to_mask(val: Val) -> u16 {
         1 << val.idx()
@@ -101,11 +175,20 @@ impl AvailSet {
 
     /// Iterator over values available in this set. Note that the iterator is non-borrowing,
     /// because it isn't necessary to keep a borrow for the iterator to work.
+    #[must_use]
     pub fn iter(self) -> AvailSetIter {
         self.into_iter()
     }
 }
 
+/// The empty set, so a set built up with `add` or `|=` starts from nothing.
+impl Default for AvailSet {
+    #[inline]
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
 impl fmt::Debug for AvailSet {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.write_char('[')?;
@@ -263,12 +346,7 @@ impl Iterator for AvailSetIter {
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        while let Some(val) = self.vals.next() {
-            if self.set.contains(val) {
-                return Some(val);
-            }
-        }
-        None
+        self.vals.by_ref().find(|&val| self.set.contains(val))
     }
 
     #[inline]
@@ -380,8 +458,7 @@ impl AvailCounter {
     /// Iterator over the counts of the values.
     pub(crate) fn counts(
         &self,
-    ) -> impl Iterator<Item = (Val, &u8)> + DoubleEndedIterator + ExactSizeIterator + FusedIterator
-    {
+    ) -> impl DoubleEndedIterator<Item = (Val, &u8)> + ExactSizeIterator + FusedIterator {
         self.0.iter()
     }
 
@@ -389,8 +466,7 @@ impl AvailCounter {
     #[allow(unused)]
     pub(crate) fn counts_mut(
         &mut self,
-    ) -> impl Iterator<Item = (Val, &mut u8)> + DoubleEndedIterator + ExactSizeIterator + FusedIterator
-    {
+    ) -> impl DoubleEndedIterator<Item = (Val, &mut u8)> + ExactSizeIterator + FusedIterator {
         self.0.iter_mut()
     }
 }
@@ -508,10 +584,11 @@ impl SubAssign<AvailSet> for AvailCounter {
 mod serde {
     use std::fmt;
 
-    use serde::de::{SeqAccess, Visitor};
+    use serde::de::{Error, SeqAccess, Visitor};
     use serde::ser::SerializeSeq;
     use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+    use crate::collections::indexed::FixedSizeIndex;
     use crate::Val;
 
     use super::AvailSet;
@@ -538,12 +615,19 @@ mod serde {
         type Value = AvailSet;
 
         fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
-            f.write_str("a sequence of values from 1-9")
+            f.write_str("a sequence of at most 9 values from 1-9")
         }
 
         fn visit_seq<S: SeqAccess<'de>>(self, mut seq: S) -> Result<Self::Value, S::Error> {
             let mut set = AvailSet::none();
+            let mut len = 0;
             while let Some(next) = seq.next_element::<Val>()? {
+                len += 1;
+                // A set can hold at most one copy of each value, so don't keep
+                // reading arbitrarily long sequences.
+                if len > Val::NUM_INDEXES {
+                    return Err(S::Error::invalid_length(len, &self));
+                }
                 set |= next;
             }
             Ok(set)
@@ -579,6 +663,67 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_bounded() {
+        let set: AvailSet = serde_json::from_str("[1,5,9]").unwrap();
+        assert_eq!(set, AvailSet(0b100010001));
+        let set: AvailSet = serde_json::from_str("[1,2,3,4,5,6,7,8,9]").unwrap();
+        assert_eq!(set, AvailSet::all());
+        let long = format!("[{}5]", "5,".repeat(1_000_000));
+        let res: Result<AvailSet, _> = serde_json::from_str(&long);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn availset_op_len() {
+        let sets = [
+            AvailSet::none(),
+            AvailSet::all(),
+            AvailSet(0b000_000_001),
+            AvailSet(0b100_000_000),
+            AvailSet(0b010_010_110),
+            AvailSet(0b101_101_001),
+            AvailSet(0b111_000_111),
+        ];
+        for &a in &sets {
+            for &b in &sets {
+                assert_eq!(a.difference_len(b), (a - b).len(), "{:?} - {:?}", a, b);
+                assert_eq!(a.intersection_len(b), (a & b).len(), "{:?} & {:?}", a, b);
+                assert_eq!(
+                    a.is_subset(b),
+                    a - b == AvailSet::none(),
+                    "{:?} <= {:?}",
+                    a,
+                    b
+                );
+                assert_eq!(a.is_superset(b), b.is_subset(a), "{:?} >= {:?}", a, b);
+                assert_eq!(a.is_disjoint(b), !a.intersects(b), "{:?}, {:?}", a, b);
+                assert_eq!(
+                    a.symmetric_difference(b),
+                    (a - b) | (b - a),
+                    "{:?} ^ {:?}",
+                    a,
+                    b
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn availset_bits() {
+        let set = AvailSet::none() | Val::new(1) | Val::new(4) | Val::new(9);
+        assert_eq!(set.as_bits(), 0b100_001_001);
+        assert_eq!(AvailSet::from_bits(0b100_001_001), Some(set));
+        assert_eq!(AvailSet::from_bits(0), Some(AvailSet::none()));
+        assert_eq!(AvailSet::from_bits(0x1ff), Some(AvailSet::all()));
+        assert_eq!(AvailSet::from_bits(0x200), None);
+        assert_eq!(AvailSet::from_bits(0xffff), None);
+        for val in AvailSet::all() {
+            assert_eq!(AvailSet::only(val).as_bits(), 1 << (val.val() - 1));
+        }
+    }
+
     #[test]
     fn availset_iter_size() {
         let mut iter = AvailSet(0b010_010_110).iter();
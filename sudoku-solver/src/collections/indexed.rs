@@ -71,28 +71,26 @@ where
     /// Iterator over all data with their corresponding keys.
     pub fn iter(
         &self,
-    ) -> impl Iterator<Item = (K, &V)> + ExactSizeIterator + DoubleEndedIterator + FusedIterator
-    {
+    ) -> impl ExactSizeIterator<Item = (K, &V)> + DoubleEndedIterator + FusedIterator {
         K::values().zip(self.values())
     }
 
     /// Iterator over all mut data with their corresponding keys.
     pub fn iter_mut(
         &mut self,
-    ) -> impl Iterator<Item = (K, &mut V)> + ExactSizeIterator + DoubleEndedIterator + FusedIterator
-    {
+    ) -> impl ExactSizeIterator<Item = (K, &mut V)> + DoubleEndedIterator + FusedIterator {
         K::values().zip(self.values_mut())
     }
 
     /// Iterator over just the values of the map.
     #[inline]
-    pub fn values(&self) -> std::slice::Iter<V> {
+    pub fn values(&self) -> std::slice::Iter<'_, V> {
         self.data.iter()
     }
 
     /// Mutable iterator over just the values of the map.
     #[inline]
-    pub fn values_mut(&mut self) -> std::slice::IterMut<V> {
+    pub fn values_mut(&mut self) -> std::slice::IterMut<'_, V> {
         self.data.iter_mut()
     }
 
@@ -141,14 +139,14 @@ where
 impl<K, V> AsRef<[V]> for IndexMap<K, V> {
     #[inline]
     fn as_ref(&self) -> &[V] {
-        &*self.data
+        &self.data
     }
 }
 
 impl<K, V> AsMut<[V]> for IndexMap<K, V> {
     #[inline]
     fn as_mut(&mut self) -> &mut [V] {
-        &mut *self.data
+        &mut self.data
     }
 }
 
@@ -223,6 +221,10 @@ impl<K: FixedSizeIndex, V, D: AsRef<[V]>> fmt::Debug for IncorrectSize<K, V, D>
 
 /// Enables a unique minimal index for intersection pairs of (Row, Sector) and
 /// (Col, Sector).
+///
+/// This trait cannot be named outside the crate, so every implementation is
+/// one of ours and `idx` is always less than `NUM_INDEXES`. That is what keeps
+/// indexing an `IndexMap` by key from panicking.
 pub trait FixedSizeIndex {
     /// Number of converted indexs.
     const NUM_INDEXES: usize;
@@ -241,6 +243,10 @@ pub trait FixedSizeIndex {
     fn idx(&self) -> usize;
 
     /// Convert from a flat index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is not less than `NUM_INDEXES`.
     fn from_idx(idx: usize) -> Self;
 }
 
@@ -252,6 +258,7 @@ pub struct Values<I> {
 
 impl<I: FixedSizeIndex> Values<I> {
     /// Gets a copy of the remaining range of indexes.
+    #[must_use]
     pub fn range(&self) -> Range<usize> {
         self.range.clone()
     }
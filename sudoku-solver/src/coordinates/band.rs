@@ -0,0 +1,354 @@
+use std::iter::FusedIterator;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::collections::indexed::FixedSizeIndex;
+use crate::coordinates::math::{coord_from_parts, offset_within_sector};
+use crate::coordinates::{FixedSizeIndexable, ZoneContaining};
+use crate::{Col, Coord, Intersect, Row, Sector, SectorCol, SectorRow};
+
+/// A horizontal band of three sectors, covering three full rows.
+/// Bands sort from top to bottom, same as their index order.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Band {
+    /// Row (y) where the band starts.
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "crate::coordinates::serde_utils::deserialize_base_row")
+    )]
+    base_row: Row,
+}
+
+impl Band {
+    /// The row where the band starts.
+    #[inline]
+    #[must_use]
+    pub fn base_row(&self) -> Row {
+        self.base_row
+    }
+
+    /// Rows within this band.
+    #[must_use]
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = Row> + ExactSizeIterator + FusedIterator {
+        let base_row = self.base_row.inner();
+        (0..Sector::HEIGHT)
+            .map(move |r| Row::new(offset_within_sector(base_row, r, Sector::HEIGHT)))
+    }
+
+    /// Sectors within this band, from left to right.
+    #[must_use]
+    pub fn sectors(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = Sector> + ExactSizeIterator + FusedIterator {
+        let base_row = self.base_row;
+        (0..Sector::SECTORS_ACROSS).map(move |s| {
+            Sector::containing_zone(Coord::new(base_row, Col::new(s * Sector::WIDTH)))
+        })
+    }
+}
+
+impl FixedSizeIndexable for Band {
+    type Item = Coord;
+
+    const NUM_ITEMS: usize = (Sector::HEIGHT * Row::WIDTH) as usize;
+
+    fn get_at_index(&self, idx: usize) -> Self::Item {
+        assert!(idx < Self::NUM_ITEMS, "index {} out of range", idx);
+        let idx = idx as u8;
+        let row = offset_within_sector(self.base_row.inner(), idx / Row::WIDTH, Sector::HEIGHT);
+        coord_from_parts(row, idx % Row::WIDTH)
+    }
+}
+
+fixed_size_indexable_into_iter!(Band);
+
+impl ZoneContaining for Band {
+    #[inline]
+    fn containing_zone(coord: Coord) -> Self {
+        Band {
+            base_row: coord.row().sector_base(),
+        }
+    }
+}
+
+impl FixedSizeIndex for Band {
+    const NUM_INDEXES: usize = Sector::SECTORS_DOWN as usize;
+
+    fn idx(&self) -> usize {
+        (self.base_row.inner() / Sector::HEIGHT) as usize
+    }
+
+    fn from_idx(idx: usize) -> Self {
+        assert!(
+            idx < Self::NUM_INDEXES,
+            "flat index must be in range [0, {}), got {}",
+            Self::NUM_INDEXES,
+            idx
+        );
+        Band {
+            base_row: Row::new(idx as u8 * Sector::HEIGHT),
+        }
+    }
+}
+
+/// A vertical stack of three sectors, covering three full columns.
+/// Stacks sort from left to right, same as their index order.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Stack {
+    /// Column (x) where the stack starts.
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "crate::coordinates::serde_utils::deserialize_base_col")
+    )]
+    base_col: Col,
+}
+
+impl Stack {
+    /// The column where the stack starts.
+    #[inline]
+    #[must_use]
+    pub fn base_col(&self) -> Col {
+        self.base_col
+    }
+
+    /// Cols within this stack.
+    #[must_use]
+    pub fn cols(&self) -> impl DoubleEndedIterator<Item = Col> + ExactSizeIterator + FusedIterator {
+        let base_col = self.base_col.inner();
+        (0..Sector::WIDTH).map(move |c| Col::new(offset_within_sector(base_col, c, Sector::WIDTH)))
+    }
+
+    /// Sectors within this stack, from top to bottom.
+    #[must_use]
+    pub fn sectors(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = Sector> + ExactSizeIterator + FusedIterator {
+        let base_col = self.base_col;
+        (0..Sector::SECTORS_DOWN).map(move |s| {
+            Sector::containing_zone(Coord::new(Row::new(s * Sector::HEIGHT), base_col))
+        })
+    }
+}
+
+impl FixedSizeIndexable for Stack {
+    type Item = Coord;
+
+    const NUM_ITEMS: usize = (Sector::WIDTH * Col::HEIGHT) as usize;
+
+    fn get_at_index(&self, idx: usize) -> Self::Item {
+        assert!(idx < Self::NUM_ITEMS, "index {} out of range", idx);
+        let idx = idx as u8;
+        let col = offset_within_sector(self.base_col.inner(), idx % Sector::WIDTH, Sector::WIDTH);
+        coord_from_parts(idx / Sector::WIDTH, col)
+    }
+}
+
+fixed_size_indexable_into_iter!(Stack);
+
+impl ZoneContaining for Stack {
+    #[inline]
+    fn containing_zone(coord: Coord) -> Self {
+        Stack {
+            base_col: coord.col().sector_base(),
+        }
+    }
+}
+
+impl FixedSizeIndex for Stack {
+    const NUM_INDEXES: usize = Sector::SECTORS_ACROSS as usize;
+
+    fn idx(&self) -> usize {
+        (self.base_col.inner() / Sector::WIDTH) as usize
+    }
+
+    fn from_idx(idx: usize) -> Self {
+        assert!(
+            idx < Self::NUM_INDEXES,
+            "flat index must be in range [0, {}), got {}",
+            Self::NUM_INDEXES,
+            idx
+        );
+        Stack {
+            base_col: Col::new(idx as u8 * Sector::WIDTH),
+        }
+    }
+}
+
+impl Intersect<Stack> for Band {
+    type Intersection = Sector;
+
+    fn intersect(self, stack: Stack) -> Option<Self::Intersection> {
+        Some(Sector::containing_zone(Coord::new(
+            self.base_row,
+            stack.base_col,
+        )))
+    }
+}
+
+impl Intersect<Row> for Band {
+    type Intersection = Row;
+
+    fn intersect(self, row: Row) -> Option<Self::Intersection> {
+        if row.sector_base() == self.base_row {
+            Some(row)
+        } else {
+            None
+        }
+    }
+}
+
+impl Intersect<Col> for Band {
+    type Intersection = SectorCol;
+
+    fn intersect(self, col: Col) -> Option<Self::Intersection> {
+        Some(SectorCol::new(self.base_row, col))
+    }
+}
+
+impl Intersect<Sector> for Band {
+    type Intersection = Sector;
+
+    fn intersect(self, sector: Sector) -> Option<Self::Intersection> {
+        if sector.base_row() == self.base_row {
+            Some(sector)
+        } else {
+            None
+        }
+    }
+}
+
+impl Intersect<SectorRow> for Band {
+    type Intersection = SectorRow;
+
+    fn intersect(self, secrow: SectorRow) -> Option<Self::Intersection> {
+        if secrow.row().sector_base() == self.base_row {
+            Some(secrow)
+        } else {
+            None
+        }
+    }
+}
+
+impl Intersect<SectorCol> for Band {
+    type Intersection = SectorCol;
+
+    fn intersect(self, seccol: SectorCol) -> Option<Self::Intersection> {
+        if seccol.base_row() == self.base_row {
+            Some(seccol)
+        } else {
+            None
+        }
+    }
+}
+
+impl Intersect<Row> for Stack {
+    type Intersection = SectorRow;
+
+    fn intersect(self, row: Row) -> Option<Self::Intersection> {
+        Some(SectorRow::new(row, self.base_col))
+    }
+}
+
+impl Intersect<Col> for Stack {
+    type Intersection = Col;
+
+    fn intersect(self, col: Col) -> Option<Self::Intersection> {
+        if col.sector_base() == self.base_col {
+            Some(col)
+        } else {
+            None
+        }
+    }
+}
+
+impl Intersect<Sector> for Stack {
+    type Intersection = Sector;
+
+    fn intersect(self, sector: Sector) -> Option<Self::Intersection> {
+        if sector.base_col() == self.base_col {
+            Some(sector)
+        } else {
+            None
+        }
+    }
+}
+
+impl Intersect<SectorRow> for Stack {
+    type Intersection = SectorRow;
+
+    fn intersect(self, secrow: SectorRow) -> Option<Self::Intersection> {
+        if secrow.base_col() == self.base_col {
+            Some(secrow)
+        } else {
+            None
+        }
+    }
+}
+
+impl Intersect<SectorCol> for Stack {
+    type Intersection = SectorCol;
+
+    fn intersect(self, seccol: SectorCol) -> Option<Self::Intersection> {
+        if seccol.col().sector_base() == self.base_col {
+            Some(seccol)
+        } else {
+            None
+        }
+    }
+}
+
+reciprocal_intersect!(<Band> for Stack);
+reciprocal_intersect!(<Band> for Row);
+reciprocal_intersect!(<Band> for Col);
+reciprocal_intersect!(<Band> for Sector);
+reciprocal_intersect!(<Band> for SectorRow);
+reciprocal_intersect!(<Band> for SectorCol);
+reciprocal_intersect!(<Stack> for Row);
+reciprocal_intersect!(<Stack> for Col);
+reciprocal_intersect!(<Stack> for Sector);
+reciprocal_intersect!(<Stack> for SectorRow);
+reciprocal_intersect!(<Stack> for SectorCol);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Zone;
+
+    #[test]
+    fn bands_iter() {
+        let result: Vec<_> = Band::values().collect();
+        assert_eq!(result.len(), 3);
+        assert_sorted!(result);
+        for (idx, band) in result.iter().enumerate() {
+            assert_eq!(band.idx(), idx);
+            let coords: Vec<_> = band.coords().collect();
+            let expected: Vec<_> = band.rows().flat_map(|row| row.coords()).collect();
+            assert_eq!(coords, expected);
+            assert!(band
+                .sectors()
+                .all(|sector| sector.base_row() == band.base_row()));
+        }
+    }
+
+    #[test]
+    fn stacks_iter() {
+        let result: Vec<_> = Stack::values().collect();
+        assert_eq!(result.len(), 3);
+        assert_sorted!(result);
+        for (idx, stack) in result.iter().enumerate() {
+            assert_eq!(stack.idx(), idx);
+            let mut coords: Vec<_> = stack.coords().collect();
+            let mut expected: Vec<_> = stack.cols().flat_map(|col| col.coords()).collect();
+            assert_sorted!(coords);
+            coords.sort();
+            expected.sort();
+            assert_eq!(coords, expected);
+            assert!(stack
+                .sectors()
+                .all(|sector| sector.base_col() == stack.base_col()));
+        }
+    }
+}
@@ -6,12 +6,13 @@ use std::iter::FusedIterator;
 use serde::{Deserialize, Serialize};
 
 use crate::collections::indexed::FixedSizeIndex;
+use crate::coordinates::math::sector_base_of;
 use crate::coordinates::{FixedSizeIndexable, ZoneContaining};
 use crate::{Coord, Row, Sector, SectorCol, Zone};
 
 /// Uniquely identifies a single column on the sudoku board. That is all cells
 /// with the same x coordinate.
-#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 #[repr(transparent)]
 #[cfg_attr(
     feature = "serde",
@@ -25,15 +26,34 @@ impl Col {
     /// Height of a column in terms of number of rows.
     pub const HEIGHT: u8 = 9;
 
-    /// Construt a column with the given index. Panic if out of bounds.
+    /// Construct a column with the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `val` is 9 or more. Use `Col::try_from` to check untrusted
+    /// input instead.
     #[inline]
+    #[must_use]
     pub fn new(val: u8) -> Self {
-        assert!((0..Self::NUM_INDEXES as u8).contains(&val));
+        assert!(
+            (0..Self::NUM_INDEXES as u8).contains(&val),
+            "column index must be in range [0, {}), got {}",
+            Self::NUM_INDEXES,
+            val
+        );
+        Self(val)
+    }
+
+    /// Construct a column in a const context. Like `new`, but the panic message
+    /// cannot include the index.
+    pub(crate) const fn new_const(val: u8) -> Self {
+        assert!(val < Self::NUM_INDEXES as u8, "column index out of range");
         Self(val)
     }
 
     /// Unwrap the inner u8 value
     #[inline]
+    #[must_use]
     pub fn inner(self) -> u8 {
         self.0
     }
@@ -41,16 +61,15 @@ impl Col {
     /// Base-col for sectors that contain this col.
     pub(crate) fn sector_cols(
         self,
-    ) -> impl Iterator<Item = SectorCol> + DoubleEndedIterator + ExactSizeIterator + FusedIterator
-    {
+    ) -> impl DoubleEndedIterator<Item = SectorCol> + ExactSizeIterator + FusedIterator {
         (0..Sector::SECTORS_DOWN).map(move |r| {
-            SectorCol::containing_zone(Coord::new(Row::new(r * Sector::HEIGHT as u8), self))
+            SectorCol::containing_zone(Coord::new(Row::new(r * Sector::HEIGHT), self))
         })
     }
 
     /// Base-col for sectors that contain this col.
     pub(crate) fn sector_base(self) -> Self {
-        Col(self.0 - self.0 % Sector::WIDTH as u8)
+        Col(sector_base_of(self.0, Sector::WIDTH))
     }
 }
 
@@ -139,6 +158,12 @@ mod tests {
         }
     }
 
+    #[test]
+    #[should_panic(expected = "column index must be in range [0, 9), got 9")]
+    fn new_out_of_range() {
+        let _ = Col::new(9);
+    }
+
     #[cfg(feature = "serde")]
     mod serde_tests {
         use super::*;
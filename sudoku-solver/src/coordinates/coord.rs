@@ -6,13 +6,35 @@ use std::iter::FusedIterator;
 use serde::{Deserialize, Serialize};
 
 use crate::collections::indexed::FixedSizeIndex;
+use crate::coordinates::math::{coord_from_parts, offset_within_sector, sector_base_of};
 use crate::coordinates::{FixedSizeIndexable, ZoneContaining};
 use crate::{Col, OutOfRange, Row, Sector, SectorCol, SectorRow, Zone};
 
+/// How two cells are related to each other. Cells sharing both a row or column
+/// and a sector report the row or column.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Relation {
+    /// Both are the same cell.
+    SameCell,
+    /// The cells are in the same row.
+    SameRow,
+    /// The cells are in the same column.
+    SameCol,
+    /// The cells are in the same sector, but not the same row or column.
+    SameBox,
+    /// The cells share no row, column, or sector.
+    Unrelated,
+}
+
 /// Coordinates of a single cell on the Sudoku board.
 /// Coordinates sort by row, then by column. This matches their index order and
 /// naural iteration order.
-#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Coord {
     /// Row (y).
@@ -21,21 +43,32 @@ pub struct Coord {
     col: Col,
 }
 
+/// Table backing `Coord::neighbors`, so the iterator can borrow from it.
+static PEER_TABLE: [[Coord; 20]; 81] = Coord::PEERS;
+
 impl Coord {
+    /// The 20 peers of each cell, indexed by row-major index, in the same
+    /// order as `neighbors`: the rest of the row, the rest of the column, then
+    /// the rest of the sector.
+    pub const PEERS: [[Coord; 20]; 81] = peers_table();
+
     /// Construct a new coordinate. Since this is (row, col), note that it is (y, x).
     #[inline]
-    pub fn new(row: Row, col: Col) -> Self {
+    #[must_use]
+    pub const fn new(row: Row, col: Col) -> Self {
         Coord { row, col }
     }
 
     /// Get the row of this coordinate (y).
     #[inline]
+    #[must_use]
     pub fn row(&self) -> Row {
         self.row
     }
 
     /// Get the col of this coordinate (x).
     #[inline]
+    #[must_use]
     pub fn col(&self) -> Col {
         self.col
     }
@@ -43,57 +76,77 @@ impl Coord {
     /// Set the row of this coordinate (y).
     #[inline]
     pub fn set_row(&mut self, row: Row) {
-        self.row = row.into();
+        self.row = row;
     }
 
     /// Set the col of this coordinate (x).
     #[inline]
     pub fn set_col(&mut self, col: Col) {
-        self.col = col.into();
+        self.col = col;
     }
 
     /// Get the sector that this coordinate is in.
     #[inline]
+    #[must_use]
     pub fn sector(&self) -> Sector {
         Sector::containing(*self)
     }
 
     /// Get the sector sub-row that this coordinate is in.
     #[inline]
+    #[must_use]
     pub fn sector_row(&self) -> SectorRow {
         SectorRow::containing(*self)
     }
 
     /// Get the sector sub-column that this coordinate is in.
     #[inline]
+    #[must_use]
     pub fn sector_col(&self) -> SectorCol {
         SectorCol::containing(*self)
     }
 
     /// Converts this coord to an index in row-major order.
     #[inline]
+    #[must_use]
     pub fn rowmajor_idx(&self) -> usize {
         self.idx()
     }
 
-    /// Create a coord from an index in row major order. Panic if out of bounds.
+    /// Create a coord from an index in row major order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is 81 or more.
     #[inline]
+    #[must_use]
     pub fn from_rowmajor_idx(idx: usize) -> Self {
         Self::from_idx(idx)
     }
 
     /// Get all coordinates in the same row, column, and sector as this
     /// coordinate.
-    pub fn neighbors(self) -> impl Iterator<Item = Coord> + DoubleEndedIterator + FusedIterator {
-        self.row
-            .coords()
-            .chain(self.col.coords())
-            .chain(
-                self.sector()
-                    .coords()
-                    .filter(move |&other| !self.row.contains(other) && !self.col.contains(other)),
-            )
-            .filter(move |other| *other != self)
+    #[must_use]
+    pub fn neighbors(
+        self,
+    ) -> impl DoubleEndedIterator<Item = Coord> + ExactSizeIterator + FusedIterator {
+        PEER_TABLE[self.idx()].iter().copied()
+    }
+
+    /// Get how this coordinate is related to the other coordinate.
+    #[must_use]
+    pub fn relation(self, other: Coord) -> Relation {
+        if self == other {
+            Relation::SameCell
+        } else if self.row.contains(other) {
+            Relation::SameRow
+        } else if self.col.contains(other) {
+            Relation::SameCol
+        } else if self.sector().contains(other) {
+            Relation::SameBox
+        } else {
+            Relation::Unrelated
+        }
     }
 }
 
@@ -142,7 +195,7 @@ impl IntoIterator for Coord {
 impl ZoneContaining for Coord {
     #[inline]
     fn containing_zone(coord: Coord) -> Self {
-        coord.into()
+        coord
     }
 }
 
@@ -160,10 +213,56 @@ impl FixedSizeIndex for Coord {
             Self::NUM_INDEXES,
             idx
         );
-        let row = Row::new((idx / Col::NUM_INDEXES) as u8);
-        let col = Col::new((idx % Col::NUM_INDEXES) as u8);
-        Coord { row, col }
+        coord_from_parts(
+            (idx / Col::NUM_INDEXES) as u8,
+            (idx % Col::NUM_INDEXES) as u8,
+        )
+    }
+}
+
+/// Build the table of peers for `Coord::PEERS`.
+const fn peers_table() -> [[Coord; 20]; 81] {
+    const WIDTH: u8 = Row::WIDTH;
+    let origin = coord_from_parts(0, 0);
+    let mut table = [[origin; 20]; 81];
+    let mut idx = 0;
+    while idx < table.len() {
+        let (r, c) = ((idx / WIDTH as usize) as u8, (idx % WIDTH as usize) as u8);
+        let peers = &mut table[idx];
+        let mut n = 0;
+        let mut i = 0;
+        while i < WIDTH {
+            if i != c {
+                peers[n] = coord_from_parts(r, i);
+                n += 1;
+            }
+            i += 1;
+        }
+        i = 0;
+        while i < WIDTH {
+            if i != r {
+                peers[n] = coord_from_parts(i, c);
+                n += 1;
+            }
+            i += 1;
+        }
+        let (base_r, base_c) = (
+            sector_base_of(r, Sector::HEIGHT),
+            sector_base_of(c, Sector::WIDTH),
+        );
+        i = 0;
+        while i < Sector::SIZE as u8 {
+            let rr = offset_within_sector(base_r, i / Sector::WIDTH, Sector::HEIGHT);
+            let cc = offset_within_sector(base_c, i % Sector::WIDTH, Sector::WIDTH);
+            if rr != r && cc != c {
+                peers[n] = coord_from_parts(rr, cc);
+                n += 1;
+            }
+            i += 1;
+        }
+        idx += 1;
     }
+    table
 }
 
 #[cfg(test)]
@@ -197,6 +296,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn coord_relation() {
+        let coord = |r, c| Coord::new(Row::new(r), Col::new(c));
+        let cases = [
+            (coord(4, 4), coord(4, 4), Relation::SameCell),
+            (coord(4, 4), coord(4, 5), Relation::SameRow),
+            (coord(4, 4), coord(4, 0), Relation::SameRow),
+            (coord(4, 4), coord(3, 4), Relation::SameCol),
+            (coord(4, 4), coord(8, 4), Relation::SameCol),
+            (coord(4, 4), coord(3, 3), Relation::SameBox),
+            (coord(0, 0), coord(2, 2), Relation::SameBox),
+            (coord(4, 4), coord(0, 0), Relation::Unrelated),
+            (coord(0, 0), coord(3, 1), Relation::Unrelated),
+        ];
+        for &(a, b, expected) in &cases {
+            assert_eq!(a.relation(b), expected, "{} and {}", a, b);
+            assert_eq!(b.relation(a), expected, "{} and {}", b, a);
+        }
+        for a in Coord::all() {
+            for b in Coord::all() {
+                let related = !matches!(a.relation(b), Relation::SameCell | Relation::Unrelated);
+                assert_eq!(related, a.neighbors().any(|n| n == b));
+            }
+        }
+    }
+
     #[test]
     fn coord_neighbors() {
         for r in 0..9 {
@@ -212,8 +337,8 @@ mod tests {
                         expected.push(Coord::new(Row::new(rr), Col::new(c)));
                     }
                 }
-                for rr in ((r - (r % 3))..).take(3) {
-                    for cc in ((c - (c % 3))..).take(3) {
+                for rr in (sector_base_of(r, 3)..).take(3) {
+                    for cc in (sector_base_of(c, 3)..).take(3) {
                         if rr != r && cc != c {
                             expected.push(Coord::new(Row::new(rr), Col::new(cc)));
                         }
@@ -226,6 +351,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn peers_table_matches_zones() {
+        for coord in Coord::all() {
+            let peers = &Coord::PEERS[coord.rowmajor_idx()];
+            let expected: Vec<_> = coord
+                .row()
+                .coords()
+                .chain(coord.col().coords())
+                .chain(
+                    coord
+                        .sector()
+                        .coords()
+                        .filter(|&other| !coord.row().contains(other))
+                        .filter(|&other| !coord.col().contains(other)),
+                )
+                .filter(|&other| other != coord)
+                .collect();
+            assert_eq!(&peers[..], &expected[..]);
+            assert!(coord.neighbors().eq(peers.iter().copied()));
+            assert_eq!(coord.neighbors().len(), 20);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "flat index must be in range [0, 81), got 81")]
+    fn from_rowmajor_idx_out_of_range() {
+        let _ = Coord::from_rowmajor_idx(81);
+    }
+
     #[cfg(feature = "serde")]
     mod serde_tests {
         use super::*;
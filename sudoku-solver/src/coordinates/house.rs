@@ -0,0 +1,215 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::collections::indexed::FixedSizeIndex;
+use crate::coordinates::{Coords, FixedSizeIndexable};
+use crate::{
+    Band, Col, Coord, DynZone, Intersect, IntersectionResult, Row, Sector, SectorCol, SectorRow,
+    Stack, Values, Zone,
+};
+
+/// Any one of the zones which must contain every value exactly once: a row, a
+/// column, or a sector. Houses sort rows first, then columns, then sectors,
+/// same as their index order.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum House {
+    Row(Row),
+    Col(Col),
+    Sector(Sector),
+}
+
+impl House {
+    /// Get an iterator over all houses.
+    #[inline]
+    #[must_use]
+    pub fn all() -> Values<Self> {
+        Self::values()
+    }
+
+    /// Get the three houses containing the given coordinate, in the order row,
+    /// column, sector.
+    #[inline]
+    #[must_use]
+    pub fn containing(coord: Coord) -> [Self; 3] {
+        [
+            House::Row(coord.row()),
+            House::Col(coord.col()),
+            House::Sector(coord.sector()),
+        ]
+    }
+
+    /// Get an iterator over the coordinates of this house.
+    #[inline]
+    #[must_use]
+    pub fn coords(&self) -> Coords<Self> {
+        (*self).into()
+    }
+
+    /// Whether this house contains the given coordinate.
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, coord: Coord) -> bool {
+        match self {
+            House::Row(row) => row.contains(coord),
+            House::Col(col) => col.contains(coord),
+            House::Sector(sector) => sector.contains(coord),
+        }
+    }
+}
+
+impl From<Row> for House {
+    fn from(row: Row) -> Self {
+        House::Row(row)
+    }
+}
+
+impl From<Col> for House {
+    fn from(col: Col) -> Self {
+        House::Col(col)
+    }
+}
+
+impl From<Sector> for House {
+    fn from(sector: Sector) -> Self {
+        House::Sector(sector)
+    }
+}
+
+impl FixedSizeIndexable for House {
+    type Item = Coord;
+
+    const NUM_ITEMS: usize = Row::SIZE;
+
+    fn get_at_index(&self, idx: usize) -> Self::Item {
+        match self {
+            House::Row(row) => row.get_at_index(idx),
+            House::Col(col) => col.get_at_index(idx),
+            House::Sector(sector) => sector.get_at_index(idx),
+        }
+    }
+}
+
+fixed_size_indexable_into_iter!(House);
+
+impl DynZone for House {
+    fn dyn_coords(&self) -> Box<dyn Iterator<Item = Coord> + '_> {
+        Box::new(self.coords())
+    }
+
+    fn dyn_contains(&self, coord: Coord) -> bool {
+        self.contains(coord)
+    }
+}
+
+impl FixedSizeIndex for House {
+    const NUM_INDEXES: usize = Row::NUM_INDEXES + Col::NUM_INDEXES + Sector::NUM_INDEXES;
+
+    fn idx(&self) -> usize {
+        match self {
+            House::Row(row) => row.idx(),
+            House::Col(col) => Row::NUM_INDEXES + col.idx(),
+            House::Sector(sector) => Row::NUM_INDEXES + Col::NUM_INDEXES + sector.idx(),
+        }
+    }
+
+    fn from_idx(idx: usize) -> Self {
+        assert!(
+            idx < Self::NUM_INDEXES,
+            "flat index must be in range [0, {}), got {}",
+            Self::NUM_INDEXES,
+            idx
+        );
+        if idx < Row::NUM_INDEXES {
+            House::Row(Row::from_idx(idx))
+        } else if idx < Row::NUM_INDEXES + Col::NUM_INDEXES {
+            House::Col(Col::from_idx(idx - Row::NUM_INDEXES))
+        } else {
+            House::Sector(Sector::from_idx(idx - Row::NUM_INDEXES - Col::NUM_INDEXES))
+        }
+    }
+}
+
+impl Intersect<Coord> for House {
+    type Intersection = Coord;
+
+    fn intersect(self, coord: Coord) -> Option<Self::Intersection> {
+        if self.contains(coord) {
+            Some(coord)
+        } else {
+            None
+        }
+    }
+}
+
+reciprocal_intersect!(<House> for Coord);
+
+macro_rules! house_zone_intersect {
+    ($z:ty) => {
+        impl Intersect<$z> for House {
+            type Intersection = IntersectionResult;
+
+            fn intersect(self, other: $z) -> Option<Self::Intersection> {
+                match self {
+                    House::Row(row) => row.intersect(other).map(Into::into),
+                    House::Col(col) => col.intersect(other).map(Into::into),
+                    House::Sector(sector) => sector.intersect(other).map(Into::into),
+                }
+            }
+        }
+
+        reciprocal_intersect!(<House> for $z);
+    };
+}
+
+house_zone_intersect!(Row);
+house_zone_intersect!(Col);
+house_zone_intersect!(Sector);
+house_zone_intersect!(SectorRow);
+house_zone_intersect!(SectorCol);
+house_zone_intersect!(Band);
+house_zone_intersect!(Stack);
+
+impl Intersect<House> for House {
+    type Intersection = IntersectionResult;
+
+    fn intersect(self, other: House) -> Option<Self::Intersection> {
+        match other {
+            House::Row(row) => self.intersect(row),
+            House::Col(col) => self.intersect(col),
+            House::Sector(sector) => self.intersect(sector),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn houses_iter() {
+        let result: Vec<_> = House::all().collect();
+        assert_eq!(result.len(), 27);
+        assert_sorted!(result);
+        for (idx, house) in result.iter().enumerate() {
+            assert_eq!(house.idx(), idx);
+            assert_eq!(House::from_idx(idx), *house);
+            assert_eq!(house.coords().len(), 9);
+            assert!(house.coords().all(|coord| house.contains(coord)));
+        }
+    }
+
+    #[test]
+    fn house_containing() {
+        for coord in Coord::all() {
+            let houses = House::containing(coord);
+            assert!(houses.iter().all(|house| house.contains(coord)));
+            let count = House::all().filter(|house| house.contains(coord)).count();
+            assert_eq!(count, houses.len());
+        }
+    }
+}
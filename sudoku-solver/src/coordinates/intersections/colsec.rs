@@ -5,13 +5,14 @@ use std::iter::FusedIterator;
 use serde::{Deserialize, Serialize};
 
 use crate::collections::indexed::FixedSizeIndex;
+use crate::coordinates::math::offset_within_sector;
 use crate::coordinates::{FixedSizeIndexable, ZoneContaining};
 use crate::{Col, Coord, Intersect, Row, Sector};
 
 /// A column within a sector.
 /// Sector rows sort in the same order as their equivalent indexes, by row then
 /// by column (so across the rows).
-#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SectorCol {
     /// The row that the sector starts at.
@@ -34,38 +35,42 @@ impl SectorCol {
 
     /// Get the sector that this col is part of.
     #[inline]
+    #[must_use]
     pub fn sector(&self) -> Sector {
         Sector::containing_zone(Coord::new(self.base_row, self.col))
     }
 
     /// Get the row that this row is part of.
     #[inline]
+    #[must_use]
     pub fn col(&self) -> Col {
         self.col
     }
 
     /// Get the base row for the sector that this row is part of.
     #[inline]
+    #[must_use]
     pub fn base_row(&self) -> Row {
         self.base_row
     }
 
     /// Gets an iterator over the two SectorCols that share the same col as this one.
     #[inline]
+    #[must_use]
     pub fn col_neighbors(self) -> array::IntoIter<Self, 2> {
         super::array_filter_single_neq(self, self.col.sector_cols()).into_iter()
     }
 
     /// Gets an iterator over the two SectorRows that share the same sector as this one.
     #[inline]
+    #[must_use]
     pub fn sector_neighbors(self) -> array::IntoIter<Self, 2> {
         super::array_filter_single_neq(self, self.sector().cols()).into_iter()
     }
 
     /// Iterator over all SectorCols in the rest of the sector and column.
-    pub fn neighbors(
-        self,
-    ) -> impl Iterator<Item = SectorCol> + DoubleEndedIterator + FusedIterator {
+    #[must_use]
+    pub fn neighbors(self) -> impl DoubleEndedIterator<Item = SectorCol> + FusedIterator {
         self.col()
             .sector_cols()
             .chain(self.sector().cols())
@@ -80,7 +85,7 @@ impl FixedSizeIndexable for SectorCol {
 
     fn get_at_index(&self, idx: usize) -> Self::Item {
         assert!(idx < Self::NUM_ITEMS, "index {} out of range", idx);
-        let row = self.base_row.inner() + idx as u8;
+        let row = offset_within_sector(self.base_row.inner(), idx as u8, Sector::HEIGHT);
         Coord::new(Row::new(row), self.col)
     }
 }
@@ -184,6 +189,7 @@ reciprocal_intersect!(<SectorCol> for Sector);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::coordinates::math::sector_base_of;
     use crate::Zone;
 
     #[test]
@@ -240,7 +246,7 @@ mod tests {
                         });
                     }
                 }
-                for cc in (c - c % 3..).take(3) {
+                for cc in (sector_base_of(c, 3)..).take(3) {
                     if cc != c {
                         expected.push(SectorCol {
                             base_row: Row::new(br),
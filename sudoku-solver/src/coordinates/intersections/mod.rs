@@ -1,11 +1,11 @@
-use crate::{Col, Coord, Row, Sector, SectorCol, SectorRow, Zone};
+use crate::{Band, Col, Coord, DynZone, Row, Sector, SectorCol, SectorRow, Stack, Zone};
 
 pub(crate) mod colsec;
 pub(crate) mod rowsec;
 
 /// Trait for the intersection of a zone with another type of zone.
-pub trait Intersect<Z: Zone> {
-    type Intersection: Zone;
+pub trait Intersect<Z> {
+    type Intersection: DynZone;
 
     /// Get the intersection of this zone with the given other zone.
     fn intersect(self, other: Z) -> Option<Self::Intersection>;
@@ -46,6 +46,8 @@ coord_zone_intersect!(Col);
 coord_zone_intersect!(Sector);
 coord_zone_intersect!(SectorRow);
 coord_zone_intersect!(SectorCol);
+coord_zone_intersect!(Band);
+coord_zone_intersect!(Stack);
 
 impl Intersect<Col> for Row {
     type Intersection = Coord;
@@ -57,11 +59,51 @@ impl Intersect<Col> for Row {
 
 reciprocal_intersect!(<Row> for Col);
 
+/// Intersection of zones where the type of the intersection depends on which
+/// instances are intersected, such as two `House`s.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum IntersectionResult {
+    Coord(Coord),
+    Row(Row),
+    Col(Col),
+    Sector(Sector),
+    SectorRow(SectorRow),
+    SectorCol(SectorCol),
+}
+
+macro_rules! intersection_result_from {
+    ($($z:ident),*) => {
+        $(
+            impl From<$z> for IntersectionResult {
+                fn from(zone: $z) -> Self {
+                    IntersectionResult::$z(zone)
+                }
+            }
+        )*
+
+        impl DynZone for IntersectionResult {
+            fn dyn_coords(&self) -> Box<dyn Iterator<Item = Coord> + '_> {
+                match self {
+                    $(IntersectionResult::$z(zone) => Box::new(zone.coords()),)*
+                }
+            }
+
+            fn dyn_contains(&self, coord: Coord) -> bool {
+                match self {
+                    $(IntersectionResult::$z(zone) => zone.contains(coord),)*
+                }
+            }
+        }
+    };
+}
+
+intersection_result_from!(Coord, Row, Col, Sector, SectorRow, SectorCol);
+
 /// Filter an iterator of N + 1 elements into an array of N elements.
 #[inline]
 fn array_filter_single_neq<T: Copy + Eq, const N: usize>(
     skip: T,
-    iter: impl Iterator<Item = T> + ExactSizeIterator,
+    iter: impl ExactSizeIterator<Item = T>,
 ) -> [T; N] {
     debug_assert!(
         iter.len() == N + 1,
@@ -80,3 +122,82 @@ fn array_filter_single_neq<T: Copy + Eq, const N: usize>(
     );
     arr
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::fmt::Debug;
+
+    use super::*;
+    use crate::House;
+
+    /// Types whose every instance can be listed.
+    trait Instances: Sized {
+        fn instances() -> Vec<Self>;
+    }
+
+    impl<Z: Zone> Instances for Z {
+        fn instances() -> Vec<Self> {
+            Z::all().collect()
+        }
+    }
+
+    impl Instances for House {
+        fn instances() -> Vec<Self> {
+            House::all().collect()
+        }
+    }
+
+    fn coord_set(zone: &dyn DynZone) -> BTreeSet<Coord> {
+        zone.dyn_coords().collect()
+    }
+
+    /// Check every pair of instances of the two types against the brute-force
+    /// intersection of their coordinates, in both directions.
+    fn check_pair<A, B>()
+    where
+        A: Intersect<B> + Instances + DynZone + Copy + Debug,
+        B: Intersect<A> + Instances + DynZone + Copy + Debug,
+    {
+        for a in A::instances() {
+            let a_coords = coord_set(&a);
+            assert!(a_coords.iter().all(|&coord| a.dyn_contains(coord)));
+            for b in B::instances() {
+                let expected: BTreeSet<_> =
+                    a_coords.intersection(&coord_set(&b)).copied().collect();
+                let ab = a.intersect(b).map(|i| coord_set(&i));
+                let ba = b.intersect(a).map(|i| coord_set(&i));
+                assert_eq!(ab, ba, "{:?} and {:?} not symmetric", a, b);
+                match ab {
+                    Some(actual) => assert_eq!(actual, expected, "{:?} & {:?}", a, b),
+                    None => assert!(
+                        expected.is_empty(),
+                        "{:?} & {:?} missed {:?}",
+                        a,
+                        b,
+                        expected
+                    ),
+                }
+            }
+        }
+    }
+
+    /// Check every ordered pair of the given types. Fails to compile if any
+    /// pair is missing an `Intersect` impl in either direction.
+    macro_rules! check_matrix {
+        ($($z:ty),*) => {
+            check_matrix!(@outer [$($z),*] [$($z),*]);
+        };
+        (@outer [$($a:ty),*] $all:tt) => {
+            $(check_matrix!(@inner $a $all);)*
+        };
+        (@inner $a:ty [$($b:ty),*]) => {
+            $(check_pair::<$a, $b>();)*
+        };
+    }
+
+    #[test]
+    fn intersect_matrix() {
+        check_matrix!(Coord, Row, Col, Sector, SectorRow, SectorCol, Band, Stack, House);
+    }
+}
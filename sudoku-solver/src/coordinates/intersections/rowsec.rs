@@ -5,13 +5,14 @@ use std::iter::FusedIterator;
 use serde::{Deserialize, Serialize};
 
 use crate::collections::indexed::FixedSizeIndex;
+use crate::coordinates::math::offset_within_sector;
 use crate::coordinates::{FixedSizeIndexable, ZoneContaining};
 use crate::{Col, Coord, Intersect, Row, Sector, SectorCol};
 
 /// A row within a sector.
 /// Sector rows sort in the same order as their equivalent indexes, by row then
 /// by column (so across the rows).
-#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SectorRow {
     /// The row relative to the sector.
@@ -32,38 +33,42 @@ impl SectorRow {
 
     /// Get the sector that this row is part of.
     #[inline]
+    #[must_use]
     pub fn sector(&self) -> Sector {
         Sector::containing_zone(Coord::new(self.row, self.base_col))
     }
 
     /// Get the row that this row is part of.
     #[inline]
+    #[must_use]
     pub fn row(&self) -> Row {
         self.row
     }
 
     /// Get the base col for the sector that this col is part of.
     #[inline]
+    #[must_use]
     pub fn base_col(&self) -> Col {
         self.base_col
     }
 
     /// Gets an iterator over the two SectorRows that share the same row as this one.
     #[inline]
+    #[must_use]
     pub fn row_neighbors(self) -> array::IntoIter<Self, 2> {
         super::array_filter_single_neq(self, self.row.sector_rows()).into_iter()
     }
 
     /// Gets an iterator over the two SectorRows that share the same sector as this one.
     #[inline]
+    #[must_use]
     pub fn sector_neighbors(self) -> array::IntoIter<Self, 2> {
         super::array_filter_single_neq(self, self.sector().rows()).into_iter()
     }
 
     /// Iterator over all SectorRows in the rest of the sector and row.
-    pub fn neighbors(
-        self,
-    ) -> impl Iterator<Item = SectorRow> + DoubleEndedIterator + FusedIterator {
+    #[must_use]
+    pub fn neighbors(self) -> impl DoubleEndedIterator<Item = SectorRow> + FusedIterator {
         self.row()
             .sector_rows()
             .chain(self.sector().rows())
@@ -78,7 +83,7 @@ impl FixedSizeIndexable for SectorRow {
 
     fn get_at_index(&self, idx: usize) -> Self::Item {
         assert!(idx < Self::NUM_ITEMS, "index {} out of range", idx);
-        let col = self.base_col.inner() + idx as u8;
+        let col = offset_within_sector(self.base_col.inner(), idx as u8, Sector::WIDTH);
         Coord::new(self.row, Col::new(col))
     }
 }
@@ -194,6 +199,7 @@ reciprocal_intersect!(<SectorRow> for SectorCol);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::coordinates::math::sector_base_of;
     use crate::Zone;
 
     #[test]
@@ -250,7 +256,7 @@ mod tests {
                         });
                     }
                 }
-                for rr in (r - r % 3..).take(3) {
+                for rr in (sector_base_of(r, 3)..).take(3) {
                     if rr != r {
                         expected.push(SectorRow {
                             row: Row::new(rr),
@@ -0,0 +1,141 @@
+//! Checked arithmetic shared by the coordinate types.
+//!
+//! Every zone that maps between a sector-relative position and an absolute
+//! row or column goes through these helpers rather than adding `u8`s directly,
+//! so a broken invariant panics with a description of what went wrong instead
+//! of silently wrapping to the wrong cell in a release build.
+use crate::{Col, Coord, Row};
+
+/// Number of rows or columns on the board.
+const BOARD_SPAN: u8 = Row::WIDTH;
+
+/// The first row or column of the sector that contains `idx`, where sectors
+/// are `span` rows or columns wide.
+///
+/// # Panics
+///
+/// Panics if `idx` is not on the board or if `span` is zero.
+#[inline]
+pub(crate) const fn sector_base_of(idx: u8, span: u8) -> u8 {
+    assert!(idx < BOARD_SPAN, "row or column index out of range");
+    assert!(span > 0, "sector span must be non-zero");
+    // idx % span <= idx, so this can never underflow.
+    idx - idx % span
+}
+
+/// The absolute row or column that is `offset` rows or columns into the
+/// sector starting at `base`, where sectors are `span` rows or columns wide.
+///
+/// # Panics
+///
+/// Panics if `base` is not the start of a sector, if `offset` falls outside the
+/// sector, or if the result would be off the board.
+#[inline]
+pub(crate) const fn offset_within_sector(base: u8, offset: u8, span: u8) -> u8 {
+    assert!(
+        sector_base_of(base, span) == base,
+        "base is not the start of a sector"
+    );
+    assert!(offset < span, "offset does not fit within the sector");
+    match base.checked_add(offset) {
+        Some(idx) if idx < BOARD_SPAN => idx,
+        _ => panic!("offset within sector is off the board"),
+    }
+}
+
+/// Build a `Coord` from a raw row and column index.
+///
+/// # Panics
+///
+/// Panics if either index is not on the board.
+#[inline]
+pub(crate) const fn coord_from_parts(row: u8, col: u8) -> Coord {
+    Coord::new(Row::new_const(row), Col::new_const(col))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::indexed::FixedSizeIndex;
+    use crate::Sector;
+
+    #[test]
+    fn sector_base_of_edges() {
+        crate::setup();
+        assert_eq!(sector_base_of(0, Sector::HEIGHT), 0);
+        assert_eq!(sector_base_of(2, Sector::HEIGHT), 0);
+        assert_eq!(sector_base_of(3, Sector::HEIGHT), 3);
+        assert_eq!(sector_base_of(8, Sector::WIDTH), 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "row or column index out of range")]
+    fn sector_base_of_past_end() {
+        crate::setup();
+        let _ = sector_base_of(9, Sector::HEIGHT);
+    }
+
+    #[test]
+    fn offset_within_sector_edges() {
+        crate::setup();
+        assert_eq!(offset_within_sector(0, 0, Sector::WIDTH), 0);
+        assert_eq!(offset_within_sector(0, 2, Sector::WIDTH), 2);
+        assert_eq!(offset_within_sector(6, 0, Sector::WIDTH), 6);
+        assert_eq!(offset_within_sector(6, 2, Sector::WIDTH), 8);
+    }
+
+    #[test]
+    #[should_panic(expected = "offset does not fit within the sector")]
+    fn offset_within_sector_past_end() {
+        crate::setup();
+        let _ = offset_within_sector(6, 3, Sector::WIDTH);
+    }
+
+    #[test]
+    #[should_panic(expected = "base is not the start of a sector")]
+    fn offset_within_sector_unaligned() {
+        crate::setup();
+        let _ = offset_within_sector(7, 0, Sector::WIDTH);
+    }
+
+    #[test]
+    fn coord_from_parts_edges() {
+        crate::setup();
+        assert_eq!(coord_from_parts(0, 0), Coord::from_idx(0));
+        assert_eq!(coord_from_parts(0, 8), Coord::from_idx(8));
+        assert_eq!(coord_from_parts(8, 0), Coord::from_idx(72));
+        assert_eq!(coord_from_parts(8, 8), Coord::from_idx(80));
+    }
+
+    #[test]
+    #[should_panic(expected = "column index out of range")]
+    fn coord_from_parts_past_end() {
+        crate::setup();
+        let _ = coord_from_parts(0, 9);
+    }
+
+    /// Every valid input to every helper succeeds and agrees with the plain
+    /// arithmetic it replaces.
+    #[test]
+    fn helpers_total_on_valid_inputs() {
+        crate::setup();
+        for span in [Sector::WIDTH, Sector::HEIGHT] {
+            for idx in 0..BOARD_SPAN {
+                let base = sector_base_of(idx, span);
+                assert_eq!(base, idx / span * span);
+                assert_eq!(offset_within_sector(base, idx - base, span), idx);
+                for offset in 0..span {
+                    let abs = offset_within_sector(base, offset, span);
+                    assert_eq!(sector_base_of(abs, span), base);
+                }
+            }
+        }
+        for row in 0..BOARD_SPAN {
+            for col in 0..BOARD_SPAN {
+                let coord = coord_from_parts(row, col);
+                assert_eq!((coord.row().inner(), coord.col().inner()), (row, col));
+                assert_eq!(coord.idx(), row as usize * 9 + col as usize);
+            }
+        }
+    }
+}
@@ -1,25 +1,30 @@
 //! Different types of coordinates on the board -- individual cells, sectors,
-//! rows, and columns.
+//! rows, columns, bands, stacks, and houses.
 use std::fmt;
 
 use thiserror::Error;
 
+pub use band::{Band, Stack};
 pub use column::Col;
-pub use coord::Coord;
+pub use coord::{Coord, Relation};
+pub use house::House;
 pub use intersections::colsec::SectorCol;
 pub use intersections::rowsec::SectorRow;
-pub use intersections::Intersect;
+pub use intersections::{Intersect, IntersectionResult};
 pub use row::Row;
 pub use sector::Sector;
-pub use zone::Zone;
-pub(crate) use zone::{Coords, FixedSizeIndexable, ZoneContaining};
+pub use zone::{Coords, DynZone, Zone};
+pub(crate) use zone::{FixedSizeIndexable, ZoneContaining};
 
 #[macro_use]
 mod shared_macros;
 
+mod band;
 mod column;
 mod coord;
+mod house;
 mod intersections;
+mod math;
 mod row;
 mod sector;
 mod zone;
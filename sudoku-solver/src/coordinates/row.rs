@@ -6,12 +6,13 @@ use std::iter::FusedIterator;
 use serde::{Deserialize, Serialize};
 
 use crate::collections::indexed::FixedSizeIndex;
+use crate::coordinates::math::sector_base_of;
 use crate::coordinates::{FixedSizeIndexable, ZoneContaining};
 use crate::{Col, Coord, Sector, SectorRow, Zone};
 
 /// Uniquely identifies a single row on the sudoku board. That is all cells with
 /// the same y coordinate.
-#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 #[repr(transparent)]
 #[cfg_attr(
     feature = "serde",
@@ -25,15 +26,34 @@ impl Row {
     /// Width of a row as a number of columns.
     pub const WIDTH: u8 = 9;
 
-    /// Construt a row with the given index. Panic if out of bounds.
+    /// Construct a row with the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `val` is 9 or more. Use `Row::try_from` to check untrusted
+    /// input instead.
     #[inline]
+    #[must_use]
     pub fn new(val: u8) -> Self {
-        assert!((0..Self::NUM_INDEXES as u8).contains(&val));
+        assert!(
+            (0..Self::NUM_INDEXES as u8).contains(&val),
+            "row index must be in range [0, {}), got {}",
+            Self::NUM_INDEXES,
+            val
+        );
+        Self(val)
+    }
+
+    /// Construct a row in a const context. Like `new`, but the panic message
+    /// cannot include the index.
+    pub(crate) const fn new_const(val: u8) -> Self {
+        assert!(val < Self::NUM_INDEXES as u8, "row index out of range");
         Self(val)
     }
 
     /// Unwrap the inner u8 value
     #[inline]
+    #[must_use]
     pub fn inner(self) -> u8 {
         self.0
     }
@@ -41,15 +61,14 @@ impl Row {
     /// Iterator over `SectorRow` in this `Row`.
     pub(crate) fn sector_rows(
         self,
-    ) -> impl Iterator<Item = SectorRow> + DoubleEndedIterator + ExactSizeIterator + FusedIterator
-    {
+    ) -> impl DoubleEndedIterator<Item = SectorRow> + ExactSizeIterator + FusedIterator {
         (0..Sector::SECTORS_ACROSS)
             .map(move |c| SectorRow::containing_zone(Coord::new(self, Col::new(c * Sector::WIDTH))))
     }
 
     /// Base-row for sectors that contain this row.
     pub(crate) fn sector_base(self) -> Self {
-        Row(self.0 - self.0 % Sector::HEIGHT)
+        Row(sector_base_of(self.0, Sector::HEIGHT))
     }
 }
 
@@ -138,6 +157,12 @@ mod tests {
         }
     }
 
+    #[test]
+    #[should_panic(expected = "row index must be in range [0, 9), got 9")]
+    fn new_out_of_range() {
+        let _ = Row::new(9);
+    }
+
     #[cfg(feature = "serde")]
     mod serde_tests {
         use super::*;
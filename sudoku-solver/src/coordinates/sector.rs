@@ -4,13 +4,14 @@ use std::iter::FusedIterator;
 use serde::{Deserialize, Serialize};
 
 use crate::collections::indexed::FixedSizeIndex;
+use crate::coordinates::math::{coord_from_parts, offset_within_sector};
 use crate::coordinates::{FixedSizeIndexable, ZoneContaining};
 use crate::{Col, Coord, Row, SectorCol, SectorRow};
 
 /// Identifies a single 3x3 sector on the sudoku board.
 /// Sectors by row then by column (across each row, same as their index order and
 /// natural iteration order).
-#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Sector {
     /// Row (y) where the sector starts.
@@ -44,34 +45,46 @@ impl Sector {
 
     /// The row where the sector starts.
     #[inline]
+    #[must_use]
     pub fn base_row(&self) -> Row {
         self.base_row
     }
 
     /// The column where the sector starts.
     #[inline]
+    #[must_use]
     pub fn base_col(&self) -> Col {
         self.base_col
     }
 
     /// Rows within this sector.
+    #[must_use]
     pub fn rows(
         &self,
-    ) -> impl Iterator<Item = SectorRow> + DoubleEndedIterator + ExactSizeIterator + FusedIterator
-    {
+    ) -> impl DoubleEndedIterator<Item = SectorRow> + ExactSizeIterator + FusedIterator {
         let base_col = self.base_col;
-        (self.base_row.inner()..self.base_row.inner() + Self::HEIGHT)
-            .map(move |r| SectorRow::new(Row::new(r), base_col))
+        let base_row = self.base_row.inner();
+        (0..Self::HEIGHT).map(move |r| {
+            SectorRow::new(
+                Row::new(offset_within_sector(base_row, r, Self::HEIGHT)),
+                base_col,
+            )
+        })
     }
 
     /// Cols within this sector.
+    #[must_use]
     pub fn cols(
         &self,
-    ) -> impl Iterator<Item = SectorCol> + DoubleEndedIterator + ExactSizeIterator + FusedIterator
-    {
+    ) -> impl DoubleEndedIterator<Item = SectorCol> + ExactSizeIterator + FusedIterator {
         let base_row = self.base_row;
-        (self.base_col.inner()..self.base_col.inner() + Self::WIDTH)
-            .map(move |c| SectorCol::new(base_row, Col::new(c)))
+        let base_col = self.base_col.inner();
+        (0..Self::WIDTH).map(move |c| {
+            SectorCol::new(
+                base_row,
+                Col::new(offset_within_sector(base_col, c, Self::WIDTH)),
+            )
+        })
     }
 }
 
@@ -83,11 +96,9 @@ impl FixedSizeIndexable for Sector {
     fn get_at_index(&self, idx: usize) -> Self::Item {
         assert!(idx < Self::NUM_ITEMS, "index {} out of range", idx);
         let idx = idx as u8;
-        let row_offset = (idx / Self::WIDTH) as u8;
-        let col_offset = (idx % Self::WIDTH) as u8;
-        let row = Row::new(self.base_row.inner() + row_offset);
-        let col = Col::new(self.base_col.inner() + col_offset);
-        Coord::new(row, col)
+        let row = offset_within_sector(self.base_row.inner(), idx / Self::WIDTH, Self::HEIGHT);
+        let col = offset_within_sector(self.base_col.inner(), idx % Self::WIDTH, Self::WIDTH);
+        coord_from_parts(row, col)
     }
 }
 
@@ -144,15 +155,15 @@ mod tests {
             for c in 0..9 {
                 let sector = Sector::containing(Coord::new(Row::new(r), Col::new(c)));
                 let baser = match r {
-                    0 | 1 | 2 => 0,
-                    3 | 4 | 5 => 3,
-                    6 | 7 | 8 => 6,
+                    0..=2 => 0,
+                    3..=5 => 3,
+                    6..=8 => 6,
                     _ => unreachable!(),
                 };
                 let basec = match c {
-                    0 | 1 | 2 => 0,
-                    3 | 4 | 5 => 3,
-                    6 | 7 | 8 => 6,
+                    0..=2 => 0,
+                    3..=5 => 3,
+                    6..=8 => 6,
                     _ => unreachable!(),
                 };
                 static OFFSETS: &[(u8, u8)] = &[
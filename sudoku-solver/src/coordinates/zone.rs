@@ -22,6 +22,7 @@ pub trait Zone:
 
     /// Get an iterator over all values of this zone.
     #[inline]
+    #[must_use]
     fn all() -> Values<Self>
     where
         Self: Sized,
@@ -39,6 +40,7 @@ pub trait Zone:
 
     /// Gets the zone of this type which contains the given coordinate.
     #[inline]
+    #[must_use]
     fn containing(coord: Coord) -> Self
     where
         Self: Sized,
@@ -70,6 +72,27 @@ where
     }
 }
 
+/// Object-safe view of a zone, for code which handles zones of several types
+/// uniformly. Implemented by every `Zone`, as well as by `House` and
+/// `IntersectionResult`, which can be one of several zone types.
+pub trait DynZone {
+    /// Get an iterator over the coordinates of this zone.
+    fn dyn_coords(&self) -> Box<dyn Iterator<Item = Coord> + '_>;
+
+    /// Whether this zone contains the given coordinate.
+    fn dyn_contains(&self, coord: Coord) -> bool;
+}
+
+impl<Z: Zone> DynZone for Z {
+    fn dyn_coords(&self) -> Box<dyn Iterator<Item = Coord> + '_> {
+        Box::new(self.coords())
+    }
+
+    fn dyn_contains(&self, coord: Coord) -> bool {
+        self.contains(coord)
+    }
+}
+
 /// Type has a size known at compile time and can be indexed to produce a value
 /// of a specific type.
 pub trait FixedSizeIndexable {
@@ -79,6 +102,10 @@ pub trait FixedSizeIndexable {
     const NUM_ITEMS: usize;
 
     /// Get the child with the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is not less than `NUM_ITEMS`.
     fn get_at_index(&self, idx: usize) -> Self::Item;
 }
 
@@ -0,0 +1,691 @@
+//! Text descriptions of boards and deductions, for screen readers.
+//!
+//! Descriptions are prose rather than grids, and never rely on layout or
+//! highlighting to make sense. Houses are described in order and cells in
+//! row-major order within them, so the same board always reads the same way.
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::collections::indexed::FixedSizeIndex;
+use crate::trace::{ArcRemaining, Deduction, DeductionReason, UnsolveableReason};
+use crate::{AvailSet, Board, Col, Coord, House, Row, Sector, SectorCol, SectorRow, Val, Zone};
+
+/// Which houses a board description is split into.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum DescribeBy {
+    /// One sentence per row, top to bottom.
+    #[default]
+    Rows,
+    /// One sentence per box, left to right and then top to bottom.
+    Boxes,
+}
+
+/// How wordy a board description is.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum DescribeStyle {
+    /// Full words, such as "Row 1: 4 at column 3, others empty."
+    #[default]
+    Verbose,
+    /// Abbreviations, such as "R1: C3 4."
+    Compact,
+}
+
+/// Options for `Board::describe`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+pub struct DescribeOptions {
+    /// Which houses to describe the board by.
+    pub by: DescribeBy,
+    /// How wordy the description is.
+    pub style: DescribeStyle,
+    /// Number rows, columns, and boxes from 1 rather than from 0 as the rest of
+    /// the crate does. People count from 1, so this is on by default.
+    pub one_indexed: bool,
+}
+
+impl Default for DescribeOptions {
+    fn default() -> Self {
+        DescribeOptions {
+            by: DescribeBy::Rows,
+            style: DescribeStyle::Verbose,
+            one_indexed: true,
+        }
+    }
+}
+
+/// Describe every filled cell of the board, as `Board::describe` does.
+pub(crate) fn board(board: &Board, opts: &DescribeOptions) -> String {
+    let num = |idx: usize| idx + opts.one_indexed as usize;
+    let row = |coord: Coord| num(coord.row().inner() as usize);
+    let col = |coord: Coord| num(coord.col().inner() as usize);
+    let sentences: Vec<String> = match opts.by {
+        DescribeBy::Rows => Row::all()
+            .map(|zone| {
+                let label = match opts.style {
+                    DescribeStyle::Verbose => format!("Row {}", num(zone.inner() as usize)),
+                    DescribeStyle::Compact => format!("R{}", num(zone.inner() as usize)),
+                };
+                house(board, opts, label, zone.coords(), |coord| {
+                    match opts.style {
+                        DescribeStyle::Verbose => format!("column {}", col(coord)),
+                        DescribeStyle::Compact => format!("C{}", col(coord)),
+                    }
+                })
+            })
+            .collect(),
+        DescribeBy::Boxes => Sector::all()
+            .map(|zone| {
+                let label = match opts.style {
+                    DescribeStyle::Verbose => format!("Box {}", num(zone.idx())),
+                    DescribeStyle::Compact => format!("B{}", num(zone.idx())),
+                };
+                house(board, opts, label, zone.coords(), |coord| {
+                    match opts.style {
+                        DescribeStyle::Verbose => {
+                            format!("row {} column {}", row(coord), col(coord))
+                        }
+                        DescribeStyle::Compact => format!("R{}C{}", row(coord), col(coord)),
+                    }
+                })
+            })
+            .collect(),
+    };
+    sentences.join(" ")
+}
+
+/// Describe the filled cells of one house.
+fn house(
+    board: &Board,
+    opts: &DescribeOptions,
+    label: String,
+    coords: impl Iterator<Item = Coord>,
+    cell: impl Fn(Coord) -> String,
+) -> String {
+    let mut empty = 0;
+    let mut filled = Vec::new();
+    for coord in coords {
+        match (board[coord], opts.style) {
+            (None, _) => empty += 1,
+            (Some(val), DescribeStyle::Verbose) => {
+                filled.push(format!("{} at {}", val.val(), cell(coord)))
+            }
+            (Some(val), DescribeStyle::Compact) => {
+                filled.push(format!("{} {}", cell(coord), val.val()))
+            }
+        }
+    }
+    if filled.is_empty() {
+        return format!("{}: empty.", label);
+    }
+    let others = match opts.style {
+        DescribeStyle::Verbose if empty > 0 => ", others empty",
+        _ => "",
+    };
+    format!("{}: {}{}.", label, filled.join(", "), others)
+}
+
+/// Describe a deduction, as `Deduction::describe_accessible` does.
+pub(crate) fn deduction(deduction: &Deduction) -> String {
+    let rem = &deduction.remaining;
+    match deduction.reason {
+        DeductionReason::InitialState => {
+            let left: usize = rem.as_ref().iter().map(|avail| avail.len()).sum();
+            format!(
+                "Starting a new round of deductions with {} candidates left.",
+                left
+            )
+        }
+        DeductionReason::CoordNeighbors { pos, val } => format!(
+            "{} can only be {}, so {} is removed from every other cell in {}, {}, and {}.",
+            capitalize(&cell(pos)),
+            val.val(),
+            val.val(),
+            row(pos.row()),
+            col(pos.col()),
+            sector(pos.sector()),
+        ),
+        DeductionReason::UniqueInRow { pos, vals } => {
+            only_place(pos, vals, rem, |coord| col(coord.col()))
+        }
+        DeductionReason::UniqueInCol { pos, vals } => {
+            only_place(pos, vals, rem, |coord| row(coord.row()))
+        }
+        DeductionReason::UniqueInSector { pos, vals } => only_place(pos, vals, rem, cell),
+        DeductionReason::SecRowTriple { pos, vals } => {
+            let held = pos
+                .coords()
+                .fold(AvailSet::none(), |acc, coord| acc | rem[coord]);
+            format!(
+                "The part of {} in {}, {}, must hold {}, so {} {} removed from the rest of {} and {}.",
+                row(pos.row()),
+                sector(pos.sector()),
+                sector_row_span(pos),
+                digits(held),
+                digits(vals),
+                are(vals),
+                row(pos.row()),
+                sector(pos.sector()),
+            )
+        }
+        DeductionReason::SecColTriple { pos, vals } => {
+            let held = pos
+                .coords()
+                .fold(AvailSet::none(), |acc, coord| acc | rem[coord]);
+            format!(
+                "The part of {} in {}, {}, must hold {}, so {} {} removed from the rest of {} and {}.",
+                col(pos.col()),
+                sector(pos.sector()),
+                sector_col_span(pos),
+                digits(held),
+                digits(vals),
+                are(vals),
+                col(pos.col()),
+                sector(pos.sector()),
+            )
+        }
+        DeductionReason::SecOnlyRow { pos, vals } => {
+            confined(&sector(pos.sector()), vals, &row(pos.row()))
+        }
+        DeductionReason::SecOnlyCol { pos, vals } => {
+            confined(&sector(pos.sector()), vals, &col(pos.col()))
+        }
+        DeductionReason::RowOnlySec { pos, vals } => {
+            confined(&row(pos.row()), vals, &sector(pos.sector()))
+        }
+        DeductionReason::ColOnlySec { pos, vals } => {
+            confined(&col(pos.col()), vals, &sector(pos.sector()))
+        }
+        DeductionReason::HiddenPair { pos, cells, vals } => hidden(pos, &cells, vals),
+        DeductionReason::HiddenTriple { pos, cells, vals } => hidden(pos, &cells, vals),
+        DeductionReason::XWing {
+            rows,
+            cols,
+            val,
+            by_rows,
+        } => {
+            let rows = format!("{} and {}", row(rows[0]), row(rows[1]));
+            let cols = format!("{} and {}", col(cols[0]), col(cols[1]));
+            if by_rows {
+                confined(&rows, AvailSet::only(val), &cols)
+            } else {
+                confined(&cols, AvailSet::only(val), &rows)
+            }
+        }
+        DeductionReason::Unsolveable(ref reason) => {
+            format!("The board has no solution: {}.", unsolveable(reason))
+        }
+    }
+}
+
+/// Describe why a board has no solution.
+fn unsolveable(reason: &UnsolveableReason) -> String {
+    match *reason {
+        UnsolveableReason::Empty { pos } => format!("{} has no candidates left", cell(pos)),
+        UnsolveableReason::RowValsMustShare { pos, vals } => must_share(&row(pos), vals),
+        UnsolveableReason::ColValsMustShare { pos, vals } => must_share(&col(pos), vals),
+        UnsolveableReason::SecValsMustShare { pos, vals } => must_share(&sector(pos), vals),
+        UnsolveableReason::RowMissingVal { pos, val } => missing(&row(pos), val),
+        UnsolveableReason::ColMissingVal { pos, val } => missing(&col(pos), val),
+        UnsolveableReason::SecMissingVal { pos, val } => missing(&sector(pos), val),
+        UnsolveableReason::SecRowTooFewVals { pos } => format!(
+            "the part of {} in {} has fewer candidates left than cells",
+            row(pos.row()),
+            sector(pos.sector()),
+        ),
+        UnsolveableReason::SecColTooFewVals { pos } => format!(
+            "the part of {} in {} has fewer candidates left than cells",
+            col(pos.col()),
+            sector(pos.sector()),
+        ),
+    }
+}
+
+/// Describe values which each have one cell left in a house, naming the cell
+/// with `place`.
+fn only_place<Z: Zone + HouseName>(
+    zone: Z,
+    vals: AvailSet,
+    rem: &ArcRemaining,
+    place: impl Fn(Coord) -> String,
+) -> String {
+    vals.iter()
+        .map(|val| {
+            let at = zone
+                .coords()
+                .find(|&coord| rem[coord].contains(val))
+                .map_or_else(|| "one cell".to_string(), &place);
+            format!(
+                "In {}, the digit {} can only be placed at {}.",
+                zone.name(),
+                val.val(),
+                at
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Describe values confined to the part of one house which overlaps another,
+/// and so removed from the rest of the other house.
+fn confined(house: &str, vals: AvailSet, other: &str) -> String {
+    let pronoun = if vals.len() == 1 { "it is" } else { "they are" };
+    format!(
+        "In {}, {} can only be placed in {}, so {} removed from the rest of {}.",
+        house,
+        digits(vals),
+        other,
+        pronoun,
+        other
+    )
+}
+
+/// Describe values confined to the same cells of a house, so every other
+/// value is removed from those cells.
+fn hidden(house: House, cells: &[Coord], vals: AvailSet) -> String {
+    let house = match house {
+        House::Row(pos) => row(pos),
+        House::Col(pos) => col(pos),
+        House::Sector(pos) => sector(pos),
+    };
+    let cells: Vec<String> = cells.iter().map(|&coord| cell(coord)).collect();
+    let cells = match cells.as_slice() {
+        [] => "no cells".to_string(),
+        [one] => one.clone(),
+        [first, second] => format!("{} and {}", first, second),
+        [init @ .., last] => format!("{}, and {}", init.join(", "), last),
+    };
+    format!(
+        "In {}, {} can only be placed in {}, so every other digit is removed from those cells.",
+        house,
+        digits(vals),
+        cells
+    )
+}
+
+fn must_share(house: &str, vals: AvailSet) -> String {
+    format!(
+        "in {}, {} can only be placed in the same cell",
+        house,
+        digits(vals)
+    )
+}
+
+fn missing(house: &str, val: Val) -> String {
+    format!(
+        "the digit {} can no longer be placed anywhere in {}",
+        val.val(),
+        house
+    )
+}
+
+/// Houses which can be named in a sentence.
+trait HouseName {
+    fn name(&self) -> String;
+}
+
+impl HouseName for Row {
+    fn name(&self) -> String {
+        row(*self)
+    }
+}
+
+impl HouseName for Col {
+    fn name(&self) -> String {
+        col(*self)
+    }
+}
+
+impl HouseName for Sector {
+    fn name(&self) -> String {
+        sector(*self)
+    }
+}
+
+fn row(row: Row) -> String {
+    format!("row {}", row.inner() + 1)
+}
+
+fn col(col: Col) -> String {
+    format!("column {}", col.inner() + 1)
+}
+
+fn sector(sector: Sector) -> String {
+    format!("box {}", sector.idx() + 1)
+}
+
+fn cell(coord: Coord) -> String {
+    format!("{} {}", row(coord.row()), col(coord.col()))
+}
+
+fn sector_row_span(pos: SectorRow) -> String {
+    let first = pos.base_col().inner() + 1;
+    format!("columns {} to {}", first, first + Sector::WIDTH - 1)
+}
+
+fn sector_col_span(pos: SectorCol) -> String {
+    let first = pos.base_row().inner() + 1;
+    format!("rows {} to {}", first, first + Sector::HEIGHT - 1)
+}
+
+/// Name a set of digits, as in "the digit 7" or "the digits 1, 3, and 7".
+fn digits(vals: AvailSet) -> String {
+    let vals: Vec<String> = vals.iter().map(|val| val.val().to_string()).collect();
+    match vals.as_slice() {
+        [] => "no digits".to_string(),
+        [one] => format!("the digit {}", one),
+        [first, second] => format!("the digits {} and {}", first, second),
+        [init @ .., last] => format!("the digits {}, and {}", init.join(", "), last),
+    }
+}
+
+fn are(vals: AvailSet) -> &'static str {
+    if vals.len() == 1 {
+        "is"
+    } else {
+        "are"
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::indexed::IndexMap;
+    use crate::fixtures::puzzle1;
+
+    fn fixture() -> Board {
+        puzzle1()
+    }
+
+    #[test]
+    fn describe_rows() {
+        let board = fixture();
+        let text = board.describe(&Default::default());
+        assert!(text.starts_with(
+            "Row 1: 1 at column 4, others empty. \
+             Row 2: 5 at column 5, 8 at column 6, 6 at column 7, 1 at column 9, others empty. "
+        ));
+        assert!(text.ends_with("Row 9: 3 at column 6, others empty."));
+
+        let compact = board.describe(&DescribeOptions {
+            style: DescribeStyle::Compact,
+            one_indexed: false,
+            ..Default::default()
+        });
+        assert!(compact.starts_with("R0: C3 1. R1: C4 5, C5 8, C6 6, C8 1. "));
+
+        assert_eq!(
+            Board::new().describe(&Default::default()),
+            (1..=9)
+                .map(|row| format!("Row {}: empty.", row))
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+        let solution = board.solve().unwrap();
+        assert!(solution
+            .describe(&Default::default())
+            .starts_with("Row 1: 4 at column 1, 6 at column 2, "));
+        assert!(!solution.describe(&Default::default()).contains("others"));
+    }
+
+    #[test]
+    fn describe_boxes() {
+        let text = fixture().describe(&DescribeOptions {
+            by: DescribeBy::Boxes,
+            ..Default::default()
+        });
+        assert!(text.starts_with(
+            "Box 1: 8 at row 3 column 1, 1 at row 3 column 3, others empty. \
+             Box 2: 1 at row 1 column 4, 5 at row 2 column 5, 8 at row 2 column 6, \
+             3 at row 3 column 4, 6 at row 3 column 5, others empty. "
+        ));
+        let compact = fixture().describe(&DescribeOptions {
+            by: DescribeBy::Boxes,
+            style: DescribeStyle::Compact,
+            ..Default::default()
+        });
+        assert!(compact.ends_with("B9: R7C7 5, R7C9 6."));
+    }
+
+    #[test]
+    fn mentions_each_cell_once() {
+        for board in [fixture(), fixture().solve().unwrap(), Board::new()] {
+            let text = board.describe(&Default::default());
+            for (row, sentence) in Row::all().zip(text.split(". Row ")) {
+                let mentioned = sentence.matches(" at column ").count();
+                let filled = row.coords().filter(|&coord| board[coord].is_some()).count();
+                assert_eq!(mentioned, filled, "{}", sentence);
+                for coord in row.coords() {
+                    if let Some(val) = board[coord] {
+                        let mention =
+                            format!("{} at column {}", val.val(), coord.col().inner() + 1);
+                        assert_eq!(sentence.matches(&mention).count(), 1, "{}", sentence);
+                    }
+                }
+            }
+        }
+    }
+
+    fn coord(row: u8, col: u8) -> Coord {
+        Coord::new(Row::new(row), Col::new(col))
+    }
+
+    /// A deduction with every candidate open except that 7 is only left at
+    /// row 4 column 6 within its row, column, and box.
+    fn deduction(reason: DeductionReason) -> Deduction {
+        let seven = AvailSet::only(Val::new(7));
+        let at = coord(3, 5);
+        let mut map = IndexMap::with_value(AvailSet::all());
+        for other in at.neighbors() {
+            map[other] -= Val::new(7);
+        }
+        map[at] = seven;
+        // The part of row 1 in box 2 only has 1, 5, and 8 left.
+        for col in 3..6 {
+            map[coord(0, col)] = AvailSet::only(Val::new(1)) | Val::new(5) | Val::new(8);
+        }
+        Deduction {
+            reason,
+            remaining: ArcRemaining::from(map),
+            before: None,
+            eliminated_count: 0,
+        }
+    }
+
+    #[test]
+    fn describe_deductions() {
+        let seven = AvailSet::only(Val::new(7));
+        let at = coord(3, 5);
+        let cases = [
+            (
+                DeductionReason::InitialState,
+                "Starting a new round of deductions with 684 candidates left.",
+            ),
+            (
+                DeductionReason::CoordNeighbors {
+                    pos: at,
+                    val: Val::new(7),
+                },
+                "Row 4 column 6 can only be 7, so 7 is removed from every other cell in row 4, \
+                 column 6, and box 5.",
+            ),
+            (
+                DeductionReason::UniqueInRow {
+                    pos: at.row(),
+                    vals: seven,
+                },
+                "In row 4, the digit 7 can only be placed at column 6.",
+            ),
+            (
+                DeductionReason::UniqueInCol {
+                    pos: at.col(),
+                    vals: seven,
+                },
+                "In column 6, the digit 7 can only be placed at row 4.",
+            ),
+            (
+                DeductionReason::UniqueInSector {
+                    pos: at.sector(),
+                    vals: seven,
+                },
+                "In box 5, the digit 7 can only be placed at row 4 column 6.",
+            ),
+            (
+                DeductionReason::SecRowTriple {
+                    pos: coord(0, 3).sector_row(),
+                    vals: AvailSet::only(Val::new(1)) | Val::new(5),
+                },
+                "The part of row 1 in box 2, columns 4 to 6, must hold the digits 1, 5, and 8, \
+                 so the digits 1 and 5 are removed from the rest of row 1 and box 2.",
+            ),
+            (
+                DeductionReason::SecColTriple {
+                    pos: at.sector_col(),
+                    vals: seven,
+                },
+                "The part of column 6 in box 5, rows 4 to 6, must hold the digits 1, 2, 3, 4, \
+                 5, 6, 7, 8, and 9, so the digit 7 is removed from the rest of column 6 and \
+                 box 5.",
+            ),
+            (
+                DeductionReason::SecOnlyRow {
+                    pos: at.sector_row(),
+                    vals: seven,
+                },
+                "In box 5, the digit 7 can only be placed in row 4, so it is removed from the \
+                 rest of row 4.",
+            ),
+            (
+                DeductionReason::SecOnlyCol {
+                    pos: at.sector_col(),
+                    vals: AvailSet::only(Val::new(2)) | Val::new(7),
+                },
+                "In box 5, the digits 2 and 7 can only be placed in column 6, so they are \
+                 removed from the rest of column 6.",
+            ),
+            (
+                DeductionReason::RowOnlySec {
+                    pos: at.sector_row(),
+                    vals: seven,
+                },
+                "In row 4, the digit 7 can only be placed in box 5, so it is removed from the \
+                 rest of box 5.",
+            ),
+            (
+                DeductionReason::ColOnlySec {
+                    pos: at.sector_col(),
+                    vals: seven,
+                },
+                "In column 6, the digit 7 can only be placed in box 5, so it is removed from \
+                 the rest of box 5.",
+            ),
+            (
+                DeductionReason::XWing {
+                    rows: [Row::new(1), Row::new(4)],
+                    cols: [Col::new(2), Col::new(7)],
+                    val: Val::new(5),
+                    by_rows: true,
+                },
+                "In row 2 and row 5, the digit 5 can only be placed in column 3 and column 8, so \
+                 it is removed from the rest of column 3 and column 8.",
+            ),
+            (
+                DeductionReason::XWing {
+                    rows: [Row::new(1), Row::new(4)],
+                    cols: [Col::new(2), Col::new(7)],
+                    val: Val::new(5),
+                    by_rows: false,
+                },
+                "In column 3 and column 8, the digit 5 can only be placed in row 2 and row 5, so \
+                 it is removed from the rest of row 2 and row 5.",
+            ),
+            (
+                DeductionReason::Unsolveable(UnsolveableReason::RowMissingVal {
+                    pos: Row::new(8),
+                    val: Val::new(3),
+                }),
+                "The board has no solution: the digit 3 can no longer be placed anywhere in \
+                 row 9.",
+            ),
+        ];
+        for (reason, text) in cases {
+            assert_eq!(deduction(reason).describe_accessible(), text);
+        }
+    }
+
+    #[test]
+    fn describe_unsolveable() {
+        let vals = AvailSet::only(Val::new(2)) | Val::new(4);
+        let srow = coord(4, 4).sector_row();
+        let scol = coord(4, 4).sector_col();
+        let cases = [
+            (
+                UnsolveableReason::Empty { pos: coord(0, 8) },
+                "row 1 column 9 has no candidates left",
+            ),
+            (
+                UnsolveableReason::RowValsMustShare {
+                    pos: Row::new(1),
+                    vals,
+                },
+                "in row 2, the digits 2 and 4 can only be placed in the same cell",
+            ),
+            (
+                UnsolveableReason::ColValsMustShare {
+                    pos: Col::new(1),
+                    vals,
+                },
+                "in column 2, the digits 2 and 4 can only be placed in the same cell",
+            ),
+            (
+                UnsolveableReason::SecValsMustShare {
+                    pos: Sector::from_idx(2),
+                    vals,
+                },
+                "in box 3, the digits 2 and 4 can only be placed in the same cell",
+            ),
+            (
+                UnsolveableReason::ColMissingVal {
+                    pos: Col::new(0),
+                    val: Val::new(9),
+                },
+                "the digit 9 can no longer be placed anywhere in column 1",
+            ),
+            (
+                UnsolveableReason::SecMissingVal {
+                    pos: Sector::from_idx(8),
+                    val: Val::new(9),
+                },
+                "the digit 9 can no longer be placed anywhere in box 9",
+            ),
+            (
+                UnsolveableReason::SecRowTooFewVals { pos: srow },
+                "the part of row 5 in box 5 has fewer candidates left than cells",
+            ),
+            (
+                UnsolveableReason::SecColTooFewVals { pos: scol },
+                "the part of column 5 in box 5 has fewer candidates left than cells",
+            ),
+        ];
+        for (reason, text) in cases {
+            assert_eq!(unsolveable(&reason), text);
+        }
+    }
+}
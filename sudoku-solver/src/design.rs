@@ -0,0 +1,270 @@
+//! Live feedback while designing a puzzle by hand.
+//!
+//! A `DesignSession` tracks whether the puzzle being designed has no solution,
+//! several, or exactly one, updating after every clue placed or cleared. Adding
+//! a clue can only shrink the set of solutions, so most edits are classified
+//! from the solutions already known without running the solver again.
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::{two_solutions, PuzzleStrength};
+use crate::{Board, Coord, Val};
+
+/// How many solutions a puzzle has.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum SolutionClass {
+    /// The puzzle has no solution.
+    NoSolution,
+    /// The puzzle has more than one solution, so it needs more clues.
+    Multiple,
+    /// The puzzle has exactly one solution.
+    Unique,
+}
+
+/// A change in the `SolutionClass` of a puzzle caused by an edit.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Transition {
+    /// Class before the edit.
+    pub from: SolutionClass,
+    /// Class after the edit.
+    pub to: SolutionClass,
+}
+
+/// What is known about the solutions of the current board.
+#[derive(Clone, Debug)]
+enum Solutions {
+    None,
+    /// The only solution.
+    Unique(Box<Board>),
+    /// Two distinct solutions.
+    Multiple(Box<Board>, Box<Board>),
+}
+
+impl Solutions {
+    /// Find the solutions of the board with the solver.
+    fn classify(board: &Board) -> Self {
+        match two_solutions(board) {
+            None => Solutions::None,
+            Some((first, None)) => Solutions::Unique(Box::new(first)),
+            Some((first, Some(second))) => Solutions::Multiple(Box::new(first), Box::new(second)),
+        }
+    }
+
+    fn class(&self) -> SolutionClass {
+        match self {
+            Solutions::None => SolutionClass::NoSolution,
+            Solutions::Unique(_) => SolutionClass::Unique,
+            Solutions::Multiple(..) => SolutionClass::Multiple,
+        }
+    }
+}
+
+/// A puzzle being designed one clue at a time, along with whether it currently
+/// has a unique solution.
+#[derive(Clone, Debug)]
+pub struct DesignSession {
+    board: Board,
+    solutions: Solutions,
+    /// Difficulty of the current board, measured on first request.
+    difficulty: Option<PuzzleStrength>,
+    full_checks: usize,
+}
+
+impl DesignSession {
+    /// Start designing from an empty board.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::from_board(Board::new())
+    }
+
+    /// Start designing from the given clues.
+    #[must_use]
+    pub fn from_board(board: Board) -> Self {
+        let mut session = DesignSession {
+            solutions: Solutions::None,
+            board,
+            difficulty: None,
+            full_checks: 0,
+        };
+        session.solutions = session.classify();
+        session
+    }
+
+    /// The clues placed so far.
+    #[must_use]
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Whether the current clues have no solution, several, or exactly one.
+    #[must_use]
+    pub fn status(&self) -> SolutionClass {
+        self.solutions.class()
+    }
+
+    /// How hard the puzzle is to solve, if it has a unique solution. Measured
+    /// the first time it is asked for after each edit.
+    pub fn difficulty(&mut self) -> Option<&PuzzleStrength> {
+        if self.status() != SolutionClass::Unique {
+            return None;
+        }
+        let board = &self.board;
+        Some(
+            self.difficulty
+                .get_or_insert_with(|| PuzzleStrength::measure(board)),
+        )
+    }
+
+    /// Number of times the solver has been run to classify the board from
+    /// scratch, because the known solutions could not decide an edit.
+    #[must_use]
+    pub fn full_checks(&self) -> usize {
+        self.full_checks
+    }
+
+    /// Place a clue, replacing any clue already in the cell. Returns the change
+    /// in status, if the status changed.
+    pub fn place(&mut self, coord: Coord, val: Val) -> Option<Transition> {
+        match self.board[coord] {
+            Some(old) if old == val => return None,
+            // Replacing a clue removes one first, which can add solutions.
+            Some(_) => {
+                self.board[coord] = Some(val);
+                let solutions = self.classify();
+                return self.update(solutions);
+            }
+            None => self.board[coord] = Some(val),
+        }
+        let matches = |solution: &Board| solution[coord] == Some(val);
+        let solutions = match &self.solutions {
+            Solutions::None => Solutions::None,
+            Solutions::Unique(solution) if matches(solution) => Solutions::Unique(solution.clone()),
+            Solutions::Unique(_) => Solutions::None,
+            Solutions::Multiple(first, second) if matches(first) && matches(second) => {
+                Solutions::Multiple(first.clone(), second.clone())
+            }
+            Solutions::Multiple(..) => self.classify(),
+        };
+        self.update(solutions)
+    }
+
+    /// Clear the clue in a cell, if there is one. Returns the change in status,
+    /// if the status changed.
+    pub fn clear(&mut self, coord: Coord) -> Option<Transition> {
+        self.board[coord].take()?;
+        let solutions = match &self.solutions {
+            // Every solution still solves the board with fewer clues.
+            Solutions::Multiple(first, second) => {
+                Solutions::Multiple(first.clone(), second.clone())
+            }
+            Solutions::None | Solutions::Unique(_) => self.classify(),
+        };
+        self.update(solutions)
+    }
+
+    /// Classify the current board from scratch.
+    fn classify(&mut self) -> Solutions {
+        self.full_checks += 1;
+        Solutions::classify(&self.board)
+    }
+
+    /// Replace the known solutions after an edit.
+    fn update(&mut self, solutions: Solutions) -> Option<Transition> {
+        self.difficulty = None;
+        let from = self.status();
+        self.solutions = solutions;
+        let to = self.status();
+        (from != to).then_some(Transition { from, to })
+    }
+}
+
+impl Default for DesignSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::puzzle1;
+    use crate::{Col, Row, Zone};
+
+    #[test]
+    fn scripted_design() {
+        crate::setup();
+
+        let puzzle = puzzle1();
+        let mut session = DesignSession::new();
+        assert_eq!(session.status(), SolutionClass::Multiple);
+        assert_eq!(session.difficulty(), None);
+
+        // Place the clues in row-major order. The puzzle only becomes unique
+        // with the last clue, in row 8 column 5.
+        let clues: Vec<_> = Coord::all().filter(|&c| puzzle[c].is_some()).collect();
+        let (&last, rest) = clues.split_last().unwrap();
+        for &coord in rest {
+            assert_eq!(session.place(coord, puzzle[coord].unwrap()), None);
+        }
+        assert_eq!(last, Coord::new(Row::new(8), Col::new(5)));
+        let unique = Transition {
+            from: SolutionClass::Multiple,
+            to: SolutionClass::Unique,
+        };
+        assert_eq!(session.place(last, puzzle[last].unwrap()), Some(unique));
+        assert_eq!(session.board(), &puzzle);
+        assert!(session.difficulty().unwrap().solved_without_guess);
+
+        // Removing that clue reverts it.
+        assert_eq!(
+            session.clear(last),
+            Some(Transition {
+                from: SolutionClass::Unique,
+                to: SolutionClass::Multiple,
+            })
+        );
+        assert_eq!(session.place(last, puzzle[last].unwrap()), Some(unique));
+        let edits = clues.len() + 2;
+
+        // Once the solution is known, new clues are checked against it
+        // without running the solver, and nothing solves a contradiction.
+        let checks = session.full_checks();
+        let blank = Coord::new(Row::new(0), Col::new(0));
+        assert_eq!(session.place(blank, Val::new(4)), None);
+        let wrong = Coord::new(Row::new(0), Col::new(1));
+        assert_eq!(
+            session.place(wrong, Val::new(1)),
+            Some(Transition {
+                from: SolutionClass::Unique,
+                to: SolutionClass::NoSolution,
+            })
+        );
+        assert_eq!(session.difficulty(), None);
+        let other = Coord::new(Row::new(0), Col::new(2));
+        assert_eq!(session.place(other, Val::new(7)), None);
+        assert_eq!(session.full_checks(), checks);
+        assert_eq!(
+            session.clear(wrong),
+            Some(Transition {
+                from: SolutionClass::NoSolution,
+                to: SolutionClass::Unique,
+            })
+        );
+        let edits = edits + 4;
+
+        // Clues which agree with both known solutions skip the solver, so it
+        // runs for fewer than one edit in each.
+        assert!(
+            session.full_checks() < edits,
+            "{} full checks for {} edits",
+            session.full_checks(),
+            edits
+        );
+    }
+}
@@ -0,0 +1,11 @@
+//! Every error type in the library, gathered in one place. Each is also
+//! available from the module it comes from.
+pub use crate::precompute::PrecomputeError;
+pub use crate::sized::ParseSizedBoardError;
+#[cfg(feature = "serde")]
+pub use crate::trace::{TraceFormatError, TraceParseError};
+#[cfg(feature = "serde")]
+pub use crate::BoardVersionError;
+pub use crate::{
+    BudgetExceeded, DeadlineExceeded, IncorrectSize, OutOfRange, ParseBoardError, UnknownRule,
+};
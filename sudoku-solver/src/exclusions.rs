@@ -0,0 +1,235 @@
+//! Explicit negative constraints on which values may appear in which cells.
+use std::iter::FusedIterator;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{AvailSet, Board, Coord, Val, Zone};
+
+/// A set of values which are forbidden from specific cells, even though no
+/// value placed on the board implies it. Used for puzzle variants and hints
+/// like "no 5 in r3c4".
+///
+/// The same coordinate may appear more than once, in which case the excluded
+/// values are combined.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct Exclusions(Vec<(Coord, AvailSet)>);
+
+impl Exclusions {
+    /// Create an empty set of exclusions.
+    #[must_use]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Forbid the given values from appearing at the given coordinate.
+    pub fn exclude(&mut self, coord: Coord, vals: AvailSet) {
+        self.0.push((coord, vals));
+    }
+
+    /// Get the set of values excluded from the given coordinate.
+    #[must_use]
+    pub fn excluded(&self, coord: Coord) -> AvailSet {
+        self.0
+            .iter()
+            .filter(|(pos, _)| *pos == coord)
+            .fold(AvailSet::none(), |acc, (_, vals)| acc | *vals)
+    }
+
+    /// Returns true if the given value is excluded from the given coordinate.
+    #[must_use]
+    pub fn is_excluded(&self, coord: Coord, val: Val) -> bool {
+        self.0
+            .iter()
+            .any(|(pos, vals)| *pos == coord && vals.contains(val))
+    }
+
+    /// Returns true if there are no exclusions.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|(_, vals)| vals.is_empty())
+    }
+
+    /// Iterator over the exclusions in the order they were added.
+    #[must_use]
+    pub fn iter(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = (Coord, AvailSet)> + ExactSizeIterator + FusedIterator + '_
+    {
+        self.0.iter().copied()
+    }
+
+    /// Iterator over the cells of the board which hold a value forbidden by
+    /// these exclusions, along with the forbidden value.
+    #[must_use]
+    pub fn violations<'a>(
+        &'a self,
+        board: &'a Board,
+    ) -> impl FusedIterator<Item = (Coord, Val)> + 'a {
+        Coord::all()
+            .filter_map(move |coord| board[coord].map(|val| (coord, val)))
+            .filter(move |&(coord, val)| self.is_excluded(coord, val))
+    }
+
+    /// Returns true if no filled cell of the board holds an excluded value.
+    #[must_use]
+    pub fn permits(&self, board: &Board) -> bool {
+        self.violations(board).next().is_none()
+    }
+}
+
+impl From<Vec<(Coord, AvailSet)>> for Exclusions {
+    fn from(exclusions: Vec<(Coord, AvailSet)>) -> Self {
+        Self(exclusions)
+    }
+}
+
+impl From<Exclusions> for Vec<(Coord, AvailSet)> {
+    fn from(exclusions: Exclusions) -> Self {
+        exclusions.0
+    }
+}
+
+impl FromIterator<(Coord, AvailSet)> for Exclusions {
+    fn from_iter<I: IntoIterator<Item = (Coord, AvailSet)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Extend<(Coord, AvailSet)> for Exclusions {
+    fn extend<I: IntoIterator<Item = (Coord, AvailSet)>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board_art;
+    use crate::{Col, Row};
+
+    /// Solution to puzzle1 with a deadly rectangle at rows 4-5, cols 2 and 4
+    /// removed, so it has exactly two solutions.
+    fn ambiguous() -> Board {
+        board_art!(
+            "467|192|385",
+            "329|458|671",
+            "851|367|294",
+            "---+---+---",
+            "518|279|463",
+            "27 |6 1|859",
+            "69 |8 5|127",
+            "---+---+---",
+            "732|984|516",
+            "145|726|938",
+            "986|513|742",
+        )
+    }
+
+    #[test]
+    fn exclusion_picks_solution() {
+        crate::setup();
+
+        let board = ambiguous();
+        let pos = Coord::new(Row::new(4), Col::new(2));
+        let mut exclusions = Exclusions::new();
+        exclusions.exclude(pos, AvailSet::only(Val::new(3)));
+
+        let expected = board_art!(
+            "467|192|385",
+            "329|458|671",
+            "851|367|294",
+            "---+---+---",
+            "518|279|463",
+            "274|631|859",
+            "693|845|127",
+            "---+---+---",
+            "732|984|516",
+            "145|726|938",
+            "986|513|742",
+        );
+        let res = board.solve_with_exclusions(&exclusions);
+        assert_eq!(res, Some(expected));
+        assert!(exclusions.permits(res.as_ref().unwrap()));
+
+        exclusions.exclude(pos, AvailSet::only(Val::new(4)));
+        assert_eq!(board.solve_with_exclusions(&exclusions), None);
+    }
+
+    #[test]
+    fn hints_respect_exclusions() {
+        crate::setup();
+
+        let board = ambiguous();
+        let pos = Coord::new(Row::new(4), Col::new(2));
+        let both = AvailSet::only(Val::new(3)) | Val::new(4);
+        let mut exclusions = Exclusions::new();
+        assert_eq!(board.legal_values(pos, &exclusions), both);
+        // Either value fits the rectangle, so logic alone can't place one.
+        assert_eq!(board.hint(), None);
+        assert_eq!(board.hint_with_exclusions(&exclusions), None);
+
+        exclusions.exclude(pos, AvailSet::only(Val::new(3)));
+        assert_eq!(
+            board.legal_values(pos, &exclusions),
+            AvailSet::only(Val::new(4))
+        );
+        // With the exclusion the board has one solution, which the hint is
+        // part of.
+        let solution = board.solve_with_exclusions(&exclusions).unwrap();
+        let hint = board.hint_with_exclusions(&exclusions).unwrap();
+        assert_eq!(board[hint.pos], None);
+        assert_eq!(solution[hint.pos], Some(hint.val));
+
+        // Hints never place an excluded value, whichever cell they pick.
+        let other = Coord::new(Row::new(5), Col::new(4));
+        let exclusions: Exclusions = vec![(other, AvailSet::only(Val::new(4)))].into();
+        let hint = board.hint_with_exclusions(&exclusions).unwrap();
+        assert!(!exclusions.is_excluded(hint.pos, hint.val));
+        assert!(board.legal_values(hint.pos, &exclusions).contains(hint.val));
+    }
+
+    #[test]
+    fn given_violates_exclusion() {
+        crate::setup();
+
+        let board = ambiguous();
+        let pos = Coord::new(Row::new(0), Col::new(0));
+        let exclusions: Exclusions = vec![(pos, AvailSet::only(Val::new(4)))].into();
+        let violations: Vec<_> = exclusions.violations(&board).collect();
+        assert_eq!(violations, vec![(pos, Val::new(4))]);
+        assert!(!exclusions.permits(&board));
+        assert_eq!(board.solve_with_exclusions(&exclusions), None);
+    }
+
+    #[test]
+    fn excluded_combines() {
+        let pos = Coord::new(Row::new(2), Col::new(7));
+        let mut exclusions = Exclusions::new();
+        assert!(exclusions.is_empty());
+        exclusions.exclude(pos, AvailSet::only(Val::new(1)));
+        exclusions.exclude(pos, AvailSet::only(Val::new(9)));
+        assert!(!exclusions.is_empty());
+        assert_eq!(
+            exclusions.excluded(pos),
+            AvailSet::only(Val::new(1)) | Val::new(9)
+        );
+        assert!(exclusions.is_excluded(pos, Val::new(9)));
+        assert!(!exclusions.is_excluded(pos, Val::new(5)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize() {
+        let exclusions: Exclusions = vec![(
+            Coord::new(Row::new(3), Col::new(4)),
+            AvailSet::only(Val::new(5)) | Val::new(7),
+        )]
+        .into();
+        let ser = serde_json::to_string(&exclusions).unwrap();
+        assert_eq!(ser, r#"[[{"row":3,"col":4},[5,7]]]"#);
+        let roundtrip: Exclusions = serde_json::from_str(&ser).unwrap();
+        assert_eq!(roundtrip, exclusions);
+    }
+}
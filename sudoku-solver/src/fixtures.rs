@@ -0,0 +1,54 @@
+//! Puzzles shared by this crate's unit tests.
+use crate::{board_art, Board};
+
+/// Arto Inkala's "hardest sudoku", which the deductive rules can't finish and
+/// so needs guessing.
+pub(crate) fn inkala() -> Board {
+    board_art!(
+        "8  |   |   ",
+        "  3|6  |   ",
+        " 7 | 9 |2  ",
+        "---+---+---",
+        " 5 |  7|   ",
+        "   | 45|7  ",
+        "   |1  | 3 ",
+        "---+---+---",
+        "  1|   | 68",
+        "  8|5  | 1 ",
+        " 9 |   |4  ",
+    )
+}
+
+/// A puzzle which deduction alone solves, without guessing.
+pub(crate) fn puzzle1() -> Board {
+    board_art!(
+        "   |1  |   ",
+        "   | 58|6 1",
+        "8 1|36 | 9 ",
+        "---+---+---",
+        "5  |   |4 3",
+        "  3|6 1|8  ",
+        "6 4|   |  7",
+        "---+---+---",
+        " 3 | 84|5 6",
+        "1 5|72 |   ",
+        "   |  3|   ",
+    )
+}
+
+/// A board with no solution, which deduction alone shows without guessing.
+pub(crate) fn contradictory() -> Board {
+    board_art!(
+        "349|   |65 ",
+        " 5 |8 7|  3",
+        "   |46 |   ",
+        "---+---+---",
+        "27 |   |   ",
+        "  4|5 1|8  ",
+        "   |   | 32",
+        "---+---+---",
+        "   | 42|   ",
+        "9  |3 6| 2 ",
+        " 27|   |31 ",
+    )
+}
@@ -0,0 +1,175 @@
+//! Deterministic puzzle generation.
+//!
+//! Puzzles are generated by filling a random grid, then removing clues in a
+//! random order for as long as the puzzle keeps a unique solution and is no
+//! harder than requested. All randomness comes from a seeded internal
+//! generator, so the same seed gives the same puzzle on every machine.
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::PuzzleStrength;
+use crate::mask::SplitMix64;
+use crate::{AvailSet, Board, Coord, ReduceOptions, RuleKind, Sector, Val, Zone};
+
+/// Number of full grids to dig before settling for a puzzle which did not reach
+/// the requested difficulty.
+const ATTEMPTS: usize = 16;
+
+/// How hard a puzzle is for a person, going by the techniques needed to solve
+/// it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Difficulty {
+    /// Solved with naked and hidden singles alone.
+    Easy,
+    /// Solved without guessing, but needs the sector and line intersection
+    /// rules.
+    Medium,
+    /// Can't be solved by the deduction rules alone, so needs a guess.
+    Hard,
+}
+
+impl Difficulty {
+    /// Rate the given puzzle, solving it with the human rule priority. Returns
+    /// None if the puzzle has no solution.
+    #[must_use]
+    pub fn of(board: &Board) -> Option<Self> {
+        let strength = PuzzleStrength::measure_with(board, &ReduceOptions::human());
+        if !strength.solved {
+            None
+        } else if !strength.solved_without_guess {
+            Some(Difficulty::Hard)
+        } else if strength.deductions.keys().all(|&kind| is_single(kind)) {
+            Some(Difficulty::Easy)
+        } else {
+            Some(Difficulty::Medium)
+        }
+    }
+}
+
+/// Whether the rule is a naked or hidden single.
+fn is_single(kind: RuleKind) -> bool {
+    matches!(
+        kind,
+        RuleKind::CoordNeighbors
+            | RuleKind::UniqueInRow
+            | RuleKind::UniqueInCol
+            | RuleKind::UniqueInSector
+    )
+}
+
+/// Generate a puzzle from the seed, as described on `Board::daily`.
+pub(crate) fn daily(seed: u64, difficulty: Difficulty) -> Board {
+    let mut rng = SplitMix64(seed);
+    let mut best: Option<(Difficulty, Board)> = None;
+    for _ in 0..ATTEMPTS {
+        let puzzle = dig(&full_grid(&mut rng), difficulty, &mut rng);
+        let reached = Difficulty::of(&puzzle).expect("dug puzzle has a solution");
+        if reached == difficulty {
+            return puzzle;
+        }
+        if best.as_ref().is_none_or(|(best, _)| reached > *best) {
+            best = Some((reached, puzzle));
+        }
+    }
+    best.expect("at least one attempt was made").1
+}
+
+/// Fill a random solved grid from the seed.
+#[cfg(feature = "rand")]
+pub(crate) fn solved(seed: u64) -> Board {
+    full_grid(&mut SplitMix64(seed))
+}
+
+/// Fill a random solved grid. The sectors on the main diagonal share no row or
+/// column, so each can be any permutation of the values, and the solver fills
+/// in the rest.
+fn full_grid(rng: &mut SplitMix64) -> Board {
+    let mut board = Board::new();
+    for sector in Sector::all().step_by(4) {
+        let mut vals: Vec<Val> = AvailSet::all().into_iter().collect();
+        rng.shuffle(&mut vals);
+        for (coord, val) in sector.coords().zip(vals) {
+            board[coord] = Some(val);
+        }
+    }
+    board.solve().expect("diagonal sectors never conflict")
+}
+
+/// Remove clues from the solution in a random order, keeping each removal only
+/// if the puzzle still has a unique solution and is no harder than the target.
+fn dig(solution: &Board, target: Difficulty, rng: &mut SplitMix64) -> Board {
+    let mut order: Vec<Coord> = Coord::all().collect();
+    rng.shuffle(&mut order);
+    let mut puzzle = solution.clone();
+    for coord in order {
+        let clue = puzzle[coord].take();
+        if puzzle.count_solutions_dlx(2) != 1
+            || Difficulty::of(&puzzle).is_none_or(|found| found > target)
+        {
+            puzzle[coord] = clue;
+        }
+    }
+    puzzle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn daily_is_deterministic() {
+        crate::setup();
+
+        for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard] {
+            let puzzle = Board::daily(20261016, difficulty);
+            assert_eq!(
+                puzzle.cells_u8(),
+                Board::daily(20261016, difficulty).cells_u8()
+            );
+            assert_eq!(puzzle.count_solutions_dlx(2), 1);
+            assert_eq!(Difficulty::of(&puzzle), Some(difficulty));
+        }
+        assert_ne!(
+            Board::daily(1, Difficulty::Easy),
+            Board::daily(2, Difficulty::Easy)
+        );
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn generate() {
+        use rand::rngs::SmallRng;
+        use rand::SeedableRng;
+
+        crate::setup();
+
+        let mut rng = SmallRng::seed_from_u64(1004);
+        let first = Board::generate(&mut rng);
+        assert!(first.is_solved());
+        assert!(!first.has_conflict());
+        assert_ne!(Board::generate(&mut rng), first);
+
+        for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard] {
+            let puzzle = Board::generate_puzzle(&mut rng, difficulty);
+            assert!(puzzle.has_unique_solution());
+            assert_eq!(Difficulty::of(&puzzle), Some(difficulty));
+        }
+    }
+
+    #[test]
+    fn rates_puzzles() {
+        let mut unsolveable = Board::new();
+        unsolveable[Coord::from_rowmajor_idx(0)] = Some(Val::new(1));
+        unsolveable[Coord::from_rowmajor_idx(1)] = Some(Val::new(1));
+        assert_eq!(Difficulty::of(&unsolveable), None);
+        assert_eq!(Difficulty::of(&Board::new()), Some(Difficulty::Hard));
+        let solved = full_grid(&mut SplitMix64(7));
+        assert!(solved.is_solved());
+        assert_eq!(Difficulty::of(&solved), Some(Difficulty::Easy));
+    }
+}
@@ -0,0 +1,267 @@
+//! Turn a trace of deductions into a lesson made of teachable steps.
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::collections::indexed::IndexMap;
+use crate::trace::{ArcRemaining, Deduction, DeductionReason, RemainingDiff};
+use crate::{AvailSet, Board, Coord, House, Val, Zone};
+
+/// Controls how deductions are grouped into lesson units.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+pub struct LessonPolicy {
+    /// Merge consecutive deductions which use the same technique into a single
+    /// unit.
+    pub merge_consecutive: bool,
+    /// Maximum number of deductions in a single unit. Must be at least 1.
+    pub max_group: usize,
+    /// Skip deductions which only eliminate candidates without filling any
+    /// cell.
+    pub skip_trivial: bool,
+}
+
+impl Default for LessonPolicy {
+    fn default() -> Self {
+        LessonPolicy {
+            merge_consecutive: true,
+            max_group: 9,
+            skip_trivial: true,
+        }
+    }
+}
+
+/// A lesson made from the deductions used to solve a board.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Lesson {
+    /// Units of the lesson, in the order they should be taught.
+    pub units: Vec<LessonUnit>,
+}
+
+/// A group of deductions which use the same technique, taught together.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LessonUnit {
+    /// Name of the technique used by every deduction in this unit.
+    pub technique: String,
+    /// Short description of the unit for display.
+    pub message: String,
+    /// The house every deduction in this unit focuses on, if they share one.
+    pub focus: Option<House>,
+    /// Cells to highlight while teaching this unit.
+    pub highlight: Vec<Coord>,
+    /// The deductions taught in this unit.
+    pub steps: Vec<DeductionReason>,
+    /// The known values of the board before this unit.
+    pub before: Board,
+    /// Cells filled by this unit, in the order they were filled.
+    pub placements: Vec<(Coord, Val)>,
+}
+
+/// Build a lesson from a trace of deductions, as recorded by a `Vec<Deduction>`
+/// passed to `Board::solve_with`. The board state before each unit is
+/// reconstructed from the snapshots in the trace. Each `InitialState` starts
+/// over from its snapshot, and the trace is cut off at the first unsolveable
+/// deduction.
+#[must_use]
+pub fn build(trace: &[Deduction], policy: &LessonPolicy) -> Lesson {
+    let max_group = policy.max_group.max(1);
+    let mut units: Vec<LessonUnit> = Vec::new();
+    let mut state = Board::new();
+    let mut prev = ArcRemaining::from(IndexMap::with_value(AvailSet::all()));
+    let mut can_merge = false;
+    for deduction in trace {
+        let reason = &deduction.reason;
+        match reason {
+            DeductionReason::InitialState => {
+                state = deduction.remaining.board();
+                prev = deduction.remaining.clone();
+                can_merge = false;
+                continue;
+            }
+            DeductionReason::Unsolveable(_) => break,
+            _ => {}
+        }
+        let placements =
+            RemainingDiff::between_cells(prev.as_ref(), deduction.remaining.as_ref()).placements();
+        prev = deduction.remaining.clone();
+        if policy.skip_trivial && placements.is_empty() {
+            continue;
+        }
+        let technique = reason.technique_name();
+        let unit = match units.last_mut() {
+            Some(unit)
+                if can_merge
+                    && policy.merge_consecutive
+                    && unit.technique == technique
+                    && unit.steps.len() < max_group =>
+            {
+                unit
+            }
+            _ => {
+                units.push(LessonUnit {
+                    technique: technique.to_string(),
+                    message: String::new(),
+                    focus: focus(reason),
+                    highlight: Vec::new(),
+                    steps: Vec::new(),
+                    before: state.clone(),
+                    placements: Vec::new(),
+                });
+                units.last_mut().unwrap()
+            }
+        };
+        if unit.focus != focus(reason) {
+            unit.focus = None;
+        }
+        for coord in highlight(deduction) {
+            if !unit.highlight.contains(&coord) {
+                unit.highlight.push(coord);
+            }
+        }
+        unit.steps.push(reason.clone());
+        unit.placements.extend(placements);
+        can_merge = true;
+        state = deduction.remaining.board();
+    }
+    for unit in &mut units {
+        unit.message = message(unit);
+    }
+    Lesson { units }
+}
+
+/// The house a deduction is based on, if any.
+pub(crate) fn focus(reason: &DeductionReason) -> Option<House> {
+    match *reason {
+        DeductionReason::UniqueInRow { pos, .. } => Some(House::Row(pos)),
+        DeductionReason::UniqueInCol { pos, .. } => Some(House::Col(pos)),
+        DeductionReason::UniqueInSector { pos, .. } => Some(House::Sector(pos)),
+        DeductionReason::SecRowTriple { pos, .. } | DeductionReason::SecOnlyRow { pos, .. } => {
+            Some(House::Sector(pos.sector()))
+        }
+        DeductionReason::SecColTriple { pos, .. } | DeductionReason::SecOnlyCol { pos, .. } => {
+            Some(House::Sector(pos.sector()))
+        }
+        DeductionReason::RowOnlySec { pos, .. } => Some(House::Row(pos.row())),
+        DeductionReason::ColOnlySec { pos, .. } => Some(House::Col(pos.col())),
+        DeductionReason::HiddenPair { pos, .. } | DeductionReason::HiddenTriple { pos, .. } => {
+            Some(pos)
+        }
+        DeductionReason::InitialState
+        | DeductionReason::CoordNeighbors { .. }
+        | DeductionReason::XWing { .. }
+        | DeductionReason::Unsolveable(_) => None,
+    }
+}
+
+/// Cells which justify a deduction.
+fn highlight(deduction: &Deduction) -> Vec<Coord> {
+    let rem = &deduction.remaining;
+    match deduction.reason {
+        DeductionReason::CoordNeighbors { pos, .. } => vec![pos],
+        DeductionReason::UniqueInRow { pos, vals } => {
+            pos.coords().filter(|&c| rem[c].intersects(vals)).collect()
+        }
+        DeductionReason::UniqueInCol { pos, vals } => {
+            pos.coords().filter(|&c| rem[c].intersects(vals)).collect()
+        }
+        DeductionReason::UniqueInSector { pos, vals } => {
+            pos.coords().filter(|&c| rem[c].intersects(vals)).collect()
+        }
+        DeductionReason::SecRowTriple { pos, .. }
+        | DeductionReason::SecOnlyRow { pos, .. }
+        | DeductionReason::RowOnlySec { pos, .. } => pos.coords().collect(),
+        DeductionReason::SecColTriple { pos, .. }
+        | DeductionReason::SecOnlyCol { pos, .. }
+        | DeductionReason::ColOnlySec { pos, .. } => pos.coords().collect(),
+        DeductionReason::HiddenPair { cells, .. } => cells.to_vec(),
+        DeductionReason::HiddenTriple { cells, .. } => cells.to_vec(),
+        DeductionReason::XWing { rows, cols, .. } => rows
+            .iter()
+            .flat_map(|&row| cols.iter().map(move |&col| Coord::new(row, col)))
+            .collect(),
+        DeductionReason::InitialState | DeductionReason::Unsolveable(_) => Vec::new(),
+    }
+}
+
+/// Short description of a lesson unit.
+fn message(unit: &LessonUnit) -> String {
+    let steps = match unit.steps.len() {
+        1 => "one step".to_string(),
+        n => format!("{} steps", n),
+    };
+    let cells = match unit.placements.len() {
+        0 => "no new cells".to_string(),
+        1 => "1 cell".to_string(),
+        n => format!("{} cells", n),
+    };
+    let focus = match unit.focus {
+        Some(House::Row(row)) => format!(" in {}", row),
+        Some(House::Col(col)) => format!(" in {}", col),
+        Some(House::Sector(sector)) => format!(
+            " in the sector at {}",
+            Coord::new(sector.base_row(), sector.base_col())
+        ),
+        None => String::new(),
+    };
+    format!("{}{}: {} filling {}", unit.technique, focus, steps, cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::puzzle1;
+
+    #[test]
+    fn lesson_fills_solution() {
+        crate::setup();
+
+        let board = puzzle1();
+        let (solution, trace) = board.solve_with(Vec::new());
+        let solution = solution.unwrap();
+        let lesson = build(&trace, &LessonPolicy::default());
+        assert!(!lesson.units.is_empty());
+
+        let mut state = board;
+        for unit in &lesson.units {
+            assert_eq!(unit.before, state);
+            assert!(!unit.placements.is_empty());
+            assert!(!unit.message.is_empty());
+            for &(coord, val) in &unit.placements {
+                assert_eq!(state[coord], None);
+                state[coord] = Some(val);
+            }
+        }
+        assert_eq!(state, solution);
+    }
+
+    #[test]
+    fn lesson_respects_cap() {
+        crate::setup();
+
+        let (_, trace) = puzzle1().solve_with(Vec::new());
+        for max_group in 1..4 {
+            let policy = LessonPolicy {
+                max_group,
+                ..Default::default()
+            };
+            let lesson = build(&trace, &policy);
+            assert!(lesson
+                .units
+                .iter()
+                .all(|unit| (1..=max_group).contains(&unit.steps.len())));
+        }
+
+        let unmerged = LessonPolicy {
+            merge_consecutive: false,
+            skip_trivial: false,
+            ..Default::default()
+        };
+        let lesson = build(&trace, &unmerged);
+        let expected = trace
+            .iter()
+            .filter(|d| d.reason != DeductionReason::InitialState)
+            .count();
+        assert_eq!(lesson.units.len(), expected);
+    }
+}
@@ -1,30 +1,75 @@
-use std::cmp::{Ordering, PartialOrd};
+#![warn(clippy::must_use_candidate, clippy::return_self_not_must_use)]
+
+use std::cell::Cell;
+use std::cmp::Ordering;
 use std::convert::{TryFrom, TryInto};
+use std::fmt;
 use std::iter::FusedIterator;
 use std::num::NonZeroU8;
 use std::ops::RangeInclusive;
 use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+use std::time::Instant;
 
 use log::trace;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 pub use collections::availset::AvailSet;
 pub use collections::indexed::{IncorrectSize, Values};
-pub use coordinates::{Col, Coord, Intersect, OutOfRange, Row, Sector, SectorCol, SectorRow, Zone};
+pub use coordinates::{
+    Band, Col, Coord, Coords, DynZone, House, Intersect, IntersectionResult, OutOfRange, Relation,
+    Row, Sector, SectorCol, SectorRow, Stack, Zone,
+};
+pub use exclusions::Exclusions;
+pub use mask::{ClueMask, Symmetry};
+pub use solve::{
+    Budget, BudgetExceeded, BudgetUsage, ExhaustionKind, LimitKind, ReduceOptions, RuleKind,
+    RulePriority, RuleSet, Solutions, SolveCode, SolveOutcome, Solver, UnknownRule, Unsolveable,
+};
+#[cfg(feature = "serde")]
+pub use versioned::{BoardVersionError, VersionedBoard};
+pub use views::{ColView, ColViewMut};
 
+use analysis::{Analysis, DisclosureLevel, Hint, Log2Millibits, PartialHint, Regime, Uniqueness};
 use collections::indexed::{FixedSizeIndex, IndexMap};
+use describe::DescribeOptions;
+use generate::Difficulty;
 use solve::remaining::RemainingTracker;
-use trace::{NopTracer, Tracer};
+use trace::{
+    Deduction, DeductionReason, DeductiveTracer, NopDeductiveTracer, NopTracer, Remaining, Tracer,
+};
 
+pub mod analysis;
+pub mod certificate;
 mod collections;
 #[macro_use]
 mod coordinates;
-mod solve;
+pub mod describe;
+pub mod design;
+pub mod errors;
+mod exclusions;
+#[cfg(test)]
+mod fixtures;
+pub mod generate;
+pub mod lesson;
+mod mask;
+pub mod precompute;
+pub mod prelude;
+pub mod sized;
+pub mod solve;
+pub mod tagged;
+#[cfg(any(test, feature = "testkit"))]
+pub mod testkit;
 pub mod trace;
+pub mod variant;
+#[cfg(feature = "serde")]
+mod versioned;
+mod views;
 
 /// A Sudoku Board value.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
 #[repr(transparent)]
 #[cfg_attr(
     feature = "serde",
@@ -49,6 +94,12 @@ impl Val {
     }
 
     /// Create a new Val with the given value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `val` is not in `VALID_RANGE`. Use `Val::try_from` to check
+    /// untrusted input instead.
+    #[must_use]
     pub fn new(val: u8) -> Self {
         assert!(
             Self::VALID_RANGE.contains(&val),
@@ -60,11 +111,19 @@ impl Val {
 
     /// Get the value as a u8.
     #[inline]
+    #[must_use]
     pub const fn val(self) -> u8 {
         self.0.get()
     }
 }
 
+impl fmt::Debug for Val {
+    /// Values debug as their digit, so assertion failures stay readable.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.val())
+    }
+}
+
 impl FixedSizeIndex for Val {
     const NUM_INDEXES: usize = (Self::MAX - Self::MIN + 1) as usize;
 
@@ -88,7 +147,14 @@ impl FixedSizeIndex for Val {
 impl PartialOrd for Val {
     #[inline]
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.0.partial_cmp(&other.0)
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Val {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
     }
 }
 
@@ -118,8 +184,129 @@ macro_rules! val_fromint {
 
 val_fromint!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize);
 
+/// Error used when solving does not finish before its deadline.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Error)]
+#[error("deadline exceeded before solving finished")]
+pub struct DeadlineExceeded;
+
+/// Error when parsing a board from text.
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+pub enum ParseBoardError {
+    /// A row did not have exactly 9 cells. Rows are counted from 0.
+    #[error("row {row} has {len} cells, expected 9")]
+    WrongRowLength { row: usize, len: usize },
+    /// A cell was not a digit or a blank. Rows and columns are counted from 0.
+    #[error("invalid cell {ch:?} at row {row} column {col}")]
+    InvalidCell { row: usize, col: usize, ch: char },
+    /// A character in a single-line board was not a digit or a blank. The index
+    /// counts characters from 0 along the line, including any leading
+    /// whitespace, so it is the position in the line as written.
+    #[error("invalid cell {ch:?} at index {index}")]
+    BadChar { index: usize, ch: char },
+    /// A single-line board did not have exactly 81 cells.
+    #[error("board has {len} cells, expected 81")]
+    WrongLength { len: usize },
+    /// A grid did not have exactly 11 lines, nine rows and two separators.
+    #[error("grid has {lines} lines, expected 11")]
+    WrongLineCount { lines: usize },
+    /// A grid had something other than `|` between groups of cells, or other
+    /// than `---+---+---` between groups of rows. Lines are counted from 0.
+    #[error("bad separator on line {line}")]
+    BadSeparator { line: usize },
+}
+
+/// Returned when the guess search is interrupted before it finishes.
+#[derive(Clone, Debug)]
+pub(crate) struct Interrupted {
+    /// State of the board after the initial deductions, if they finished.
+    pub(crate) partial: Option<Remaining>,
+}
+
+/// Tracer which keeps only the branch of the search which reached the
+/// solution, as a list of the boards reduced along it from the root down.
+#[derive(Default)]
+struct SolutionPath {
+    steps: Vec<PathStep>,
+    solved: bool,
+}
+
+/// A board reduced along the search: the guess which led to it, unless it is
+/// the root, and the board after each deduction made reducing it.
+#[derive(Default)]
+struct PathStep {
+    guess: Option<Guess>,
+    boards: Vec<Board>,
+}
+
+impl DeductiveTracer for PathStep {
+    fn deduce(&mut self, _: DeductionReason, remaining: Remaining) {
+        self.boards.push(remaining.board());
+    }
+}
+
+impl Tracer for SolutionPath {
+    type Deductive = PathStep;
+
+    fn deductive_tracer() -> Self::Deductive {
+        Default::default()
+    }
+
+    fn solution(step: Self::Deductive) -> Self {
+        SolutionPath {
+            steps: vec![step],
+            solved: true,
+        }
+    }
+
+    fn unsolveable(_: Self::Deductive) -> Self {
+        Default::default()
+    }
+
+    fn guess(step: Self::Deductive) -> Self {
+        SolutionPath {
+            steps: vec![step],
+            solved: false,
+        }
+    }
+
+    /// Abandoned guesses are dropped, so at most one child is kept.
+    fn add_child(&mut self, child: Self) {
+        if child.solved {
+            self.steps.extend(child.steps);
+            self.solved = true;
+        }
+    }
+}
+
+/// Number of cells in a band of three rows.
+const BAND_CELLS: usize = Row::WIDTH as usize * Sector::HEIGHT as usize;
+
+// Cells are one byte, using the niche in `NonZeroU8` for empty cells, and a
+// board is exactly nine rows of nine. The array views of the board rely on the
+// latter.
+const _: () = assert!(std::mem::size_of::<Option<Val>>() == 1);
+const _: () = assert!(std::mem::align_of::<Option<Val>>() == 1);
+const _: () = assert!(Row::WIDTH == 9 && Col::HEIGHT == 9);
+const _: () = assert!(Board::SIZE == 81 && BAND_CELLS * 3 == Board::SIZE);
+
+/// A guess made while solving: the cell guessed at, the values it had left to
+/// choose from, and the value picked.
+pub type Guess = (Coord, AvailSet, Val);
+
+/// Two filled cells in the same house which hold the same value.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Conflict {
+    /// The house the cells share.
+    pub house: House,
+    /// The value both cells hold.
+    pub val: Val,
+    /// The two cells, in the order the house lists them.
+    pub cells: [Coord; 2],
+}
+
 /// Sudoku board, with some values optionally specified.
-#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[derive(Clone, Default, Eq, PartialEq, Hash)]
 #[repr(transparent)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
 pub struct Board(IndexMap<Coord, Option<Val>>);
@@ -135,42 +322,503 @@ impl Board {
     pub const HEIGHT: u8 = Col::HEIGHT;
 
     /// Create a new board with no positions specified.
+    #[must_use]
     pub fn new() -> Self {
         Default::default()
     }
 
+    /// Build a board from nine rows of nine cells each, with no separators.
+    /// Cells are `1`-`9` for a value, or `.`, `0`, or a space for a blank.
+    pub fn from_rows(rows: [&str; 9]) -> Result<Self, ParseBoardError> {
+        let mut board = Board::new();
+        for (r, text) in rows.iter().enumerate() {
+            let len = text.chars().count();
+            if len != Row::WIDTH as usize {
+                return Err(ParseBoardError::WrongRowLength { row: r, len });
+            }
+            for (c, ch) in text.chars().enumerate() {
+                let pos = Coord::new(Row::new(r as u8), Col::new(c as u8));
+                board[pos] = match ch {
+                    '1'..='9' => Some(Val::new(ch as u8 - b'0')),
+                    '.' | '0' | ' ' => None,
+                    _ => return Err(ParseBoardError::InvalidCell { row: r, col: c, ch }),
+                };
+            }
+        }
+        Ok(board)
+    }
+
+    /// Build a board from the common single-line format: 81 cells in row-major
+    /// order, each `1`-`9` for a value or `.` or `0` for a blank. Whitespace
+    /// around the line is ignored.
+    pub fn from_line(line: &str) -> Result<Self, ParseBoardError> {
+        let trimmed = line.trim_start();
+        let skipped = line.len() - trimmed.len();
+        let trimmed = trimmed.trim_end();
+        let len = trimmed.chars().count();
+        if len != Board::SIZE {
+            return Err(ParseBoardError::WrongLength { len });
+        }
+        // Only whitespace was trimmed, so count the skipped characters rather
+        // than bytes to get the position in the original text.
+        let offset = line[..skipped].chars().count();
+        let mut board = Board::new();
+        for ((coord, ch), index) in Coord::all().zip(trimmed.chars()).zip(offset..) {
+            board[coord] = match ch {
+                '1'..='9' => Some(Val::new(ch as u8 - b'0')),
+                '.' | '0' => None,
+                _ => return Err(ParseBoardError::BadChar { index, ch }),
+            };
+        }
+        Ok(board)
+    }
+
+    /// Write the board in the single-line format read by `from_line`, with `.`
+    /// for blanks. `Display` writes the grid form instead.
+    #[must_use]
+    pub fn to_line(&self) -> String {
+        self.row_major()
+            .iter()
+            .map(|cell| cell.map_or('.', |val| (b'0' + val.val()) as char))
+            .collect()
+    }
+
+    /// Draw the board as the grid `Display` draws, with the given character
+    /// for empty cells.
+    #[must_use]
+    pub fn to_pretty_string(&self, blank: char) -> String {
+        let mut grid = String::new();
+        self.write_grid(&mut grid, blank, false)
+            .expect("writing to a string cannot fail");
+        grid
+    }
+
+    /// Parse a board from a grid of three groups of three rows separated by
+    /// `---+---+---`, with groups of three cells separated by `|`, as drawn by
+    /// `Display` and used for boards in tests. Cells are `1`-`9`, or `.`, `0`,
+    /// or a space for a blank, and rows may leave off trailing blanks. Blank
+    /// lines before and after the grid are ignored.
+    pub fn from_pretty_str(text: &str) -> Result<Self, ParseBoardError> {
+        Board::from_grid(&significant_lines(text))
+    }
+
+    /// Draw the board as a grid, optionally with a border around it.
+    fn write_grid(&self, out: &mut impl fmt::Write, blank: char, border: bool) -> fmt::Result {
+        if border {
+            out.write_str("+---+---+---+\n")?;
+        }
+        for (r, row) in self.rows_arrays().iter().enumerate() {
+            if r > 0 {
+                out.write_str("\n")?;
+                if r % 3 == 0 {
+                    out.write_str(if border {
+                        "|---+---+---|\n"
+                    } else {
+                        "---+---+---\n"
+                    })?;
+                }
+            }
+            if border {
+                out.write_char('|')?;
+            }
+            for (c, cell) in row.iter().enumerate() {
+                if c > 0 && c % 3 == 0 {
+                    out.write_char('|')?;
+                }
+                match cell {
+                    Some(val) => write!(out, "{}", val.val())?,
+                    None => out.write_char(blank)?,
+                }
+            }
+            if border {
+                out.write_char('|')?;
+            }
+        }
+        if border {
+            out.write_str("\n+---+---+---+")?;
+        }
+        Ok(())
+    }
+
+    /// Parse the lines of a grid, each with its line number in the input.
+    fn from_grid(lines: &[(usize, &str)]) -> Result<Self, ParseBoardError> {
+        const SEPARATOR: &str = "---+---+---";
+        if lines.len() != 11 {
+            return Err(ParseBoardError::WrongLineCount { lines: lines.len() });
+        }
+        let mut board = Board::new();
+        let mut rows = Row::all();
+        for (i, &(line, text)) in lines.iter().enumerate() {
+            let text = text.trim_end();
+            if i % 4 == 3 {
+                if text != SEPARATOR {
+                    return Err(ParseBoardError::BadSeparator { line });
+                }
+                continue;
+            }
+            let row = rows.next().unwrap();
+            let len = text.chars().count();
+            if len > SEPARATOR.len() {
+                // Every full row has two separators between its cells.
+                return Err(ParseBoardError::WrongRowLength {
+                    row: row.inner() as usize,
+                    len: len - 2,
+                });
+            }
+            let mut cols = Col::all();
+            for (idx, ch) in format!("{:11}", text).chars().enumerate() {
+                if idx % 4 == 3 {
+                    if ch != '|' {
+                        return Err(ParseBoardError::BadSeparator { line });
+                    }
+                    continue;
+                }
+                let col = cols.next().unwrap();
+                board[Coord::new(row, col)] = match ch {
+                    '1'..='9' => Some(Val::new(ch as u8 - b'0')),
+                    '.' | '0' | ' ' => None,
+                    _ => {
+                        return Err(ParseBoardError::InvalidCell {
+                            row: row.inner() as usize,
+                            col: col.inner() as usize,
+                            ch,
+                        })
+                    }
+                };
+            }
+        }
+        Ok(board)
+    }
+
     /// Attempts to solve this board, returning a board containing all solved values, if a
     /// solution is possible. Otherwise returns None.
+    ///
+    /// This is a shim over `Solver::run` with the default solver, which can
+    /// tell apart a board with no solution from one the solver gave up on.
+    #[must_use]
     pub fn solve(&self) -> Option<Self> {
-        let (solution, _) = self.solve_traced::<NopTracer>();
-        solution
+        Solver::default().run(self).into_solution()
+    }
+
+    /// Solve the board after a single cell was edited, given the solution from
+    /// before the edit. The old solution is kept for every cell outside the
+    /// edited cell's row, column, and sector, so only that region is searched
+    /// again. If the old solution does not fit the edited board, this falls
+    /// back to a full `solve`. For a board with several solutions, the result
+    /// may differ from what `solve` would pick.
+    #[must_use]
+    pub fn resolve_after_edit(&self, prev_solution: &Board, edited: Coord) -> Option<Self> {
+        let mut hinted = self.clone();
+        for coord in Coord::all() {
+            if hinted[coord].is_none() && coord.relation(edited) == Relation::Unrelated {
+                hinted[coord] = prev_solution[coord];
+            }
+        }
+        if let Some(solution) = hinted.solve() {
+            return Some(solution);
+        }
+        trace!("Previous solution does not fit after editing {}", edited);
+        self.solve()
     }
 
-    /// Attempts to solve this board, returning a board containing all solve
-    /// values, if a solution is possible, along with a tracer shoing the steps
+    /// Attempts to solve this board, returning a board containing all solved
+    /// values, if a solution is possible, along with a tracer showing the steps
     /// needed to reach the solution.
+    ///
+    /// With `TraceTree`, the tracer is the full search tree: each board which
+    /// needed guesses is a `Guess` node holding one child per guess tried, each
+    /// dead end is an `Unsolveable` node, and the branch which solved the board
+    /// ends in a `Solution` node. With `NopTracer` this is the same search
+    /// `solve` runs.
+    #[must_use]
     pub fn solve_traced<T: Tracer>(&self) -> (Option<Self>, T) {
-        let mut stack =
-            match solve::deductive::reduce(RemainingTracker::new(self), T::deductive_tracer()) {
-                (Some(reduced), trace) if reduced.is_solved() => {
-                    trace!("Solved without guessing");
-                    return (Some(reduced.into_board()), T::solution(trace));
+        Self::solve_tracker(RemainingTracker::new(self))
+    }
+
+    /// Attempts to solve this board, returning the solution along with whether
+    /// it was reached by deduction alone, without guessing. Returns None if
+    /// the board has no solution.
+    #[must_use]
+    pub fn solve_logical_flag(&self) -> Option<(Self, bool)> {
+        let mut guessed = false;
+        let (solution, _) = Self::search::<NopTracer, _>(
+            RemainingTracker::new(self),
+            |tracker| solve::deductive::reduce(tracker, &Default::default(), NopDeductiveTracer),
+            RemainingTracker::specify_one,
+            |depth| {
+                guessed |= depth > 0;
+                false
+            },
+        )
+        .expect("search cannot be interrupted");
+        solution.map(|solution| (solution, !guessed))
+    }
+
+    /// Attempts to solve this board, also returning each guess on the path to
+    /// the solution: the cell guessed at, the values it had left to choose
+    /// from, and the value which led to the solution. Guesses which were
+    /// abandoned are left out, so a board solved without guessing gives an
+    /// empty list. Returns None if the board has no solution.
+    #[must_use]
+    pub fn solve_with_guesses(&self) -> Option<(Self, Vec<Guess>)> {
+        let (solution, path) = self.solution_path()?;
+        let guesses = path.into_iter().filter_map(|step| step.guess).collect();
+        Some((solution, guesses))
+    }
+
+    /// Run the same search as `solve`, returning the solution along with the
+    /// boards reduced on the branch which reached it. Returns None if the board
+    /// has no solution.
+    fn solution_path(&self) -> Option<(Self, Vec<PathStep>)> {
+        // Each guess is recorded as it is made, and picked up by the reduction
+        // of the board it led to, which the search runs right after.
+        let guess = Cell::new(None);
+        let (solution, path) = Self::search::<SolutionPath, _>(
+            RemainingTracker::new(self),
+            |tracker| {
+                let step = PathStep {
+                    guess: guess.take(),
+                    boards: Vec::new(),
+                };
+                let (reduced, mut step) =
+                    solve::deductive::reduce(tracker, &Default::default(), step);
+                // Keep the final board too, in case the last cells were filled
+                // without a deduction of their own.
+                if let Some(reduced) = &reduced {
+                    step.boards.push(reduced.remaining().board());
                 }
-                (Some(reduced), trace) => {
-                    trace!("Guesses will be required to solve");
-                    vec![(T::guess(trace), reduced.specify_one())]
+                (reduced, step)
+            },
+            |reduced| {
+                let before = reduced.get::<Coord>().clone();
+                let guess = &guess;
+                reduced.specify_one().inspect(move |next| {
+                    // A guess narrows one cell to a single value and leaves
+                    // the rest as they were.
+                    let (coord, avail) = Coord::all()
+                        .map(|coord| (coord, next.get::<Coord>()[coord]))
+                        .find(|&(coord, avail)| avail != before[coord])
+                        .expect("guess changes a cell");
+                    let val = avail.get_single().expect("guess specifies a single value");
+                    guess.set(Some((coord, before[coord], val)));
+                })
+            },
+            |_| false,
+        )
+        .expect("search cannot be interrupted");
+        Some((solution?, path.steps))
+    }
+
+    /// Solve the board, returning each cell in the order its final value became
+    /// known, with `true` marking the guesses on the path to the solution.
+    /// Clues come first in row-major order, followed by the cells filled by
+    /// each deduction and guess in turn, so every cell appears exactly once.
+    /// Cells filled only along guesses which were later abandoned are left
+    /// out. Returns None if the board has no solution.
+    #[must_use]
+    pub fn solve_order(&self) -> Option<Vec<(Coord, Val, bool)>> {
+        let (_, path) = self.solution_path()?;
+        let mut known = self.clone();
+        let mut order: Vec<_> = Coord::all()
+            .filter_map(|coord| self[coord].map(|val| (coord, val, false)))
+            .collect();
+        for step in path {
+            if let Some((coord, _, val)) = step.guess {
+                known[coord] = Some(val);
+                order.push((coord, val, true));
+            }
+            for board in step.boards {
+                for coord in Coord::all() {
+                    if let (None, Some(val)) = (known[coord], board[coord]) {
+                        known[coord] = Some(val);
+                        order.push((coord, val, false));
+                    }
                 }
-                (None, trace) => {
-                    trace!("Initial board proved unsolvable");
-                    return (None, T::unsolveable(trace));
+            }
+        }
+        Some(order)
+    }
+
+    /// Solve the board one cell at a time, yielding the board after each cell
+    /// is filled. Cells are filled in the order of `solve_order`, skipping the
+    /// clues, so each board has exactly one more cell filled than the one
+    /// before and the last is the solution. Yields nothing if the board is
+    /// already full or has no solution.
+    #[must_use]
+    pub fn progressive(&self) -> impl ExactSizeIterator<Item = Board> + FusedIterator {
+        let clues = self.row_major().iter().flatten().count();
+        let order = self.solve_order().unwrap_or_default();
+        let mut board = self.clone();
+        order.into_iter().skip(clues).map(move |(coord, val, _)| {
+            board[coord] = Some(val);
+            board.clone()
+        })
+    }
+
+    /// Attempts to solve this board while honoring the given exclusions,
+    /// returning a board containing all solved values if a solution is
+    /// possible. Otherwise returns None.
+    #[must_use]
+    pub fn solve_with_exclusions(&self, exclusions: &Exclusions) -> Option<Self> {
+        let solver = Solver {
+            exclusions: exclusions.clone(),
+            ..Default::default()
+        };
+        solver.run(self).into_solution()
+    }
+
+    /// The deductions made reducing this board by logic alone, without
+    /// guessing, in the order they were applied. Ends when no rule makes any
+    /// more progress, whether or not the board is solved, or with an
+    /// `Unsolveable` deduction if the board is found to have no solution.
+    #[must_use]
+    pub fn deduce_steps(&self) -> impl ExactSizeIterator<Item = Deduction> + FusedIterator {
+        let (_, deductions) = solve::deductive::reduce(
+            RemainingTracker::new(self),
+            &Default::default(),
+            Vec::<Deduction>::new(),
+        );
+        // Every reduction starts by recording the initial state, which isn't
+        // a deduction.
+        deductions.into_iter().skip(1)
+    }
+
+    /// Attempts to solve this board, passing every deduction made along the
+    /// way to the given deductive tracer. This includes deductions made in
+    /// guesses which turned out to be unsolveable, in the order they were made.
+    pub fn solve_with<D: DeductiveTracer>(&self, tracer: D) -> (Option<Self>, D) {
+        self.solve_with_options(&Default::default(), tracer)
+    }
+
+    /// Like `solve_with`, but reducing each board with the given options.
+    pub(crate) fn solve_with_options<D: DeductiveTracer>(
+        &self,
+        options: &ReduceOptions,
+        mut tracer: D,
+    ) -> (Option<Self>, D) {
+        let (solution, _) = Self::search::<NopTracer, _>(
+            RemainingTracker::new(self),
+            |tracker| {
+                let (reduced, _) = solve::deductive::reduce(tracker, options, &mut tracer);
+                (reduced, NopDeductiveTracer)
+            },
+            RemainingTracker::specify_one,
+            |_| false,
+        )
+        .expect("search cannot be interrupted");
+        (solution, tracer)
+    }
+
+    /// Attempts to solve this board, giving up once the given deadline has
+    /// passed. The deadline is checked before each board the solver reduces,
+    /// so a deadline which has already passed fails without doing any work.
+    pub fn solve_until(&self, deadline: Instant) -> Result<Option<Self>, DeadlineExceeded> {
+        let solver = Solver {
+            budget: Budget::new().with_time(deadline.saturating_duration_since(Instant::now())),
+            ..Default::default()
+        };
+        match solver.run(self) {
+            SolveOutcome::Solved(solution) => Ok(Some(solution)),
+            SolveOutcome::Unsolveable(_) => Ok(None),
+            SolveOutcome::Exhausted { .. } => Err(DeadlineExceeded),
+        }
+    }
+
+    /// Attempts to solve this board with randomized guessing, making up to
+    /// `restarts` attempts. Each attempt guesses at a random cell among those
+    /// with the fewest values left and tries its values in a random order.
+    /// Every attempt but the last gives up after reducing a number of boards
+    /// which doubles with each attempt, and the last runs to completion, so
+    /// this finds a solution exactly when `solve` does.
+    #[cfg(feature = "rand")]
+    pub fn solve_with_restarts(&self, restarts: usize, rng: &mut impl rand::Rng) -> Option<Self> {
+        /// Number of boards the first attempt may reduce before restarting.
+        const FIRST_LIMIT: u64 = 64;
+
+        let mut limit = FIRST_LIMIT;
+        for attempt in 1..=restarts.max(1) {
+            let mut budget = Budget::new();
+            if attempt < restarts {
+                budget = budget.with_steps(limit);
+            }
+            let mut meter = budget.meter();
+            let result = Self::search::<NopTracer, _>(
+                RemainingTracker::new(self),
+                |tracker| {
+                    solve::deductive::reduce(tracker, &Default::default(), NopDeductiveTracer)
+                },
+                |tracker| tracker.specify_random(rng),
+                |depth| meter.exceeded(depth),
+            );
+            meter.finish();
+            match result {
+                Ok((solution, _)) => return solution,
+                Err(_) => {
+                    trace!(
+                        "Restarting after attempt {} reduced {} boards",
+                        attempt,
+                        limit
+                    );
+                    limit *= 2;
                 }
-            };
+            }
+        }
+        unreachable!("the last attempt cannot be interrupted")
+    }
+
+    /// Run the solver starting from the given tracker.
+    fn solve_tracker<T: Tracer>(tracker: RemainingTracker) -> (Option<Self>, T) {
+        Self::search(
+            tracker,
+            |tracker| solve::deductive::reduce(tracker, &Default::default(), T::deductive_tracer()),
+            RemainingTracker::specify_one,
+            |_| false,
+        )
+        .expect("search cannot be interrupted")
+    }
+
+    /// Run the guess search starting from the given tracker, using the given
+    /// function to deductively reduce each board, and the `guesses` function
+    /// to list the boards to try when a reduced board is not solved. The
+    /// `interrupted` function is checked before each reduction with the number
+    /// of guesses on the stack, and if it returns true the search is abandoned.
+    pub(crate) fn search<T: Tracer, G: Iterator<Item = RemainingTracker>>(
+        tracker: RemainingTracker,
+        mut reduce: impl FnMut(RemainingTracker) -> (Option<RemainingTracker>, T::Deductive),
+        mut guesses: impl FnMut(RemainingTracker) -> G,
+        mut interrupted: impl FnMut(usize) -> bool,
+    ) -> Result<(Option<Self>, T), Interrupted> {
+        if interrupted(0) {
+            trace!("Interrupted before solving");
+            return Err(Interrupted { partial: None });
+        }
+        let (mut stack, partial) = match reduce(tracker) {
+            (Some(reduced), trace) if reduced.is_solved() => {
+                trace!("Solved without guessing");
+                return Ok((Some(reduced.into_board()), T::solution(trace)));
+            }
+            (Some(reduced), trace) => {
+                trace!("Guesses will be required to solve");
+                let partial = reduced.remaining();
+                (vec![(T::guess(trace), guesses(reduced))], partial)
+            }
+            (None, trace) => {
+                trace!("Initial board proved unsolvable");
+                return Ok((None, T::unsolveable(trace)));
+            }
+        };
 
         loop {
             // Get the next possible guess from the top guess node on the stack.
             match stack.last_mut().unwrap().1.next() {
                 Some(guess) => {
-                    match solve::deductive::reduce(guess, T::deductive_tracer()) {
+                    if interrupted(stack.len()) {
+                        trace!("Interrupted at depth {}", stack.len());
+                        return Err(Interrupted {
+                            partial: Some(partial),
+                        });
+                    }
+                    match reduce(guess) {
                         (Some(reduced), trace) if reduced.is_solved() => {
                             trace!("Solved at depth {}", stack.len());
                             let (mut parent, _) = stack.pop().unwrap();
@@ -181,7 +829,7 @@ impl Board {
                                 next.add_child(parent);
                                 parent = next;
                             }
-                            return (Some(reduced.into_board()), parent);
+                            return Ok((Some(reduced.into_board()), parent));
                         }
                         (Some(reduced), trace) => {
                             trace!(
@@ -189,7 +837,7 @@ impl Board {
                                 stack.len()
                             );
                             // Push a guess node for the next iteration to start visiting.
-                            stack.push((T::guess(trace), reduced.specify_one()));
+                            stack.push((T::guess(trace), guesses(reduced)));
                         }
                         (None, trace) => {
                             trace!("Board at depth {} unsolveable", stack.len());
@@ -213,7 +861,7 @@ impl Board {
                         // No parent, nothing left in the stack to try. No solution.
                         None => {
                             trace!("Ran out of boards to try");
-                            return (None, trace);
+                            return Ok((None, trace));
                         }
                     }
                 }
@@ -221,18 +869,332 @@ impl Board {
         }
     }
 
-    /// Return true if the board is known to be unsolveable.
+    /// Return true if the board is known to be unsolveable. This is a cheap
+    /// check which can miss some unsolveable boards, but never returns true for
+    /// a board that has a solution.
+    #[must_use]
     pub fn known_unsolveable(&self) -> bool {
         RemainingTracker::new(self).known_unsolveable()
     }
 
-    /// Return true if the board is solved.
+    /// Return true if the board is solved, meaning every cell is filled and no
+    /// row, column, or sector repeats a value. A solved board is its own
+    /// solution.
+    #[must_use]
     pub fn is_solved(&self) -> bool {
         RemainingTracker::new(self).is_solved()
     }
 
+    /// Analyze the board using logic alone, reporting whether it is solved,
+    /// can make progress, needs a guess, or is contradictory.
+    #[must_use]
+    pub fn analysis(&self) -> Analysis {
+        analysis::analyze(self)
+    }
+
+    /// Classify how constrained the board is, to decide up front whether
+    /// analysis such as `uniqueness_certificate` is worth running. Costs one
+    /// deductive reduction, and nothing at all for boards with fewer than 17
+    /// clues or no empty cells.
+    #[must_use]
+    pub fn analysis_regime(&self) -> Regime {
+        analysis::regime(self)
+    }
+
+    /// Check whether the board has exactly one solution. If it has more, two
+    /// distinct solutions are returned as proof.
+    #[must_use]
+    pub fn uniqueness_certificate(&self) -> Uniqueness {
+        analysis::uniqueness(self)
+    }
+
+    /// Like `uniqueness_certificate`, but giving up once the budget runs out.
+    /// Half of the budget goes to finding the first solution and the rest to
+    /// looking for a second, and the budget is charged with the work done.
+    pub fn uniqueness_within(&self, budget: &mut Budget) -> Result<Uniqueness, BudgetExceeded> {
+        analysis::uniqueness_within(self, budget)
+    }
+
+    /// Count the solutions to the board, stopping once `limit` have been found.
+    /// This runs the same deductive search as `solve`, carrying on past the
+    /// first solution, so a limit of 2 tells apart boards with no solution,
+    /// one solution, or several without enumerating every solution of a
+    /// sparse board.
+    #[must_use]
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        self.solutions().take(limit).count()
+    }
+
+    /// Iterate over every solution of the board, finding each only when it is
+    /// asked for. The first solution is the one `solve` finds.
+    #[must_use]
+    pub fn solutions(&self) -> Solutions {
+        Solutions::new(self)
+    }
+
+    /// Whether the board has exactly one solution, as a well-formed puzzle
+    /// does. Stops searching as soon as a second solution is found.
+    #[must_use]
+    pub fn has_unique_solution(&self) -> bool {
+        self.count_solutions(2) == 1
+    }
+
+    /// Count the solutions to the board, stopping once `limit` have been found.
+    /// Uses an exact cover search rather than the deductive solver, which is
+    /// faster for checking uniqueness over many boards: a limit of 2 tells
+    /// apart boards with no solution, one solution, or several.
+    #[must_use]
+    pub fn count_solutions_dlx(&self, limit: usize) -> usize {
+        solve::dlx::count_solutions(self, limit)
+    }
+
+    /// Generate a puzzle with a unique solution from the seed, for a puzzle of
+    /// the day. A random full grid is filled and clues are removed for as long
+    /// as the puzzle stays unique and no harder than `difficulty`. The puzzle
+    /// is at the requested difficulty unless several grids in a row fail to
+    /// reach it, in which case the hardest one found is returned.
+    ///
+    /// Randomness comes from an internal generator rather than `rand`, so the
+    /// same seed and difficulty give the same puzzle on every machine and with
+    /// every version of the library which uses the same generator and rules.
+    #[must_use]
+    pub fn daily(seed: u64, difficulty: Difficulty) -> Board {
+        generate::daily(seed, difficulty)
+    }
+
+    /// Generate a random solved board.
+    #[cfg(feature = "rand")]
+    #[must_use]
+    pub fn generate(rng: &mut impl rand::Rng) -> Board {
+        generate::solved(rng.gen())
+    }
+
+    /// Generate a random puzzle with a unique solution, digging clues out of
+    /// a random solved board as `daily` does.
+    #[cfg(feature = "rand")]
+    #[must_use]
+    pub fn generate_puzzle(rng: &mut impl rand::Rng, difficulty: Difficulty) -> Board {
+        generate::daily(rng.gen(), difficulty)
+    }
+
+    /// Describe every filled cell of the board in prose, for screen readers.
+    /// Houses are described one sentence each, in order, as in "Row 1: 4 at
+    /// column 3, 7 at column 6, others empty." To describe only the clues of a
+    /// puzzle in progress, describe the original puzzle.
+    #[must_use]
+    pub fn describe(&self, opts: &DescribeOptions) -> String {
+        describe::board(self, opts)
+    }
+
+    /// Get the next cell which can be filled by logic alone, if there is one.
+    #[must_use]
+    pub fn hint(&self) -> Option<Hint> {
+        match self.analysis() {
+            Analysis::Solvable { hint } => Some(hint),
+            _ => None,
+        }
+    }
+
+    /// Get the next cell which can be filled by logic alone, as `hint` does,
+    /// while honoring the given exclusions. An excluded value is never hinted,
+    /// and a cell may be hinted because exclusions leave it one value.
+    #[must_use]
+    pub fn hint_with_exclusions(&self, exclusions: &Exclusions) -> Option<Hint> {
+        match analysis::analyze_with_exclusions(self, exclusions) {
+            Analysis::Solvable { hint } => Some(hint),
+            _ => None,
+        }
+    }
+
+    /// Get the next cell which can be filled by logic alone, as `hint` does,
+    /// revealing only as much as the given level allows.
+    #[must_use]
+    pub fn hint_at(&self, level: DisclosureLevel) -> Option<PartialHint> {
+        self.hint().map(|hint| hint.at_level(level))
+    }
+
+    /// Get the set of values filled in any cell sharing a row, column, or
+    /// sector with the given coordinate. These are the values which cannot be
+    /// placed at that coordinate.
+    #[must_use]
+    pub fn peer_values(&self, coord: Coord) -> AvailSet {
+        coord
+            .neighbors()
+            .filter_map(|neighbor| self[neighbor])
+            .fold(AvailSet::none(), |acc, val| acc | val)
+    }
+
+    /// Get the values which may be placed at the given coordinate: those not
+    /// filled in any cell sharing a row, column, or sector with it, and not
+    /// forbidden there by the exclusions. The cell's own value is ignored, so
+    /// for a filled cell this tells whether its value is legal.
+    #[must_use]
+    pub fn legal_values(&self, coord: Coord, exclusions: &Exclusions) -> AvailSet {
+        !self.peer_values(coord) - exclusions.excluded(coord)
+    }
+
+    /// Get every pair of filled cells which share a row, column, or sector and
+    /// hold the same value, with the value they share. Each pair is listed
+    /// once, with the cells in row-major order, and pairs are sorted by their
+    /// first and then second cell. Use `has_conflict` when only whether there
+    /// is a conflict matters.
+    #[must_use]
+    pub fn conflicts(&self) -> Vec<(Coord, Coord, Val)> {
+        let mut conflicts = Vec::new();
+        for (coord, &cell) in Coord::all().zip(self.row_major()) {
+            if let Some(val) = cell {
+                let mut peers: Vec<_> = coord
+                    .neighbors()
+                    .filter(|&peer| peer > coord && self[peer] == Some(val))
+                    .collect();
+                peers.sort_unstable();
+                conflicts.extend(peers.into_iter().map(|peer| (coord, peer, val)));
+            }
+        }
+        conflicts
+    }
+
+    /// Get every pair of filled cells which repeat a value within a row,
+    /// column, or sector, along with the house they repeat it in. Unlike
+    /// `conflicts`, a pair which shares both a row or column and a sector is
+    /// listed once for each house. Houses are listed in the order of
+    /// `House::all`.
+    #[must_use]
+    pub fn violations(&self) -> Vec<Conflict> {
+        let mut violations = Vec::new();
+        for house in House::all() {
+            let filled: Vec<(Coord, Val)> = house
+                .coords()
+                .filter_map(|coord| Some((coord, self[coord]?)))
+                .collect();
+            for (i, &(first, val)) in filled.iter().enumerate() {
+                violations.extend(
+                    filled[i + 1..]
+                        .iter()
+                        .filter(|&&(_, other)| other == val)
+                        .map(|&(second, _)| Conflict {
+                            house,
+                            val,
+                            cells: [first, second],
+                        }),
+                );
+            }
+        }
+        violations
+    }
+
+    /// Returns true if no row, column, or sector repeats a value, that is if
+    /// `violations` is empty. Empty cells never make a board invalid.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.violations().is_empty()
+    }
+
+    /// Returns true if any row, column, or sector repeats a value. This makes
+    /// a single pass over the board, stopping at the first repeat, so it is
+    /// cheap enough to run on every edit.
+    #[must_use]
+    pub fn has_conflict(&self) -> bool {
+        let mut rows = [AvailSet::none(); 9];
+        let mut cols = [AvailSet::none(); 9];
+        let mut sectors = [AvailSet::none(); 9];
+        Coord::all().zip(self.row_major()).any(|(coord, &cell)| {
+            cell.is_some_and(|val| {
+                !rows[coord.row().idx()].add(val)
+                    | !cols[coord.col().idx()].add(val)
+                    | !sectors[coord.sector().idx()].add(val)
+            })
+        })
+    }
+
+    /// Fill every cell which has only one value left once the values already
+    /// in its row, column, and sector are ruled out, repeating until no such
+    /// cell remains. Returns the filled board and how many cells were filled.
+    /// This uses no solver state beyond a value set per house, so it is a cheap
+    /// first pass before anything more expensive.
+    #[must_use]
+    pub fn fill_forced(&self) -> (Board, usize) {
+        let mut board = self.clone();
+        let mut rows = [AvailSet::none(); 9];
+        let mut cols = [AvailSet::none(); 9];
+        let mut sectors = [AvailSet::none(); 9];
+        for (coord, &cell) in Coord::all().zip(board.row_major()) {
+            if let Some(val) = cell {
+                rows[coord.row().idx()] |= val;
+                cols[coord.col().idx()] |= val;
+                sectors[coord.sector().idx()] |= val;
+            }
+        }
+        let mut filled = 0;
+        loop {
+            let mut progress = false;
+            for coord in Coord::all() {
+                if board[coord].is_some() {
+                    continue;
+                }
+                let (r, c, s) = (coord.row().idx(), coord.col().idx(), coord.sector().idx());
+                if let Some(val) = (!(rows[r] | cols[c] | sectors[s])).get_single() {
+                    board[coord] = Some(val);
+                    rows[r] |= val;
+                    cols[c] |= val;
+                    sectors[s] |= val;
+                    filled += 1;
+                    progress = true;
+                }
+            }
+            if !progress {
+                return (board, filled);
+            }
+        }
+    }
+
+    /// Complete the board using only `fill_forced`, for boards which are
+    /// nearly done. Returns None if that leaves cells empty or the board
+    /// breaks the rules, in which case `solve` is needed.
+    pub fn complete_trivial(&self) -> Option<Board> {
+        if self.has_conflict() {
+            return None;
+        }
+        let (board, _) = self.fill_forced();
+        board
+            .row_major()
+            .iter()
+            .all(Option::is_some)
+            .then_some(board)
+    }
+
+    /// Set all nine cells of the given sector, in the order of
+    /// `sector.coords()`: left to right, then top to bottom.
+    pub fn specify_sector(&mut self, sector: Sector, vals: [Option<Val>; 9]) {
+        for (coord, val) in sector.coords().zip(vals) {
+            self[coord] = val;
+        }
+    }
+
+    /// Create a puzzle from a solution by keeping only the cells in the mask.
+    #[must_use]
+    pub fn apply_mask(solution: &Board, mask: &ClueMask) -> Board {
+        let mut puzzle = Board::new();
+        for coord in mask.iter() {
+            puzzle[coord] = solution[coord];
+        }
+        puzzle
+    }
+
+    /// Create a new board by applying the given function to every filled cell.
+    /// Empty cells stay empty.
+    #[must_use]
+    pub fn map_values(&self, f: impl Fn(Val) -> Val) -> Board {
+        let mut board = self.clone();
+        for val in board.row_major_mut().iter_mut().flatten() {
+            *val = f(*val);
+        }
+        board
+    }
+
     /// View of the board as a flat slice in row-major order.
     #[inline]
+    #[must_use]
     pub fn row_major(&self) -> &[Option<Val>] {
         self.0.as_ref()
     }
@@ -243,48 +1205,197 @@ impl Board {
         self.0.as_mut()
     }
 
-    /// Iterator over const references to the rows of this board.
+    /// View of the board as its three horizontal bands, from top to bottom,
+    /// each 27 contiguous cells in row-major order.
+    #[must_use]
+    pub fn bands(&self) -> [&[Option<Val>]; 3] {
+        let (top, rest) = self.row_major().split_at(BAND_CELLS);
+        let (middle, bottom) = rest.split_at(BAND_CELLS);
+        [top, middle, bottom]
+    }
+
+    /// Mutable view of the board as its three horizontal bands.
+    pub fn bands_mut(&mut self) -> [&mut [Option<Val>]; 3] {
+        let (top, rest) = self.row_major_mut().split_at_mut(BAND_CELLS);
+        let (middle, bottom) = rest.split_at_mut(BAND_CELLS);
+        [top, middle, bottom]
+    }
+
+    /// View of the board as a 9x9 array indexed by row and then column. This
+    /// splits the row-major slice into rows without copying.
+    #[must_use]
+    pub fn rows_arrays(&self) -> &[[Option<Val>; 9]; 9] {
+        self.row_major().as_chunks::<9>().0.try_into().unwrap()
+    }
+
+    /// Mutable view of the board as a 9x9 array indexed by row and then
+    /// column.
+    pub fn rows_arrays_mut(&mut self) -> &mut [[Option<Val>; 9]; 9] {
+        self.row_major_mut()
+            .as_chunks_mut::<9>()
+            .0
+            .try_into()
+            .unwrap()
+    }
+
+    /// Copy the cells into a flat array in row-major order, with 0 for empty
+    /// cells. This is the form to hand to SIMD or GPU code, which wants plain
+    /// bytes rather than options.
+    #[must_use]
+    pub fn cells_u8(&self) -> [u8; Board::SIZE] {
+        let cells = self.row_major();
+        std::array::from_fn(|i| cells[i].map_or(0, Val::val))
+    }
+
+    /// The starting candidates of each cell as bit masks in row-major order,
+    /// with bit `v - 1` set if value `v` is a candidate. A filled cell has only
+    /// its own value and an empty cell has all nine, since nothing is
+    /// eliminated yet. This is what the solver starts from, for bulk callers
+    /// which run their own solving loop over plain masks.
+    #[must_use]
+    pub fn to_candidate_state(&self) -> [u16; Board::SIZE] {
+        let cells = self.row_major();
+        std::array::from_fn(|i| cells[i].map_or(AvailSet::all(), AvailSet::only).as_bits())
+    }
+
+    /// How open the board is, as the log2 of the number of ways to fill every
+    /// empty cell from the values its peers leave it, ignoring the conflicts
+    /// between those choices. Filled cells contribute nothing. The value is an
+    /// integer count of millibits, so it compares exactly across platforms.
+    #[must_use]
+    pub fn candidate_entropy(&self) -> Log2Millibits {
+        let cells: Vec<_> = Coord::all()
+            .map(|coord| match self[coord] {
+                Some(val) => AvailSet::only(val),
+                None => !self.peer_values(coord),
+            })
+            .collect();
+        Log2Millibits::of_candidates(&cells)
+    }
+
+    /// Iterator over the rows of this board, as slices of their cells.
+    #[must_use]
     pub fn rows(
         &self,
-    ) -> impl '_ + Iterator<Item = &RowRef> + DoubleEndedIterator + ExactSizeIterator + FusedIterator
+    ) -> impl '_ + DoubleEndedIterator<Item = &[Option<Val>]> + ExactSizeIterator + FusedIterator
     {
-        Row::values().map(move |row| &self[row])
+        self.row_major().chunks_exact(Row::SIZE)
     }
 
-    /// Iterator over mut references to the rows of this board.
+    /// Iterator over the rows of this board, as mutable slices of their cells.
     pub fn rows_mut(
         &mut self,
-    ) -> impl '_ + Iterator<Item = &mut RowRef> + DoubleEndedIterator + ExactSizeIterator + FusedIterator
+    ) -> impl '_ + DoubleEndedIterator<Item = &mut [Option<Val>]> + ExactSizeIterator + FusedIterator
     {
-        let mut start: *mut _ = &mut self.0.as_mut()[0];
-        (0..Row::NUM_INDEXES).map(move |_| {
-            // This is safe because rows won't alias.
-            let res = unsafe { &mut *start.cast() };
-            start = unsafe { start.add(Row::SIZE) };
-            res
-        })
+        self.row_major_mut().chunks_exact_mut(Row::SIZE)
+    }
+
+    /// Iterator over the cells of the given row, column, or sector, along with
+    /// their coordinates, in the order of `zone.coords()`.
+    pub fn house_cells<'a, Z: 'a + Zone>(
+        &'a self,
+        zone: Z,
+    ) -> impl 'a + DoubleEndedIterator<Item = (Coord, &'a Option<Val>)> + ExactSizeIterator + FusedIterator
+    {
+        zone.coords().map(move |coord| (coord, &self[coord]))
     }
 
-    /// Iterator over const references to the cols of this board.
+    /// Returns true if any cell of the given row, column, or sector holds the
+    /// given value.
+    pub fn house_contains<Z: Zone>(&self, zone: Z, val: Val) -> bool {
+        zone.coords().any(|coord| self[coord] == Some(val))
+    }
+
+    /// View of the given column, indexed by row.
+    #[must_use]
+    pub fn col(&self, col: Col) -> ColView<'_> {
+        ColView::new(self, col)
+    }
+
+    /// Mutable view of the given column, indexed by row.
+    pub fn col_mut(&mut self, col: Col) -> ColViewMut<'_> {
+        ColViewMut::new(self, col)
+    }
+
+    /// Iterator over views of the columns of this board.
+    #[must_use]
     pub fn cols(
         &self,
-    ) -> impl '_ + Iterator<Item = &ColRef> + DoubleEndedIterator + ExactSizeIterator + FusedIterator
-    {
-        Col::values().map(move |col| &self[col])
+    ) -> impl '_ + DoubleEndedIterator<Item = ColView<'_>> + ExactSizeIterator + FusedIterator {
+        Col::values().map(move |col| self.col(col))
     }
 
-    /// Iterator over mut references to the rows of this board.
+    /// Iterator over mutable views of the columns of this board, which can all
+    /// be held at once.
     pub fn cols_mut(
         &mut self,
-    ) -> impl '_ + Iterator<Item = &mut RowRef> + DoubleEndedIterator + ExactSizeIterator + FusedIterator
+    ) -> impl '_ + DoubleEndedIterator<Item = ColViewMut<'_>> + ExactSizeIterator + FusedIterator
     {
-        let mut start: *mut _ = &mut self.0.as_mut()[0];
-        (0..Col::NUM_INDEXES).map(move |_| {
-            // This is safe because we won't alias.
-            let res = unsafe { &mut *start.cast() };
-            start = unsafe { start.add(1) };
-            res
-        })
+        ColViewMut::split(self).into_iter()
+    }
+}
+
+impl fmt::Debug for Board {
+    /// Boards debug as their 81 cells in row-major order, with `.` for empty
+    /// cells, rather than as a list of 81 options.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Board({})", self.to_line())
+    }
+}
+
+impl fmt::Display for Board {
+    /// Boards display as a grid of three groups of three rows separated by
+    /// `---+---+---`, with groups of three cells separated by `|` and a space
+    /// for empty cells, which `Board::from_str` parses back. The alternate
+    /// form, `{:#}`, also draws a border around the grid.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let border = f.alternate();
+        self.write_grid(f, ' ', border)
+    }
+}
+
+impl FromStr for Board {
+    type Err = ParseBoardError;
+
+    /// Parse a board either from a single line, as `Board::from_line`, or
+    /// from a grid, as `Board::from_pretty_str`.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match &significant_lines(text)[..] {
+            [] => Board::from_line(""),
+            [(_, line)] => Board::from_line(line),
+            lines => Board::from_grid(lines),
+        }
+    }
+}
+
+/// The lines of the text with their line numbers, leaving out blank lines
+/// before and after the rest.
+fn significant_lines(text: &str) -> Vec<(usize, &str)> {
+    let mut lines: Vec<_> = text
+        .lines()
+        .enumerate()
+        .skip_while(|(_, line)| line.trim().is_empty())
+        .collect();
+    let end = lines
+        .iter()
+        .rposition(|(_, line)| !line.trim().is_empty())
+        .map_or(0, |last| last + 1);
+    lines.truncate(end);
+    lines
+}
+
+impl PartialOrd for Board {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Board {
+    /// Boards are ordered lexicographically by their cells in row-major order,
+    /// with empty cells ordered before any value.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.row_major().cmp(other.row_major())
     }
 }
 
@@ -356,164 +1467,24 @@ impl From<IndexMap<Coord, Option<Val>>> for Board {
     }
 }
 
-/// Reference to a particular row.
-///
-/// This type always exists behind a reference as a slice within a board. Taking
-/// the value out of the reference is undefined behavior.
-// transparent is needed for correctness because the layout of rust types is unspecified to allow
-// for optimization.
-#[repr(transparent)]
-pub struct RowRef(Option<Val>);
+/// Indexing a board by row gives the row's cells as a slice, in column order.
+/// Index the slice with `col.inner()`, or index the board with a `Coord`.
+impl Index<Row> for Board {
+    type Output = [Option<Val>];
 
-impl RowRef {
-    /// Iterator over const references to the elements of this row.
-    pub fn iter(
-        &self,
-    ) -> impl '_ + Iterator<Item = &Option<Val>> + DoubleEndedIterator + ExactSizeIterator + FusedIterator
-    {
-        Col::values().map(move |col| &self[col])
-    }
-
-    /// Iterator over mut references to the elements of this row.
-    pub fn iter_mut(
-        &mut self,
-    ) -> impl '_
-           + Iterator<Item = &mut Option<Val>>
-           + DoubleEndedIterator
-           + ExactSizeIterator
-           + FusedIterator {
-        let start: *mut _ = &mut self.0;
-        Col::values().map(move |col| {
-            let offset = col.idx();
-            // This is safe (no aliasing) as long as col is unique for each iteration.
-            unsafe { &mut *start.add(offset) }
-        })
-    }
-}
-
-impl Index<Row> for Board {
-    type Output = RowRef;
-
-    fn index(&self, row: Row) -> &Self::Output {
-        let start = Coord::new(row, Col::new(0)).idx();
-        let start: *const _ = &self.0.as_ref()[start];
-        unsafe { &*start.cast() }
+    fn index(&self, row: Row) -> &[Option<Val>] {
+        let width = Row::WIDTH as usize;
+        &self.row_major()[row.idx() * width..][..width]
     }
 }
 
 impl IndexMut<Row> for Board {
-    fn index_mut(&mut self, row: Row) -> &mut Self::Output {
-        let start = Coord::new(row, Col::new(0)).idx();
-        let start: *mut _ = &mut self.0.as_mut()[start];
-        unsafe { &mut *start.cast() }
-    }
-}
-
-impl Index<Col> for RowRef {
-    type Output = Option<Val>;
-
-    fn index(&self, col: Col) -> &Self::Output {
-        let start: *const _ = &self.0;
-        let offset = col.idx();
-        unsafe { &*start.add(offset) }
-    }
-}
-
-impl IndexMut<Col> for RowRef {
-    fn index_mut(&mut self, col: Col) -> &mut Self::Output {
-        let start: *mut _ = &mut self.0;
-        let offset = col.idx();
-        unsafe { &mut *start.add(offset) }
-    }
-}
-
-impl PartialEq for RowRef {
-    fn eq(&self, other: &Self) -> bool {
-        Col::values().all(|col| self[col] == other[col])
-    }
-}
-
-impl Eq for RowRef {}
-
-/// Reference to a particular row.
-///
-/// This type always exists behind a reference as a slice within a board. Taking
-/// the value out of the reference is undefined behavior.
-// transparent is needed for correctness because the layout of rust types is unspecified to allow
-// for optimization.
-#[repr(transparent)]
-pub struct ColRef(Option<Val>);
-
-impl ColRef {
-    /// Iterator over const references to the elements of this col.
-    pub fn iter(
-        &self,
-    ) -> impl '_ + Iterator<Item = &Option<Val>> + DoubleEndedIterator + ExactSizeIterator + FusedIterator
-    {
-        Row::values().map(move |row| &self[row])
-    }
-
-    /// Iterator over mut references to the elements of this col.
-    pub fn iter_mut(
-        &mut self,
-    ) -> impl '_
-           + Iterator<Item = &mut Option<Val>>
-           + DoubleEndedIterator
-           + ExactSizeIterator
-           + FusedIterator {
-        let start: *mut _ = &mut self.0;
-        Row::values().map(move |row| {
-            let offset = row.idx() * Col::NUM_INDEXES;
-            // This is safe (no aliasing) as long as row is unique for each iteration.
-            unsafe { &mut *start.add(offset) }
-        })
-    }
-}
-
-impl Index<Col> for Board {
-    type Output = ColRef;
-
-    fn index(&self, col: Col) -> &Self::Output {
-        let start = Coord::new(Row::new(0), col).idx();
-        let start: *const _ = &self.0.as_ref()[start];
-        unsafe { &*start.cast() }
-    }
-}
-
-impl IndexMut<Col> for Board {
-    fn index_mut(&mut self, col: Col) -> &mut Self::Output {
-        let start = Coord::new(Row::new(0), col).idx();
-        let start: *mut _ = &mut self.0.as_mut()[start];
-        unsafe { &mut *start.cast() }
+    fn index_mut(&mut self, row: Row) -> &mut [Option<Val>] {
+        let width = Row::WIDTH as usize;
+        &mut self.row_major_mut()[row.idx() * width..][..width]
     }
 }
 
-impl Index<Row> for ColRef {
-    type Output = Option<Val>;
-
-    fn index(&self, row: Row) -> &Self::Output {
-        let start: *const _ = &self.0;
-        let offset = row.idx() * Col::NUM_INDEXES;
-        unsafe { &*start.add(offset) }
-    }
-}
-
-impl IndexMut<Row> for ColRef {
-    fn index_mut(&mut self, row: Row) -> &mut Self::Output {
-        let start: *mut _ = &mut self.0;
-        let offset = row.idx() * Col::NUM_INDEXES;
-        unsafe { &mut *start.add(offset) }
-    }
-}
-
-impl PartialEq for ColRef {
-    fn eq(&self, other: &Self) -> bool {
-        Row::values().all(|row| self[row] == other[row])
-    }
-}
-
-impl Eq for ColRef {}
-
 /// Set up for testing -- enables logging.
 #[cfg(test)]
 pub(crate) fn setup() {
@@ -522,7 +1493,13 @@ pub(crate) fn setup() {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::{BTreeSet, HashSet};
+    use std::time::Duration;
+
     use super::*;
+    use crate::fixtures::{contradictory, inkala, puzzle1};
+    use crate::trace::DeductionReason;
+    use crate::{assert_boards_eq, board_art};
 
     impl From<[&str; 11]> for Board {
         fn from(values: [&str; 11]) -> Self {
@@ -531,39 +1508,186 @@ mod tests {
     }
 
     impl From<&[&str]> for Board {
-        /// Convenience method for building a board for in a test. Use a
-        /// single-dimensional slice of 11 rows. 1-9 means that number, '|' must be
-        /// used as a column separator, ' ' means no value, and any other character
-        /// causes a panic. Each row must have eactly 11 characters (9 numbers + 2 separators).
-        /// Rows 3 and 7 must be "---+---+---"
+        /// Build a board from a slice of the 11 lines of board art used by
+        /// `testkit::board_from_art`.
         fn from(rows: &[&str]) -> Self {
-            assert!(rows.len() == 11);
-            assert!(rows[3] == "---+---+---" && rows[7] == "---+---+---");
-            let mut board = Board::new();
-            for (r, &row) in
-                Row::values().zip(rows[0..3].iter().chain(&rows[4..7]).chain(&rows[8..11]))
-            {
-                for (c, val) in Col::values().zip(parse_row(row)) {
-                    board[Coord::new(r, c)] = val;
-                }
-            }
-            board
+            testkit::board_from_art(&rows.join("\n"))
         }
     }
 
-    fn parse_row(row: &str) -> impl '_ + Iterator<Item = Option<Val>> {
-        let row = row.as_bytes();
-        assert!(row.len() == 11);
-        assert!(row[3] == b'|' && row[7] == b'|');
-        row[0..3]
-            .iter()
-            .chain(&row[4..7])
-            .chain(&row[8..11])
-            .map(|ch| match ch {
-                b'1'..=b'9' => Some(Val::new(ch - b'0')),
-                b' ' => None,
-                _ => panic!("unsupported val: {}", ch),
+    #[test]
+    fn from_rows() {
+        let board = Board::from_rows([
+            "...1.....",
+            "000 586 1",
+            "8.136..9.",
+            "5.....4.3",
+            "..36.18..",
+            "6.4.....7",
+            ".3..845.6",
+            "1.572....",
+            ".....3...",
+        ])
+        .unwrap();
+        let expected = puzzle1();
+        assert_eq!(board, expected);
+
+        let mut rows = ["........."; 9];
+        rows[4] = "1234";
+        let err = Board::from_rows(rows).unwrap_err();
+        assert_eq!(err, ParseBoardError::WrongRowLength { row: 4, len: 4 });
+        assert_eq!(err.to_string(), "row 4 has 4 cells, expected 9");
+        rows[4] = "1234x....";
+        assert_eq!(
+            Board::from_rows(rows),
+            Err(ParseBoardError::InvalidCell {
+                row: 4,
+                col: 4,
+                ch: 'x'
             })
+        );
+    }
+
+    #[test]
+    fn from_line() {
+        let line =
+            "...1.....000.586.18.136..9.5.....4.3..36.18..6.4.....7.3..845.61.572.........3...";
+        let board = Board::from_line(&format!("  {}\n", line)).unwrap();
+        let expected = puzzle1();
+        assert_eq!(board, expected);
+
+        assert_eq!(board.to_line(), line.replace('0', "."));
+        assert_eq!(board.to_line().parse::<Board>(), Ok(board.clone()));
+        let solved = board.solve().unwrap();
+        assert_eq!(Board::from_line(&solved.to_line()), Ok(solved));
+        assert_eq!(Board::new().to_line(), ".".repeat(81));
+
+        let err = Board::from_line(&line[1..]).unwrap_err();
+        assert_eq!(err, ParseBoardError::WrongLength { len: 80 });
+        assert_eq!(err.to_string(), "board has 80 cells, expected 81");
+        assert_eq!(
+            Board::from_line(&format!("{}1", line)),
+            Err(ParseBoardError::WrongLength { len: 82 })
+        );
+        assert_eq!(
+            Board::from_line(&line.replacen("8.136", "8.1 6", 1)),
+            Err(ParseBoardError::BadChar { index: 21, ch: ' ' })
+        );
+        let err = Board::from_line(&line.replacen("4.3", "4.a", 1)).unwrap_err();
+        assert_eq!(err, ParseBoardError::BadChar { index: 35, ch: 'a' });
+        assert_eq!(err.to_string(), "invalid cell 'a' at index 35");
+        // Leading whitespace counts towards the index, as it is part of the
+        // text the user wrote.
+        assert_eq!(
+            Board::from_line(&format!(" \u{a0}{}", line.replacen("4.3", "4.a", 1))),
+            Err(ParseBoardError::BadChar { index: 37, ch: 'a' })
+        );
+    }
+
+    #[test]
+    fn from_str() {
+        let board = puzzle1();
+        let line =
+            "...1.....000.586.18.136..9.5.....4.3..36.18..6.4.....7.3..845.61.572.........3...";
+        assert_eq!(line.parse::<Board>(), Ok(board.clone()));
+        assert_eq!(
+            format!("\n{}\n\n", line).parse::<Board>(),
+            Ok(board.clone())
+        );
+        let art = testkit::board_to_art(&board);
+        assert_eq!(art.parse::<Board>(), Ok(board.clone()));
+        let stripped: Vec<_> = art.lines().map(|line| line.replace('.', " ")).collect();
+        let stripped = format!("\n{}\n", stripped.join("\n").trim_end());
+        assert_eq!(stripped.parse::<Board>(), Ok(board));
+
+        assert_eq!(
+            "".parse::<Board>(),
+            Err(ParseBoardError::WrongLength { len: 0 })
+        );
+        let lines: Vec<_> = art.lines().collect();
+        assert_eq!(
+            lines[..10].join("\n").parse::<Board>(),
+            Err(ParseBoardError::WrongLineCount { lines: 10 })
+        );
+        let mut bad = lines.clone();
+        bad[7] = "---+---+--+";
+        let err = bad.join("\n").parse::<Board>().unwrap_err();
+        assert_eq!(err, ParseBoardError::BadSeparator { line: 7 });
+        assert_eq!(err.to_string(), "bad separator on line 7");
+        bad[7] = lines[7];
+        bad[2] = "8.1.36..9.";
+        assert_eq!(
+            bad.join("\n").parse::<Board>(),
+            Err(ParseBoardError::BadSeparator { line: 2 })
+        );
+        bad[2] = "8.1|36.|.9.|";
+        assert_eq!(
+            bad.join("\n").parse::<Board>(),
+            Err(ParseBoardError::WrongRowLength { row: 2, len: 10 })
+        );
+        bad[2] = "8.1|3x.|.9.";
+        assert_eq!(
+            bad.join("\n").parse::<Board>(),
+            Err(ParseBoardError::InvalidCell {
+                row: 2,
+                col: 4,
+                ch: 'x'
+            })
+        );
+    }
+
+    #[test]
+    fn display() {
+        let board = puzzle1();
+        let grid = [
+            "   |1  |   ",
+            "   | 58|6 1",
+            "8 1|36 | 9 ",
+            "---+---+---",
+            "5  |   |4 3",
+            "  3|6 1|8  ",
+            "6 4|   |  7",
+            "---+---+---",
+            " 3 | 84|5 6",
+            "1 5|72 |   ",
+            "   |  3|   ",
+        ];
+        assert_eq!(board.to_string(), grid.join("\n"));
+        assert_eq!(board.to_string().parse::<Board>(), Ok(board.clone()));
+        assert_eq!(Board::from(&grid[..]), board);
+
+        let boxed = [
+            "+---+---+---+",
+            "|   |1  |   |",
+            "|   | 58|6 1|",
+            "|8 1|36 | 9 |",
+            "|---+---+---|",
+            "|5  |   |4 3|",
+            "|  3|6 1|8  |",
+            "|6 4|   |  7|",
+            "|---+---+---|",
+            "| 3 | 84|5 6|",
+            "|1 5|72 |   |",
+            "|   |  3|   |",
+            "+---+---+---+",
+        ];
+        assert_eq!(format!("{:#}", board), boxed.join("\n"));
+
+        let dotted = board.to_pretty_string('.');
+        assert_eq!(dotted, grid.join("\n").replace(' ', "."));
+        assert_eq!(Board::from_pretty_str(&dotted), Ok(board.clone()));
+        assert_eq!(
+            Board::from_pretty_str(&board.to_string()),
+            Ok(board.clone())
+        );
+        assert_eq!(
+            Board::from_pretty_str(&board.to_line()),
+            Err(ParseBoardError::WrongLineCount { lines: 1 })
+        );
+
+        let solved = board.solve().unwrap();
+        assert_eq!(solved.to_string().parse::<Board>(), Ok(solved));
+        assert_eq!(Board::new().to_string().parse::<Board>(), Ok(Board::new()));
     }
 
     #[test]
@@ -581,24 +1705,49 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn to_candidate_state() {
+        let board = latin_board();
+        let mut partial = board.clone();
+        for coord in Coord::all().filter(|coord| coord.rowmajor_idx() % 3 == 0) {
+            partial[coord] = None;
+        }
+        for board in [Board::new(), board, partial] {
+            let masks = board.to_candidate_state();
+            let tracker = RemainingTracker::new(&board);
+            for coord in Coord::all() {
+                let mask = masks[coord.rowmajor_idx()];
+                assert_eq!(mask, tracker[coord].as_bits(), "at {}", coord);
+                for val in Val::values() {
+                    assert_eq!(
+                        mask & (1 << (val.val() - 1)) != 0,
+                        tracker[coord].contains(val)
+                    );
+                }
+            }
+        }
+        assert_eq!(Board::new().to_candidate_state(), [0x1ff; Board::SIZE]);
+    }
+
+    #[test]
+    fn debug_formats() {
+        assert_eq!(format!("{:?}", Val::new(7)), "7");
+        assert_eq!(format!("{:?}", Some(Val::new(7))), "Some(7)");
+        let mut board = Board::new();
+        board[Coord::new(Row::new(0), Col::new(1))] = Some(Val::new(3));
+        board[Coord::new(Row::new(8), Col::new(8))] = Some(Val::new(9));
+        assert_eq!(
+            format!("{:?}", board),
+            format!("Board(.3{}9)", ".".repeat(Board::SIZE - 3))
+        );
+    }
+
     #[test]
     fn solve_puzzle1() {
         crate::setup();
 
-        let board = Board::from([
-            "   |1  |   ",
-            "   | 58|6 1",
-            "8 1|36 | 9 ",
-            "---+---+---",
-            "5  |   |4 3",
-            "  3|6 1|8  ",
-            "6 4|   |  7",
-            "---+---+---",
-            " 3 | 84|5 6",
-            "1 5|72 |   ",
-            "   |  3|   ",
-        ]);
-        let expected = Board::from([
+        let board = puzzle1();
+        let expected = board_art!(
             "467|192|385",
             "329|458|671",
             "851|367|294",
@@ -610,16 +1759,15 @@ mod tests {
             "732|984|516",
             "145|726|938",
             "986|513|742",
-        ]);
-        let res = board.solve();
-        assert_eq!(res, Some(expected));
+        );
+        assert_boards_eq!(board.solve(), Some(expected), puzzle = board);
     }
 
     #[test]
     fn solve_puzzle2() {
         crate::setup();
 
-        let board = Board::from([
+        let board = board_art!(
             "   |8  | 14",
             "1 6|4  |75 ",
             " 47|53 |   ",
@@ -631,8 +1779,8 @@ mod tests {
             "   | 87|34 ",
             " 14|  5|6 9",
             "89 |  4|   ",
-        ]);
-        let expected = Board::from([
+        );
+        let expected = board_art!(
             "359|876|214",
             "186|492|753",
             "247|531|896",
@@ -644,16 +1792,15 @@ mod tests {
             "562|987|341",
             "714|325|689",
             "893|614|527",
-        ]);
-        let res = board.solve();
-        assert_eq!(res, Some(expected));
+        );
+        assert_boards_eq!(board.solve(), Some(expected), puzzle = board);
     }
 
     #[test]
     fn solve_puzzle3() {
         crate::setup();
 
-        let board = Board::from([
+        let board = board_art!(
             " 49|   |65 ",
             " 5 |8 7|  3",
             "   |46 |   ",
@@ -665,8 +1812,8 @@ mod tests {
             "   | 42|   ",
             "9  |3 6| 2 ",
             " 27|   |31 ",
-        ]);
-        let expected = Board::from([
+        );
+        let expected = board_art!(
             "749|213|658",
             "156|897|243",
             "832|465|971",
@@ -678,30 +1825,446 @@ mod tests {
             "563|142|789",
             "981|376|524",
             "427|958|316",
-        ]);
-        let res = board.solve();
-        assert_eq!(res, Some(expected));
+        );
+        assert_boards_eq!(board.solve(), Some(expected), puzzle = board);
+    }
+
+    #[test]
+    fn resolve_after_edit() {
+        crate::setup();
+
+        let board = puzzle1();
+        let solution = board.solve().unwrap();
+        let at = |r, c| Coord::new(Row::new(r), Col::new(c));
+
+        // Filling in a correct value keeps the same solution.
+        let mut edited = board.clone();
+        edited[at(4, 4)] = solution[at(4, 4)];
+        assert_boards_eq!(
+            edited.resolve_after_edit(&solution, at(4, 4)),
+            edited.solve()
+        );
+
+        // Removing any clue which leaves the solution unique.
+        let mut unique = 0;
+        for coord in Coord::all().filter(|&coord| board[coord].is_some()) {
+            let mut edited = board.clone();
+            edited[coord] = None;
+            if edited.count_solutions_dlx(2) == 1 {
+                unique += 1;
+                assert_boards_eq!(
+                    edited.resolve_after_edit(&solution, coord),
+                    Some(solution.clone())
+                );
+            }
+        }
+        assert!(unique > 0);
+
+        // A wrong value leaves no solution either way.
+        let mut edited = board.clone();
+        edited[at(4, 4)] = Some(Val::new(9));
+        assert_eq!(edited.solve(), None);
+        assert_eq!(edited.resolve_after_edit(&solution, at(4, 4)), None);
+
+        // A previous solution which does not fit falls back to a full solve.
+        let relabeled = solution.map_values(|val| Val::from_idx((val.idx() + 1) % 9));
+        assert_boards_eq!(
+            board.resolve_after_edit(&relabeled, at(4, 4)),
+            Some(solution)
+        );
     }
 
     #[test]
     fn solve_bad() {
         crate::setup();
 
-        let board = Board::from([
-            "349|   |65 ",
-            " 5 |8 7|  3",
-            "   |46 |   ",
+        let board = contradictory();
+        assert_boards_eq!(board.solve(), None::<Board>);
+        assert_eq!(board.solve_order(), None);
+        assert_eq!(board.solve_logical_flag(), None);
+        assert_eq!(board.progressive().count(), 0);
+    }
+
+    #[test]
+    fn solve_order() {
+        crate::setup();
+
+        let deductive = puzzle1();
+        let guessing = inkala();
+        for board in [&deductive, &guessing] {
+            let order = board.solve_order().unwrap();
+            assert_eq!(order.len(), Board::SIZE);
+            let coords: BTreeSet<_> = order.iter().map(|&(coord, _, _)| coord).collect();
+            assert_eq!(coords.len(), Board::SIZE);
+
+            let clues = board.row_major().iter().flatten().count();
+            assert!(order[..clues]
+                .iter()
+                .all(|&(coord, val, guess)| board[coord] == Some(val) && !guess));
+            let mut replay = Board::new();
+            for &(coord, val, _) in &order {
+                replay[coord] = Some(val);
+            }
+            assert_boards_eq!(replay, board.solve().unwrap());
+        }
+        let guesses = |board: &Board| {
+            board
+                .solve_order()
+                .unwrap()
+                .iter()
+                .filter(|&&(_, _, guess)| guess)
+                .count()
+        };
+        assert_eq!(guesses(&deductive), 0);
+        assert!(guesses(&guessing) > 0);
+        assert_eq!(
+            deductive.solve_logical_flag(),
+            Some((deductive.solve().unwrap(), true))
+        );
+        assert_eq!(
+            guessing.solve_logical_flag(),
+            Some((guessing.solve().unwrap(), false))
+        );
+    }
+
+    /// Guesses fix one cell to each of its values in turn, so sibling branches
+    /// differ in that cell from then on and no state can be reached twice in
+    /// one search. This is why the search keeps no table of states already
+    /// proven unsolveable: it would never be hit.
+    #[test]
+    fn guess_states_never_repeat() {
+        crate::setup();
+
+        let inkala = inkala();
+        for board in [Board::new(), inkala] {
+            let mut seen = HashSet::new();
+            let (solution, _) = Board::search::<NopTracer, _>(
+                RemainingTracker::new(&board),
+                |tracker| {
+                    let remaining = tracker.remaining();
+                    let cells: Vec<_> = Coord::all().map(|coord| remaining[coord]).collect();
+                    assert!(seen.insert(cells), "state reached twice");
+                    solve::deductive::reduce(tracker, &Default::default(), NopDeductiveTracer)
+                },
+                RemainingTracker::specify_one,
+                |_| false,
+            )
+            .unwrap();
+            assert!(solution.is_some());
+            assert!(seen.len() > 1, "board solved without guessing");
+        }
+    }
+
+    #[test]
+    fn solve_with_guesses() {
+        crate::setup();
+
+        let one_guess = board_art!(
+            " 5 |  6| 9 ",
+            "   |48 |   ",
+            "2 6|   |   ",
             "---+---+---",
-            "27 |   |   ",
-            "  4|5 1|8  ",
-            "   |   | 32",
+            "  1|   |  2",
+            "  2|36 |   ",
+            " 9 |  1| 65",
             "---+---+---",
-            "   | 42|   ",
-            "9  |3 6| 2 ",
-            " 27|   |31 ",
-        ]);
-        let res = board.solve();
-        assert_eq!(res, None);
+            "   |8  |53 ",
+            "4  |7 2|   ",
+            "   | 5 |  1",
+        );
+        let (solution, guesses) = one_guess.solve_with_guesses().unwrap();
+        assert_boards_eq!(&solution, one_guess.solve());
+        assert_eq!(guesses.len(), 1);
+        let (coord, avail, val) = guesses[0];
+        assert_eq!(one_guess[coord], None);
+        assert!(avail.len() >= 2);
+        assert!(avail.contains(val));
+        assert_eq!(solution[coord], Some(val));
+
+        let inkala = inkala();
+        let (solution, guesses) = inkala.solve_with_guesses().unwrap();
+        assert_boards_eq!(&solution, inkala.solve());
+        assert!(!guesses.is_empty());
+        for &(coord, avail, val) in &guesses {
+            assert!(avail.len() >= 2 && avail.contains(val));
+            assert_eq!(solution[coord], Some(val));
+        }
+
+        let (_, guesses) = latin_board().solve_with_guesses().unwrap();
+        assert!(guesses.is_empty());
+    }
+
+    #[test]
+    fn progressive() {
+        crate::setup();
+
+        let board = inkala();
+        let filled = |board: &Board| board.row_major().iter().flatten().count();
+        let steps: Vec<_> = board.progressive().collect();
+        assert_eq!(steps.len(), Board::SIZE - filled(&board));
+        let mut prev = &board;
+        for step in &steps {
+            assert_eq!(filled(step), filled(prev) + 1);
+            assert!(Coord::all().all(|pos| prev[pos].is_none() || prev[pos] == step[pos]));
+            prev = step;
+        }
+        assert_boards_eq!(steps.last(), board.solve());
+
+        let solution = board.solve().unwrap();
+        assert_eq!(solution.progressive().count(), 0);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn solve_with_restarts() {
+        use rand::rngs::SmallRng;
+        use rand::SeedableRng;
+
+        crate::setup();
+
+        let guessing = inkala();
+        let unsolveable = contradictory();
+        let mut rng = SmallRng::seed_from_u64(954);
+        for board in [Board::new(), guessing, unsolveable] {
+            let expected = board.solve();
+            for restarts in [0, 1, 2, 5] {
+                let solution = board.solve_with_restarts(restarts, &mut rng);
+                assert_eq!(solution.is_some(), expected.is_some());
+                if let Some(solution) = solution {
+                    assert!(solution.is_solved());
+                    assert!(
+                        Coord::all().all(|pos| board[pos].is_none() || board[pos] == solution[pos])
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn peer_values() {
+        let board = puzzle1();
+        for coord in Coord::all() {
+            let expected = coord
+                .row()
+                .coords()
+                .chain(coord.col().coords())
+                .chain(coord.sector().coords())
+                .filter(|&other| other != coord)
+                .filter_map(|other| board[other])
+                .fold(AvailSet::none(), |acc, val| acc | val);
+            assert_eq!(board.peer_values(coord), expected, "at {}", coord);
+        }
+        let coord = Coord::new(Row::new(0), Col::new(0));
+        let expected = [1, 5, 6, 8]
+            .iter()
+            .fold(AvailSet::none(), |acc, &v| acc | Val::new(v));
+        assert_eq!(board.peer_values(coord), expected);
+        assert!(Board::new().peer_values(coord).is_empty());
+    }
+
+    #[test]
+    fn violations() {
+        let at = |r, c| Coord::new(Row::new(r), Col::new(c));
+        let mut board = Board::new();
+        board[at(0, 0)] = Some(Val::new(3));
+        board[at(0, 2)] = Some(Val::new(3));
+        board[at(5, 2)] = Some(Val::new(3));
+        board[at(8, 8)] = Some(Val::new(4));
+        assert_eq!(
+            board.violations(),
+            vec![
+                Conflict {
+                    house: House::Row(Row::new(0)),
+                    val: Val::new(3),
+                    cells: [at(0, 0), at(0, 2)],
+                },
+                Conflict {
+                    house: House::Col(Col::new(2)),
+                    val: Val::new(3),
+                    cells: [at(0, 2), at(5, 2)],
+                },
+                Conflict {
+                    house: House::Sector(at(0, 0).sector()),
+                    val: Val::new(3),
+                    cells: [at(0, 0), at(0, 2)],
+                },
+            ]
+        );
+        assert!(Board::new().violations().is_empty());
+        board[at(0, 2)] = None;
+        assert!(board.violations().is_empty());
+    }
+
+    #[test]
+    fn is_valid() {
+        let puzzle = inkala();
+        assert!(puzzle.is_valid());
+        assert!(puzzle.solve().unwrap().is_valid());
+        assert!(Board::new().is_valid());
+
+        let mut invalid = puzzle;
+        invalid[Coord::new(Row::new(0), Col::new(8))] = Some(Val::new(8));
+        assert!(!invalid.is_valid());
+    }
+
+    #[test]
+    fn conflicts() {
+        let puzzle = puzzle1();
+        let at = |r, c| Coord::new(Row::new(r), Col::new(c));
+        let with = |cells: &[(Coord, u8)]| {
+            let mut board = puzzle.clone();
+            for &(coord, val) in cells {
+                board[coord] = Some(Val::new(val));
+            }
+            board
+        };
+
+        let row = with(&[(at(3, 4), 4)]);
+        assert_eq!(row.conflicts(), vec![(at(3, 4), at(3, 6), Val::new(4))]);
+        let col = with(&[(at(8, 4), 5)]);
+        assert_eq!(col.conflicts(), vec![(at(1, 4), at(8, 4), Val::new(5))]);
+        let sector = with(&[(at(8, 3), 8)]);
+        assert_eq!(sector.conflicts(), vec![(at(6, 4), at(8, 3), Val::new(8))]);
+        // Three copies in one row give every pair.
+        let triple = with(&[(at(4, 1), 8), (at(4, 7), 8)]);
+        assert_eq!(
+            triple.conflicts(),
+            vec![
+                (at(4, 1), at(4, 6), Val::new(8)),
+                (at(4, 1), at(4, 7), Val::new(8)),
+                (at(4, 6), at(4, 7), Val::new(8)),
+            ]
+        );
+
+        let solution = puzzle.solve().unwrap();
+        for board in [Board::new(), puzzle, solution, row, col, sector, triple] {
+            assert_eq!(
+                board.has_conflict(),
+                !board.conflicts().is_empty(),
+                "{:?}",
+                board
+            );
+            assert_eq!(
+                board.violations().is_empty(),
+                board.conflicts().is_empty(),
+                "{:?}",
+                board
+            );
+            assert_eq!(board.is_valid(), !board.has_conflict(), "{:?}", board);
+        }
+    }
+
+    #[test]
+    fn three_copies_in_a_house() {
+        let at = |r, c| Coord::new(Row::new(r), Col::new(c));
+        let mut board = Board::new();
+        // The copies share only their sector, so each pair is one violation.
+        for coord in [at(0, 0), at(1, 1), at(2, 2)] {
+            board[coord] = Some(Val::new(7));
+        }
+        assert!(!board.is_valid());
+        assert!(board.has_conflict());
+        assert_eq!(
+            board.conflicts(),
+            vec![
+                (at(0, 0), at(1, 1), Val::new(7)),
+                (at(0, 0), at(2, 2), Val::new(7)),
+                (at(1, 1), at(2, 2), Val::new(7)),
+            ]
+        );
+        let sector = House::Sector(at(0, 0).sector());
+        assert_eq!(
+            board.violations(),
+            vec![
+                Conflict {
+                    house: sector,
+                    val: Val::new(7),
+                    cells: [at(0, 0), at(1, 1)],
+                },
+                Conflict {
+                    house: sector,
+                    val: Val::new(7),
+                    cells: [at(0, 0), at(2, 2)],
+                },
+                Conflict {
+                    house: sector,
+                    val: Val::new(7),
+                    cells: [at(1, 1), at(2, 2)],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn fill_forced() {
+        let puzzle = inkala();
+        let solution = puzzle.solve().unwrap();
+
+        let mut missing_row = solution.clone();
+        missing_row[Row::new(4)]
+            .iter_mut()
+            .for_each(|cell| *cell = None);
+        assert_eq!(missing_row.fill_forced(), (solution.clone(), 9));
+        assert_boards_eq!(missing_row.complete_trivial(), Some(solution.clone()));
+        assert_boards_eq!(solution.complete_trivial(), Some(solution.clone()));
+
+        // Needs guesses, so forced cells alone cannot finish it.
+        assert_eq!(puzzle.complete_trivial(), None);
+        let mut partial = puzzle.clone();
+        for sector in [Sector::from_idx(4), Sector::from_idx(8)] {
+            for coord in sector.coords() {
+                partial[coord] = solution[coord];
+            }
+        }
+        let (forced, filled) = partial.fill_forced();
+        assert!(filled > 0);
+        assert_eq!(
+            forced.row_major().iter().flatten().count(),
+            partial.row_major().iter().flatten().count() + filled
+        );
+        assert_eq!(forced.complete_trivial(), None);
+        for coord in Coord::all() {
+            match forced[coord] {
+                Some(val) => assert_eq!(solution[coord], Some(val), "at {}", coord),
+                None => assert!((!forced.peer_values(coord)).len() > 1, "at {}", coord),
+            }
+        }
+
+        let mut conflicting = missing_row;
+        conflicting[Coord::new(Row::new(4), Col::new(0))] =
+            solution[Coord::new(Row::new(3), Col::new(0))];
+        assert_eq!(conflicting.complete_trivial(), None);
+    }
+
+    #[test]
+    fn board_ord() {
+        let empty = Board::new();
+        let mut one = Board::new();
+        one[Coord::new(Row::new(0), Col::new(1))] = Some(Val::new(1));
+        let mut two = Board::new();
+        two[Coord::new(Row::new(0), Col::new(1))] = Some(Val::new(2));
+        let mut later = Board::new();
+        later[Coord::new(Row::new(8), Col::new(8))] = Some(Val::new(9));
+
+        assert!(empty < later);
+        assert!(later < one);
+        assert!(one < two);
+        assert_eq!(one.cmp(&one.clone()), Ordering::Equal);
+
+        let mut boards = vec![
+            two.clone(),
+            empty.clone(),
+            one.clone(),
+            two.clone(),
+            later.clone(),
+        ];
+        boards.sort();
+        boards.dedup();
+        let expected = vec![empty, later, one, two];
+        assert_eq!(boards, expected);
+
+        let set: BTreeSet<_> = boards.iter().rev().chain(&boards).cloned().collect();
+        assert_eq!(set.into_iter().collect::<Vec<_>>(), expected);
     }
 
     #[test]
@@ -711,4 +2274,345 @@ mod tests {
         let res = Board::new().solve();
         assert!(res.is_some());
     }
+
+    #[test]
+    fn count_solutions() {
+        crate::setup();
+
+        assert_eq!(Board::new().count_solutions(2), 2);
+        assert_eq!(Board::new().count_solutions(0), 0);
+
+        // A solved board with a deadly rectangle removed has exactly two
+        // solutions.
+        let board = board_art!(
+            "467|192|385",
+            "329|458|671",
+            "851|367|294",
+            "---+---+---",
+            "518|279|463",
+            "27 |6 1|859",
+            "69 |8 5|127",
+            "---+---+---",
+            "732|984|516",
+            "145|726|938",
+            "986|513|742",
+        );
+        assert_eq!(board.count_solutions(1), 1);
+        assert_eq!(board.count_solutions(10), 2);
+        assert!(!board.has_unique_solution());
+        let solution = board.solve().unwrap();
+        assert_eq!(solution.count_solutions(2), 1);
+        assert!(solution.has_unique_solution());
+        assert!(!Board::new().has_unique_solution());
+
+        let mut bad = solution;
+        bad[Coord::new(Row::new(0), Col::new(0))] = None;
+        bad[Coord::new(Row::new(0), Col::new(1))] = None;
+        bad[Coord::new(Row::new(1), Col::new(0))] = Some(Val::new(6));
+        assert_eq!(bad.count_solutions(2), 0);
+        assert_eq!(bad.count_solutions_dlx(2), 0);
+        assert!(!bad.has_unique_solution());
+    }
+
+    #[test]
+    fn deduce_steps() {
+        crate::setup();
+
+        let board = puzzle1();
+        let steps: Vec<_> = board.deduce_steps().collect();
+        assert!(!steps.is_empty());
+        assert!(steps
+            .iter()
+            .all(|step| step.reason != DeductionReason::InitialState));
+        assert_boards_eq!(Some(steps.last().unwrap().remaining.board()), board.solve());
+
+        assert_eq!(Board::new().deduce_steps().len(), 0);
+
+        // Logic alone can't place anything in a deadly rectangle.
+        let stuck = board_art!(
+            "467|192|385",
+            "329|458|671",
+            "851|367|294",
+            "---+---+---",
+            "518|279|463",
+            "27 |6 1|859",
+            "69 |8 5|127",
+            "---+---+---",
+            "732|984|516",
+            "145|726|938",
+            "986|513|742",
+        );
+        assert!(stuck.deduce_steps().all(|step| {
+            !step.remaining.board().is_solved()
+                && !matches!(step.reason, DeductionReason::Unsolveable(_))
+        }));
+
+        let mut bad = board.clone();
+        bad[Coord::new(Row::new(0), Col::new(0))] = Some(Val::new(8));
+        assert!(matches!(
+            bad.deduce_steps().last().map(|step| step.reason),
+            Some(DeductionReason::Unsolveable(_))
+        ));
+    }
+
+    #[test]
+    fn solve_until() {
+        crate::setup();
+
+        let board = inkala();
+        let past = Instant::now() - Duration::from_secs(1);
+        assert_eq!(board.solve_until(past), Err(DeadlineExceeded));
+
+        let future = Instant::now() + Duration::from_secs(3600);
+        assert_eq!(board.solve_until(future), Ok(board.solve()));
+        assert!(board.solve().is_some());
+    }
+
+    #[test]
+    fn specify_sector() {
+        let sector = Sector::containing(Coord::new(Row::new(4), Col::new(7)));
+        let vals = [
+            Some(Val::new(1)),
+            None,
+            Some(Val::new(3)),
+            Some(Val::new(4)),
+            Some(Val::new(5)),
+            None,
+            Some(Val::new(7)),
+            Some(Val::new(8)),
+            Some(Val::new(9)),
+        ];
+        let mut board = Board::new();
+        board.specify_sector(sector, vals);
+        let result: Vec<_> = sector.coords().map(|coord| board[coord]).collect();
+        assert_eq!(result, vals);
+        assert_eq!(
+            board[Coord::new(Row::new(3), Col::new(6))],
+            Some(Val::new(1))
+        );
+        assert_eq!(
+            board[Coord::new(Row::new(4), Col::new(6))],
+            Some(Val::new(4))
+        );
+        assert_eq!(
+            board[Coord::new(Row::new(5), Col::new(8))],
+            Some(Val::new(9))
+        );
+        let filled = board.row_major().iter().filter(|v| v.is_some()).count();
+        assert_eq!(filled, 7);
+    }
+
+    #[test]
+    fn house_cells() {
+        let board = puzzle1();
+        let sector = Sector::containing(Coord::new(Row::new(1), Col::new(7)));
+        let cells: Vec<_> = board.house_cells(sector).collect();
+        let expected: Vec<_> = [
+            (0, 6),
+            (0, 7),
+            (0, 8),
+            (1, 6),
+            (1, 7),
+            (1, 8),
+            (2, 6),
+            (2, 7),
+            (2, 8),
+        ]
+        .iter()
+        .map(|&(r, c)| Coord::new(Row::new(r), Col::new(c)))
+        .map(|coord| (coord, &board[coord]))
+        .collect();
+        assert_eq!(cells, expected);
+        assert_eq!(
+            cells[3..].iter().map(|(_, val)| **val).collect::<Vec<_>>(),
+            [
+                Some(Val::new(6)),
+                None,
+                Some(Val::new(1)),
+                None,
+                Some(Val::new(9)),
+                None
+            ]
+        );
+
+        let row: Vec<_> = board.house_cells(Row::new(2)).map(|(_, v)| *v).collect();
+        assert_eq!(row, board[Row::new(2)].to_vec());
+
+        fn contains<Z: Zone>(board: &Board, zone: Z) -> Vec<u8> {
+            Val::values()
+                .filter(|&val| board.house_contains(zone, val))
+                .map(Val::val)
+                .collect()
+        }
+        assert_eq!(contains(&board, sector), [1, 6, 9]);
+        assert_eq!(contains(&board, Row::new(2)), [1, 3, 6, 8, 9]);
+        assert_eq!(contains(&board, Col::new(0)), [1, 5, 6, 8]);
+        assert_eq!(contains(&board, Row::new(8)), [3]);
+    }
+
+    #[test]
+    fn map_values() {
+        let board = puzzle1();
+        assert_eq!(board.map_values(|val| val), board);
+
+        let rotated = board.map_values(|val| Val::new(val.val() % 9 + 1));
+        let expected = board_art!(
+            "   |2  |   ",
+            "   | 69|7 2",
+            "9 2|47 | 1 ",
+            "---+---+---",
+            "6  |   |5 4",
+            "  4|7 2|9  ",
+            "7 5|   |  8",
+            "---+---+---",
+            " 4 | 95|6 7",
+            "2 6|83 |   ",
+            "   |  4|   ",
+        );
+        assert_eq!(rotated, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "value must be in range [1, 9], got 0")]
+    fn val_out_of_range() {
+        let _ = Val::new(0);
+    }
+
+    /// Board where every cell holds a different value for its row and column,
+    /// so reading the wrong cell is always noticed.
+    fn latin_board() -> Board {
+        let mut board = Board::new();
+        for coord in Coord::all() {
+            let idx = (coord.row().idx() * 3 + coord.row().idx() / 3 + coord.col().idx()) % 9;
+            board[coord] = Some(Val::from_idx(idx));
+        }
+        board
+    }
+
+    #[test]
+    fn row_col_views_read() {
+        let board = latin_board();
+        for coord in Coord::all() {
+            let expected = board.row_major()[coord.rowmajor_idx()];
+            assert_eq!(
+                board[coord.row()][usize::from(coord.col().inner())],
+                expected
+            );
+            assert_eq!(board.col(coord.col())[coord.row()], expected);
+        }
+        assert_eq!(board.rows().len(), 9);
+        for (row, cells) in Row::values().zip(board.rows()) {
+            let expected: Vec<_> = row.coords().map(|coord| board[coord]).collect();
+            assert_eq!(cells, &expected[..]);
+            assert_eq!(cells, &board[row]);
+        }
+        assert_eq!(board.cols().len(), 9);
+        for (col, view) in Col::values().zip(board.cols()) {
+            assert_eq!(view.col(), col);
+            let cells: Vec<_> = view.iter().copied().collect();
+            let expected: Vec<_> = col.coords().map(|coord| board[coord]).collect();
+            assert_eq!(cells, expected);
+            assert_eq!(view.iter().rev().count(), 9);
+            assert_eq!(view, board.col(col));
+        }
+        assert_ne!(board[Row::new(0)], board[Row::new(8)]);
+        assert_ne!(board.col(Col::new(0)), board.col(Col::new(8)));
+        // Views compare by their cells, not their position.
+        let mut shifted = Board::new();
+        for coord in Coord::all() {
+            shifted[coord] =
+                board[Coord::new(coord.row(), Col::new((coord.col().inner() + 1) % 9))];
+        }
+        assert_eq!(shifted.col(Col::new(0)), board.col(Col::new(1)));
+    }
+
+    #[test]
+    fn row_col_views_write() {
+        let expected = latin_board();
+
+        let mut board = Board::new();
+        for coord in Coord::all() {
+            board[coord.row()][usize::from(coord.col().inner())] = expected[coord];
+        }
+        assert_eq!(board, expected);
+
+        let mut board = Board::new();
+        for coord in Coord::all() {
+            board.col_mut(coord.col())[coord.row()] = expected[coord];
+        }
+        assert_eq!(board, expected);
+
+        let mut board = Board::new();
+        for (row, cells) in Row::values().zip(board.rows_mut()) {
+            for (col, cell) in Col::values().zip(cells.iter_mut()) {
+                *cell = expected[Coord::new(row, col)];
+            }
+        }
+        assert_eq!(board, expected);
+
+        let mut board = Board::new();
+        for col in Col::values() {
+            let mut view = board.col_mut(col);
+            assert_eq!(view.col(), col);
+            for (row, cell) in Row::values().zip(view.iter_mut()) {
+                *cell = expected[Coord::new(row, col)];
+            }
+        }
+        assert_eq!(board, expected);
+
+        // Every column can be held mutably at once.
+        let mut board = Board::new();
+        let mut cols: Vec<_> = board.cols_mut().collect();
+        for row in Row::values() {
+            for view in &mut cols {
+                view[row] = expected[Coord::new(row, view.col())];
+            }
+        }
+        assert!(cols.iter().all(|view| view
+            .iter()
+            .zip(expected.col(view.col()).iter())
+            .all(|(a, b)| a == b)));
+        drop(cols);
+        assert_eq!(board, expected);
+    }
+
+    #[test]
+    fn chunked_views() {
+        let board = latin_board();
+        let cells = board.row_major();
+        let bands = board.bands();
+        for (b, band) in bands.iter().enumerate() {
+            assert_eq!(band.len(), 27);
+            for (i, cell) in band.iter().enumerate() {
+                assert_eq!(*cell, cells[b * 27 + i]);
+            }
+        }
+        let rows = board.rows_arrays();
+        for coord in Coord::all() {
+            let expected = board[coord];
+            assert_eq!(rows[coord.row().idx()][coord.col().idx()], expected);
+            assert_eq!(
+                board.cells_u8()[coord.rowmajor_idx()],
+                expected.map_or(0, Val::val)
+            );
+        }
+        assert_eq!(Board::new().cells_u8(), [0; Board::SIZE]);
+    }
+
+    #[test]
+    fn chunked_views_mut() {
+        let mut board = Board::new();
+        board.rows_arrays_mut()[4][7] = Some(Val::new(3));
+        assert_eq!(
+            board[Coord::new(Row::new(4), Col::new(7))],
+            Some(Val::new(3))
+        );
+        board.bands_mut()[2][26] = Some(Val::new(9));
+        assert_eq!(
+            board[Coord::new(Row::new(8), Col::new(8))],
+            Some(Val::new(9))
+        );
+        assert_eq!(board.rows_arrays()[8][8], Some(Val::new(9)));
+        assert_eq!(board.row_major().iter().flatten().count(), 2);
+    }
 }
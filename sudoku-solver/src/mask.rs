@@ -0,0 +1,444 @@
+//! Sets of clue positions, for turning solutions into puzzles and back.
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Sub, SubAssign};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Board, Col, Coord, Row, Zone};
+
+/// Number of cells on the board, and so the number of bits in a mask.
+const CELLS: usize = Row::SIZE * Col::SIZE;
+
+/// Number of hex digits needed to hold every cell of a mask.
+const HEX_DIGITS: usize = CELLS.div_ceil(4);
+
+/// The set of cells which are given as clues in a puzzle.
+///
+/// Serializes as a hex string of 21 digits, where bit `i` is set if the cell
+/// with row-major index `i` is a clue. Deserializes from either that string or
+/// a list of coordinates.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct ClueMask(u128);
+
+impl ClueMask {
+    /// Create a mask with no clues.
+    #[must_use]
+    pub const fn new() -> Self {
+        ClueMask(0)
+    }
+
+    /// Create a mask with every cell as a clue.
+    #[must_use]
+    pub const fn all() -> Self {
+        ClueMask((1 << CELLS) - 1)
+    }
+
+    /// Get the mask of the filled cells of a puzzle.
+    #[must_use]
+    pub fn from_puzzle(puzzle: &Board) -> Self {
+        Coord::all()
+            .filter(|&coord| puzzle[coord].is_some())
+            .collect()
+    }
+
+    /// Choose `n` clue positions uniformly at random. The same seed always
+    /// gives the same mask.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is more than the 81 cells of the board.
+    #[must_use]
+    pub fn random(n: usize, seed: u64) -> Self {
+        assert!(
+            n <= CELLS,
+            "mask can hold at most {} clues, got {}",
+            CELLS,
+            n
+        );
+        let mut rng = SplitMix64(seed);
+        let mut cells: Vec<usize> = (0..CELLS).collect();
+        for i in 0..n {
+            let j = i + (rng.next() % (CELLS - i) as u64) as usize;
+            cells.swap(i, j);
+        }
+        cells[..n]
+            .iter()
+            .map(|&idx| Coord::from_rowmajor_idx(idx))
+            .collect()
+    }
+
+    /// Returns true if there are no clues.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Number of clues.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Returns true if the given cell is a clue.
+    #[must_use]
+    pub fn contains(&self, coord: Coord) -> bool {
+        self.0 & Self::bit(coord) != 0
+    }
+
+    /// Add a clue. Return true if it wasn't already a clue.
+    pub fn insert(&mut self, coord: Coord) -> bool {
+        let added = !self.contains(coord);
+        self.0 |= Self::bit(coord);
+        added
+    }
+
+    /// Remove a clue. Return true if it was a clue.
+    pub fn remove(&mut self, coord: Coord) -> bool {
+        let removed = self.contains(coord);
+        self.0 &= !Self::bit(coord);
+        removed
+    }
+
+    /// Iterator over the clues in row-major order.
+    pub fn iter(self) -> impl Iterator<Item = Coord> {
+        Coord::all().filter(move |&coord| self.contains(coord))
+    }
+
+    /// The image of this mask with every clue moved by the given symmetry.
+    #[must_use]
+    pub fn image(self, symmetry: Symmetry) -> Self {
+        self.iter().map(|coord| symmetry.apply(coord)).collect()
+    }
+
+    /// Returns true if the mask is unchanged by the given symmetry.
+    #[must_use]
+    pub fn is_symmetric(&self, symmetry: Symmetry) -> bool {
+        self.image(symmetry) == *self
+    }
+
+    /// Add clues until the mask is symmetric under the given symmetry, by
+    /// repeatedly taking the union with its image.
+    #[must_use]
+    pub fn symmetrize(mut self, symmetry: Symmetry) -> Self {
+        loop {
+            let next = self | self.image(symmetry);
+            if next == self {
+                return self;
+            }
+            self = next;
+        }
+    }
+
+    /// Format the mask as a hex string of 21 digits.
+    #[must_use]
+    pub fn to_hex(&self) -> String {
+        format!("{:0width$x}", self.0, width = HEX_DIGITS)
+    }
+
+    /// Parse a mask from a hex string as produced by `to_hex`. Returns None if
+    /// the string is not hex or sets bits past the last cell.
+    #[must_use]
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        if hex.is_empty() || hex.len() > HEX_DIGITS {
+            return None;
+        }
+        let bits = u128::from_str_radix(hex, 16).ok()?;
+        if bits & !Self::all().0 != 0 {
+            return None;
+        }
+        Some(ClueMask(bits))
+    }
+
+    #[inline]
+    fn bit(coord: Coord) -> u128 {
+        1 << coord.rowmajor_idx()
+    }
+}
+
+impl FromIterator<Coord> for ClueMask {
+    fn from_iter<I: IntoIterator<Item = Coord>>(iter: I) -> Self {
+        let mut mask = ClueMask::new();
+        for coord in iter {
+            mask.insert(coord);
+        }
+        mask
+    }
+}
+
+impl Extend<Coord> for ClueMask {
+    fn extend<I: IntoIterator<Item = Coord>>(&mut self, iter: I) {
+        for coord in iter {
+            self.insert(coord);
+        }
+    }
+}
+
+impl BitOr for ClueMask {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(mut self, rhs: Self) -> Self::Output {
+        self |= rhs;
+        self
+    }
+}
+
+impl BitOrAssign for ClueMask {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for ClueMask {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(mut self, rhs: Self) -> Self::Output {
+        self &= rhs;
+        self
+    }
+}
+
+impl BitAndAssign for ClueMask {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.0 &= rhs.0;
+    }
+}
+
+impl Sub for ClueMask {
+    type Output = Self;
+
+    #[inline]
+    fn sub(mut self, rhs: Self) -> Self::Output {
+        self -= rhs;
+        self
+    }
+}
+
+impl SubAssign for ClueMask {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0 &= !rhs.0;
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for ClueMask {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for ClueMask {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de;
+        use std::fmt;
+
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = ClueMask;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a hex clue mask or a list of coordinates")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                ClueMask::from_hex(v).ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))
+            }
+
+            fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut mask = ClueMask::new();
+                while let Some(coord) = seq.next_element::<Coord>()? {
+                    mask.insert(coord);
+                }
+                Ok(mask)
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+/// A symmetry of the board, used to make clue patterns symmetric.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Symmetry {
+    /// Rotation by half a turn around the center.
+    Rotate180,
+    /// Rotation by a quarter turn around the center. Masks symmetric under
+    /// this are also symmetric under `Rotate180`.
+    Rotate90,
+    /// Reflection across the middle row, swapping top and bottom.
+    MirrorRows,
+    /// Reflection across the middle column, swapping left and right.
+    MirrorCols,
+    /// Reflection across the diagonal from the top left to the bottom right.
+    Diagonal,
+    /// Reflection across the diagonal from the top right to the bottom left.
+    AntiDiagonal,
+}
+
+impl Symmetry {
+    /// Move a coordinate by this symmetry.
+    #[must_use]
+    pub fn apply(self, coord: Coord) -> Coord {
+        const LAST: u8 = Row::WIDTH - 1;
+        let row = coord.row().inner();
+        let col = coord.col().inner();
+        let (row, col) = match self {
+            Symmetry::Rotate180 => (LAST - row, LAST - col),
+            Symmetry::Rotate90 => (col, LAST - row),
+            Symmetry::MirrorRows => (LAST - row, col),
+            Symmetry::MirrorCols => (row, LAST - col),
+            Symmetry::Diagonal => (col, row),
+            Symmetry::AntiDiagonal => (LAST - col, LAST - row),
+        };
+        Coord::new(Row::new(row), Col::new(col))
+    }
+}
+
+/// Small deterministic random number generator, so masks can be reproduced
+/// from a seed without an external dependency.
+pub(crate) struct SplitMix64(pub(crate) u64);
+
+impl SplitMix64 {
+    pub(crate) fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Shuffle the items in place, with every order equally likely.
+    pub(crate) fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = (self.next() % (i + 1) as u64) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::puzzle1;
+
+    #[test]
+    fn apply_inverts_from_puzzle() {
+        crate::setup();
+
+        let puzzle = puzzle1();
+        let solution = puzzle.solve().unwrap();
+        let mask = ClueMask::from_puzzle(&puzzle);
+        assert_eq!(mask.len(), 30);
+        assert_eq!(Board::apply_mask(&solution, &mask), puzzle);
+        assert_eq!(ClueMask::from_puzzle(&solution), ClueMask::all());
+        assert_eq!(Board::apply_mask(&solution, &ClueMask::new()), Board::new());
+    }
+
+    #[test]
+    fn set_ops() {
+        let a = Coord::new(Row::new(0), Col::new(0));
+        let b = Coord::new(Row::new(8), Col::new(8));
+        let mut mask = ClueMask::new();
+        assert!(mask.is_empty());
+        assert!(mask.insert(a));
+        assert!(!mask.insert(a));
+        let other: ClueMask = [b].into_iter().collect();
+        assert_eq!((mask | other).iter().collect::<Vec<_>>(), [a, b]);
+        assert!((mask & other).is_empty());
+        assert_eq!(
+            ClueMask::all() - mask,
+            ClueMask::all() - [a].into_iter().collect()
+        );
+        assert_eq!((ClueMask::all() - mask).len(), 80);
+        assert!(mask.remove(a));
+        assert!(!mask.remove(a));
+    }
+
+    #[test]
+    fn hex() {
+        let mask = ClueMask::from_puzzle(&puzzle1());
+        let hex = mask.to_hex();
+        assert_eq!(hex.len(), 21);
+        assert_eq!(ClueMask::from_hex(&hex), Some(mask));
+        assert_eq!(ClueMask::all().to_hex(), "1ffffffffffffffffffff");
+        assert_eq!(ClueMask::from_hex("3ffffffffffffffffffff"), None);
+        assert_eq!(ClueMask::from_hex("xyz"), None);
+        assert_eq!(ClueMask::from_hex(""), None);
+    }
+
+    #[test]
+    fn symmetrize() {
+        let symmetries = [
+            Symmetry::Rotate180,
+            Symmetry::Rotate90,
+            Symmetry::MirrorRows,
+            Symmetry::MirrorCols,
+            Symmetry::Diagonal,
+            Symmetry::AntiDiagonal,
+        ];
+        for seed in 0..10 {
+            let mask = ClueMask::random(20, seed);
+            assert_eq!(mask.len(), 20);
+            for symmetry in symmetries {
+                let sym = mask.symmetrize(symmetry);
+                assert!(sym.is_symmetric(symmetry), "{:?}", symmetry);
+                assert_eq!(sym & mask, mask);
+            }
+            assert!(mask
+                .symmetrize(Symmetry::Rotate90)
+                .is_symmetric(Symmetry::Rotate180));
+        }
+        let center = Coord::new(Row::new(4), Col::new(4));
+        let corner = Coord::new(Row::new(0), Col::new(0));
+        let mask: ClueMask = [center, corner].into_iter().collect();
+        assert_eq!(mask.symmetrize(Symmetry::Rotate90).len(), 5);
+        assert_eq!(mask.symmetrize(Symmetry::Diagonal), mask);
+    }
+
+    #[test]
+    fn random_is_seeded() {
+        assert_eq!(ClueMask::random(30, 7), ClueMask::random(30, 7));
+        assert_ne!(ClueMask::random(30, 7), ClueMask::random(30, 8));
+        assert_eq!(ClueMask::random(0, 1), ClueMask::new());
+        assert_eq!(ClueMask::random(81, 1), ClueMask::all());
+    }
+
+    #[test]
+    #[should_panic(expected = "mask can hold at most 81 clues, got 82")]
+    fn random_too_many() {
+        let _ = ClueMask::random(82, 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize() {
+        let mask: ClueMask = [
+            Coord::new(Row::new(0), Col::new(1)),
+            Coord::new(Row::new(8), Col::new(8)),
+        ]
+        .into_iter()
+        .collect();
+        let ser = serde_json::to_string(&mask).unwrap();
+        assert_eq!(ser, r#""100000000000000000002""#);
+        let roundtrip: ClueMask = serde_json::from_str(&ser).unwrap();
+        assert_eq!(roundtrip, mask);
+
+        let list = serde_json::to_string(&mask.iter().collect::<Vec<_>>()).unwrap();
+        assert_eq!(list, r#"[{"row":0,"col":1},{"row":8,"col":8}]"#);
+        let from_list: ClueMask = serde_json::from_str(&list).unwrap();
+        assert_eq!(from_list, mask);
+
+        assert!(serde_json::from_str::<ClueMask>(r#""zz""#).is_err());
+    }
+}
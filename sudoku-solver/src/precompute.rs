@@ -0,0 +1,467 @@
+//! Compute a puzzle's artifacts in the background.
+//!
+//! A frontend can start on everything it might show for a puzzle as soon as
+//! the puzzle is selected, then use whichever artifacts are ready when they
+//! are needed. Each artifact is computed on its own thread. Artifacts built
+//! from the solver's trace share a single traced solve, so asking for both the
+//! strength and the lesson solves the board once.
+use std::ops::{BitOr, BitOrAssign};
+#[cfg(test)]
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::task::Poll;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use thiserror::Error;
+
+use crate::analysis::PuzzleStrength;
+use crate::lesson::{self, Lesson, LessonPolicy};
+use crate::trace::{Deduction, NopDeductiveTracer};
+use crate::{Board, SolveOutcome, Solver};
+
+/// The kinds of artifact which can be computed for a puzzle.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ArtifactKind {
+    /// The outcome of running the solver.
+    Solution,
+    /// How much work the solver did, as a `PuzzleStrength`.
+    Strength,
+    /// A lesson built from the solver's trace with the default policy.
+    Lesson,
+}
+
+impl ArtifactKind {
+    /// Every kind of artifact.
+    pub const ALL: [ArtifactKind; 3] = [
+        ArtifactKind::Solution,
+        ArtifactKind::Strength,
+        ArtifactKind::Lesson,
+    ];
+
+    /// Whether this artifact is built from the solver's trace.
+    fn uses_trace(self) -> bool {
+        !matches!(self, ArtifactKind::Solution)
+    }
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// A set of artifact kinds to compute.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct TaskSet(u8);
+
+impl TaskSet {
+    /// The empty set.
+    #[must_use]
+    pub const fn new() -> Self {
+        TaskSet(0)
+    }
+
+    /// Every kind of artifact.
+    #[must_use]
+    pub fn all() -> Self {
+        ArtifactKind::ALL.into_iter().collect()
+    }
+
+    /// Returns true if no artifacts are in the set.
+    #[must_use]
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns true if the given kind is in the set.
+    #[must_use]
+    pub fn contains(self, kind: ArtifactKind) -> bool {
+        self.0 & kind.bit() != 0
+    }
+
+    /// Add a kind to the set.
+    pub fn insert(&mut self, kind: ArtifactKind) {
+        self.0 |= kind.bit();
+    }
+
+    /// Iterate over the kinds in the set.
+    pub fn iter(self) -> impl Iterator<Item = ArtifactKind> {
+        ArtifactKind::ALL
+            .into_iter()
+            .filter(move |&kind| self.contains(kind))
+    }
+}
+
+impl From<ArtifactKind> for TaskSet {
+    fn from(kind: ArtifactKind) -> Self {
+        TaskSet(kind.bit())
+    }
+}
+
+impl FromIterator<ArtifactKind> for TaskSet {
+    fn from_iter<I: IntoIterator<Item = ArtifactKind>>(iter: I) -> Self {
+        let mut set = TaskSet::new();
+        for kind in iter {
+            set.insert(kind);
+        }
+        set
+    }
+}
+
+impl BitOr for TaskSet {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        TaskSet(self.0 | rhs.0)
+    }
+}
+
+impl BitOr<ArtifactKind> for TaskSet {
+    type Output = Self;
+
+    fn bitor(self, rhs: ArtifactKind) -> Self {
+        self | TaskSet::from(rhs)
+    }
+}
+
+impl BitOr for ArtifactKind {
+    type Output = TaskSet;
+
+    fn bitor(self, rhs: Self) -> TaskSet {
+        TaskSet::from(self) | rhs
+    }
+}
+
+impl BitOrAssign<ArtifactKind> for TaskSet {
+    fn bitor_assign(&mut self, rhs: ArtifactKind) {
+        self.insert(rhs);
+    }
+}
+
+/// A computed artifact.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Artifact {
+    /// The outcome of running the solver.
+    Solution(SolveOutcome),
+    /// How much work the solver did.
+    Strength(PuzzleStrength),
+    /// A lesson built from the solver's trace.
+    Lesson(Lesson),
+}
+
+impl Artifact {
+    /// The kind of this artifact.
+    #[must_use]
+    pub fn kind(&self) -> ArtifactKind {
+        match self {
+            Artifact::Solution(_) => ArtifactKind::Solution,
+            Artifact::Strength(_) => ArtifactKind::Strength,
+            Artifact::Lesson(_) => ArtifactKind::Lesson,
+        }
+    }
+}
+
+/// Reasons an artifact is not available.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Error)]
+pub enum PrecomputeError {
+    /// The artifact was not in the requested tasks.
+    #[error("{0:?} was not requested")]
+    NotRequested(ArtifactKind),
+    /// The precomputation was cancelled before the artifact was finished.
+    #[error("precomputation was cancelled")]
+    Cancelled,
+    /// The artifact was not finished before the timeout.
+    #[error("timed out waiting for {0:?}")]
+    TimedOut(ArtifactKind),
+}
+
+/// State of a single artifact.
+#[derive(Debug)]
+enum Slot {
+    NotRequested,
+    Pending,
+    Ready(Artifact),
+    Cancelled,
+}
+
+impl Slot {
+    fn poll(&self, kind: ArtifactKind) -> Poll<Result<Artifact, PrecomputeError>> {
+        match self {
+            Slot::NotRequested => Poll::Ready(Err(PrecomputeError::NotRequested(kind))),
+            Slot::Pending => Poll::Pending,
+            Slot::Ready(artifact) => Poll::Ready(Ok(artifact.clone())),
+            Slot::Cancelled => Poll::Ready(Err(PrecomputeError::Cancelled)),
+        }
+    }
+}
+
+/// State shared between the handle and the worker threads.
+struct Shared {
+    board: Board,
+    solver: Arc<Solver>,
+    tasks: TaskSet,
+    cancelled: AtomicBool,
+    /// Outcome and trace of the traced solve, or None if it was cancelled.
+    trace: OnceLock<Option<(SolveOutcome, Vec<Deduction>)>>,
+    slots: Mutex<[Slot; 3]>,
+    changed: Condvar,
+    /// Number of traced solves run, which should never be more than one.
+    #[cfg(test)]
+    traces_run: AtomicUsize,
+}
+
+impl Shared {
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Run the traced solve, or wait for the thread already running it.
+    fn traced(&self) -> Option<&(SolveOutcome, Vec<Deduction>)> {
+        self.trace
+            .get_or_init(|| {
+                #[cfg(test)]
+                self.traces_run.fetch_add(1, Ordering::Relaxed);
+                let mut budget = self.solver.budget.clone();
+                let (outcome, trace) =
+                    self.solver
+                        .run_with(&self.board, &mut budget, Vec::new(), || self.is_cancelled());
+                outcome.map(|outcome| (outcome, trace))
+            })
+            .as_ref()
+    }
+
+    /// Compute an artifact, returning None if cancelled.
+    fn compute(&self, kind: ArtifactKind) -> Option<Artifact> {
+        if self.is_cancelled() {
+            return None;
+        }
+        match kind {
+            // Only pay for tracing when something else needs the trace.
+            ArtifactKind::Solution if !self.tasks.iter().any(ArtifactKind::uses_trace) => {
+                let mut budget = self.solver.budget.clone();
+                let (outcome, _) =
+                    self.solver
+                        .run_with(&self.board, &mut budget, NopDeductiveTracer, || {
+                            self.is_cancelled()
+                        });
+                outcome.map(Artifact::Solution)
+            }
+            ArtifactKind::Solution => self
+                .traced()
+                .map(|(outcome, _)| Artifact::Solution(outcome.clone())),
+            ArtifactKind::Strength => self.traced().map(|(outcome, trace)| {
+                Artifact::Strength(PuzzleStrength::from_trace(outcome.is_solved(), trace))
+            }),
+            ArtifactKind::Lesson => self
+                .traced()
+                .map(|(_, trace)| Artifact::Lesson(lesson::build(trace, &LessonPolicy::default()))),
+        }
+    }
+
+    /// Store the result of a worker, unless the artifact was already marked
+    /// cancelled.
+    fn finish(&self, kind: ArtifactKind, artifact: Option<Artifact>) {
+        let mut slots = self.slots.lock().unwrap();
+        let slot = &mut slots[kind as usize];
+        if let Slot::Pending = slot {
+            *slot = artifact.map_or(Slot::Cancelled, Slot::Ready);
+        }
+        self.changed.notify_all();
+    }
+}
+
+/// Handle to artifacts being computed in the background for a puzzle.
+/// Dropping the handle cancels any work still outstanding.
+pub struct Precompute {
+    shared: Arc<Shared>,
+}
+
+impl Precompute {
+    /// Start computing the given artifacts for the board, each on its own
+    /// thread, using the given solver's options and budget.
+    #[must_use]
+    pub fn spawn(board: Board, tasks: TaskSet, solver: Arc<Solver>) -> Self {
+        let slots = ArtifactKind::ALL.map(|kind| {
+            if tasks.contains(kind) {
+                Slot::Pending
+            } else {
+                Slot::NotRequested
+            }
+        });
+        let shared = Arc::new(Shared {
+            board,
+            solver,
+            tasks,
+            cancelled: AtomicBool::new(false),
+            trace: OnceLock::new(),
+            slots: Mutex::new(slots),
+            changed: Condvar::new(),
+            #[cfg(test)]
+            traces_run: AtomicUsize::new(0),
+        });
+        for kind in tasks.iter() {
+            let shared = Arc::clone(&shared);
+            thread::spawn(move || {
+                let artifact = shared.compute(kind);
+                shared.finish(kind, artifact);
+            });
+        }
+        Precompute { shared }
+    }
+
+    /// The artifacts which were requested.
+    #[must_use]
+    pub fn tasks(&self) -> TaskSet {
+        self.shared.tasks
+    }
+
+    /// Get the artifact if it is finished, without blocking.
+    pub fn poll(&self, kind: ArtifactKind) -> Poll<Result<Artifact, PrecomputeError>> {
+        self.shared.slots.lock().unwrap()[kind as usize].poll(kind)
+    }
+
+    /// Wait up to `timeout` for the artifact to finish.
+    pub fn wait(&self, kind: ArtifactKind, timeout: Duration) -> Result<Artifact, PrecomputeError> {
+        let deadline = Instant::now() + timeout;
+        let mut slots = self.shared.slots.lock().unwrap();
+        loop {
+            if let Poll::Ready(result) = slots[kind as usize].poll(kind) {
+                return result;
+            }
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return Err(PrecomputeError::TimedOut(kind));
+            }
+            slots = self.shared.changed.wait_timeout(slots, left).unwrap().0;
+        }
+    }
+
+    /// Stop any work still outstanding. Solves stop before the next board they
+    /// would reduce, and artifacts which are not finished report
+    /// `PrecomputeError::Cancelled`. Finished artifacts are kept.
+    pub fn cancel(&self) {
+        self.shared.cancelled.store(true, Ordering::Relaxed);
+        let mut slots = self.shared.slots.lock().unwrap();
+        for slot in slots.iter_mut() {
+            if let Slot::Pending = slot {
+                *slot = Slot::Cancelled;
+            }
+        }
+        self.shared.changed.notify_all();
+    }
+
+    /// Returns true if the precomputation was cancelled.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.shared.is_cancelled()
+    }
+}
+
+impl Drop for Precompute {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::inkala;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn artifacts_match_direct() {
+        crate::setup();
+
+        let board = inkala();
+        let handle = Precompute::spawn(board.clone(), TaskSet::all(), Arc::new(Solver::new()));
+        let solution = handle.wait(ArtifactKind::Solution, TIMEOUT).unwrap();
+        let strength = handle.wait(ArtifactKind::Strength, TIMEOUT).unwrap();
+        let lesson = handle.wait(ArtifactKind::Lesson, TIMEOUT).unwrap();
+
+        assert_eq!(solution, Artifact::Solution(Solver::new().run(&board)));
+        assert_eq!(
+            strength,
+            Artifact::Strength(PuzzleStrength::measure(&board))
+        );
+        let (_, trace) = board.solve_with(Vec::new());
+        assert_eq!(
+            lesson,
+            Artifact::Lesson(lesson::build(&trace, &LessonPolicy::default()))
+        );
+        assert_eq!(handle.shared.traces_run.load(Ordering::Relaxed), 1);
+        assert!(matches!(
+            handle.poll(ArtifactKind::Lesson),
+            Poll::Ready(Ok(Artifact::Lesson(_)))
+        ));
+    }
+
+    #[test]
+    fn untraced_solution() {
+        let board = inkala();
+        let handle = Precompute::spawn(
+            board.clone(),
+            ArtifactKind::Solution.into(),
+            Arc::new(Solver::human()),
+        );
+        assert_eq!(
+            handle.wait(ArtifactKind::Solution, TIMEOUT),
+            Ok(Artifact::Solution(Solver::human().run(&board)))
+        );
+        assert_eq!(handle.shared.traces_run.load(Ordering::Relaxed), 0);
+        assert_eq!(
+            handle.poll(ArtifactKind::Strength),
+            Poll::Ready(Err(PrecomputeError::NotRequested(ArtifactKind::Strength)))
+        );
+        assert_eq!(
+            handle.wait(ArtifactKind::Lesson, TIMEOUT),
+            Err(PrecomputeError::NotRequested(ArtifactKind::Lesson))
+        );
+    }
+
+    #[test]
+    fn cancel() {
+        let handle = Precompute::spawn(inkala(), TaskSet::all(), Arc::new(Solver::new()));
+        handle.cancel();
+        assert!(handle.is_cancelled());
+        for kind in ArtifactKind::ALL {
+            // Anything not already finished reports cancellation right away.
+            match handle.wait(kind, Duration::ZERO) {
+                Ok(artifact) => assert_eq!(artifact.kind(), kind),
+                Err(err) => assert_eq!(err, PrecomputeError::Cancelled),
+            }
+        }
+    }
+
+    #[test]
+    fn cancel_stops_solve() {
+        let mut checks = 0;
+        let (outcome, trace) =
+            Solver::new().run_with(&inkala(), &mut Default::default(), Vec::new(), || {
+                checks += 1;
+                checks == 3
+            });
+        assert_eq!(outcome, None);
+        assert_eq!(checks, 3);
+        // Two boards were reduced before the solve was cancelled.
+        let reductions = trace
+            .iter()
+            .filter(|deduction| deduction.reason == crate::trace::DeductionReason::InitialState)
+            .count();
+        assert_eq!(reductions, 2);
+    }
+
+    #[test]
+    fn task_set() {
+        let set = ArtifactKind::Solution | ArtifactKind::Lesson;
+        assert!(set.contains(ArtifactKind::Solution));
+        assert!(!set.contains(ArtifactKind::Strength));
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            [ArtifactKind::Solution, ArtifactKind::Lesson]
+        );
+        assert_eq!(set | ArtifactKind::Strength, TaskSet::all());
+        assert!(TaskSet::new().is_empty());
+    }
+}
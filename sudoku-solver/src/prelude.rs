@@ -0,0 +1,39 @@
+//! The types most programs using the solver need, for glob importing.
+//!
+//! ```
+//! use sudoku_solver::prelude::*;
+//!
+//! let board = Board::from_rows([
+//!     "...1.....",
+//!     "....586.1",
+//!     "8.136..9.",
+//!     "5.....4.3",
+//!     "..36.18..",
+//!     "6.4.....7",
+//!     ".3..845.6",
+//!     "1.572....",
+//!     ".....3...",
+//! ])
+//! .unwrap();
+//! assert!(board.hint().is_some());
+//! assert_eq!(Difficulty::of(&board), Some(Difficulty::Easy));
+//! assert!(Solver::new().run(&board).is_solved());
+//! ```
+//!
+//! Everything here is available without any optional features. Items which
+//! need a feature are in a sub-prelude named after it, such as
+//! `prelude::with_serde`, to be glob imported alongside this one. It is not
+//! named `serde` so the glob does not shadow the `serde` crate.
+pub use crate::analysis::Hint;
+pub use crate::generate::Difficulty;
+pub use crate::{
+    AvailSet, Board, Col, Coord, House, Intersect, ParseBoardError, Row, Sector, SolveOutcome,
+    Solver, Val, Zone,
+};
+
+/// Items of the everyday surface which need the `serde` feature.
+#[cfg(feature = "serde")]
+pub mod with_serde {
+    pub use crate::trace::TraceDocument;
+    pub use crate::{BoardVersionError, VersionedBoard};
+}
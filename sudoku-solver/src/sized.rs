@@ -0,0 +1,375 @@
+//! Boards of other sizes, with rectangular boxes, such as 6x6 boards with
+//! boxes two rows tall and three columns wide.
+//!
+//! `Board` and the deductive solver are fixed at 9x9, which lets them use
+//! lookup tables and fixed-size storage throughout. Boards here are described
+//! by a `Dims` type instead and solved by a simpler search which only applies
+//! naked and hidden singles before guessing. `Nine` is included so results can
+//! be checked against the main solver.
+use std::fmt;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+
+use thiserror::Error;
+
+use crate::{Board, Coord, Zone};
+
+/// Dimensions of a board with rectangular boxes.
+pub trait Dims: Copy + Default + fmt::Debug + Eq + Hash {
+    /// Height of each box in rows.
+    const BOX_HEIGHT: usize;
+    /// Width of each box in columns.
+    const BOX_WIDTH: usize;
+    /// Number of rows and columns, and the largest value.
+    const SIDE: usize = Self::BOX_HEIGHT * Self::BOX_WIDTH;
+    /// Total number of cells.
+    const CELLS: usize = Self::SIDE * Self::SIDE;
+}
+
+/// 6x6 boards with boxes two rows tall and three columns wide.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Six;
+
+impl Dims for Six {
+    const BOX_HEIGHT: usize = 2;
+    const BOX_WIDTH: usize = 3;
+}
+
+/// Standard 9x9 boards with 3x3 boxes.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Nine;
+
+impl Dims for Nine {
+    const BOX_HEIGHT: usize = 3;
+    const BOX_WIDTH: usize = 3;
+}
+
+/// 12x12 boards with boxes three rows tall and four columns wide.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Twelve;
+
+impl Dims for Twelve {
+    const BOX_HEIGHT: usize = 3;
+    const BOX_WIDTH: usize = 4;
+}
+
+/// Error when parsing a sized board from text.
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+pub enum ParseSizedBoardError {
+    /// There was not one row per row of the board.
+    #[error("found {len} rows, expected {expected}")]
+    WrongRowCount { len: usize, expected: usize },
+    /// A row did not have one cell per column. Rows are counted from 0.
+    #[error("row {row} has {len} cells, expected {expected}")]
+    WrongRowLength {
+        row: usize,
+        len: usize,
+        expected: usize,
+    },
+    /// A cell was not a value on this board or a blank. Rows and columns are
+    /// counted from 0.
+    #[error("invalid cell {ch:?} at row {row} column {col}")]
+    InvalidCell { row: usize, col: usize, ch: char },
+}
+
+/// Board with the given dimensions. Cells are indexed by `(row, col)`, counted
+/// from 0, and hold values from 1 to `D::SIDE`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SizedBoard<D: Dims> {
+    cells: Vec<Option<u8>>,
+    _dims: PhantomData<D>,
+}
+
+impl<D: Dims> SizedBoard<D> {
+    /// Create a new board with no cells filled.
+    #[must_use]
+    pub fn new() -> Self {
+        const { assert!(D::SIDE <= 16, "candidates are stored as 16 bit masks") };
+        SizedBoard {
+            cells: vec![None; D::CELLS],
+            _dims: PhantomData,
+        }
+    }
+
+    /// Build a board from one string per row. Cells are `1`-`9`, then `A`-`G`
+    /// for 10-16, or `.`, `0`, or a space for a blank.
+    pub fn from_rows(rows: &[&str]) -> Result<Self, ParseSizedBoardError> {
+        if rows.len() != D::SIDE {
+            return Err(ParseSizedBoardError::WrongRowCount {
+                len: rows.len(),
+                expected: D::SIDE,
+            });
+        }
+        let mut board = Self::new();
+        for (row, text) in rows.iter().enumerate() {
+            let len = text.chars().count();
+            if len != D::SIDE {
+                return Err(ParseSizedBoardError::WrongRowLength {
+                    row,
+                    len,
+                    expected: D::SIDE,
+                });
+            }
+            for (col, ch) in text.chars().enumerate() {
+                board[(row, col)] = match ch {
+                    '.' | '0' | ' ' => None,
+                    _ => match ch.to_digit(17) {
+                        Some(val) if (1..=D::SIDE as u32).contains(&val) => Some(val as u8),
+                        _ => return Err(ParseSizedBoardError::InvalidCell { row, col, ch }),
+                    },
+                };
+            }
+        }
+        Ok(board)
+    }
+
+    /// View of the board as a flat slice in row-major order.
+    #[must_use]
+    pub fn row_major(&self) -> &[Option<u8>] {
+        &self.cells
+    }
+
+    /// Returns true if every cell is filled and no row, column, or box repeats
+    /// a value.
+    pub fn is_solved(&self) -> bool {
+        self.cells.iter().all(Option::is_some)
+            && houses::<D>().all(|house| {
+                let mut seen = 0u16;
+                house.iter().all(|&idx| {
+                    let bit = 1 << (self.cells[idx].unwrap() - 1);
+                    let fresh = seen & bit == 0;
+                    seen |= bit;
+                    fresh
+                })
+            })
+    }
+
+    /// Solve the board, returning the first solution found, or None if it has
+    /// no solution.
+    #[must_use]
+    pub fn solve(&self) -> Option<Self> {
+        let all = (1u16 << D::SIDE) - 1;
+        let mut cands: Vec<u16> = self
+            .cells
+            .iter()
+            .map(|cell| cell.map_or(all, |val| 1 << (val - 1)))
+            .collect();
+        if !reduce::<D>(&mut cands) {
+            return None;
+        }
+        let solved = search::<D>(cands)?;
+        let mut board = Self::new();
+        for (cell, cands) in board.cells.iter_mut().zip(solved) {
+            *cell = Some(cands.trailing_zeros() as u8 + 1);
+        }
+        Some(board)
+    }
+}
+
+impl<D: Dims> Default for SizedBoard<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D: Dims> Index<(usize, usize)> for SizedBoard<D> {
+    type Output = Option<u8>;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        assert!(row < D::SIDE && col < D::SIDE, "cell out of range");
+        &self.cells[row * D::SIDE + col]
+    }
+}
+
+impl<D: Dims> IndexMut<(usize, usize)> for SizedBoard<D> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Self::Output {
+        assert!(row < D::SIDE && col < D::SIDE, "cell out of range");
+        &mut self.cells[row * D::SIDE + col]
+    }
+}
+
+impl From<&Board> for SizedBoard<Nine> {
+    fn from(board: &Board) -> Self {
+        let mut sized = Self::new();
+        for (coord, &cell) in Coord::all().zip(board.row_major()) {
+            sized.cells[coord.rowmajor_idx()] = cell.map(|val| val.val());
+        }
+        sized
+    }
+}
+
+/// Every row, then every column, then every box, as lists of cell indexes.
+fn houses<D: Dims>() -> impl Iterator<Item = Vec<usize>> {
+    let side = D::SIDE;
+    let rows = (0..side).map(move |r| (0..side).map(|c| r * side + c).collect());
+    let cols = (0..side).map(move |c| (0..side).map(|r| r * side + c).collect());
+    let boxes = (0..side).map(move |b| {
+        let base_row = b / D::BOX_HEIGHT * D::BOX_HEIGHT;
+        let base_col = b % D::BOX_HEIGHT * D::BOX_WIDTH;
+        (0..side)
+            .map(|i| (base_row + i / D::BOX_WIDTH) * side + base_col + i % D::BOX_WIDTH)
+            .collect()
+    });
+    rows.chain(cols).chain(boxes)
+}
+
+/// Apply naked and hidden singles until neither makes progress. Returns false
+/// if the candidates contradict.
+fn reduce<D: Dims>(cands: &mut [u16]) -> bool {
+    let houses: Vec<Vec<usize>> = houses::<D>().collect();
+    loop {
+        let mut changed = false;
+        for house in &houses {
+            // Naked singles: a cell with one value left removes it from the
+            // rest of the house.
+            for &idx in house {
+                if cands[idx].count_ones() != 1 {
+                    continue;
+                }
+                for &other in house {
+                    if other != idx && cands[other] & cands[idx] != 0 {
+                        cands[other] &= !cands[idx];
+                        if cands[other] == 0 {
+                            return false;
+                        }
+                        changed = true;
+                    }
+                }
+            }
+            // Hidden singles: a value with one cell left in the house goes
+            // there.
+            for val in 0..D::SIDE {
+                let bit = 1 << val;
+                let mut cells = house.iter().filter(|&&idx| cands[idx] & bit != 0);
+                match (cells.next(), cells.next()) {
+                    (None, _) => return false,
+                    (Some(&idx), None) if cands[idx] != bit => {
+                        cands[idx] = bit;
+                        changed = true;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        if !changed {
+            return true;
+        }
+    }
+}
+
+/// Guess on the cell with the fewest values left, reducing after each guess,
+/// until a solution is found.
+fn search<D: Dims>(cands: Vec<u16>) -> Option<Vec<u16>> {
+    let guess = cands
+        .iter()
+        .enumerate()
+        .filter(|(_, cands)| cands.count_ones() > 1)
+        .min_by_key(|(_, cands)| cands.count_ones())
+        .map(|(idx, _)| idx);
+    let idx = match guess {
+        Some(idx) => idx,
+        None => return Some(cands),
+    };
+    let mut remaining = cands[idx];
+    while remaining != 0 {
+        let bit = remaining & remaining.wrapping_neg();
+        remaining &= !bit;
+        let mut copy = cands.clone();
+        copy[idx] = bit;
+        if reduce::<D>(&mut copy) {
+            if let Some(solved) = search::<D>(copy) {
+                return Some(solved);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::inkala;
+
+    #[test]
+    fn solve_six() {
+        crate::setup();
+
+        let puzzle = SizedBoard::<Six>::from_rows(&[
+            "1..4..", //
+            "..6..3", //
+            ".3..6.", //
+            "5..2..", //
+            "..2..5", //
+            "6..3..", //
+        ])
+        .unwrap();
+        let expected = SizedBoard::<Six>::from_rows(&[
+            "123456", //
+            "456123", //
+            "231564", //
+            "564231", //
+            "312645", //
+            "645312", //
+        ])
+        .unwrap();
+        assert!(expected.is_solved());
+        assert!(!puzzle.is_solved());
+        assert_eq!(puzzle.solve(), Some(expected));
+    }
+
+    #[test]
+    fn solve_twelve() {
+        crate::setup();
+
+        let solution = SizedBoard::<Twelve>::new().solve().unwrap();
+        assert!(solution.is_solved());
+        assert_eq!(solution.row_major().len(), 144);
+        assert_eq!(solution.row_major().iter().flatten().max(), Some(&12));
+    }
+
+    #[test]
+    fn nine_agrees_with_board() {
+        crate::setup();
+
+        let board = inkala();
+        let solution = SizedBoard::from(&board).solve().unwrap();
+        assert_eq!(solution, SizedBoard::from(&board.solve().unwrap()));
+    }
+
+    #[test]
+    fn unsolveable() {
+        let mut board = SizedBoard::<Six>::new();
+        board[(0, 0)] = Some(1);
+        board[(0, 5)] = Some(1);
+        assert_eq!(board.solve(), None);
+        assert!(!board.is_solved());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            SizedBoard::<Six>::from_rows(&["123456"]),
+            Err(ParseSizedBoardError::WrongRowCount {
+                len: 1,
+                expected: 6
+            })
+        );
+        let err = SizedBoard::<Six>::from_rows(&["123456", "12", "", "", "", ""]).unwrap_err();
+        assert_eq!(err.to_string(), "row 1 has 2 cells, expected 6");
+        let err = SizedBoard::<Six>::from_rows(&["..7...", "", "", "", "", ""]).unwrap_err();
+        assert_eq!(
+            err,
+            ParseSizedBoardError::InvalidCell {
+                row: 0,
+                col: 2,
+                ch: '7'
+            }
+        );
+        let mut rows = vec!["............"; 12];
+        rows[3] = "...C..B.A...";
+        let board = SizedBoard::<Twelve>::from_rows(&rows).unwrap();
+        assert_eq!(board[(3, 3)], Some(12));
+        assert_eq!(board[(3, 8)], Some(10));
+    }
+}
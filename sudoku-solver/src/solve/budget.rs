@@ -0,0 +1,398 @@
+//! Limits on how much work solving may do, shared by every operation that can
+//! give up part way.
+//!
+//! A `Budget` holds optional limits on steps, guesses, wall-clock time, and
+//! estimated memory, along with how much of each has been used. Composite
+//! operations split their budget into sub-budgets for each piece and charge
+//! the parent with what each piece used, so the caller's overall limit holds
+//! across all of them.
+use std::fmt;
+use std::mem;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::solve::remaining::RemainingTracker;
+
+/// Estimated bytes of search state for each board on the guess stack.
+const FRAME_BYTES: u64 = mem::size_of::<RemainingTracker>() as u64;
+
+/// The kinds of limit a `Budget` can have.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum LimitKind {
+    /// Number of boards reduced, counting the initial board.
+    Steps,
+    /// Number of guessed boards reduced.
+    Guesses,
+    /// Wall-clock time, in milliseconds.
+    WallClock,
+    /// Estimated peak size of the guess stack, in bytes.
+    Memory,
+}
+
+impl LimitKind {
+    /// Unit the limit is measured in.
+    #[must_use]
+    pub fn unit(self) -> &'static str {
+        match self {
+            LimitKind::Steps => "steps",
+            LimitKind::Guesses => "guesses",
+            LimitKind::WallClock => "ms",
+            LimitKind::Memory => "bytes",
+        }
+    }
+}
+
+impl fmt::Display for LimitKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            LimitKind::Steps => "step",
+            LimitKind::Guesses => "guess",
+            LimitKind::WallClock => "wall-clock",
+            LimitKind::Memory => "memory",
+        })
+    }
+}
+
+/// Error when work stops because a limit of its budget was reached.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Error)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[error("{} limit of {} {} exceeded", .which, .limit, .which.unit())]
+pub struct BudgetExceeded {
+    /// The limit which was reached.
+    pub which: LimitKind,
+    /// Amount used when the work stopped, in the unit of the limit.
+    pub consumed: u64,
+    /// The limit, in the same unit.
+    pub limit: u64,
+}
+
+/// Amount of each limit a budget has used.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BudgetUsage {
+    /// Boards reduced.
+    pub steps: u64,
+    /// Guessed boards reduced.
+    pub guesses: u64,
+    /// Time spent working.
+    pub time: Duration,
+    /// Largest estimated size of the guess stack, in bytes.
+    pub peak_memory: u64,
+}
+
+/// Limits on the work an operation may do, and how much it has done. The
+/// default budget has no limits; add them with the `with_` methods.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Budget {
+    /// Most boards that may be reduced.
+    pub max_steps: Option<u64>,
+    /// Most guessed boards that may be reduced.
+    pub max_guesses: Option<u64>,
+    /// Most time that may be spent working.
+    pub max_time: Option<Duration>,
+    /// Largest estimated size of the guess stack, in bytes.
+    pub max_memory: Option<u64>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    consumed: BudgetUsage,
+}
+
+impl Budget {
+    /// Create a budget with no limits.
+    #[must_use]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Limit the number of boards reduced.
+    #[must_use]
+    pub fn with_steps(mut self, max: u64) -> Self {
+        self.max_steps = Some(max);
+        self
+    }
+
+    /// Limit the number of guessed boards reduced.
+    #[must_use]
+    pub fn with_guesses(mut self, max: u64) -> Self {
+        self.max_guesses = Some(max);
+        self
+    }
+
+    /// Limit the time spent working.
+    #[must_use]
+    pub fn with_time(mut self, max: Duration) -> Self {
+        self.max_time = Some(max);
+        self
+    }
+
+    /// Limit the estimated size of the guess stack, in bytes.
+    #[must_use]
+    pub fn with_memory(mut self, max: u64) -> Self {
+        self.max_memory = Some(max);
+        self
+    }
+
+    /// How much of the budget has been used.
+    #[must_use]
+    pub fn consumed(&self) -> BudgetUsage {
+        self.consumed
+    }
+
+    /// Create a sub-budget with the given fraction, from 0 to 1, of what is
+    /// left of each limit. Memory is a peak rather than a total, so the
+    /// sub-budget keeps the whole memory limit. Charge the parent with what
+    /// the sub-budget used with `charge` once the work is done.
+    #[must_use]
+    pub fn split(&self, fraction: f64) -> Budget {
+        let fraction = fraction.clamp(0.0, 1.0);
+        let part = |limit: Option<u64>, used: u64| {
+            limit.map(|limit| (limit.saturating_sub(used) as f64 * fraction) as u64)
+        };
+        Budget {
+            max_steps: part(self.max_steps, self.consumed.steps),
+            max_guesses: part(self.max_guesses, self.consumed.guesses),
+            max_time: self
+                .max_time
+                .map(|limit| limit.saturating_sub(self.consumed.time).mul_f64(fraction)),
+            max_memory: self.max_memory,
+            consumed: Default::default(),
+        }
+    }
+
+    /// Add the work used by a sub-budget to this budget.
+    pub fn charge(&mut self, sub: &Budget) {
+        let used = &mut self.consumed;
+        used.steps += sub.consumed.steps;
+        used.guesses += sub.consumed.guesses;
+        used.time += sub.consumed.time;
+        used.peak_memory = used.peak_memory.max(sub.consumed.peak_memory);
+    }
+
+    /// Start timing work against this budget.
+    pub(crate) fn meter(&mut self) -> Meter<'_> {
+        Meter {
+            budget: self,
+            start: Instant::now(),
+            exceeded: None,
+        }
+    }
+}
+
+/// Counts work against a budget while a search runs, and charges the time
+/// spent once it is finished.
+pub(crate) struct Meter<'a> {
+    budget: &'a mut Budget,
+    start: Instant,
+    exceeded: Option<BudgetExceeded>,
+}
+
+impl Meter<'_> {
+    /// Count a board about to be reduced with the given number of guesses
+    /// below it on the stack. Returns true if doing so would exceed a limit,
+    /// without counting the board.
+    pub(crate) fn exceeded(&mut self, depth: usize) -> bool {
+        let budget = &mut *self.budget;
+        let used = &mut budget.consumed;
+        let guess = depth > 0;
+        let memory = (depth as u64 + 1) * FRAME_BYTES;
+        let time = used.time + self.start.elapsed();
+        let millis = |time: Duration| time.as_millis() as u64;
+        let exceeded = |which, consumed, limit| {
+            Some(BudgetExceeded {
+                which,
+                consumed,
+                limit,
+            })
+        };
+        self.exceeded = match budget {
+            Budget {
+                max_steps: Some(limit),
+                ..
+            } if used.steps >= *limit => exceeded(LimitKind::Steps, used.steps, *limit),
+            Budget {
+                max_guesses: Some(limit),
+                ..
+            } if guess && used.guesses >= *limit => {
+                exceeded(LimitKind::Guesses, used.guesses, *limit)
+            }
+            Budget {
+                max_time: Some(limit),
+                ..
+            } if time >= *limit => exceeded(LimitKind::WallClock, millis(time), millis(*limit)),
+            Budget {
+                max_memory: Some(limit),
+                ..
+            } if memory > *limit => exceeded(LimitKind::Memory, memory, *limit),
+            _ => None,
+        };
+        if self.exceeded.is_some() {
+            return true;
+        }
+        used.steps += 1;
+        used.guesses += guess as u64;
+        used.peak_memory = used.peak_memory.max(memory);
+        false
+    }
+
+    /// Charge the time spent to the budget, and return the limit which
+    /// stopped the work, if any.
+    pub(crate) fn finish(self) -> Option<BudgetExceeded> {
+        self.budget.consumed.time += self.start.elapsed();
+        self.exceeded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::inkala;
+    use crate::solve::{ExhaustionKind, SolveOutcome, Solver};
+    use crate::Board;
+
+    /// Run the solver on the board within the budget, returning the limit
+    /// which stopped it.
+    fn exceeded(board: &Board, budget: &mut Budget) -> Option<BudgetExceeded> {
+        match Solver::new().run_within(board, budget) {
+            SolveOutcome::Exhausted {
+                reason: ExhaustionKind::Budget(exceeded),
+                ..
+            } => Some(exceeded),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn split_and_charge() {
+        let mut budget = Budget::new()
+            .with_steps(100)
+            .with_time(Duration::from_secs(10))
+            .with_memory(4096);
+        let mut first = budget.split(0.25);
+        assert_eq!(first.max_steps, Some(25));
+        assert_eq!(first.max_guesses, None);
+        assert_eq!(first.max_time, Some(Duration::from_millis(2500)));
+        assert_eq!(first.max_memory, Some(4096));
+
+        first.consumed.steps = 20;
+        first.consumed.peak_memory = 512;
+        budget.charge(&first);
+        assert_eq!(budget.consumed().steps, 20);
+        // The rest of the budget is split from what is left.
+        assert_eq!(budget.split(1.0).max_steps, Some(80));
+        assert_eq!(budget.split(2.0).max_steps, Some(80));
+
+        let mut second = budget.split(1.0);
+        second.consumed.peak_memory = 256;
+        budget.charge(&second);
+        assert_eq!(budget.consumed().peak_memory, 512);
+    }
+
+    #[test]
+    fn each_limit() {
+        crate::setup();
+
+        let board = inkala();
+        let limited = [
+            Budget::new().with_steps(3),
+            Budget::new().with_guesses(2),
+            Budget::new().with_memory(FRAME_BYTES * 2),
+        ];
+        let expected = [
+            (LimitKind::Steps, 3, 3),
+            (LimitKind::Guesses, 2, 2),
+            (LimitKind::Memory, FRAME_BYTES * 3, FRAME_BYTES * 2),
+        ];
+        for (mut budget, (which, consumed, limit)) in limited.into_iter().zip(expected) {
+            let exceeded = exceeded(&board, &mut budget).unwrap();
+            assert_eq!(
+                exceeded,
+                BudgetExceeded {
+                    which,
+                    consumed,
+                    limit,
+                }
+            );
+            let used = budget.consumed();
+            assert!(used.steps <= budget.max_steps.unwrap_or(u64::MAX));
+            assert!(used.guesses <= budget.max_guesses.unwrap_or(u64::MAX));
+            assert!(used.peak_memory <= budget.max_memory.unwrap_or(u64::MAX));
+        }
+        assert_eq!(
+            BudgetExceeded {
+                which: LimitKind::Steps,
+                consumed: 3,
+                limit: 3,
+            }
+            .to_string(),
+            "step limit of 3 steps exceeded"
+        );
+
+        // Without limits the budget records the work done.
+        let mut budget = Budget::new();
+        assert_eq!(exceeded(&board, &mut budget), None);
+        let used = budget.consumed();
+        assert_eq!(used.steps, used.guesses + 1);
+        assert!(used.peak_memory >= FRAME_BYTES * 2);
+    }
+
+    #[test]
+    fn zero_limits_fail_fast() {
+        crate::setup();
+
+        let zero = [
+            (LimitKind::Steps, Budget::new().with_steps(0)),
+            (
+                LimitKind::WallClock,
+                Budget::new().with_time(Duration::ZERO),
+            ),
+            (LimitKind::Memory, Budget::new().with_memory(0)),
+        ];
+        for (which, mut budget) in zero {
+            let outcome = Solver::new().run_within(&inkala(), &mut budget);
+            match outcome {
+                SolveOutcome::Exhausted {
+                    reason: ExhaustionKind::Budget(exceeded),
+                    partial: None,
+                } => assert_eq!(exceeded.which, which),
+                other => panic!("expected {} limit, got {:?}", which, other),
+            }
+            assert_eq!(budget.consumed().steps, 0);
+        }
+
+        // A guess limit of zero still allows the initial deductions.
+        let mut budget = Budget::new().with_guesses(0);
+        assert_eq!(
+            exceeded(&inkala(), &mut budget).map(|e| e.which),
+            Some(LimitKind::Guesses)
+        );
+        assert_eq!(budget.consumed().steps, 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize() {
+        let exceeded = BudgetExceeded {
+            which: LimitKind::WallClock,
+            consumed: 12,
+            limit: 10,
+        };
+        let ser = serde_json::to_string(&exceeded).unwrap();
+        assert_eq!(ser, r#"{"which":"wall_clock","consumed":12,"limit":10}"#);
+
+        // Budgets from requests only carry limits.
+        let budget: Budget = serde_json::from_str(
+            r#"{"max_steps":5,"max_guesses":null,"max_time":null,"max_memory":null}"#,
+        )
+        .unwrap();
+        assert_eq!(budget.max_steps, Some(5));
+        assert_eq!(budget.consumed(), BudgetUsage::default());
+    }
+}
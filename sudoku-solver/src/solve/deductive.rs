@@ -7,16 +7,24 @@ use log::trace;
 
 use crate::collections::availset::AvailCounter;
 use crate::solve::remaining::RemainingTracker;
-use crate::trace::{DeductionReason, DeductiveTracer, UnsolveableReason};
-use crate::{AvailSet, Col, Coord, Row, Sector, SectorCol, SectorRow, Val, Zone};
+use crate::solve::techniques;
+use crate::solve::{ReduceOptions, RuleKind, RulePriority, RuleSet};
+use crate::trace::{
+    DeductionOpportunity, DeductionReason, DeductiveTracer, Remaining, UnsolveableReason,
+};
+use crate::{AvailSet, Col, Coord, House, Row, Sector, SectorCol, SectorRow, Val, Zone};
 
 use super::remaining::ExtractRem;
 
-pub(crate) fn reduce<T>(remaining: RemainingTracker, tracer: T) -> (Option<RemainingTracker>, T)
+pub(crate) fn reduce<T>(
+    remaining: RemainingTracker,
+    options: &ReduceOptions,
+    tracer: T,
+) -> (Option<RemainingTracker>, T)
 where
     T: DeductiveTracer,
 {
-    let mut reducer = DeductiveReducer::new(remaining, tracer);
+    let mut reducer = DeductiveReducer::new(remaining, options, tracer);
     match reducer.reduce() {
         Ok(()) => (Some(reducer.remaining), reducer.tracer),
         Err(()) => (None, reducer.tracer),
@@ -27,16 +35,19 @@ struct DeductiveReducer<T> {
     remaining: RemainingTracker,
     queue: ReduceQueue,
     tracer: T,
+    /// State when the current step began, if the tracer wants it.
+    step_start: Option<Remaining>,
 }
 
-impl<'a, T: DeductiveTracer> DeductiveReducer<T> {
+impl<T: DeductiveTracer> DeductiveReducer<T> {
     /// Construct a reducer and enqueue the initial reduction steps.
-    fn new(remaining: RemainingTracker, tracer: T) -> Self {
-        let queue = build_queue(&remaining);
+    fn new(remaining: RemainingTracker, options: &ReduceOptions, tracer: T) -> Self {
+        let queue = build_queue(&remaining, options);
         DeductiveReducer {
             remaining,
             queue,
             tracer,
+            step_start: None,
         }
     }
 
@@ -45,159 +56,52 @@ impl<'a, T: DeductiveTracer> DeductiveReducer<T> {
         self.tracer.deduce(reason, self.remaining.remaining());
     }
 
-    /// Record the current state of the board with the given reason.
+    /// Record the current state of the board as unsolveable for the given
+    /// reason, along with the state from the start of the failing step.
     fn fail(&mut self, reason: UnsolveableReason) {
-        self.tracer.deduce(
-            DeductionReason::Unsolveable(reason),
-            self.remaining.remaining(),
-        );
+        let before = self.step_start.take();
+        self.tracer.fail(reason, before, self.remaining.remaining());
     }
 
     /// Reduce the given board by applying the reduction rules.
     fn reduce(&mut self) -> Result<(), ()> {
         self.deduce(DeductionReason::InitialState);
+        let wants_pre_state = self.tracer.wants_pre_state();
         while let Some(next_step) = self.queue.pop() {
-            match next_step {
-                ReduceStep::CoordSingularized(coord) => self.coord_singularized(coord)?,
-                ReduceStep::RowValsSingularized(row) => self.rcs_vals_singularized(row)?,
-                ReduceStep::ColValsSingularized(col) => self.rcs_vals_singularized(col)?,
-                ReduceStep::SecValsSingularized(sec) => self.rcs_vals_singularized(sec)?,
-                ReduceStep::SecRowTripleized(secrow) => self.secrow_seccol_tripleized(secrow)?,
-                ReduceStep::SecColTripleized(seccol) => self.secrow_seccol_tripleized(seccol)?,
-                ReduceStep::RowOnlySec(secrow) => self.secrow_seccol_only_in_line(secrow)?,
-                ReduceStep::SecOnlyRow(secrow) => self.secrow_seccol_only_in_sec(secrow)?,
-                ReduceStep::ColOnlySec(seccol) => self.secrow_seccol_only_in_line(seccol)?,
-                ReduceStep::SecOnlyCol(seccol) => self.secrow_seccol_only_in_sec(seccol)?,
+            if wants_pre_state {
+                self.step_start = Some(self.remaining.remaining());
+            }
+            let detection = detect(&self.remaining, next_step);
+            let deduced = detection.reason.is_some();
+            self.apply(detection)?;
+            // Subset and fish steps make one deduction at a time, so look for
+            // another one in the same place.
+            if deduced && next_step.finds_one() {
+                self.queue.push(next_step);
             }
         }
         Ok(())
     }
 
-    /// Visit a coordinate that has been singularized.
-    fn coord_singularized(&mut self, coord: Coord) -> Result<(), ()> {
-        let mut any_eliminated = false;
-        // Note: if a different step eliminates the last number from this cell, we have to
-        // stop before we get here again.
-        let val = self.remaining[coord].get_single().unwrap();
-        for neighbor in coord.neighbors() {
-            any_eliminated |= self.eliminate(neighbor, val)?;
-        }
-        if any_eliminated {
-            self.deduce(DeductionReason::CoordNeighbors { pos: coord, val });
-        }
-        Ok(())
-    }
-
-    /// Visit a row which now has only one cell left for some value.
-    fn rcs_vals_singularized<Z: RowColSec>(&mut self, rcs: Z) -> Result<(), ()> {
-        let singles =
-            self.remaining[rcs]
-                .counts()
-                .fold(AvailSet::none(), |mut singles, (val, &count)| {
-                    if count == 1 {
-                        singles |= val;
-                    }
-                    singles
-                });
-        let mut deduced = AvailSet::none();
-        for coord in rcs.coords() {
-            let rem = self.remaining[coord];
-            let matches = rem & singles;
-            if !matches.is_empty() {
-                if !matches.is_single() {
-                    trace!(
-                        "Stopped deductive because {:?} had two values {:?} with {:?} as their only possible position",
-                        rcs,
-                        matches,
-                        coord,
-                    );
-                    self.fail(rcs.fail_must_share(matches));
-                    return Err(());
-                }
-                let others = rem - singles;
-                if !self.eliminate_all(coord, others)?.is_empty() {
-                    deduced |= matches;
-                }
+    /// Apply the eliminations found by detecting a step, then record the
+    /// deduction, or the contradiction the step found.
+    fn apply(&mut self, detection: Detection) -> Result<(), ()> {
+        for (coord, vals) in detection.eliminations {
+            for val in vals {
+                let existed = self.eliminate(coord, val)?;
+                debug_assert!(existed, "detection listed a value that was already gone");
             }
         }
-        if !deduced.is_empty() {
-            self.deduce(rcs.deduced(deduced));
+        if let Some(reason) = detection.conflict {
+            self.fail(reason);
+            return Err(());
         }
-        Ok(())
-    }
-
-    /// Eliminates all values in this sector-row from the rest of the row and sector.
-    fn secrow_seccol_tripleized<Z: SecRowSecCol>(&mut self, srsc: Z) -> Result<(), ()> {
-        let values = self.remaining[srsc].avail();
-        // If this fails we became unsolveable but didn't stop.
-        debug_assert!(values.len() == Z::SIZE);
-        let eliminated = self.eliminate_all(
-            srsc.line_neighbors().chain(srsc.sec_neighbors()).flatten(),
-            values,
-        )?;
-        if !eliminated.is_empty() {
-            self.deduce(srsc.deduced_size_match(eliminated));
+        if let Some(reason) = detection.reason {
+            self.deduce(reason);
         }
         Ok(())
     }
 
-    /// Eliminates values in this sector-row/sector-col which have the same count
-    /// as the row/col from the rest of the sector.
-    fn secrow_seccol_only_in_line<Z: SecRowSecCol>(&mut self, srsc: Z) -> Result<(), ()> {
-        let uniques =
-            self.remaining[srsc]
-                .counts()
-                .fold(AvailSet::none(), |mut uniques, (val, &count)| {
-                    if count == self.remaining[srsc.line()][val] {
-                        uniques |= val;
-                    }
-                    uniques
-                });
-        let deduced = self.eliminate_all(srsc.sec_neighbors().flatten(), uniques)?;
-        if !deduced.is_empty() {
-            self.deduce(srsc.deduced_only_in_line(deduced));
-        }
-        Ok(())
-    }
-
-    /// Eliminates values in this sector-row/sector-col which have the same count
-    /// as the sector from the rest of the row/col.
-    fn secrow_seccol_only_in_sec<Z: SecRowSecCol>(&mut self, srsc: Z) -> Result<(), ()> {
-        let uniques =
-            self.remaining[srsc]
-                .counts()
-                .fold(AvailSet::none(), |mut uniques, (val, &count)| {
-                    if count == self.remaining[srsc.sector()][val] {
-                        uniques |= val;
-                    }
-                    uniques
-                });
-        let deduced = self.eliminate_all(srsc.line_neighbors().flatten(), uniques)?;
-        if !deduced.is_empty() {
-            self.deduce(srsc.deduced_only_in_sec(deduced));
-        }
-        Ok(())
-    }
-
-    /// Convenience function to eliminate all values in the given AvailSet from all coords
-    /// in the given zone.
-    /// Returns the set of values that were eliminated.
-    fn eliminate_all(
-        &mut self,
-        zone: impl IntoIterator<Item = Coord>,
-        vals: impl IntoIterator<Item = Val> + Copy,
-    ) -> Result<AvailSet, ()> {
-        let mut eliminated = AvailSet::none();
-        for coord in zone {
-            for val in vals {
-                if self.eliminate(coord, val)? {
-                    eliminated |= val;
-                }
-            }
-        }
-        Ok(eliminated)
-    }
-
     /// Eliminate the given value from a single cell, pushing new reduce steps
     /// for the effects on the row, column, an sector. Return true if the value
     /// existed previously.
@@ -253,6 +157,14 @@ impl<'a, T: DeductiveTracer> DeductiveReducer<T> {
                 return Err(());
             }
             Some(1) => self.queue.push(rcs.visit()),
+            Some(2) => {
+                self.queue.push(ReduceStep::HiddenPair(rcs.into()));
+                self.queue.push(ReduceStep::HiddenTriple(rcs.into()));
+                if !matches!(rcs.into(), House::Sector(_)) {
+                    self.queue.push(ReduceStep::XWing(val));
+                }
+            }
+            Some(3) => self.queue.push(ReduceStep::HiddenTriple(rcs.into())),
             Some(_) => {}
             None => panic!("Value was previously eliminated but reduction did not stop"),
         }
@@ -299,8 +211,339 @@ impl<'a, T: DeductiveTracer> DeductiveReducer<T> {
     }
 }
 
+/// Eliminations a single reduce step would make, found without changing the
+/// tracker.
+struct Detection {
+    /// Values to eliminate from each cell, in the order they are eliminated.
+    eliminations: Vec<(Coord, AvailSet)>,
+    /// Reason to record once the eliminations are made, if there are any.
+    reason: Option<DeductionReason>,
+    /// Contradiction the step found after the eliminations, if any.
+    conflict: Option<UnsolveableReason>,
+}
+
+impl Detection {
+    fn new() -> Self {
+        Detection {
+            eliminations: Vec::new(),
+            reason: None,
+            conflict: None,
+        }
+    }
+
+    /// Eliminate the given values from every coord in the zone which still
+    /// allows them. Returns the set of values that would be eliminated.
+    fn eliminate_all(
+        &mut self,
+        rem: &RemainingTracker,
+        zone: impl IntoIterator<Item = Coord>,
+        vals: AvailSet,
+    ) -> AvailSet {
+        let mut eliminated = AvailSet::none();
+        for coord in zone {
+            let found = rem[coord] & vals;
+            if !found.is_empty() {
+                self.eliminations.push((coord, found));
+                eliminated |= found;
+            }
+        }
+        eliminated
+    }
+}
+
+/// Find what the given step would eliminate from the tracker.
+fn detect(rem: &RemainingTracker, step: ReduceStep) -> Detection {
+    match step {
+        ReduceStep::CoordSingularized(coord) => detect_coord_singularized(rem, coord),
+        ReduceStep::RowValsSingularized(row) => detect_rcs_vals_singularized(rem, row),
+        ReduceStep::ColValsSingularized(col) => detect_rcs_vals_singularized(rem, col),
+        ReduceStep::SecValsSingularized(sec) => detect_rcs_vals_singularized(rem, sec),
+        ReduceStep::SecRowTripleized(secrow) => detect_secrow_seccol_tripleized(rem, secrow),
+        ReduceStep::SecColTripleized(seccol) => detect_secrow_seccol_tripleized(rem, seccol),
+        ReduceStep::RowOnlySec(secrow) => detect_secrow_seccol_only_in_line(rem, secrow),
+        ReduceStep::SecOnlyRow(secrow) => detect_secrow_seccol_only_in_sec(rem, secrow),
+        ReduceStep::ColOnlySec(seccol) => detect_secrow_seccol_only_in_line(rem, seccol),
+        ReduceStep::SecOnlyCol(seccol) => detect_secrow_seccol_only_in_sec(rem, seccol),
+        ReduceStep::HiddenPair(house) => detect_hidden_subset(rem, house, 2),
+        ReduceStep::HiddenTriple(house) => detect_hidden_subset(rem, house, 3),
+        ReduceStep::XWing(val) => detect_x_wing(rem, val),
+    }
+}
+
+/// Visit a coordinate that has been singularized.
+fn detect_coord_singularized(rem: &RemainingTracker, coord: Coord) -> Detection {
+    let mut detection = Detection::new();
+    // Note: if a different step eliminates the last number from this cell, we have to
+    // stop before we get here again.
+    let val = rem[coord].get_single().unwrap();
+    if !detection
+        .eliminate_all(rem, coord.neighbors(), AvailSet::only(val))
+        .is_empty()
+    {
+        detection.reason = Some(DeductionReason::CoordNeighbors { pos: coord, val });
+    }
+    detection
+}
+
+/// Visit a row which now has only one cell left for some value.
+fn detect_rcs_vals_singularized<Z: RowColSec>(rem: &RemainingTracker, rcs: Z) -> Detection {
+    let mut detection = Detection::new();
+    let singles = rem[rcs]
+        .counts()
+        .fold(AvailSet::none(), |mut singles, (val, &count)| {
+            if count == 1 {
+                singles |= val;
+            }
+            singles
+        });
+    let mut deduced = AvailSet::none();
+    for coord in rcs.coords() {
+        let cell = rem[coord];
+        let matches = cell & singles;
+        if !matches.is_empty() {
+            if !matches.is_single() {
+                trace!(
+                    "Stopped deductive because {:?} had two values {:?} with {:?} as their only possible position",
+                    rcs,
+                    matches,
+                    coord,
+                );
+                detection.conflict = Some(rcs.fail_must_share(matches));
+                return detection;
+            }
+            let others = cell - singles;
+            if !detection.eliminate_all(rem, [coord], others).is_empty() {
+                deduced |= matches;
+            }
+        }
+    }
+    if !deduced.is_empty() {
+        detection.reason = Some(rcs.deduced(deduced));
+    }
+    detection
+}
+
+/// Eliminates all values in this sector-row from the rest of the row and sector.
+fn detect_secrow_seccol_tripleized<Z: SecRowSecCol>(rem: &RemainingTracker, srsc: Z) -> Detection {
+    let mut detection = Detection::new();
+    let values = rem[srsc].avail();
+    // If this fails we became unsolveable but didn't stop.
+    debug_assert!(values.len() == Z::SIZE);
+    let eliminated = detection.eliminate_all(
+        rem,
+        srsc.line_neighbors().chain(srsc.sec_neighbors()).flatten(),
+        values,
+    );
+    if !eliminated.is_empty() {
+        detection.reason = Some(srsc.deduced_size_match(eliminated));
+    }
+    detection
+}
+
+/// Eliminates values in this sector-row/sector-col which have the same count
+/// as the row/col from the rest of the sector.
+fn detect_secrow_seccol_only_in_line<Z: SecRowSecCol>(
+    rem: &RemainingTracker,
+    srsc: Z,
+) -> Detection {
+    let mut detection = Detection::new();
+    let uniques = rem[srsc]
+        .counts()
+        .fold(AvailSet::none(), |mut uniques, (val, &count)| {
+            if count == rem[srsc.line()][val] {
+                uniques |= val;
+            }
+            uniques
+        });
+    let deduced = detection.eliminate_all(rem, srsc.sec_neighbors().flatten(), uniques);
+    if !deduced.is_empty() {
+        detection.reason = Some(srsc.deduced_only_in_line(deduced));
+    }
+    detection
+}
+
+/// Eliminates values in this sector-row/sector-col which have the same count
+/// as the sector from the rest of the row/col.
+fn detect_secrow_seccol_only_in_sec<Z: SecRowSecCol>(rem: &RemainingTracker, srsc: Z) -> Detection {
+    let mut detection = Detection::new();
+    let uniques = rem[srsc]
+        .counts()
+        .fold(AvailSet::none(), |mut uniques, (val, &count)| {
+            if count == rem[srsc.sector()][val] {
+                uniques |= val;
+            }
+            uniques
+        });
+    let deduced = detection.eliminate_all(rem, srsc.line_neighbors().flatten(), uniques);
+    if !deduced.is_empty() {
+        detection.reason = Some(srsc.deduced_only_in_sec(deduced));
+    }
+    detection
+}
+
+/// Find `size` values in the house which are confined to the same `size`
+/// cells, and eliminate every other value from those cells. Only the first
+/// such subset which eliminates anything is used.
+fn detect_hidden_subset(rem: &RemainingTracker, house: House, size: usize) -> Detection {
+    let mut detection = Detection::new();
+    let coords: Vec<Coord> = house.coords().collect();
+    let counts = match house {
+        House::Row(row) => &rem[row],
+        House::Col(col) => &rem[col],
+        House::Sector(sector) => &rem[sector],
+    };
+    // Each value with more than one but at most `size` cells left, with the
+    // cells as a mask of indexes into `coords`.
+    let places: Vec<(Val, u16)> = counts
+        .counts()
+        .filter(|&(_, &count)| count > 1 && count as usize <= size)
+        .map(|(val, _)| {
+            let mask = coords
+                .iter()
+                .enumerate()
+                .filter(|&(_, &coord)| rem[coord].contains(val))
+                .fold(0, |mask, (idx, _)| mask | 1 << idx);
+            (val, mask)
+        })
+        .collect();
+    for chosen in 0u16..1 << places.len() {
+        if chosen.count_ones() as usize != size {
+            continue;
+        }
+        let (vals, cells) = places
+            .iter()
+            .enumerate()
+            .filter(|&(idx, _)| chosen & 1 << idx != 0)
+            .fold(
+                (AvailSet::none(), 0u16),
+                |(vals, cells), (_, &(val, mask))| (vals | val, cells | mask),
+            );
+        if cells.count_ones() as usize != size {
+            continue;
+        }
+        let cells: Vec<Coord> = coords
+            .iter()
+            .enumerate()
+            .filter(|&(idx, _)| cells & 1 << idx != 0)
+            .map(|(_, &coord)| coord)
+            .collect();
+        if !detection
+            .eliminate_all(rem, cells.iter().copied(), !vals)
+            .is_empty()
+        {
+            detection.reason = Some(match size {
+                2 => DeductionReason::HiddenPair {
+                    pos: house,
+                    cells: [cells[0], cells[1]],
+                    vals,
+                },
+                _ => DeductionReason::HiddenTriple {
+                    pos: house,
+                    cells: [cells[0], cells[1], cells[2]],
+                    vals,
+                },
+            });
+            break;
+        }
+    }
+    detection
+}
+
+/// Find two rows where the value is confined to the same two columns, or two
+/// columns where it is confined to the same two rows, and eliminate it from
+/// the rest of those columns or rows. Only the first such X-Wing which
+/// eliminates anything is used.
+fn detect_x_wing(rem: &RemainingTracker, val: Val) -> Detection {
+    let mut detection = Detection::new();
+    if let Some(fish) = techniques::fish(rem, val, 2).into_iter().next() {
+        let by_rows = matches!(fish.base[0], House::Row(_));
+        let lines: Vec<House> = fish.base.into_iter().chain(fish.cover).collect();
+        let rows: Vec<Row> = lines
+            .iter()
+            .filter_map(|&line| match line {
+                House::Row(row) => Some(row),
+                _ => None,
+            })
+            .collect();
+        let cols: Vec<Col> = lines
+            .iter()
+            .filter_map(|&line| match line {
+                House::Col(col) => Some(col),
+                _ => None,
+            })
+            .collect();
+        detection.eliminate_all(rem, fish.eliminations, AvailSet::only(val));
+        detection.reason = Some(DeductionReason::XWing {
+            rows: [rows[0], rows[1]],
+            cols: [cols[0], cols[1]],
+            val,
+            by_rows,
+        });
+    }
+    detection
+}
+
+/// Find every deduction the given rules could make from the tracker, without
+/// applying any of them. Steps which would find a contradiction are left out.
+pub(crate) fn opportunities(rem: &RemainingTracker, rules: RuleSet) -> Vec<DeductionOpportunity> {
+    let options = ReduceOptions {
+        disabled: RuleSet::all() - rules,
+        ..Default::default()
+    };
+    let mut queue = build_queue(rem, &options);
+    let mut opportunities = Vec::new();
+    while let Some(step) = queue.pop() {
+        let detection = detect(rem, step);
+        if detection.conflict.is_some() {
+            continue;
+        }
+        if let Some(reason) = detection.reason {
+            opportunities.push(DeductionOpportunity {
+                rule: step.rule_kind(),
+                cells: based_on(rem, &reason),
+                reason,
+                eliminations: detection
+                    .eliminations
+                    .into_iter()
+                    .flat_map(|(coord, vals)| vals.into_iter().map(move |val| (coord, val)))
+                    .collect(),
+            });
+        }
+    }
+    opportunities
+}
+
+/// Cells a deduction is based on, in row-major order.
+fn based_on(rem: &RemainingTracker, reason: &DeductionReason) -> Vec<Coord> {
+    match *reason {
+        DeductionReason::CoordNeighbors { pos, .. } => vec![pos],
+        DeductionReason::UniqueInRow { pos, vals } => {
+            pos.coords().filter(|&c| rem[c].intersects(vals)).collect()
+        }
+        DeductionReason::UniqueInCol { pos, vals } => {
+            pos.coords().filter(|&c| rem[c].intersects(vals)).collect()
+        }
+        DeductionReason::UniqueInSector { pos, vals } => {
+            pos.coords().filter(|&c| rem[c].intersects(vals)).collect()
+        }
+        DeductionReason::SecRowTriple { pos, .. }
+        | DeductionReason::SecOnlyRow { pos, .. }
+        | DeductionReason::RowOnlySec { pos, .. } => pos.coords().collect(),
+        DeductionReason::SecColTriple { pos, .. }
+        | DeductionReason::SecOnlyCol { pos, .. }
+        | DeductionReason::ColOnlySec { pos, .. } => pos.coords().collect(),
+        DeductionReason::HiddenPair { cells, .. } => cells.to_vec(),
+        DeductionReason::HiddenTriple { cells, .. } => cells.to_vec(),
+        DeductionReason::XWing { rows, cols, .. } => rows
+            .iter()
+            .flat_map(|&row| cols.iter().map(move |&col| Coord::new(row, col)))
+            .collect(),
+        DeductionReason::InitialState | DeductionReason::Unsolveable(_) => Vec::new(),
+    }
+}
+
 /// Helper for generalizing row/col/sector.
-trait RowColSec: Zone + fmt::Debug + Copy + ExtractRem<Avail = AvailCounter> {
+trait RowColSec: Zone + fmt::Debug + Copy + Into<House> + ExtractRem<Avail = AvailCounter> {
     /// Build a reduce step to visit this.
     fn visit(self) -> ReduceStep;
 
@@ -518,35 +761,89 @@ enum ReduceStep {
     /// rest of the col.
     /// May be enqueued more than once per sector-col.
     SecOnlyCol(SectorCol),
+    /// Some value in the house changed to have only two cells left, so two
+    /// values may be confined to the same two cells.
+    /// May be enqueued more than once per house.
+    HiddenPair(House),
+    /// Some value in the house changed to have only two or three cells left,
+    /// so three values may be confined to the same three cells.
+    /// May be enqueued more than once per house.
+    HiddenTriple(House),
+    /// Some value changed to have only two cells left in a row or column, so
+    /// it may form an X-Wing.
+    /// May be enqueued more than once per value.
+    XWing(Val),
+}
+
+impl ReduceStep {
+    /// The rule this step applies.
+    fn rule_kind(self) -> RuleKind {
+        match self {
+            ReduceStep::SecValsSingularized(_) => RuleKind::UniqueInSector,
+            ReduceStep::CoordSingularized(_) => RuleKind::CoordNeighbors,
+            ReduceStep::SecRowTripleized(_) => RuleKind::SecRowTriple,
+            ReduceStep::SecColTripleized(_) => RuleKind::SecColTriple,
+            ReduceStep::RowValsSingularized(_) => RuleKind::UniqueInRow,
+            ReduceStep::ColValsSingularized(_) => RuleKind::UniqueInCol,
+            ReduceStep::RowOnlySec(_) => RuleKind::RowOnlySec,
+            ReduceStep::SecOnlyRow(_) => RuleKind::SecOnlyRow,
+            ReduceStep::ColOnlySec(_) => RuleKind::ColOnlySec,
+            ReduceStep::SecOnlyCol(_) => RuleKind::SecOnlyCol,
+            ReduceStep::HiddenPair(_) => RuleKind::HiddenPair,
+            ReduceStep::HiddenTriple(_) => RuleKind::HiddenTriple,
+            ReduceStep::XWing(_) => RuleKind::XWing,
+        }
+    }
+
+    /// Whether the step makes only one of possibly several deductions, so it
+    /// should be visited again after it makes one.
+    fn finds_one(self) -> bool {
+        matches!(
+            self,
+            ReduceStep::HiddenPair(_) | ReduceStep::HiddenTriple(_) | ReduceStep::XWing(_)
+        )
+    }
 }
 
 /// Reduce queue which auto-combines certain reduce operations.
 struct ReduceQueue {
-    /// Min heap of ReduceSteps to be executed.
-    pending: BinaryHeap<Reverse<ReduceStep>>,
+    /// Min heap of ReduceSteps to be executed, ordered first by the rank of
+    /// their rule.
+    pending: BinaryHeap<Reverse<(usize, ReduceStep)>>,
     /// Hash set used to dedup the heap.
     dedup: HashSet<ReduceStep>,
+    /// Rules whose steps are never enqueued.
+    disabled: RuleSet,
+    /// Order rules are applied in.
+    priority: RulePriority,
 }
 
 impl ReduceQueue {
-    fn new() -> Self {
+    fn new(options: &ReduceOptions) -> Self {
         Self {
             pending: BinaryHeap::new(),
             dedup: HashSet::new(),
+            disabled: options.disabled,
+            priority: options.priority,
         }
     }
 
-    /// Add a reduce step to the queue if not already there.
+    /// Add a reduce step to the queue if not already there and its rule is not
+    /// disabled.
     fn push(&mut self, step: ReduceStep) {
+        if self.disabled.contains(step.rule_kind()) {
+            return;
+        }
         if self.dedup.insert(step) {
-            self.pending.push(Reverse(step));
+            let rank = self.priority.rank(step.rule_kind());
+            self.pending.push(Reverse((rank, step)));
         }
     }
 
     /// Remove a reduce step from the queue.
     fn pop(&mut self) -> Option<ReduceStep> {
         match self.pending.pop() {
-            Some(Reverse(step)) => {
+            Some(Reverse((_, step))) => {
                 assert!(self.dedup.remove(&step));
                 Some(step)
             }
@@ -556,8 +853,8 @@ impl ReduceQueue {
 }
 
 /// Find all reduction rules we should start with for the given board.
-fn build_queue(remaining: &RemainingTracker) -> ReduceQueue {
-    let mut queue = ReduceQueue::new();
+fn build_queue(remaining: &RemainingTracker, options: &ReduceOptions) -> ReduceQueue {
+    let mut queue = ReduceQueue::new(options);
     for (coord, avail) in remaining.get::<Coord>().iter() {
         if avail.is_single() {
             queue.push(ReduceStep::CoordSingularized(coord))
@@ -578,6 +875,17 @@ fn build_row_col_sec_queue<Z: RowColSec>(rem: &RemainingTracker, queue: &mut Red
         if avail.counts().any(|(_, &count)| count == 1) {
             queue.push(rcs.visit());
         }
+        if avail.counts().any(|(_, &count)| count == 2) {
+            queue.push(ReduceStep::HiddenPair(rcs.into()));
+        }
+        if avail.counts().any(|(_, &count)| count == 2 || count == 3) {
+            queue.push(ReduceStep::HiddenTriple(rcs.into()));
+        }
+        if !matches!(rcs.into(), House::Sector(_)) {
+            for (val, _) in avail.counts().filter(|&(_, &count)| count == 2) {
+                queue.push(ReduceStep::XWing(val));
+            }
+        }
     }
 }
 
@@ -601,3 +909,408 @@ fn build_secrow_seccol_queue<Z: SecRowSecCol>(rem: &RemainingTracker, queue: &mu
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::indexed::IndexMap;
+    use crate::trace::{Deduction, NopDeductiveTracer, RemainingDiff};
+
+    fn pos(row: u8, col: u8) -> Coord {
+        Coord::new(Row::new(row), Col::new(col))
+    }
+
+    fn vals(vals: &[u8]) -> AvailSet {
+        vals.iter()
+            .fold(AvailSet::none(), |set, &val| set | Val::new(val))
+    }
+
+    /// Reduce with only the given rule enabled, starting from a board where
+    /// the given cells allow only the given values and the `without` cells
+    /// allow anything but the given values.
+    fn fail_trace(
+        rule: RuleKind,
+        only: &[(Coord, &[u8])],
+        without: &[(Coord, &[u8])],
+    ) -> Vec<Deduction> {
+        let mut tracker = RemainingTracker::new(&Default::default());
+        for &(coord, allowed) in only {
+            tracker.exclude(coord, !vals(allowed));
+        }
+        for &(coord, excluded) in without {
+            tracker.exclude(coord, vals(excluded));
+        }
+        let options = ReduceOptions {
+            disabled: RuleSet::all() - RuleSet::only(rule),
+            ..Default::default()
+        };
+        // Trace through a reference, to check it forwards the pre-state.
+        let mut trace = Vec::new();
+        let (reduced, _) = reduce(tracker, &options, &mut trace);
+        assert!(reduced.is_none());
+        trace
+    }
+
+    /// Check that the trace ends in the expected failure, with the state from
+    /// before the failing step, and that the step only eliminated candidates
+    /// from cells in its scope.
+    fn check_failure(trace: &[Deduction], expected: UnsolveableReason, scope: &[Coord]) {
+        let (last, rest) = trace.split_last().unwrap();
+        assert_eq!(last.reason, DeductionReason::Unsolveable(expected));
+        assert_eq!(rest[0].reason, DeductionReason::InitialState);
+        assert!(rest[1..].iter().all(|deduction| deduction.before.is_none()));
+        let before = last.before.as_ref().expect("failure without pre-state");
+        assert_eq!(before, &rest.last().unwrap().remaining);
+        for coord in Coord::all() {
+            let (pre, post) = (before[coord], last.remaining[coord]);
+            assert_eq!(post - pre, AvailSet::none(), "{} gained values", coord);
+            if pre != post {
+                assert!(scope.contains(&coord), "{} changed out of scope", coord);
+            }
+        }
+    }
+
+    #[test]
+    fn failures_record_pre_state() {
+        crate::setup();
+
+        // Placing the 1 at row 0 column 0 empties the cell next to it.
+        let trace = fail_trace(
+            RuleKind::CoordNeighbors,
+            &[(pos(0, 0), &[1]), (pos(0, 1), &[1])],
+            &[],
+        );
+        let peers: Vec<_> = pos(0, 0).neighbors().collect();
+        check_failure(&trace, UnsolveableReason::Empty { pos: pos(0, 1) }, &peers);
+
+        // 1 and 2 both fit only at the first cell of a house.
+        let without_row: Vec<_> = (1..9).map(|col| (pos(0, col), &[1, 2][..])).collect();
+        let without_col: Vec<_> = (1..9).map(|row| (pos(row, 0), &[1, 2][..])).collect();
+        let without_sec: Vec<_> = Sector::containing(pos(0, 0))
+            .coords()
+            .skip(1)
+            .map(|coord| (coord, &[1, 2][..]))
+            .collect();
+        let cases = [
+            (
+                RuleKind::UniqueInRow,
+                without_row,
+                UnsolveableReason::RowValsMustShare {
+                    pos: Row::new(0),
+                    vals: vals(&[1, 2]),
+                },
+            ),
+            (
+                RuleKind::UniqueInCol,
+                without_col,
+                UnsolveableReason::ColValsMustShare {
+                    pos: Col::new(0),
+                    vals: vals(&[1, 2]),
+                },
+            ),
+            (
+                RuleKind::UniqueInSector,
+                without_sec,
+                UnsolveableReason::SecValsMustShare {
+                    pos: Sector::containing(pos(0, 0)),
+                    vals: vals(&[1, 2]),
+                },
+            ),
+        ];
+        for (rule, without, expected) in cases {
+            check_failure(&fail_trace(rule, &[], &without), expected, &[]);
+        }
+
+        // Placing a 1 removes the last 1 from a house through row 0 column 0,
+        // which keeps its 2.
+        let only_one = |coord: Coord| (coord, &[1][..]);
+        let cases = [
+            (
+                pos(1, 1),
+                (1..9).map(|col| only_one(pos(0, col))).collect::<Vec<_>>(),
+                UnsolveableReason::RowMissingVal {
+                    pos: Row::new(0),
+                    val: Val::new(1),
+                },
+            ),
+            (
+                pos(1, 1),
+                (1..9).map(|row| only_one(pos(row, 0))).collect(),
+                UnsolveableReason::ColMissingVal {
+                    pos: Col::new(0),
+                    val: Val::new(1),
+                },
+            ),
+            (
+                pos(0, 5),
+                Sector::containing(pos(0, 0))
+                    .coords()
+                    .skip(1)
+                    .map(only_one)
+                    .collect(),
+                UnsolveableReason::SecMissingVal {
+                    pos: Sector::containing(pos(0, 0)),
+                    val: Val::new(1),
+                },
+            ),
+        ];
+        for (single, without, expected) in cases {
+            let trace = fail_trace(
+                RuleKind::CoordNeighbors,
+                &[(single, &[1]), (pos(0, 0), &[1, 2])],
+                &without,
+            );
+            let peers: Vec<_> = single.neighbors().collect();
+            check_failure(&trace, expected, &peers);
+        }
+
+        // Placing a 3 leaves a sector-row or sector-col with only 1 and 2.
+        let triple: &[u8] = &[1, 2, 3];
+        let trace = fail_trace(
+            RuleKind::CoordNeighbors,
+            &[
+                (pos(5, 0), &[3]),
+                (pos(0, 0), triple),
+                (pos(1, 0), triple),
+                (pos(2, 0), triple),
+            ],
+            &[],
+        );
+        let peers: Vec<_> = pos(5, 0).neighbors().collect();
+        check_failure(
+            &trace,
+            UnsolveableReason::SecColTooFewVals {
+                pos: SectorCol::containing(pos(0, 0)),
+            },
+            &peers,
+        );
+        let trace = fail_trace(
+            RuleKind::CoordNeighbors,
+            &[
+                (pos(0, 5), &[3]),
+                (pos(0, 0), triple),
+                (pos(0, 1), triple),
+                (pos(0, 2), triple),
+            ],
+            &[],
+        );
+        let peers: Vec<_> = pos(0, 5).neighbors().collect();
+        check_failure(
+            &trace,
+            UnsolveableReason::SecRowTooFewVals {
+                pos: SectorRow::containing(pos(0, 0)),
+            },
+            &peers,
+        );
+    }
+
+    #[test]
+    fn available_deductions() {
+        crate::setup();
+
+        // 1 fits only at row 0 column 0 in column 0 and in the first sector,
+        // so it is confined to row 0 in that sector.
+        let mut tracker = RemainingTracker::new(&Default::default());
+        for row in 1..9 {
+            tracker.exclude(pos(row, 0), vals(&[1]));
+        }
+        for row in 0..3 {
+            for col in 1..3 {
+                tracker.exclude(pos(row, col), vals(&[1]));
+            }
+        }
+        let remaining = tracker.remaining();
+        let found = remaining.available_deductions(&RuleSet::all());
+        let others: Vec<_> = (2..=9).map(|val| (pos(0, 0), Val::new(val))).collect();
+        let row_rest: Vec<_> = (3..9).map(|col| (pos(0, col), Val::new(1))).collect();
+        assert_eq!(
+            found,
+            [
+                DeductionOpportunity {
+                    rule: RuleKind::UniqueInSector,
+                    reason: DeductionReason::UniqueInSector {
+                        pos: pos(0, 0).sector(),
+                        vals: vals(&[1]),
+                    },
+                    cells: vec![pos(0, 0)],
+                    eliminations: others.clone(),
+                },
+                DeductionOpportunity {
+                    rule: RuleKind::UniqueInCol,
+                    reason: DeductionReason::UniqueInCol {
+                        pos: Col::new(0),
+                        vals: vals(&[1]),
+                    },
+                    cells: vec![pos(0, 0)],
+                    eliminations: others,
+                },
+                DeductionOpportunity {
+                    rule: RuleKind::SecOnlyRow,
+                    reason: DeductionReason::SecOnlyRow {
+                        pos: pos(0, 0).sector_row(),
+                        vals: vals(&[1]),
+                    },
+                    cells: vec![pos(0, 0), pos(0, 1), pos(0, 2)],
+                    eliminations: row_rest,
+                },
+            ]
+        );
+        let rules: RuleSet = [RuleKind::UniqueInCol, RuleKind::SecOnlyRow]
+            .into_iter()
+            .collect();
+        let kinds: Vec<_> = remaining
+            .available_deductions(&rules)
+            .iter()
+            .map(|opportunity| opportunity.rule)
+            .collect();
+        assert_eq!(kinds, [RuleKind::UniqueInCol, RuleKind::SecOnlyRow]);
+
+        // Reducing with only the opportunity's rule makes it the first step,
+        // which eliminates exactly what was listed.
+        for opportunity in found {
+            let options = ReduceOptions {
+                disabled: RuleSet::all() - RuleSet::only(opportunity.rule),
+                ..Default::default()
+            };
+            let (_, trace) = reduce(tracker.clone(), &options, Vec::<Deduction>::new());
+            assert_eq!(trace[1].reason, opportunity.reason);
+            assert_eq!(
+                RemainingDiff::between_cells(
+                    trace[0].remaining.as_ref(),
+                    trace[1].remaining.as_ref()
+                )
+                .eliminations(),
+                opportunity.eliminations
+            );
+        }
+        assert!(Remaining::from(IndexMap::with_value(AvailSet::all()))
+            .available_deductions(&RuleSet::all())
+            .is_empty());
+    }
+
+    #[test]
+    fn hidden_pair() {
+        crate::setup();
+
+        // 1 and 2 fit only at columns 0 and 1 in row 0, so those cells can't
+        // hold anything else.
+        let mut tracker = RemainingTracker::new(&Default::default());
+        for col in 2..9 {
+            tracker.exclude(pos(0, col), vals(&[1, 2]));
+        }
+        let options = ReduceOptions {
+            disabled: RuleSet::all() - RuleSet::only(RuleKind::HiddenPair),
+            ..Default::default()
+        };
+        let (_, trace) = reduce(tracker, &options, Vec::<Deduction>::new());
+        assert_eq!(
+            trace[1].reason,
+            DeductionReason::HiddenPair {
+                pos: House::Row(Row::new(0)),
+                cells: [pos(0, 0), pos(0, 1)],
+                vals: vals(&[1, 2]),
+            }
+        );
+        assert_eq!(trace[1].remaining[pos(0, 0)], vals(&[1, 2]));
+        assert_eq!(trace[1].remaining[pos(0, 1)], vals(&[1, 2]));
+
+        // This puzzle needs a guess unless hidden pairs are allowed.
+        let board = crate::board_art!(
+            "8 4|   |   ",
+            "  1|5  |874",
+            "   | 7 | 3 ",
+            "---+---+---",
+            "   |  3|26 ",
+            " 1 | 6 |7 9",
+            "2  |  9|   ",
+            "---+---+---",
+            "   |1  |  2",
+            "1  |6  | 4 ",
+            " 52|   |  7",
+        );
+        let without = ReduceOptions {
+            disabled: RuleSet::only(RuleKind::HiddenPair) | RuleSet::only(RuleKind::HiddenTriple),
+            ..Default::default()
+        };
+        let (reduced, _) = reduce(RemainingTracker::new(&board), &without, NopDeductiveTracer);
+        assert!(!reduced.unwrap().is_solved());
+        let (reduced, trace) = reduce(
+            RemainingTracker::new(&board),
+            &Default::default(),
+            Vec::<Deduction>::new(),
+        );
+        assert!(reduced.unwrap().is_solved());
+        assert!(trace
+            .iter()
+            .any(|deduction| matches!(deduction.reason, DeductionReason::HiddenPair { .. })));
+    }
+
+    #[test]
+    fn x_wing() {
+        crate::setup();
+
+        // 1 fits only at columns 2 and 7 in rows 0 and 4, so it can't go
+        // anywhere else in those columns.
+        let mut tracker = RemainingTracker::new(&Default::default());
+        for row in [0, 4] {
+            for col in (0..9).filter(|&col| col != 2 && col != 7) {
+                tracker.exclude(pos(row, col), vals(&[1]));
+            }
+        }
+        let options = ReduceOptions {
+            disabled: RuleSet::all() - RuleSet::only(RuleKind::XWing),
+            ..Default::default()
+        };
+        let (_, trace) = reduce(tracker, &options, Vec::<Deduction>::new());
+        assert_eq!(
+            trace[1].reason,
+            DeductionReason::XWing {
+                rows: [Row::new(0), Row::new(4)],
+                cols: [Col::new(2), Col::new(7)],
+                val: Val::new(1),
+                by_rows: true,
+            }
+        );
+        for row in 0..9 {
+            let corner = row == 0 || row == 4;
+            assert_eq!(
+                trace[1].remaining[pos(row, 2)].contains(Val::new(1)),
+                corner
+            );
+            assert_eq!(
+                trace[1].remaining[pos(row, 7)].contains(Val::new(1)),
+                corner
+            );
+        }
+
+        // This puzzle needs a guess unless X-Wings are allowed.
+        let board = crate::board_art!(
+            " 4 |  1|9  ",
+            "6  |   |   ",
+            "9 5|   | 7 ",
+            "---+---+---",
+            "  6| 1 |   ",
+            " 1 |3  | 26",
+            "   |4  |8 7",
+            "---+---+---",
+            " 82|  4|  3",
+            "  4| 9 |7  ",
+            "5  |7  |1  ",
+        );
+        let without = ReduceOptions {
+            disabled: RuleSet::only(RuleKind::XWing),
+            ..Default::default()
+        };
+        let (reduced, _) = reduce(RemainingTracker::new(&board), &without, NopDeductiveTracer);
+        assert!(!reduced.unwrap().is_solved());
+        let (reduced, trace) = reduce(
+            RemainingTracker::new(&board),
+            &Default::default(),
+            Vec::<Deduction>::new(),
+        );
+        assert!(reduced.unwrap().is_solved());
+        assert!(trace
+            .iter()
+            .any(|deduction| matches!(deduction.reason, DeductionReason::XWing { .. })));
+    }
+}
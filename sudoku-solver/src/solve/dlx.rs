@@ -0,0 +1,277 @@
+//! Counts solutions by treating the board as an exact cover problem, solved
+//! with dancing links.
+use crate::collections::indexed::FixedSizeIndex;
+use crate::{Board, Coord, Val, Zone};
+
+/// Number of constraints each placement covers: its cell, and its value in its
+/// row, column, and sector.
+const CONSTRAINTS_PER_ROW: usize = 4;
+/// Number of distinct constraints of each kind.
+const CONSTRAINT_SIZE: usize = Coord::NUM_INDEXES;
+/// Number of constraint columns in the cover matrix.
+const NUM_COLUMNS: usize = CONSTRAINTS_PER_ROW * CONSTRAINT_SIZE;
+/// Number of candidate placements, each a row of the cover matrix.
+const NUM_ROWS: usize = Coord::NUM_INDEXES * Val::NUM_INDEXES;
+
+/// Count the solutions to the board, stopping once `limit` have been found.
+pub(crate) fn count_solutions(board: &Board, limit: usize) -> usize {
+    if limit == 0 {
+        return 0;
+    }
+    let mut links = Links::new();
+    for coord in Coord::all() {
+        if let Some(val) = board[coord] {
+            if !links.select(coord, val) {
+                return 0;
+            }
+        }
+    }
+    let mut count = 0;
+    links.search(limit, &mut count);
+    count
+}
+
+/// The constraint columns covered by placing the value in the cell.
+fn constraints(coord: Coord, val: Val) -> [usize; CONSTRAINTS_PER_ROW] {
+    let val = val.idx();
+    let per_house = |house: usize| house * Val::NUM_INDEXES + val;
+    [
+        coord.idx(),
+        CONSTRAINT_SIZE + per_house(coord.row().idx()),
+        2 * CONSTRAINT_SIZE + per_house(coord.col().idx()),
+        3 * CONSTRAINT_SIZE + per_house(coord.sector().idx()),
+    ]
+}
+
+/// Sparse cover matrix as circular doubly linked lists. Node 0 is the root,
+/// nodes `1..=NUM_COLUMNS` are the column headers, and the remaining nodes are
+/// the cells of the matrix, `CONSTRAINTS_PER_ROW` per candidate placement.
+struct Links {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    /// Column header of each node.
+    column: Vec<usize>,
+    /// Number of nodes left in each column, indexed by header node.
+    size: Vec<usize>,
+    /// Whether each column, indexed by header node, has been covered by a
+    /// selected placement.
+    covered: Vec<bool>,
+}
+
+impl Links {
+    /// Build the full matrix of every placement on an empty board.
+    fn new() -> Self {
+        let num_nodes = 1 + NUM_COLUMNS + NUM_ROWS * CONSTRAINTS_PER_ROW;
+        let mut links = Links {
+            left: Vec::with_capacity(num_nodes),
+            right: Vec::with_capacity(num_nodes),
+            up: Vec::with_capacity(num_nodes),
+            down: Vec::with_capacity(num_nodes),
+            column: Vec::with_capacity(num_nodes),
+            size: vec![0; 1 + NUM_COLUMNS],
+            covered: vec![false; 1 + NUM_COLUMNS],
+        };
+        for header in 0..=NUM_COLUMNS {
+            links
+                .left
+                .push(if header == 0 { NUM_COLUMNS } else { header - 1 });
+            links
+                .right
+                .push(if header == NUM_COLUMNS { 0 } else { header + 1 });
+            links.up.push(header);
+            links.down.push(header);
+            links.column.push(header);
+        }
+        for coord in Coord::all() {
+            for val in (0..Val::NUM_INDEXES).map(Val::from_idx) {
+                let first = links.column.len();
+                for (i, constraint) in constraints(coord, val).into_iter().enumerate() {
+                    let node = first + i;
+                    let header = constraint + 1;
+                    links.left.push(if i == 0 {
+                        first + CONSTRAINTS_PER_ROW - 1
+                    } else {
+                        node - 1
+                    });
+                    links.right.push(if i == CONSTRAINTS_PER_ROW - 1 {
+                        first
+                    } else {
+                        node + 1
+                    });
+                    links.up.push(links.up[header]);
+                    links.down.push(header);
+                    links.column.push(header);
+                    let last = links.up[header];
+                    links.down[last] = node;
+                    links.up[header] = node;
+                    links.size[header] += 1;
+                }
+            }
+        }
+        links
+    }
+
+    /// Fix the value in the cell before searching. Returns false if the
+    /// placement conflicts with one already selected.
+    fn select(&mut self, coord: Coord, val: Val) -> bool {
+        let headers = constraints(coord, val).map(|constraint| constraint + 1);
+        if headers.iter().any(|&header| self.covered[header]) {
+            return false;
+        }
+        for header in headers {
+            self.covered[header] = true;
+            self.cover(header);
+        }
+        true
+    }
+
+    /// Remove the column and every row which intersects it.
+    fn cover(&mut self, header: usize) {
+        self.right[self.left[header]] = self.right[header];
+        self.left[self.right[header]] = self.left[header];
+        let mut row = self.down[header];
+        while row != header {
+            let mut node = self.right[row];
+            while node != row {
+                self.down[self.up[node]] = self.down[node];
+                self.up[self.down[node]] = self.up[node];
+                self.size[self.column[node]] -= 1;
+                node = self.right[node];
+            }
+            row = self.down[row];
+        }
+    }
+
+    /// Undo `cover` for the column.
+    fn uncover(&mut self, header: usize) {
+        let mut row = self.up[header];
+        while row != header {
+            let mut node = self.left[row];
+            while node != row {
+                self.size[self.column[node]] += 1;
+                self.down[self.up[node]] = node;
+                self.up[self.down[node]] = node;
+                node = self.left[node];
+            }
+            row = self.up[row];
+        }
+        self.right[self.left[header]] = header;
+        self.left[self.right[header]] = header;
+    }
+
+    /// Count exact covers of the remaining columns, stopping once the count
+    /// reaches the limit.
+    fn search(&mut self, limit: usize, count: &mut usize) {
+        if self.right[0] == 0 {
+            *count += 1;
+            return;
+        }
+        // Branch on the column with the fewest rows left.
+        let mut header = self.right[0];
+        let mut best = header;
+        while header != 0 {
+            if self.size[header] < self.size[best] {
+                best = header;
+            }
+            header = self.right[header];
+        }
+        if self.size[best] == 0 {
+            return;
+        }
+        self.cover(best);
+        let mut row = self.down[best];
+        while row != best && *count < limit {
+            let mut node = self.right[row];
+            while node != row {
+                self.cover(self.column[node]);
+                node = self.right[node];
+            }
+            self.search(limit, count);
+            let mut node = self.left[row];
+            while node != row {
+                self.uncover(self.column[node]);
+                node = self.left[node];
+            }
+            row = self.down[row];
+        }
+        self.uncover(best);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::Uniqueness;
+    use crate::board_art;
+    use crate::fixtures::{contradictory, inkala, puzzle1};
+    use crate::{Col, Row};
+
+    fn puzzles() -> Vec<Board> {
+        vec![
+            puzzle1(),
+            contradictory(),
+            // A solved board with a deadly rectangle removed.
+            board_art!(
+                "467|192|385",
+                "329|458|671",
+                "851|367|294",
+                "---+---+---",
+                "518|279|463",
+                "27 |6 1|859",
+                "69 |8 5|127",
+                "---+---+---",
+                "732|984|516",
+                "145|726|938",
+                "986|513|742",
+            ),
+            Board::new(),
+        ]
+    }
+
+    #[test]
+    fn agrees_with_uniqueness() {
+        crate::setup();
+
+        for board in puzzles() {
+            let expected = match board.uniqueness_certificate() {
+                Uniqueness::NoSolution => 0,
+                Uniqueness::Unique => 1,
+                Uniqueness::Multiple(..) => 2,
+            };
+            assert_eq!(count_solutions(&board, 2), expected, "{:?}", board);
+        }
+    }
+
+    #[test]
+    fn hard_puzzle_unique() {
+        crate::setup();
+
+        // Checking uniqueness with the deductive solver takes many slow solves
+        // of this board, so only check that it is known to be unique.
+        let board = inkala();
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+
+    #[test]
+    fn respects_limit() {
+        crate::setup();
+
+        // The deadly rectangle can go either way, so it has exactly two.
+        let board = &puzzles()[2];
+        assert_eq!(count_solutions(board, 0), 0);
+        assert_eq!(count_solutions(board, 1), 1);
+        assert_eq!(count_solutions(board, 2), 2);
+        assert_eq!(count_solutions(board, 100), 2);
+        assert_eq!(count_solutions(&Board::new(), 50), 50);
+    }
+
+    #[test]
+    fn conflicting_givens() {
+        let mut board = Board::new();
+        board[Coord::new(Row::new(0), Col::new(0))] = Some(Val::new(5));
+        board[Coord::new(Row::new(7), Col::new(0))] = Some(Val::new(5));
+        assert_eq!(count_solutions(&board, 2), 0);
+    }
+}
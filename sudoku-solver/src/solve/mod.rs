@@ -1,2 +1,14 @@
+//! Solving boards, and the outcomes solving can have.
+mod budget;
 pub(crate) mod deductive;
+pub(crate) mod dlx;
 pub(crate) mod remaining;
+mod rules;
+mod solutions;
+mod solver;
+pub mod techniques;
+
+pub use budget::{Budget, BudgetExceeded, BudgetUsage, LimitKind};
+pub use rules::{ReduceOptions, RuleKind, RulePriority, RuleSet, UnknownRule};
+pub use solutions::Solutions;
+pub use solver::{ExhaustionKind, SolveCode, SolveOutcome, Solver, Unsolveable};
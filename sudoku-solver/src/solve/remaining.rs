@@ -4,8 +4,8 @@ use log::trace;
 
 use crate::collections::availset::{AvailCounter, AvailSet};
 use crate::collections::indexed::{FixedSizeIndex, IndexMap};
-use crate::trace::Remaining;
-use crate::{Board, Col, Coord, Row, Sector, SectorCol, SectorRow, Zone};
+use crate::trace::{ClosedRemaining, Remaining};
+use crate::{Board, Col, Coord, Exclusions, Row, Sector, SectorCol, SectorRow, Val, Zone};
 
 /// Tracks remaining values in a board.
 #[derive(Clone, Debug)]
@@ -42,6 +42,42 @@ impl RemainingTracker {
         tracker
     }
 
+    /// Construct a new tracker from the given board, with the given values
+    /// additionally removed from the cells they are excluded from.
+    pub(crate) fn with_exclusions(board: &Board, exclusions: &Exclusions) -> Self {
+        let mut tracker = Self::new(board);
+        for (coord, vals) in exclusions.iter() {
+            tracker.exclude(coord, vals);
+        }
+        tracker
+    }
+
+    /// Construct a new tracker from closed candidates.
+    pub(crate) fn from_closed(candidates: &ClosedRemaining) -> Self {
+        Self::from_remaining(candidates)
+    }
+
+    /// Construct a new tracker from candidates, which need not be closed.
+    pub(crate) fn from_remaining(candidates: &Remaining) -> Self {
+        let mut tracker = Self::new(&Board::new());
+        for (coord, avail) in candidates.iter() {
+            tracker.exclude(coord, !avail);
+        }
+        tracker
+    }
+
+    /// Remove the given values from the given cell, updating the counters for
+    /// all zones containing the cell.
+    pub(crate) fn exclude(&mut self, coord: Coord, vals: AvailSet) {
+        let removed = self.board[coord] & vals;
+        self.board[coord] -= removed;
+        self[coord.row()] -= removed;
+        self[coord.col()] -= removed;
+        self[coord.sector()] -= removed;
+        self[coord.sector_row()] -= removed;
+        self[coord.sector_col()] -= removed;
+    }
+
     /// Get the mapping for this type from the tracker.
     pub(crate) fn get<T: ExtractRem>(&self) -> &IndexMap<T, T::Avail> {
         T::get(self)
@@ -95,6 +131,39 @@ impl RemainingTracker {
         self.board.into()
     }
 
+    /// Return true if filling the given cell with the given value would
+    /// immediately leave one of its neighbors with no values, or leave some
+    /// other row, column, or sector containing a neighbor with no place for
+    /// the value. Only looks at the neighbors of the cell, so it is cheap
+    /// enough to check before every guess.
+    pub(crate) fn quick_contradiction(&self, coord: Coord, val: Val) -> bool {
+        let mut rows = IndexMap::<Row, u8>::with_value(0);
+        let mut cols = IndexMap::<Col, u8>::with_value(0);
+        let mut sectors = IndexMap::<Sector, u8>::with_value(0);
+        for neighbor in coord.neighbors() {
+            let avail = self.board[neighbor];
+            if !avail.contains(val) {
+                continue;
+            }
+            if avail.is_single() {
+                return true;
+            }
+            rows[neighbor.row()] += 1;
+            cols[neighbor.col()] += 1;
+            sectors[neighbor.sector()] += 1;
+        }
+        // The houses containing the cell keep the value there, so only the
+        // others can run out.
+        rows.iter()
+            .any(|(row, &lost)| lost > 0 && row != coord.row() && lost == self.rows[row][val])
+            || cols
+                .iter()
+                .any(|(col, &lost)| lost > 0 && col != coord.col() && lost == self.cols[col][val])
+            || sectors.iter().any(|(sector, &lost)| {
+                lost > 0 && sector != coord.sector() && lost == self.sectors[sector][val]
+            })
+    }
+
     /// Find the first cell with multiple values and return an iterator over copies of
     /// this board with that cell specified to each of the possible values.
     pub(crate) fn specify_one(self) -> impl Iterator<Item = Self> {
@@ -107,7 +176,41 @@ impl RemainingTracker {
             .map(|(coord, avail)| (coord, *avail))
             .unwrap();
         trace!("Guessing {:?} with values {:?}", coord, avail);
-        avail.iter().filter_map(move |val| {
+        self.specify(coord, avail.iter())
+    }
+
+    /// Pick a random cell among those with the fewest values left, and return
+    /// an iterator over copies of this board with that cell specified to each
+    /// of its values in a random order.
+    #[cfg(feature = "rand")]
+    pub(crate) fn specify_random(self, rng: &mut impl rand::Rng) -> impl Iterator<Item = Self> {
+        use rand::seq::{IteratorRandom, SliceRandom};
+
+        let fewest = self
+            .board
+            .values()
+            .map(AvailSet::len)
+            .filter(|&len| len > 1)
+            .min()
+            .unwrap();
+        let (coord, avail) = self
+            .board
+            .iter()
+            .filter(|(_, avail)| avail.len() == fewest)
+            .choose(rng)
+            .map(|(coord, avail)| (coord, *avail))
+            .unwrap();
+        let mut vals: Vec<_> = avail.iter().collect();
+        vals.shuffle(rng);
+        trace!("Guessing {:?} with values {:?}", coord, vals);
+        self.specify(coord, vals.into_iter())
+    }
+
+    /// Return an iterator over copies of this board with the cell specified to
+    /// each of the given values, skipping ones which are known to fail.
+    fn specify(self, coord: Coord, vals: impl Iterator<Item = Val>) -> impl Iterator<Item = Self> {
+        let avail = self.board[coord];
+        vals.filter_map(move |val| {
             let mut copy = self.clone();
             let removed_values = avail - val;
             copy[coord] = AvailSet::only(val);
@@ -116,7 +219,7 @@ impl RemainingTracker {
             copy[coord.sector()] -= removed_values;
             copy[coord.sector_row()] -= removed_values;
             copy[coord.sector_col()] -= removed_values;
-            if copy.known_unsolveable() {
+            if copy.known_unsolveable() || self.quick_contradiction(coord, val) {
                 trace!("Skipping {:?} because it is known to be unsolveable.", val);
                 None
             } else {
@@ -186,3 +289,58 @@ impl From<RemainingTracker> for Remaining {
         tracker.into_remaining()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::inkala;
+    use crate::solve::deductive;
+    use crate::trace::NopDeductiveTracer;
+
+    /// Fill the cell with the value the same way a guess does.
+    fn guess(tracker: &RemainingTracker, coord: Coord, val: Val) -> RemainingTracker {
+        let mut copy = tracker.clone();
+        let removed = tracker[coord] - val;
+        copy.exclude(coord, removed);
+        copy
+    }
+
+    #[test]
+    fn quick_contradiction_implies_reduce_fails() {
+        crate::setup();
+
+        // Sample states before and after reduction, and after a few guesses.
+        let mut states = vec![RemainingTracker::new(&inkala())];
+        let (reduced, _) =
+            deductive::reduce(states[0].clone(), &Default::default(), NopDeductiveTracer);
+        states.push(reduced.unwrap());
+        for _ in 0..3 {
+            let next = states.last().unwrap().clone().specify_one().next().unwrap();
+            let (reduced, _) =
+                deductive::reduce(next.clone(), &Default::default(), NopDeductiveTracer);
+            states.push(next);
+            states.extend(reduced);
+        }
+
+        let mut rejected = 0;
+        for state in &states {
+            for (coord, avail) in state.get::<Coord>().iter() {
+                if avail.len() < 2 {
+                    continue;
+                }
+                for val in *avail {
+                    if state.quick_contradiction(coord, val) {
+                        rejected += 1;
+                        let (reduced, _) = deductive::reduce(
+                            guess(state, coord, val),
+                            &Default::default(),
+                            NopDeductiveTracer,
+                        );
+                        assert!(reduced.is_none(), "{:?} at {:?} reduced", val, coord);
+                    }
+                }
+            }
+        }
+        assert!(rejected > 0);
+    }
+}
@@ -0,0 +1,648 @@
+//! Stable identifiers for the deduction rules, shared by everything which needs
+//! to refer to a specific rule.
+use std::fmt;
+use std::iter::FromIterator;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Sub, SubAssign};
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// One of the rules the deductive solver uses. The string form of each rule
+/// matches the `kind` of the deductions it makes.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[non_exhaustive]
+pub enum RuleKind {
+    /// A cell with one value left eliminates that value from its neighbors.
+    CoordNeighbors,
+    /// A value with one cell left in a row fills that cell.
+    UniqueInRow,
+    /// A value with one cell left in a column fills that cell.
+    UniqueInCol,
+    /// A value with one cell left in a sector fills that cell.
+    UniqueInSector,
+    /// A sector-row with exactly three values left eliminates them from the
+    /// rest of its row and sector.
+    SecRowTriple,
+    /// A sector-col with exactly three values left eliminates them from the
+    /// rest of its column and sector.
+    SecColTriple,
+    /// Values confined to one sector-row of a sector are eliminated from the
+    /// rest of the row.
+    SecOnlyRow,
+    /// Values confined to one sector-col of a sector are eliminated from the
+    /// rest of the column.
+    SecOnlyCol,
+    /// Values confined to one sector-row of a row are eliminated from the rest
+    /// of the sector.
+    RowOnlySec,
+    /// Values confined to one sector-col of a column are eliminated from the
+    /// rest of the sector.
+    ColOnlySec,
+    /// Two values confined to the same two cells of a house eliminate every
+    /// other value from those cells.
+    HiddenPair,
+    /// Three values confined to the same three cells of a house eliminate
+    /// every other value from those cells.
+    HiddenTriple,
+    /// A value confined to the same two columns in two rows, or the same two
+    /// rows in two columns, is eliminated from the rest of those lines.
+    XWing,
+}
+
+impl RuleKind {
+    /// Every rule, in order.
+    pub const ALL: &'static [RuleKind] = &[
+        RuleKind::CoordNeighbors,
+        RuleKind::UniqueInRow,
+        RuleKind::UniqueInCol,
+        RuleKind::UniqueInSector,
+        RuleKind::SecRowTriple,
+        RuleKind::SecColTriple,
+        RuleKind::SecOnlyRow,
+        RuleKind::SecOnlyCol,
+        RuleKind::RowOnlySec,
+        RuleKind::ColOnlySec,
+        RuleKind::HiddenPair,
+        RuleKind::HiddenTriple,
+        RuleKind::XWing,
+    ];
+
+    /// The stable name of the rule.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            RuleKind::CoordNeighbors => "coord_neighbors",
+            RuleKind::UniqueInRow => "unique_in_row",
+            RuleKind::UniqueInCol => "unique_in_col",
+            RuleKind::UniqueInSector => "unique_in_sector",
+            RuleKind::SecRowTriple => "sec_row_triple",
+            RuleKind::SecColTriple => "sec_col_triple",
+            RuleKind::SecOnlyRow => "sec_only_row",
+            RuleKind::SecOnlyCol => "sec_only_col",
+            RuleKind::RowOnlySec => "row_only_sec",
+            RuleKind::ColOnlySec => "col_only_sec",
+            RuleKind::HiddenPair => "hidden_pair",
+            RuleKind::HiddenTriple => "hidden_triple",
+            RuleKind::XWing => "x_wing",
+        }
+    }
+
+    #[inline]
+    fn mask(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+impl fmt::Display for RuleKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Returned when parsing a name which isn't a rule.
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+#[error("unknown rule {0:?}")]
+pub struct UnknownRule(pub String);
+
+impl FromStr for RuleKind {
+    type Err = UnknownRule;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RuleKind::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.name() == s)
+            .ok_or_else(|| UnknownRule(s.to_string()))
+    }
+}
+
+/// A set of rules. Serializes as a list of rule names.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(into = "Vec<RuleKind>", from = "Vec<RuleKind>")
+)]
+pub struct RuleSet(u32);
+
+impl RuleSet {
+    /// Set containing every rule.
+    #[must_use]
+    pub fn all() -> Self {
+        RuleKind::ALL.iter().copied().collect()
+    }
+
+    /// Set containing no rules.
+    #[must_use]
+    pub const fn none() -> Self {
+        RuleSet(0)
+    }
+
+    /// Set containing only the given rule.
+    #[must_use]
+    pub fn only(kind: RuleKind) -> Self {
+        RuleSet(kind.mask())
+    }
+
+    /// Returns true if there are no rules in the set.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Number of rules in the set.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Returns true if the rule is in the set.
+    #[must_use]
+    pub fn contains(&self, kind: RuleKind) -> bool {
+        self.0 & kind.mask() != 0
+    }
+
+    /// Add a rule to the set. Return true if it wasn't already in the set.
+    pub fn insert(&mut self, kind: RuleKind) -> bool {
+        let added = !self.contains(kind);
+        self.0 |= kind.mask();
+        added
+    }
+
+    /// Remove a rule from the set. Return true if it was in the set.
+    pub fn remove(&mut self, kind: RuleKind) -> bool {
+        let removed = self.contains(kind);
+        self.0 &= !kind.mask();
+        removed
+    }
+
+    /// Iterate over the rules in the set, in order.
+    pub fn iter(self) -> impl Iterator<Item = RuleKind> {
+        RuleKind::ALL
+            .iter()
+            .copied()
+            .filter(move |&kind| self.contains(kind))
+    }
+}
+
+impl fmt::Debug for RuleSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl From<RuleKind> for RuleSet {
+    fn from(kind: RuleKind) -> Self {
+        RuleSet::only(kind)
+    }
+}
+
+impl FromIterator<RuleKind> for RuleSet {
+    fn from_iter<I: IntoIterator<Item = RuleKind>>(iter: I) -> Self {
+        let mut set = RuleSet::none();
+        for kind in iter {
+            set.insert(kind);
+        }
+        set
+    }
+}
+
+impl From<Vec<RuleKind>> for RuleSet {
+    fn from(kinds: Vec<RuleKind>) -> Self {
+        kinds.into_iter().collect()
+    }
+}
+
+impl From<RuleSet> for Vec<RuleKind> {
+    fn from(set: RuleSet) -> Self {
+        set.iter().collect()
+    }
+}
+
+impl BitOr for RuleSet {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(mut self, rhs: Self) -> Self::Output {
+        self |= rhs;
+        self
+    }
+}
+
+impl BitOrAssign for RuleSet {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for RuleSet {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(mut self, rhs: Self) -> Self::Output {
+        self &= rhs;
+        self
+    }
+}
+
+impl BitAndAssign for RuleSet {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.0 &= rhs.0;
+    }
+}
+
+impl Sub for RuleSet {
+    type Output = Self;
+
+    #[inline]
+    fn sub(mut self, rhs: Self) -> Self::Output {
+        self -= rhs;
+        self
+    }
+}
+
+impl SubAssign for RuleSet {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0 &= !rhs.0;
+    }
+}
+
+/// The order in which deductive reduction prefers to apply rules. When steps
+/// for several rules are pending, steps for rules ranked earlier always run
+/// first. Rules which share a rank run in the solver's built-in order, so the
+/// default priority, where every rule shares a rank, leaves reduction unchanged.
+/// Serializes as the list of ranked rules, earliest first.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(into = "Vec<RuleKind>", from = "Vec<RuleKind>")
+)]
+pub struct RulePriority([u8; RuleKind::ALL.len()]);
+
+impl RulePriority {
+    /// Priority for rating puzzles the way a person would solve them: singles
+    /// first, then box/line interactions, then the sector-row and sector-col
+    /// subsets, then hidden pairs and triples, and only then X-Wings.
+    #[must_use]
+    pub fn human() -> Self {
+        [
+            RuleKind::CoordNeighbors,
+            RuleKind::UniqueInSector,
+            RuleKind::UniqueInRow,
+            RuleKind::UniqueInCol,
+            RuleKind::SecOnlyRow,
+            RuleKind::SecOnlyCol,
+            RuleKind::RowOnlySec,
+            RuleKind::ColOnlySec,
+            RuleKind::SecRowTriple,
+            RuleKind::SecColTriple,
+            RuleKind::HiddenPair,
+            RuleKind::HiddenTriple,
+            RuleKind::XWing,
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    /// The rank of the given rule. Lower ranks are applied first.
+    #[must_use]
+    pub fn rank(&self, kind: RuleKind) -> usize {
+        self.0[kind as usize] as usize
+    }
+
+    /// Of the given rules, the one ranked last, or None if there are none.
+    /// Ties go to the rule which is last in `RuleKind::ALL`.
+    pub fn hardest(&self, kinds: impl IntoIterator<Item = RuleKind>) -> Option<RuleKind> {
+        kinds
+            .into_iter()
+            .max_by_key(|&kind| (self.rank(kind), kind))
+    }
+
+    /// The ranked rules, earliest first. Rules left sharing the last rank are
+    /// not included.
+    #[must_use]
+    pub fn order(&self) -> Vec<RuleKind> {
+        let mut order = RuleKind::ALL.to_vec();
+        order.sort_by_key(|&kind| self.rank(kind));
+        let last = order.last().map(|&kind| self.rank(kind));
+        let unranked = order
+            .iter()
+            .filter(|&&kind| Some(self.rank(kind)) == last)
+            .count();
+        if unranked > 1 {
+            order.truncate(order.len() - unranked);
+        }
+        order
+    }
+}
+
+impl fmt::Debug for RulePriority {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.order()).finish()
+    }
+}
+
+/// Ranks the rules in the order given. Rules which aren't listed share the
+/// rank after the last listed rule, and repeated rules keep their first rank.
+impl FromIterator<RuleKind> for RulePriority {
+    fn from_iter<I: IntoIterator<Item = RuleKind>>(iter: I) -> Self {
+        let mut seen = RuleSet::none();
+        let mut ranks = [0; RuleKind::ALL.len()];
+        for kind in iter {
+            if seen.insert(kind) {
+                ranks[kind as usize] = seen.len() as u8 - 1;
+            }
+        }
+        let unranked = seen.len() as u8;
+        for kind in (RuleSet::all() - seen).iter() {
+            ranks[kind as usize] = unranked;
+        }
+        RulePriority(ranks)
+    }
+}
+
+impl From<Vec<RuleKind>> for RulePriority {
+    fn from(kinds: Vec<RuleKind>) -> Self {
+        kinds.into_iter().collect()
+    }
+}
+
+impl From<RulePriority> for Vec<RuleKind> {
+    fn from(priority: RulePriority) -> Self {
+        priority.order()
+    }
+}
+
+/// Options controlling which rules deductive reduction applies.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+pub struct ReduceOptions {
+    /// Rules which are never applied. Guessing still fills in whatever these
+    /// rules would have found.
+    pub disabled: RuleSet,
+    /// The order rules are preferred in when more than one applies.
+    pub priority: RulePriority,
+}
+
+impl ReduceOptions {
+    /// Options which apply every rule, simplest first, as in
+    /// `RulePriority::human`.
+    #[must_use]
+    pub fn human() -> Self {
+        ReduceOptions {
+            priority: RulePriority::human(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::puzzle1;
+    use crate::solve::deductive;
+    use crate::solve::remaining::RemainingTracker;
+    use crate::trace::{Deduction, DeductionReason, UnsolveableReason};
+    use crate::{AvailSet, Col, Coord, House, Row, Solver, Val};
+
+    const NAMES: &[&str] = &[
+        "coord_neighbors",
+        "unique_in_row",
+        "unique_in_col",
+        "unique_in_sector",
+        "sec_row_triple",
+        "sec_col_triple",
+        "sec_only_row",
+        "sec_only_col",
+        "row_only_sec",
+        "col_only_sec",
+        "hidden_pair",
+        "hidden_triple",
+        "x_wing",
+    ];
+
+    #[test]
+    fn rule_names() {
+        let names: Vec<_> = RuleKind::ALL.iter().map(|kind| kind.to_string()).collect();
+        assert_eq!(names, NAMES);
+        for &kind in RuleKind::ALL {
+            assert_eq!(kind.name().parse::<RuleKind>(), Ok(kind));
+        }
+        assert_eq!(
+            "guess".parse::<RuleKind>(),
+            Err(UnknownRule("guess".to_string()))
+        );
+    }
+
+    #[test]
+    fn reason_kinds() {
+        let coord = Coord::new(Row::new(0), Col::new(0));
+        let vals = AvailSet::all();
+        let secrow = coord.sector_row();
+        let seccol = coord.sector_col();
+        let reasons = [
+            DeductionReason::CoordNeighbors {
+                pos: coord,
+                val: Val::new(1),
+            },
+            DeductionReason::UniqueInRow {
+                pos: coord.row(),
+                vals,
+            },
+            DeductionReason::UniqueInCol {
+                pos: coord.col(),
+                vals,
+            },
+            DeductionReason::UniqueInSector {
+                pos: coord.sector(),
+                vals,
+            },
+            DeductionReason::SecRowTriple { pos: secrow, vals },
+            DeductionReason::SecColTriple { pos: seccol, vals },
+            DeductionReason::SecOnlyRow { pos: secrow, vals },
+            DeductionReason::SecOnlyCol { pos: seccol, vals },
+            DeductionReason::RowOnlySec { pos: secrow, vals },
+            DeductionReason::ColOnlySec { pos: seccol, vals },
+            DeductionReason::HiddenPair {
+                pos: House::Row(coord.row()),
+                cells: [coord, Coord::new(Row::new(0), Col::new(1))],
+                vals,
+            },
+            DeductionReason::HiddenTriple {
+                pos: House::Sector(coord.sector()),
+                cells: [
+                    coord,
+                    Coord::new(Row::new(0), Col::new(1)),
+                    Coord::new(Row::new(1), Col::new(0)),
+                ],
+                vals,
+            },
+            DeductionReason::XWing {
+                rows: [coord.row(), Row::new(4)],
+                cols: [coord.col(), Col::new(7)],
+                val: Val::new(1),
+                by_rows: true,
+            },
+        ];
+        let kinds: Vec<_> = reasons.iter().map(|r| r.rule_kind().unwrap()).collect();
+        assert_eq!(kinds, RuleKind::ALL);
+        assert_eq!(DeductionReason::InitialState.rule_kind(), None);
+        let unsolveable = DeductionReason::Unsolveable(UnsolveableReason::Empty { pos: coord });
+        assert_eq!(unsolveable.rule_kind(), None);
+    }
+
+    #[test]
+    fn rule_set_ops() {
+        let mut set = RuleSet::none();
+        assert!(set.is_empty());
+        assert!(set.insert(RuleKind::SecOnlyRow));
+        assert!(!set.insert(RuleKind::SecOnlyRow));
+        set |= RuleKind::CoordNeighbors.into();
+        assert_eq!(set.len(), 2);
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            [RuleKind::CoordNeighbors, RuleKind::SecOnlyRow]
+        );
+        assert_eq!(
+            set & RuleSet::only(RuleKind::SecOnlyRow),
+            RuleSet::only(RuleKind::SecOnlyRow)
+        );
+        assert_eq!(
+            set - RuleSet::only(RuleKind::SecOnlyRow),
+            RuleSet::only(RuleKind::CoordNeighbors)
+        );
+        assert!(set.remove(RuleKind::SecOnlyRow));
+        assert!(!set.remove(RuleKind::SecOnlyRow));
+        assert_eq!(RuleSet::all().len(), RuleKind::ALL.len());
+        assert_eq!(format!("{:?}", set), "{CoordNeighbors}");
+    }
+
+    #[test]
+    fn rule_priority() {
+        let priority: RulePriority = [RuleKind::SecOnlyRow, RuleKind::CoordNeighbors]
+            .into_iter()
+            .collect();
+        assert_eq!(priority.rank(RuleKind::SecOnlyRow), 0);
+        assert_eq!(priority.rank(RuleKind::CoordNeighbors), 1);
+        assert_eq!(priority.rank(RuleKind::UniqueInRow), 2);
+        assert_eq!(priority.rank(RuleKind::ColOnlySec), 2);
+        assert_eq!(
+            priority.order(),
+            [RuleKind::SecOnlyRow, RuleKind::CoordNeighbors]
+        );
+        assert_eq!(
+            priority.hardest([RuleKind::SecOnlyRow, RuleKind::CoordNeighbors]),
+            Some(RuleKind::CoordNeighbors)
+        );
+        assert_eq!(
+            priority.hardest([RuleKind::UniqueInRow, RuleKind::UniqueInCol]),
+            Some(RuleKind::UniqueInCol)
+        );
+        assert_eq!(priority.hardest([]), None);
+
+        assert!(RulePriority::default().order().is_empty());
+        let human = RulePriority::human();
+        assert_eq!(human.order().len(), RuleKind::ALL.len());
+        assert_eq!(human.order().into_iter().collect::<RulePriority>(), human);
+        assert_eq!(
+            human.hardest(RuleKind::ALL.iter().copied()),
+            Some(RuleKind::XWing)
+        );
+    }
+
+    #[test]
+    fn human_priority_prefers_singles() {
+        crate::setup();
+
+        let board = puzzle1();
+        let options = ReduceOptions::human();
+        let (reduced, trace): (_, Vec<Deduction>) =
+            deductive::reduce(RemainingTracker::new(&board), &options, Vec::new());
+        assert!(reduced.unwrap().is_solved());
+        // Singles are always available on this board, so nothing harder runs.
+        assert!(trace
+            .iter()
+            .filter_map(|deduction| deduction.reason.rule_kind())
+            .all(|kind| options.priority.rank(kind) < 4));
+        assert_eq!(Solver::human().run(&board).into_solution(), board.solve());
+    }
+
+    #[test]
+    fn disabled_rules_not_traced() {
+        crate::setup();
+
+        let board = puzzle1();
+        let solution = board.solve().unwrap();
+        for &kind in RuleKind::ALL {
+            let options = ReduceOptions {
+                disabled: kind.into(),
+                ..Default::default()
+            };
+            let (_, trace): (_, Vec<Deduction>) =
+                deductive::reduce(RemainingTracker::new(&board), &options, Vec::new());
+            assert!(trace
+                .iter()
+                .all(|deduction| deduction.reason.rule_kind() != Some(kind)));
+
+            // Without naked singles solving falls back to guessing nearly every
+            // cell, which is too slow to check here.
+            if kind != RuleKind::CoordNeighbors {
+                let solver = Solver {
+                    reduce: options,
+                    ..Default::default()
+                };
+                assert_eq!(solver.run(&board).into_solution(), Some(solution.clone()));
+            }
+        }
+
+        // With every rule disabled nothing is deduced.
+        let options = ReduceOptions {
+            disabled: RuleSet::all(),
+            ..Default::default()
+        };
+        let (reduced, trace) =
+            deductive::reduce(RemainingTracker::new(&board), &options, Vec::new());
+        assert_eq!(trace.len(), 1);
+        assert_eq!(reduced.unwrap().into_board(), board);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize() {
+        let ser = serde_json::to_string(RuleKind::ALL).unwrap();
+        let expected = serde_json::to_string(NAMES).unwrap();
+        assert_eq!(ser, expected);
+
+        let set: RuleSet = [RuleKind::UniqueInRow, RuleKind::ColOnlySec]
+            .into_iter()
+            .collect();
+        let ser = serde_json::to_string(&set).unwrap();
+        assert_eq!(ser, r#"["unique_in_row","col_only_sec"]"#);
+        let roundtrip: RuleSet = serde_json::from_str(&ser).unwrap();
+        assert_eq!(roundtrip, set);
+
+        let options: ReduceOptions = serde_json::from_str("{}").unwrap();
+        assert_eq!(options, ReduceOptions::default());
+
+        let priority: RulePriority = [RuleKind::SecOnlyRow, RuleKind::CoordNeighbors]
+            .into_iter()
+            .collect();
+        let ser = serde_json::to_string(&priority).unwrap();
+        assert_eq!(ser, r#"["sec_only_row","coord_neighbors"]"#);
+        let roundtrip: RulePriority = serde_json::from_str(&ser).unwrap();
+        assert_eq!(roundtrip, priority);
+        let ser = serde_json::to_string(&ReduceOptions::default()).unwrap();
+        assert_eq!(ser, r#"{"disabled":[],"priority":[]}"#);
+    }
+}
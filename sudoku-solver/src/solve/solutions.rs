@@ -0,0 +1,102 @@
+//! Lazily enumerating every solution of a board.
+use std::iter::FusedIterator;
+
+use crate::solve::{deductive, remaining::RemainingTracker};
+use crate::trace::NopDeductiveTracer;
+use crate::Board;
+
+/// Iterator over every solution of a board, returned by `Board::solutions`.
+///
+/// Runs the same search as `Board::solve`, so the first solution is the one
+/// `solve` finds, but holds its stack of untried guesses between calls to
+/// `next` rather than stopping at the first solution. Only the guesses still
+/// to try are held, so a board with many solutions can be iterated without
+/// collecting them.
+#[derive(Clone, Debug)]
+pub struct Solutions {
+    /// Boards still to reduce, with the next to try on top.
+    stack: Vec<RemainingTracker>,
+}
+
+impl Solutions {
+    pub(crate) fn new(board: &Board) -> Self {
+        Solutions {
+            stack: vec![RemainingTracker::new(board)],
+        }
+    }
+}
+
+impl Iterator for Solutions {
+    type Item = Board;
+
+    fn next(&mut self) -> Option<Board> {
+        while let Some(tracker) = self.stack.pop() {
+            match deductive::reduce(tracker, &Default::default(), NopDeductiveTracer).0 {
+                Some(reduced) if reduced.is_solved() => return Some(reduced.into_board()),
+                Some(reduced) => {
+                    // Push the guesses so the first is on top, to try them in
+                    // the same order as solve.
+                    let start = self.stack.len();
+                    self.stack.extend(reduced.specify_one());
+                    self.stack[start..].reverse();
+                }
+                None => {}
+            }
+        }
+        None
+    }
+}
+
+impl FusedIterator for Solutions {}
+
+#[cfg(test)]
+mod tests {
+    use crate::board_art;
+    use crate::Board;
+
+    #[test]
+    fn solutions() {
+        crate::setup();
+
+        // A solved board with a deadly rectangle removed has exactly two
+        // solutions.
+        let board = board_art!(
+            "467|192|385",
+            "329|458|671",
+            "851|367|294",
+            "---+---+---",
+            "518|279|463",
+            "27 |6 1|859",
+            "69 |8 5|127",
+            "---+---+---",
+            "732|984|516",
+            "145|726|938",
+            "986|513|742",
+        );
+        let mut solutions = board.solutions();
+        let first = solutions.next().unwrap();
+        assert_eq!(Some(first.clone()), board.solve());
+        let second = solutions.next().unwrap();
+        assert_ne!(first, second);
+        assert!(second.is_solved());
+        assert_eq!(solutions.next(), None);
+        assert_eq!(solutions.next(), None);
+
+        let solved = board.solve().unwrap();
+        let mut solutions = solved.solutions();
+        assert_eq!(solutions.next().as_ref(), Some(&solved));
+        assert_eq!(solutions.next(), None);
+
+        let mut bad = solved;
+        bad[crate::Coord::from_rowmajor_idx(0)] = bad[crate::Coord::from_rowmajor_idx(1)];
+        assert_eq!(bad.solutions().next(), None);
+
+        let many: Vec<_> = Board::new().solutions().take(5).collect();
+        assert_eq!(many.len(), 5);
+        assert_eq!(Some(many[0].clone()), Board::new().solve());
+        for (i, solution) in many.iter().enumerate() {
+            assert!(solution.is_solved());
+            assert!(!many[..i].contains(solution));
+        }
+    }
+}
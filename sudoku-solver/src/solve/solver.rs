@@ -0,0 +1,859 @@
+//! Configurable solver with a single outcome type shared by every solve entry
+//! point.
+use std::fmt;
+
+use log::trace;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::certificate::{self, verify_certificate, verify_solution, UnsolveableCertificate};
+use crate::solve::budget::{Budget, BudgetExceeded};
+use crate::solve::deductive;
+use crate::solve::remaining::RemainingTracker;
+use crate::solve::ReduceOptions;
+use crate::trace::{
+    ClosedRemaining, DeductionReason, DeductiveTracer, NopDeductiveTracer, NopTracer, Remaining,
+    TraceTree, UnsolveableReason,
+};
+use crate::variant::Variants;
+use crate::{Board, Coord, Exclusions, Val};
+
+/// Options controlling how a board is solved.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Solver {
+    /// Give up once any limit of this budget is reached. Checked before each
+    /// board the solver reduces.
+    pub budget: Budget,
+    /// Values forbidden from specific cells in addition to the normal rules.
+    pub exclusions: Exclusions,
+    /// Options for the deductive reduction done before each guess.
+    pub reduce: ReduceOptions,
+    /// Rules added to the classic ones, for solving variants.
+    pub variants: Variants,
+    /// Check every verdict of `run` and `run_within` independently of the
+    /// deduction engine. Solutions are checked against the board, and boards
+    /// with no solution get an `UnsolveableCertificate`, which is checked
+    /// before it is returned. Building a certificate repeats the search with
+    /// the whole trace kept, so this roughly doubles the cost of a board with
+    /// no solution. Certificates only cover the classic rules, so none is made
+    /// when `variants` has any.
+    ///
+    /// If a check fails, which means the solver has a bug, the verdict is
+    /// dropped and the outcome is `Exhausted` with
+    /// `ExhaustionKind::VerificationFailed`.
+    pub high_assurance: bool,
+}
+
+impl Solver {
+    /// Create a solver with the default options.
+    #[must_use]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Create a solver which applies the simplest rule available first, the
+    /// way a person would, as in `ReduceOptions::human`.
+    #[must_use]
+    pub fn human() -> Self {
+        Solver {
+            reduce: ReduceOptions::human(),
+            ..Default::default()
+        }
+    }
+
+    /// Solve the given board.
+    #[must_use]
+    pub fn run(&self, board: &Board) -> SolveOutcome {
+        self.run_within(board, &mut self.budget.clone())
+    }
+
+    /// Solve the given board within the given budget in place of the solver's
+    /// own, charging the budget with the work done. Use this for each piece of
+    /// a larger operation, with sub-budgets made by `Budget::split`.
+    pub fn run_within(&self, board: &Board, budget: &mut Budget) -> SolveOutcome {
+        let outcome = match self.check_givens(board) {
+            Some(outcome) => outcome,
+            None => {
+                let tracker = RemainingTracker::with_exclusions(board, &self.exclusions);
+                self.run_tracker(tracker, budget)
+            }
+        };
+        if self.high_assurance {
+            self.assure(board, outcome)
+        } else {
+            outcome
+        }
+    }
+
+    /// Solve a board given as bytes in row-major order, with 0 for an empty
+    /// cell, writing the solution over the input. The input is only changed if
+    /// the board is solved. This is meant for callers across an FFI boundary,
+    /// which have the bytes already and want a plain result code back.
+    pub fn solve_in_place(&self, cells: &mut [u8; Board::SIZE]) -> SolveCode {
+        self.solve_in_place_within(cells, &mut self.budget.clone())
+    }
+
+    /// Like `solve_in_place`, but within the given budget in place of the
+    /// solver's own, as in `run_within`.
+    pub fn solve_in_place_within(
+        &self,
+        cells: &mut [u8; Board::SIZE],
+        budget: &mut Budget,
+    ) -> SolveCode {
+        if cells.iter().any(|&cell| cell > Val::MAX) {
+            return SolveCode::Invalid;
+        }
+        let mut board = Board::new();
+        for (cell, &byte) in board.row_major_mut().iter_mut().zip(cells.iter()) {
+            *cell = Val::try_from(byte).ok();
+        }
+        // Going through `run_within` checks the givens against the variants
+        // and exclusions and applies `high_assurance`, the same as `run`.
+        match self.run_within(&board, budget) {
+            SolveOutcome::Solved(solution) => {
+                for (cell, val) in cells.iter_mut().zip(solution.row_major()) {
+                    *cell = val.map_or(0, Val::val);
+                }
+                SolveCode::Solved
+            }
+            SolveOutcome::Unsolveable(unsolveable)
+                if !unsolveable.conflicts.is_empty() || !unsolveable.excluded.is_empty() =>
+            {
+                SolveCode::Invalid
+            }
+            SolveOutcome::Unsolveable(_) => SolveCode::Unsolveable,
+            SolveOutcome::Exhausted {
+                reason: ExhaustionKind::Budget(_),
+                ..
+            } => SolveCode::BudgetExceeded,
+            SolveOutcome::Exhausted {
+                reason: ExhaustionKind::VerificationFailed,
+                ..
+            } => SolveCode::VerificationFailed,
+        }
+    }
+
+    /// Solve starting from the given candidates rather than a board, such as
+    /// pencil marks imported from elsewhere.
+    #[must_use]
+    pub fn run_candidates(&self, candidates: &ClosedRemaining) -> SolveOutcome {
+        let mut tracker = RemainingTracker::from_closed(candidates);
+        for (coord, vals) in self.exclusions.iter() {
+            tracker.exclude(coord, vals);
+        }
+        self.run_tracker(tracker, &mut self.budget.clone())
+    }
+
+    /// Check the outcome of solving the board, as described on
+    /// `high_assurance`.
+    fn assure(&self, board: &Board, outcome: SolveOutcome) -> SolveOutcome {
+        match outcome {
+            SolveOutcome::Solved(solution) => {
+                if verify_solution(board, &solution, &self.exclusions)
+                    && self.variants.conflicts(&solution).is_empty()
+                {
+                    return SolveOutcome::Solved(solution);
+                }
+                trace!("Solver returned an invalid solution {:?}", solution);
+            }
+            SolveOutcome::Unsolveable(mut unsolveable) if self.variants.is_classic() => {
+                match self.certify(board) {
+                    Some(certificate) if verify_certificate(board, &certificate) => {
+                        unsolveable.certificate = Some(certificate);
+                        return SolveOutcome::Unsolveable(unsolveable);
+                    }
+                    Some(certificate) => {
+                        trace!("Certificate failed to verify: {:?}", certificate);
+                    }
+                    None => trace!("No certificate for the unsolveable verdict"),
+                }
+            }
+            outcome => return outcome,
+        }
+        SolveOutcome::Exhausted {
+            reason: ExhaustionKind::VerificationFailed,
+            partial: None,
+        }
+    }
+
+    /// Search the board again keeping the whole trace, and build a certificate
+    /// from it if there is no solution.
+    fn certify(&self, board: &Board) -> Option<UnsolveableCertificate> {
+        let tracker = RemainingTracker::with_exclusions(board, &self.exclusions);
+        let start = tracker.remaining();
+        certificate::certify(start.as_ref(), &self.exclusions, || {
+            let (solution, trace) = Board::search::<TraceTree, _>(
+                tracker,
+                |tracker| deductive::reduce(tracker, &self.reduce, Vec::new()),
+                RemainingTracker::specify_one,
+                |_| false,
+            )
+            .expect("search cannot be interrupted");
+            solution.is_none().then_some(trace)
+        })
+    }
+
+    /// Check the givens before searching. A board whose givens repeat a value
+    /// or hold an excluded value has no solution, and the offending givens are
+    /// named. A board with no empty cells is otherwise its own solution if the
+    /// variants allow it. Returns None if the board needs to be searched.
+    fn check_givens(&self, board: &Board) -> Option<SolveOutcome> {
+        let mut conflicts = board.conflicts();
+        conflicts.extend(self.variants.conflicts(board));
+        let excluded: Vec<_> = self.exclusions.violations(board).collect();
+        if !conflicts.is_empty() || !excluded.is_empty() {
+            trace!("Board has invalid givens");
+            conflicts.sort_unstable();
+            conflicts.dedup();
+            return Some(SolveOutcome::Unsolveable(Unsolveable {
+                reason: None,
+                conflicts,
+                excluded,
+                certificate: None,
+            }));
+        }
+        if board.row_major().contains(&None) {
+            return None;
+        }
+        Some(
+            if self.variants.allows_branch(&RemainingTracker::new(board)) {
+                SolveOutcome::Solved(board.clone())
+            } else {
+                SolveOutcome::Unsolveable(Unsolveable {
+                    reason: None,
+                    conflicts: Vec::new(),
+                    excluded: Vec::new(),
+                    certificate: None,
+                })
+            },
+        )
+    }
+
+    /// Apply the deduction rules and the variant rules in turn until neither
+    /// makes progress.
+    fn reduce<D: DeductiveTracer>(
+        &self,
+        mut tracker: RemainingTracker,
+        mut tracer: D,
+    ) -> Option<RemainingTracker> {
+        loop {
+            tracker = deductive::reduce(tracker, &self.reduce, &mut tracer).0?;
+            match self.variants.propagate(&mut tracker) {
+                Ok(true) => {}
+                Ok(false) => return Some(tracker),
+                Err(()) => return None,
+            }
+        }
+    }
+
+    fn run_tracker(&self, tracker: RemainingTracker, budget: &mut Budget) -> SolveOutcome {
+        let (outcome, _) = self.run_tracker_with(tracker, budget, NopDeductiveTracer, || false);
+        outcome.expect("solve cannot be cancelled")
+    }
+
+    /// Solve the given board within the budget as `run_within` does, recording
+    /// every deduction to the tracer. `cancelled` is checked before each board
+    /// the solver reduces, and once it returns true the solve stops and the
+    /// outcome is None.
+    pub(crate) fn run_with<D: DeductiveTracer>(
+        &self,
+        board: &Board,
+        budget: &mut Budget,
+        tracer: D,
+        cancelled: impl FnMut() -> bool,
+    ) -> (Option<SolveOutcome>, D) {
+        if let Some(outcome) = self.check_givens(board) {
+            return (Some(outcome), tracer);
+        }
+        let tracker = RemainingTracker::with_exclusions(board, &self.exclusions);
+        self.run_tracker_with(tracker, budget, tracer, cancelled)
+    }
+
+    fn run_tracker_with<D: DeductiveTracer>(
+        &self,
+        tracker: RemainingTracker,
+        budget: &mut Budget,
+        mut tracer: D,
+        mut cancelled: impl FnMut() -> bool,
+    ) -> (Option<SolveOutcome>, D) {
+        if tracker.known_unsolveable() {
+            trace!("Board known unsolveable before solving");
+            let outcome = SolveOutcome::Unsolveable(Unsolveable {
+                reason: None,
+                conflicts: Vec::new(),
+                excluded: Vec::new(),
+                certificate: None,
+            });
+            return (Some(outcome), tracer);
+        }
+        // Remember why the initial board failed, if it fails without guessing.
+        let mut first = true;
+        let mut root_reason = None;
+        let mut was_cancelled = false;
+        let mut meter = budget.meter();
+        let result = Board::search::<NopTracer, _>(
+            tracker,
+            |tracker| {
+                let reduced = if first {
+                    first = false;
+                    let mut last = LastUnsolveable {
+                        reason: None,
+                        inner: &mut tracer,
+                    };
+                    let reduced = self.reduce(tracker, &mut last);
+                    root_reason = last.reason;
+                    reduced
+                } else {
+                    self.reduce(tracker, &mut tracer)
+                };
+                (reduced, NopDeductiveTracer)
+            },
+            |tracker| {
+                tracker
+                    .specify_one()
+                    .filter(|branch| self.variants.allows_branch(branch))
+            },
+            |depth| {
+                was_cancelled = cancelled();
+                was_cancelled || meter.exceeded(depth)
+            },
+        );
+        let exceeded = meter.finish();
+        let outcome = match result {
+            Ok((Some(solution), _)) => SolveOutcome::Solved(solution),
+            Ok((None, _)) => SolveOutcome::Unsolveable(Unsolveable {
+                reason: root_reason,
+                conflicts: Vec::new(),
+                excluded: Vec::new(),
+                certificate: None,
+            }),
+            Err(_) if was_cancelled => {
+                trace!("Solve cancelled");
+                return (None, tracer);
+            }
+            Err(interrupted) => SolveOutcome::Exhausted {
+                reason: ExhaustionKind::Budget(exceeded.expect("search stopped without a limit")),
+                partial: interrupted.partial,
+            },
+        };
+        (Some(outcome), tracer)
+    }
+}
+
+/// Deductive tracer which keeps the reason for the board being unsolveable,
+/// passing every deduction on to an inner tracer.
+struct LastUnsolveable<D> {
+    reason: Option<UnsolveableReason>,
+    inner: D,
+}
+
+impl<D: DeductiveTracer> DeductiveTracer for LastUnsolveable<D> {
+    fn deduce(&mut self, reason: DeductionReason, remaining: Remaining) {
+        if let DeductionReason::Unsolveable(reason) = &reason {
+            self.reason = Some(reason.clone());
+        }
+        self.inner.deduce(reason, remaining)
+    }
+
+    fn wants_pre_state(&self) -> bool {
+        self.inner.wants_pre_state()
+    }
+
+    fn fail(&mut self, reason: UnsolveableReason, before: Option<Remaining>, remaining: Remaining) {
+        self.reason = Some(reason.clone());
+        self.inner.fail(reason, before, remaining)
+    }
+}
+
+/// Result of running the solver on a board.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum SolveOutcome {
+    /// The board was solved.
+    Solved(Board),
+    /// The board was proven to have no solution.
+    Unsolveable(Unsolveable),
+    /// The solver gave up before finding a solution or proving there is none.
+    Exhausted {
+        /// Why the solver gave up.
+        reason: ExhaustionKind,
+        /// The board after the initial deductions, if they finished.
+        partial: Option<Remaining>,
+    },
+}
+
+impl SolveOutcome {
+    /// Returns true if the board was solved.
+    #[must_use]
+    pub fn is_solved(&self) -> bool {
+        matches!(self, SolveOutcome::Solved(_))
+    }
+
+    /// Get the solution, if the board was solved.
+    #[must_use]
+    pub fn solution(&self) -> Option<&Board> {
+        match self {
+            SolveOutcome::Solved(solution) => Some(solution),
+            _ => None,
+        }
+    }
+
+    /// Convert into the solution, if the board was solved.
+    #[must_use]
+    pub fn into_solution(self) -> Option<Board> {
+        match self {
+            SolveOutcome::Solved(solution) => Some(solution),
+            _ => None,
+        }
+    }
+}
+
+impl From<SolveOutcome> for Option<Board> {
+    fn from(outcome: SolveOutcome) -> Self {
+        outcome.into_solution()
+    }
+}
+
+impl fmt::Display for SolveOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolveOutcome::Solved(_) => f.write_str("solved"),
+            SolveOutcome::Unsolveable(_) => f.write_str("no solution"),
+            SolveOutcome::Exhausted { reason, .. } => write!(f, "gave up: {}", reason),
+        }
+    }
+}
+
+/// Result of `Solver::solve_in_place`. This is a plain enum with fixed values
+/// so it can be passed across an FFI boundary as a byte.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[repr(u8)]
+pub enum SolveCode {
+    /// The board was solved and the solution written over the input.
+    Solved = 0,
+    /// The board was proven to have no solution.
+    Unsolveable = 1,
+    /// A cell was greater than 9, a row, column, or sector repeated a value, or
+    /// a given broke the solver's variants or exclusions.
+    Invalid = 2,
+    /// A limit of the solver's budget was reached.
+    BudgetExceeded = 3,
+    /// The solver's verdict failed the checks of `Solver::high_assurance`.
+    VerificationFailed = 4,
+}
+
+/// Details of a board proven to have no solution.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Unsolveable {
+    /// Why the board has no solution, if deduction alone proved it. None if
+    /// the board was only proven unsolveable by trying every guess.
+    pub reason: Option<UnsolveableReason>,
+    /// Pairs of givens which repeat a value in a row, column, or sector, as
+    /// from `Board::conflicts`. Empty unless the input itself is invalid, which
+    /// tells a wrong board apart from one which has no solution.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub conflicts: Vec<(Coord, Coord, Val)>,
+    /// Givens which hold a value the solver's exclusions forbid from their
+    /// cell, as from `Exclusions::violations`. Like `conflicts`, empty unless
+    /// the input itself is invalid.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub excluded: Vec<(Coord, Val)>,
+    /// Proof that the board has no solution, which can be checked with
+    /// `verify_certificate`. Only made by a solver with `high_assurance` set.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub certificate: Option<UnsolveableCertificate>,
+}
+
+/// Reasons the solver can give up without an answer.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum ExhaustionKind {
+    /// A limit of the solver's budget was reached.
+    Budget(BudgetExceeded),
+    /// The solver reached a verdict, but it failed the checks of
+    /// `Solver::high_assurance`, so it was dropped.
+    VerificationFailed,
+}
+
+impl fmt::Display for ExhaustionKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExhaustionKind::Budget(exceeded) => exceeded.fmt(f),
+            ExhaustionKind::VerificationFailed => f.write_str("verdict failed verification"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::collections::indexed::{FixedSizeIndex, IndexMap};
+    use crate::fixtures::{contradictory, inkala, puzzle1};
+    use crate::solve::LimitKind;
+    use crate::variant::ExtraHouse;
+    use crate::{AvailSet, Col, Coord, Row, Val, Zone};
+
+    #[test]
+    fn run_matches_traced() {
+        crate::setup();
+
+        for board in [puzzle1(), inkala(), Board::new()] {
+            let (expected, _) = board.solve_traced::<NopTracer>();
+            let outcome = Solver::new().run(&board);
+            assert!(outcome.is_solved());
+            assert_eq!(outcome.solution(), expected.as_ref());
+            assert_eq!(board.solve(), expected);
+            assert_eq!(Option::<Board>::from(outcome), expected);
+        }
+    }
+
+    #[test]
+    fn filled_boards() {
+        crate::setup();
+
+        let solved = puzzle1().solve().unwrap();
+        assert!(solved.is_solved());
+        assert!(!solved.known_unsolveable());
+        assert_eq!(
+            Solver::new().run(&solved),
+            SolveOutcome::Solved(solved.clone())
+        );
+        assert_eq!(solved.solve(), Some(solved.clone()));
+        let mut cells = solved.cells_u8();
+        assert_eq!(Solver::new().solve_in_place(&mut cells), SolveCode::Solved);
+        assert_eq!(cells, solved.cells_u8());
+
+        // Swapping cells of a row from different sectors repeats both values in
+        // their columns and sectors.
+        let mut invalid = solved.clone();
+        let (a, b) = (
+            Coord::new(Row::new(0), Col::new(0)),
+            Coord::new(Row::new(0), Col::new(4)),
+        );
+        invalid[a] = solved[b];
+        invalid[b] = solved[a];
+        let conflicts = invalid.conflicts();
+        assert_eq!(conflicts.len(), 4);
+        assert!(conflicts
+            .iter()
+            .all(|&(x, y, _)| [x, y].contains(&a) ^ [x, y].contains(&b)));
+        assert!(!invalid.is_solved());
+        assert!(invalid.known_unsolveable());
+        assert_eq!(
+            Solver::new().run(&invalid),
+            SolveOutcome::Unsolveable(Unsolveable {
+                reason: None,
+                conflicts,
+                excluded: Vec::new(),
+                certificate: None,
+            })
+        );
+        assert_eq!(invalid.solve(), None);
+        let mut cells = invalid.cells_u8();
+        assert_eq!(Solver::new().solve_in_place(&mut cells), SolveCode::Invalid);
+
+        // A given which the exclusions forbid makes the board invalid, whether
+        // or not the board is full.
+        let solver = Solver {
+            exclusions: vec![(a, AvailSet::only(solved[a].unwrap()))].into(),
+            ..Default::default()
+        };
+        let mut partial = solved.clone();
+        partial[b] = None;
+        for board in [&solved, &partial] {
+            assert_eq!(
+                solver.run(board),
+                SolveOutcome::Unsolveable(Unsolveable {
+                    reason: None,
+                    conflicts: Vec::new(),
+                    excluded: vec![(a, solved[a].unwrap())],
+                    certificate: None,
+                })
+            );
+            let mut cells = board.cells_u8();
+            assert_eq!(solver.solve_in_place(&mut cells), SolveCode::Invalid);
+        }
+        let assured = Solver {
+            high_assurance: true,
+            ..solver.clone()
+        };
+        match assured.run(&partial) {
+            SolveOutcome::Unsolveable(unsolveable) => {
+                assert_eq!(unsolveable.excluded, [(a, solved[a].unwrap())]);
+                assert!(unsolveable.certificate.is_some());
+            }
+            other => panic!("expected no solution, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn solve_in_place() {
+        crate::setup();
+
+        let solver = Solver::new();
+        for board in [puzzle1(), inkala(), Board::new()] {
+            let mut cells = board.cells_u8();
+            assert_eq!(solver.solve_in_place(&mut cells), SolveCode::Solved);
+            assert_eq!(cells, board.solve().unwrap().cells_u8());
+            // Solving the same buffer again leaves the solution as it is.
+            let solved = cells;
+            assert_eq!(solver.solve_in_place(&mut cells), SolveCode::Solved);
+            assert_eq!(cells, solved);
+        }
+
+        let unchanged = |cells: [u8; Board::SIZE], expected| {
+            let mut copy = cells;
+            assert_eq!(solver.solve_in_place(&mut copy), expected);
+            assert_eq!(copy, cells);
+        };
+        let mut unsolveable = puzzle1().cells_u8();
+        unsolveable[40] = 9;
+        unchanged(unsolveable, SolveCode::Unsolveable);
+        let mut out_of_range = puzzle1().cells_u8();
+        out_of_range[0] = 10;
+        unchanged(out_of_range, SolveCode::Invalid);
+        let mut repeated = puzzle1().cells_u8();
+        repeated[0] = 1;
+        unchanged(repeated, SolveCode::Invalid);
+
+        // Givens which repeat a value on an extra house are invalid too.
+        let diagonal = ExtraHouse::new((0..9).map(|i| Coord::new(Row::new(i), Col::new(i))));
+        let variant = Solver {
+            variants: Variants::new().with_house(diagonal),
+            ..Solver::new()
+        };
+        let mut cells = [0; Board::SIZE];
+        cells[0] = 5;
+        cells[Board::SIZE - 1] = 5;
+        let mut copy = cells;
+        assert_eq!(variant.solve_in_place(&mut copy), SolveCode::Invalid);
+        assert_eq!(copy, cells);
+
+        // With high assurance, the verdicts match those of `run`, which checks
+        // each of them.
+        let assured = Solver {
+            high_assurance: true,
+            ..Solver::new()
+        };
+        let code = |outcome| match outcome {
+            SolveOutcome::Solved(_) => SolveCode::Solved,
+            SolveOutcome::Unsolveable(Unsolveable { conflicts, .. }) if !conflicts.is_empty() => {
+                SolveCode::Invalid
+            }
+            SolveOutcome::Unsolveable(_) => SolveCode::Unsolveable,
+            SolveOutcome::Exhausted { .. } => SolveCode::BudgetExceeded,
+        };
+        for cells in [
+            puzzle1().cells_u8(),
+            inkala().cells_u8(),
+            unsolveable,
+            repeated,
+        ] {
+            let board =
+                Board::try_from(cells.map(|cell| Val::try_from(cell).ok()).to_vec()).unwrap();
+            let mut copy = cells;
+            assert_eq!(assured.solve_in_place(&mut copy), code(assured.run(&board)));
+        }
+
+        let mut cells = inkala().cells_u8();
+        let before = cells;
+        let mut budget = Budget::new().with_steps(1);
+        assert_eq!(
+            solver.solve_in_place_within(&mut cells, &mut budget),
+            SolveCode::BudgetExceeded
+        );
+        assert_eq!(cells, before);
+        assert_eq!(SolveCode::BudgetExceeded as u8, 3);
+    }
+
+    #[test]
+    fn run_unsolveable() {
+        crate::setup();
+
+        let board = contradictory();
+        match Solver::new().run(&board) {
+            SolveOutcome::Unsolveable(Unsolveable {
+                reason: Some(_), ..
+            }) => {}
+            other => panic!("expected unsolveable with reason, got {:?}", other),
+        }
+        assert_eq!(board.solve(), None);
+    }
+
+    #[test]
+    fn run_exclusions() {
+        crate::setup();
+
+        let board = puzzle1();
+        let solution = board.solve().unwrap();
+        let pos = Coord::new(Row::new(0), Col::new(0));
+        let mut solver = Solver::new();
+        solver
+            .exclusions
+            .exclude(pos, AvailSet::only(solution[pos].unwrap()));
+        let outcome = solver.run(&board);
+        assert!(matches!(outcome, SolveOutcome::Unsolveable(_)));
+        assert_eq!(outcome.to_string(), "no solution");
+        assert_eq!(board.solve_with_exclusions(&solver.exclusions), None);
+
+        solver.exclusions = Exclusions::new();
+        solver.exclusions.exclude(pos, AvailSet::only(Val::new(9)));
+        assert_eq!(solver.run(&board), SolveOutcome::Solved(solution));
+    }
+
+    #[test]
+    fn run_time_limit() {
+        crate::setup();
+
+        let board = inkala();
+        let solver = Solver {
+            budget: Budget::new().with_time(Duration::ZERO),
+            ..Default::default()
+        };
+        let outcome = solver.run(&board);
+        assert_eq!(
+            outcome,
+            SolveOutcome::Exhausted {
+                reason: ExhaustionKind::Budget(BudgetExceeded {
+                    which: LimitKind::WallClock,
+                    consumed: 0,
+                    limit: 0,
+                }),
+                partial: None,
+            }
+        );
+        assert_eq!(
+            outcome.to_string(),
+            "gave up: wall-clock limit of 0 ms exceeded"
+        );
+        assert_eq!(outcome.into_solution(), None);
+        // The solver's own budget is left untouched.
+        assert_eq!(solver.budget.consumed().steps, 0);
+    }
+
+    #[test]
+    fn high_assurance_rejects_bad_verdicts() {
+        crate::setup();
+
+        let solver = Solver {
+            high_assurance: true,
+            ..Default::default()
+        };
+        let failed = SolveOutcome::Exhausted {
+            reason: ExhaustionKind::VerificationFailed,
+            partial: None,
+        };
+
+        // A solution which breaks the board's givens.
+        let board = puzzle1();
+        let solution = board.solve().unwrap();
+        let wrong = solution.map_values(|val| Val::from_idx((val.idx() + 1) % 9));
+        let outcome = solver.assure(&board, SolveOutcome::Solved(wrong));
+        assert_eq!(outcome, failed);
+        assert_eq!(outcome.to_string(), "gave up: verdict failed verification");
+        assert_eq!(
+            solver.assure(&board, SolveOutcome::Solved(solution.clone())),
+            SolveOutcome::Solved(solution)
+        );
+
+        // No solution claimed for a board which has one, so no certificate can
+        // be made.
+        let unsolveable = Unsolveable {
+            reason: None,
+            conflicts: Vec::new(),
+            excluded: Vec::new(),
+            certificate: None,
+        };
+        assert_eq!(
+            solver.assure(&board, SolveOutcome::Unsolveable(unsolveable.clone())),
+            failed
+        );
+        assert!(matches!(
+            solver.assure(&contradictory(), SolveOutcome::Unsolveable(unsolveable)),
+            SolveOutcome::Unsolveable(Unsolveable {
+                certificate: Some(_),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn run_candidates() {
+        crate::setup();
+
+        let board = puzzle1();
+        let solution = board.solve().unwrap();
+        let mut candidates: Remaining = IndexMap::with_value(AvailSet::all()).into();
+        for coord in Coord::all() {
+            if let Some(val) = board[coord] {
+                candidates[coord] = AvailSet::only(val);
+            }
+        }
+        let closed = candidates.close().unwrap();
+        let outcome = Solver::new().run_candidates(&closed);
+        assert_eq!(outcome, SolveOutcome::Solved(solution.clone()));
+
+        // Pencil marks which rule out the solution leave nothing to find.
+        let pos = Coord::new(Row::new(0), Col::new(0));
+        candidates[pos] -= solution[pos].unwrap();
+        let closed = candidates.close().unwrap();
+        assert!(matches!(
+            Solver::new().run_candidates(&closed),
+            SolveOutcome::Unsolveable(_)
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize() {
+        let outcome = SolveOutcome::Unsolveable(Unsolveable {
+            reason: Some(UnsolveableReason::Empty {
+                pos: Coord::new(Row::new(1), Col::new(2)),
+            }),
+            conflicts: Vec::new(),
+            excluded: Vec::new(),
+            certificate: None,
+        });
+        let ser = serde_json::to_string(&outcome).unwrap();
+        assert_eq!(
+            ser,
+            r#"{"unsolveable":{"reason":{"reason":"empty","pos":{"row":1,"col":2}}}}"#
+        );
+        let roundtrip: SolveOutcome = serde_json::from_str(&ser).unwrap();
+        assert_eq!(roundtrip, outcome);
+
+        let outcome = SolveOutcome::Exhausted {
+            reason: ExhaustionKind::Budget(BudgetExceeded {
+                which: LimitKind::Steps,
+                consumed: 10,
+                limit: 10,
+            }),
+            partial: None,
+        };
+        let ser = serde_json::to_string(&outcome).unwrap();
+        assert_eq!(
+            ser,
+            r#"{"exhausted":{"reason":{"budget":{"which":"steps","consumed":10,"limit":10}},"partial":null}}"#
+        );
+    }
+}
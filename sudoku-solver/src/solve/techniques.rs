@@ -0,0 +1,301 @@
+//! Detection of solving techniques over a candidate state, for building custom
+//! solvers on top of the engine.
+use std::ops::Index;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::trace::Remaining;
+use crate::{AvailSet, Col, Coord, House, Row, Val, Zone};
+
+/// A fish pattern for a single value: the value's candidates in the base
+/// houses all lie within the same number of cover houses, so it can be
+/// eliminated from the rest of the cover houses.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FishPattern {
+    /// The value the pattern applies to.
+    pub value: Val,
+    /// The rows or columns holding the pattern, in order.
+    pub base: Vec<House>,
+    /// The columns or rows covering every candidate in the base, in order.
+    pub cover: Vec<House>,
+    /// Cells of the cover houses outside the base which can no longer hold the
+    /// value, in row-major order.
+    pub eliminations: Vec<Coord>,
+}
+
+/// Find all fish of the given size for the given value in the candidate state.
+/// Size 2 finds X-Wings, size 3 Swordfish, and size 4 Jellyfish. Both row-based
+/// and column-based fish are found, rows first. Only patterns which eliminate
+/// at least one candidate are returned.
+#[must_use]
+pub fn find_fish(state: &Remaining, value: Val, size: usize) -> Vec<FishPattern> {
+    fish(state, value, size)
+}
+
+/// Find fish as `find_fish` does, in any candidate state which can be indexed
+/// by cell.
+pub(crate) fn fish<S>(state: &S, value: Val, size: usize) -> Vec<FishPattern>
+where
+    S: Index<Coord, Output = AvailSet> + ?Sized,
+{
+    let mut patterns = Vec::new();
+    if size == 0 || size >= Row::SIZE {
+        return patterns;
+    }
+    find_oriented(state, value, size, true, &mut patterns);
+    find_oriented(state, value, size, false, &mut patterns);
+    patterns
+}
+
+/// How two candidates for the same value in a house constrain each other.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum LinkStrength {
+    /// The two cells are the only ones left in the house which allow the
+    /// value, so one of them must hold it. The cells are a conjugate pair.
+    Strong,
+    /// Other cells in the house also allow the value, so the two cells only
+    /// exclude each other.
+    Weak,
+}
+
+/// A link between two cells which allow the same value and share a house.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Link {
+    /// The value both cells allow.
+    pub value: Val,
+    /// The house the cells share.
+    pub house: House,
+    /// The first cell, which sorts before `b`.
+    pub a: Coord,
+    /// The second cell.
+    pub b: Coord,
+    /// Whether the link is strong or weak.
+    pub strength: LinkStrength,
+}
+
+/// Find every link between candidates for the value in the candidate state.
+/// Cells sharing more than one house are linked once per house. Links are
+/// ordered by house, rows first, then by cells in row-major order.
+#[must_use]
+pub fn find_links(state: &Remaining, value: Val) -> Vec<Link> {
+    let mut links = Vec::new();
+    for house in House::all() {
+        let cells: Vec<Coord> = house
+            .coords()
+            .filter(|&pos| state[pos].contains(value))
+            .collect();
+        let strength = if cells.len() == 2 {
+            LinkStrength::Strong
+        } else {
+            LinkStrength::Weak
+        };
+        for_each_combination(&(0..cells.len()).collect::<Vec<_>>(), 2, &mut |pair| {
+            links.push(Link {
+                value,
+                house,
+                a: cells[pair[0]],
+                b: cells[pair[1]],
+                strength,
+            });
+        });
+    }
+    links
+}
+
+/// Find the conjugate pairs for the value: pairs of cells which are the only
+/// two left in some house allowing it.
+#[must_use]
+pub fn conjugate_pairs(state: &Remaining, value: Val) -> Vec<Link> {
+    let mut links = find_links(state, value);
+    links.retain(|link| link.strength == LinkStrength::Strong);
+    links
+}
+
+/// Find fish with rows as the base if `by_rows`, otherwise with columns as the
+/// base.
+fn find_oriented<S>(
+    state: &S,
+    value: Val,
+    size: usize,
+    by_rows: bool,
+    patterns: &mut Vec<FishPattern>,
+) where
+    S: Index<Coord, Output = AvailSet> + ?Sized,
+{
+    let coord = |base: usize, cross: usize| {
+        if by_rows {
+            Coord::new(Row::new(base as u8), Col::new(cross as u8))
+        } else {
+            Coord::new(Row::new(cross as u8), Col::new(base as u8))
+        }
+    };
+    let house = |line: usize, base: bool| {
+        if by_rows == base {
+            House::Row(Row::new(line as u8))
+        } else {
+            House::Col(Col::new(line as u8))
+        }
+    };
+    // Bitmask of the positions of the value along each base line.
+    let masks: Vec<u16> = (0..Row::SIZE)
+        .map(|base| {
+            (0..Row::SIZE)
+                .filter(|&cross| state[coord(base, cross)].contains(value))
+                .fold(0, |mask, cross| mask | 1 << cross)
+        })
+        .collect();
+    let candidates: Vec<usize> = (0..Row::SIZE)
+        .filter(|&base| (2..=size).contains(&(masks[base].count_ones() as usize)))
+        .collect();
+
+    for_each_combination(&candidates, size, &mut |bases| {
+        let cover = bases.iter().fold(0, |mask, &base| mask | masks[base]);
+        if cover.count_ones() as usize != size {
+            return;
+        }
+        let covers: Vec<usize> = (0..Row::SIZE).filter(|&c| cover & 1 << c != 0).collect();
+        let mut eliminations: Vec<Coord> = (0..Row::SIZE)
+            .filter(|base| !bases.contains(base))
+            .flat_map(|base| covers.iter().map(move |&cross| (base, cross)))
+            .map(|(base, cross)| coord(base, cross))
+            .filter(|&pos| state[pos].contains(value))
+            .collect();
+        if eliminations.is_empty() {
+            return;
+        }
+        eliminations.sort();
+        patterns.push(FishPattern {
+            value,
+            base: bases.iter().map(|&line| house(line, true)).collect(),
+            cover: covers.iter().map(|&line| house(line, false)).collect(),
+            eliminations,
+        });
+    });
+}
+
+/// Call the function with every combination of `k` items from the slice, in
+/// lexicographic order.
+fn for_each_combination(items: &[usize], k: usize, f: &mut impl FnMut(&[usize])) {
+    fn recurse(items: &[usize], k: usize, chosen: &mut Vec<usize>, f: &mut impl FnMut(&[usize])) {
+        if chosen.len() == k {
+            f(chosen);
+            return;
+        }
+        for (i, &item) in items.iter().enumerate() {
+            if items.len() - i < k - chosen.len() {
+                break;
+            }
+            chosen.push(item);
+            recurse(&items[i + 1..], k, chosen, f);
+            chosen.pop();
+        }
+    }
+    recurse(items, k, &mut Vec::with_capacity(k), f);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::indexed::IndexMap;
+
+    #[test]
+    fn find_x_wing() {
+        crate::setup();
+
+        let five = Val::new(5);
+        let mut state: Remaining = IndexMap::with_value(AvailSet::all()).into();
+        // Rows 1 and 6 only allow 5 in columns 2 and 7.
+        for row in [1, 6] {
+            for coord in Row::new(row).coords() {
+                if ![2, 7].contains(&coord.col().inner()) {
+                    state[coord] -= five;
+                }
+            }
+        }
+
+        let patterns = find_fish(&state, five, 2);
+        assert_eq!(patterns.len(), 1);
+        let fish = &patterns[0];
+        assert_eq!(fish.value, five);
+        assert_eq!(
+            fish.base,
+            vec![House::Row(Row::new(1)), House::Row(Row::new(6))]
+        );
+        assert_eq!(
+            fish.cover,
+            vec![House::Col(Col::new(2)), House::Col(Col::new(7))]
+        );
+        let expected: Vec<_> = Coord::all()
+            .filter(|c| [2, 7].contains(&c.col().inner()))
+            .filter(|c| ![1, 6].contains(&c.row().inner()))
+            .collect();
+        assert_eq!(fish.eliminations, expected);
+
+        // Other values and sizes find nothing.
+        assert!(find_fish(&state, Val::new(4), 2).is_empty());
+        assert!(find_fish(&state, five, 3).is_empty());
+        assert!(find_fish(&state, five, 0).is_empty());
+    }
+
+    #[test]
+    fn links() {
+        crate::setup();
+
+        let three = Val::new(3);
+        let mut state: Remaining = IndexMap::with_value(AvailSet::all()).into();
+        for pos in Coord::all() {
+            state[pos] -= three;
+        }
+        // 3 is left in the corners of a rectangle across two sectors, and a
+        // third cell in row 0.
+        let cells = [(0, 0), (0, 4), (0, 8), (5, 0), (5, 4)];
+        for (row, col) in cells {
+            state[Coord::new(Row::new(row), Col::new(col))] |= three;
+        }
+        let pos = |row, col| Coord::new(Row::new(row), Col::new(col));
+
+        let links = find_links(&state, three);
+        let summary: Vec<_> = links
+            .iter()
+            .map(|link| (link.house, link.a, link.b, link.strength))
+            .collect();
+        use LinkStrength::*;
+        assert_eq!(
+            summary,
+            [
+                (House::Row(Row::new(0)), pos(0, 0), pos(0, 4), Weak),
+                (House::Row(Row::new(0)), pos(0, 0), pos(0, 8), Weak),
+                (House::Row(Row::new(0)), pos(0, 4), pos(0, 8), Weak),
+                (House::Row(Row::new(5)), pos(5, 0), pos(5, 4), Strong),
+                (House::Col(Col::new(0)), pos(0, 0), pos(5, 0), Strong),
+                (House::Col(Col::new(4)), pos(0, 4), pos(5, 4), Strong),
+            ]
+        );
+        assert!(links.iter().all(|link| link.value == three));
+        assert_eq!(conjugate_pairs(&state, three), links[3..]);
+        assert!(find_links(&state, Val::new(4)).len() > links.len());
+    }
+
+    #[test]
+    fn combinations() {
+        let mut result = Vec::new();
+        for_each_combination(&[1, 2, 3, 4], 2, &mut |c| result.push(c.to_vec()));
+        let expected = vec![
+            vec![1, 2],
+            vec![1, 3],
+            vec![1, 4],
+            vec![2, 3],
+            vec![2, 4],
+            vec![3, 4],
+        ];
+        assert_eq!(result, expected);
+    }
+}
@@ -0,0 +1,270 @@
+//! Provenance metadata carried alongside a board.
+//!
+//! Applications often need to know where a puzzle came from and what has been
+//! done to it since. Rather than adding that to `Board`, a `Tagged` value pairs
+//! any value with a `Meta`, and operations on a `Tagged` value record
+//! themselves in the meta's log so the history of a puzzle can be audited.
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::Board;
+
+/// One operation applied to a tagged value, with the parameters it was run
+/// with, such as a seed or the cell which was changed.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Operation {
+    /// Name of the operation.
+    pub name: String,
+    /// Parameters of the operation, by name.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    pub params: BTreeMap<String, String>,
+}
+
+impl Operation {
+    /// Create an operation with no parameters.
+    pub fn new(name: impl Into<String>) -> Self {
+        Operation {
+            name: name.into(),
+            params: BTreeMap::new(),
+        }
+    }
+
+    /// Add a parameter to the operation.
+    #[must_use]
+    pub fn with_param(mut self, key: impl Into<String>, value: impl fmt::Display) -> Self {
+        self.params.insert(key.into(), value.to_string());
+        self
+    }
+}
+
+/// Metadata about where a value came from and what has been done to it.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+pub struct Meta {
+    /// Where the puzzle was taken from, such as a book or website.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub source: Option<String>,
+    /// Who wrote the puzzle.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub author: Option<String>,
+    /// When the puzzle was created, in whatever format the source used.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub created: Option<String>,
+    /// License the puzzle is distributed under.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub license: Option<String>,
+    /// Any other fields, by name.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "BTreeMap::is_empty"))]
+    pub fields: BTreeMap<String, String>,
+    /// Operations applied since the value was tagged, oldest first.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    pub log: Vec<Operation>,
+}
+
+impl Meta {
+    /// Get one of the other fields.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields.get(key).map(String::as_str)
+    }
+
+    /// Set one of the other fields, returning the previous value.
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) -> Option<String> {
+        self.fields.insert(key.into(), value.into())
+    }
+
+    /// Add an operation to the end of the log.
+    pub fn record(&mut self, op: Operation) {
+        self.log.push(op);
+    }
+}
+
+/// A value along with metadata about where it came from.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Tagged<T> {
+    /// The value.
+    pub value: T,
+    /// Metadata about the value.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub meta: Meta,
+}
+
+impl<T> Tagged<T> {
+    /// Tag a value with empty metadata.
+    pub fn new(value: T) -> Self {
+        Self::with_meta(value, Meta::default())
+    }
+
+    /// Tag a value with the given metadata.
+    pub fn with_meta(value: T, meta: Meta) -> Self {
+        Tagged { value, meta }
+    }
+
+    /// Split into the value and its metadata.
+    pub fn into_parts(self) -> (T, Meta) {
+        (self.value, self.meta)
+    }
+
+    /// Transform the value, keeping the metadata unchanged.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Tagged<U> {
+        Tagged {
+            value: f(self.value),
+            meta: self.meta,
+        }
+    }
+
+    /// Transform the value with a function which may fail, keeping the metadata
+    /// if it succeeds.
+    pub fn try_map<U, E>(self, f: impl FnOnce(T) -> Result<U, E>) -> Result<Tagged<U>, E> {
+        Ok(Tagged {
+            value: f(self.value)?,
+            meta: self.meta,
+        })
+    }
+
+    /// Transform the value and record the operation in the log.
+    pub fn apply<U>(mut self, op: Operation, f: impl FnOnce(T) -> U) -> Tagged<U> {
+        self.meta.record(op);
+        self.map(f)
+    }
+
+    /// Transform the value with a function which may fail, recording the
+    /// operation in the log if it succeeds.
+    pub fn try_apply<U, E>(
+        self,
+        op: Operation,
+        f: impl FnOnce(T) -> Result<U, E>,
+    ) -> Result<Tagged<U>, E> {
+        let mut tagged = self.try_map(f)?;
+        tagged.meta.record(op);
+        Ok(tagged)
+    }
+}
+
+impl<T> From<T> for Tagged<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl Tagged<Board> {
+    /// Fill in forced cells as `Board::fill_forced` does, recording how many
+    /// cells were filled.
+    #[must_use]
+    pub fn fill_forced(self) -> Self {
+        let (board, filled) = self.value.fill_forced();
+        let mut meta = self.meta;
+        meta.record(Operation::new("fill_forced").with_param("filled", filled));
+        Tagged::with_meta(board, meta)
+    }
+
+    /// Solve the board as `Board::solve` does, keeping the metadata with the
+    /// solution.
+    #[must_use]
+    pub fn solve(self) -> Option<Self> {
+        self.try_apply(Operation::new("solve"), |board| board.solve().ok_or(()))
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::puzzle1;
+    use crate::{Col, Coord, Row};
+
+    fn tagged_puzzle() -> Tagged<Board> {
+        let board = puzzle1();
+        let mut meta = Meta {
+            source: Some("test suite".into()),
+            author: Some("nobody".into()),
+            license: Some("CC0".into()),
+            ..Default::default()
+        };
+        meta.insert("difficulty", "easy");
+        Tagged::with_meta(board, meta)
+    }
+
+    #[test]
+    fn pipeline_logs_operations() {
+        crate::setup();
+
+        let original = tagged_puzzle();
+        let solution = original.value.solve().unwrap();
+        let at = Coord::new(Row::new(0), Col::new(0));
+        let val = solution[at].unwrap();
+        let placed = original.clone().apply(
+            Operation::new("place")
+                .with_param("coord", at)
+                .with_param("val", val.val()),
+            |mut board| {
+                board[at] = Some(val);
+                board
+            },
+        );
+        let given = placed.value.row_major().iter().flatten().count();
+        let forced = placed.fill_forced();
+        let solved = forced.clone().solve().unwrap();
+
+        assert_eq!(solved.value, solution);
+        assert_eq!(solved.meta.source, original.meta.source);
+        assert_eq!(solved.meta.get("difficulty"), Some("easy"));
+        let names: Vec<_> = solved.meta.log.iter().map(|op| op.name.as_str()).collect();
+        assert_eq!(names, ["place", "fill_forced", "solve"]);
+        assert_eq!(solved.meta.log[0].params["coord"], at.to_string());
+        assert_eq!(solved.meta.log[0].params["val"], val.val().to_string());
+        let filled = forced.value.row_major().iter().flatten().count() - given;
+        assert_eq!(solved.meta.log[1].params["filled"], filled.to_string());
+        assert!(solved.meta.log[2].params.is_empty());
+    }
+
+    #[test]
+    fn map_keeps_meta() {
+        let tagged = tagged_puzzle();
+        let meta = tagged.meta.clone();
+        let count = tagged
+            .clone()
+            .map(|board| board.row_major().iter().flatten().count());
+        assert_eq!(count.meta, meta);
+
+        let failed: Result<Tagged<Board>, ()> = tagged
+            .clone()
+            .try_apply(Operation::new("fail"), |_| Err(()));
+        assert!(failed.is_err());
+        let ok = tagged.try_map(Ok::<_, ()>).unwrap();
+        assert_eq!(ok.meta, meta);
+
+        let unsolveable = Tagged::new(Board::new()).apply(Operation::new("break"), |mut board| {
+            board[Coord::new(Row::new(0), Col::new(0))] = Some(crate::Val::new(1));
+            board[Coord::new(Row::new(0), Col::new(1))] = Some(crate::Val::new(1));
+            board
+        });
+        assert!(unsolveable.solve().is_none());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize() {
+        let tagged = tagged_puzzle().fill_forced();
+        let ser = serde_json::to_string(&tagged).unwrap();
+        assert!(ser.contains(r#""source":"test suite""#));
+        assert!(!ser.contains("created"));
+        assert!(ser.contains(r#""log":[{"name":"fill_forced","params":{"filled":"#));
+        assert_eq!(serde_json::from_str::<Tagged<Board>>(&ser).unwrap(), tagged);
+
+        // Metadata is optional when reading.
+        let bare = format!(
+            r#"{{"value":{}}}"#,
+            serde_json::to_string(&tagged.value).unwrap()
+        );
+        let de: Tagged<Board> = serde_json::from_str(&bare).unwrap();
+        assert_eq!(de.meta, Meta::default());
+    }
+}
@@ -0,0 +1,574 @@
+//! Helpers for writing boards in tests as text art, for this crate and for
+//! crates which depend on it. Enabled with the `testkit` feature.
+//!
+//! The art has 11 lines: three groups of three rows separated by
+//! `---+---+---`. Each row has three groups of three cells separated by `|`,
+//! where a cell is a digit from 1 to 9, or a space or `.` if it is empty.
+//!
+//! ```text
+//!    |1  |
+//!    | 58|6 1
+//! 8 1|36 | 9
+//! ---+---+---
+//! 5  |   |4 3
+//!   3|6 1|8
+//! 6 4|   |  7
+//! ---+---+---
+//!  3 | 84|5 6
+//! 1 5|72 |
+//!    |  3|
+//! ```
+//!
+//! Blank lines before and after the art are ignored, and all lines may be
+//! indented by the same amount as the separator lines. Rows may leave off
+//! trailing empty cells, since editors tend to strip trailing spaces.
+use std::fmt::{self, Write};
+
+use crate::analysis::Analysis;
+use crate::{Board, Col, Coord, Row, Val, Zone};
+
+/// The line separating groups of rows.
+const SEPARATOR: &str = "---+---+---";
+/// Number of characters in a full row, including separators.
+const ROW_LEN: usize = SEPARATOR.len();
+/// Number of differing cells listed by coordinate in a diff message.
+const LISTED_DIFFS: usize = 5;
+
+/// Build a board from text art. Panics with the line and column of the first
+/// problem if the art is malformed. Lines and columns are counted from 1,
+/// with lines counted from the start of the string and columns from the start
+/// of the art after any indentation.
+pub fn board_from_art(art: &str) -> Board {
+    let lines: Vec<(usize, &str)> = art
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .skip_while(|(_, line)| line.trim().is_empty())
+        .collect();
+    let end = lines
+        .iter()
+        .rposition(|(_, line)| !line.trim().is_empty())
+        .map_or(0, |last| last + 1);
+    let lines = &lines[..end];
+    let indent = lines
+        .iter()
+        .find_map(|(_, line)| {
+            let trimmed = line.trim_start();
+            trimmed
+                .starts_with(SEPARATOR)
+                .then_some(&line[..line.len() - trimmed.len()])
+        })
+        .unwrap_or("");
+    if lines.len() != 11 {
+        let line = lines.first().map_or(1, |&(line, _)| line);
+        fail(
+            line,
+            1,
+            format_args!("expected 11 lines, found {}", lines.len()),
+        );
+    }
+
+    let mut board = Board::new();
+    let mut rows = (0..Col::HEIGHT).map(Row::new);
+    for (i, &(line, text)) in lines.iter().enumerate() {
+        let text = text
+            .strip_prefix(indent)
+            .unwrap_or_else(|| fail(line, 1, format_args!("expected indent {:?}", indent)));
+        if i % 4 == 3 {
+            if text.trim_end() != SEPARATOR {
+                fail(line, 1, format_args!("expected {:?}", SEPARATOR));
+            }
+            continue;
+        }
+        if text.len() > ROW_LEN {
+            fail(line, ROW_LEN + 1, "row is too long");
+        }
+        let row = rows.next().unwrap();
+        let mut cols = (0..Row::WIDTH).map(Col::new);
+        for (idx, ch) in format!("{:width$}", text, width = ROW_LEN)
+            .chars()
+            .enumerate()
+        {
+            let col = idx + 1;
+            if idx % 4 == 3 {
+                if ch != '|' {
+                    fail(line, col, format_args!("expected '|', found {:?}", ch));
+                }
+                continue;
+            }
+            let pos = Coord::new(row, cols.next().unwrap());
+            board[pos] = match ch {
+                '1'..='9' => Some(Val::new(ch as u8 - b'0')),
+                ' ' | '.' => None,
+                _ => fail(line, col, format_args!("unsupported cell {:?}", ch)),
+            };
+        }
+    }
+    board
+}
+
+/// Build a puzzle and its solution from text art, checking that the solution
+/// is solved and agrees with every given in the puzzle, so fixtures cannot
+/// silently rot.
+#[must_use]
+pub fn solution_pair_from_art(puzzle: &str, solution: &str) -> (Board, Board) {
+    let puzzle = board_from_art(puzzle);
+    let solution = board_from_art(solution);
+    assert!(solution.is_solved(), "solution art is not a solved board");
+    if let Some(pos) =
+        Coord::all().find(|&pos| puzzle[pos].is_some() && puzzle[pos] != solution[pos])
+    {
+        panic!(
+            "solution does not solve the puzzle: {} has {} in the puzzle but {} in the solution",
+            pos,
+            puzzle[pos].unwrap().val(),
+            solution[pos].unwrap().val(),
+        );
+    }
+    (puzzle, solution)
+}
+
+/// Render a board as text art, with `.` for empty cells. The output is
+/// accepted by `board_from_art`.
+#[must_use]
+pub fn board_to_art(board: &Board) -> String {
+    art_lines(|pos| cell_char(board[pos])).join("\n")
+}
+
+/// Describe how two boards differ, for assertion failures. The boards are
+/// drawn side by side with a third grid marking each differing cell with `*`,
+/// followed by the first few differing cells in `r#c#` notation.
+#[must_use]
+pub fn board_diff_message(actual: &Board, expected: &Board) -> String {
+    let diffs: Vec<Coord> = Coord::all()
+        .filter(|&pos| actual[pos] != expected[pos])
+        .collect();
+    let mut msg = format!(
+        "boards differ in {} cell{}\n",
+        diffs.len(),
+        if diffs.len() == 1 { "" } else { "s" }
+    );
+    let grids = [
+        art_lines(|pos| cell_char(actual[pos])),
+        art_lines(|pos| cell_char(expected[pos])),
+        art_lines(|pos| {
+            if actual[pos] != expected[pos] {
+                '*'
+            } else {
+                ' '
+            }
+        }),
+    ];
+    writeln!(
+        msg,
+        "{:<w$}  {:<w$}  diff",
+        "actual",
+        "expected",
+        w = ROW_LEN
+    )
+    .unwrap();
+    let [actual_art, expected_art, diff_art] = grids;
+    for ((a, e), d) in actual_art.iter().zip(&expected_art).zip(&diff_art) {
+        writeln!(msg, "{}  {}  {}", a, e, d).unwrap();
+    }
+    for &pos in diffs.iter().take(LISTED_DIFFS) {
+        writeln!(
+            msg,
+            "r{}c{}: actual {}, expected {}",
+            pos.row().inner(),
+            pos.col().inner(),
+            cell_char(actual[pos]),
+            cell_char(expected[pos]),
+        )
+        .unwrap();
+    }
+    if diffs.len() > LISTED_DIFFS {
+        writeln!(msg, "... and {} more", diffs.len() - LISTED_DIFFS).unwrap();
+    }
+    msg
+}
+
+/// Something `assert_boards_eq!` can compare: a board, or the optional board
+/// returned by the solvers.
+pub trait BoardOutcome {
+    /// The board, if there is one.
+    fn board(&self) -> Option<&Board>;
+}
+
+impl BoardOutcome for Board {
+    fn board(&self) -> Option<&Board> {
+        Some(self)
+    }
+}
+
+impl BoardOutcome for Option<Board> {
+    fn board(&self) -> Option<&Board> {
+        self.as_ref()
+    }
+}
+
+impl BoardOutcome for Option<&Board> {
+    fn board(&self) -> Option<&Board> {
+        *self
+    }
+}
+
+impl<B: BoardOutcome + ?Sized> BoardOutcome for &B {
+    fn board(&self) -> Option<&Board> {
+        (**self).board()
+    }
+}
+
+/// Implementation of `assert_boards_eq!`. When `puzzle` is given and a
+/// solution was expected but none was found, the failure says what logic
+/// alone makes of the puzzle.
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_boards_eq_impl(
+    actual: &dyn BoardOutcome,
+    expected: &dyn BoardOutcome,
+    puzzle: Option<&Board>,
+    context: Option<fmt::Arguments<'_>>,
+) {
+    let msg = match (actual.board(), expected.board()) {
+        (Some(actual), Some(expected)) if actual == expected => return,
+        (None, None) => return,
+        (Some(actual), Some(expected)) => board_diff_message(actual, expected),
+        (None, Some(expected)) => match puzzle {
+            Some(puzzle) => format!(
+                "expected a solution but got None (reduce reports: {})\nexpected:\n{}",
+                explain(puzzle),
+                board_to_art(expected),
+            ),
+            None => format!(
+                "expected a solution but got None\nexpected:\n{}",
+                board_to_art(expected)
+            ),
+        },
+        (Some(actual), None) => format!(
+            "expected None but got a board\nactual:\n{}",
+            board_to_art(actual)
+        ),
+    };
+    match context {
+        Some(context) => panic!(
+            "assertion `actual == expected` failed: {}\n{}",
+            context, msg
+        ),
+        None => panic!("assertion `actual == expected` failed\n{}", msg),
+    }
+}
+
+/// Summarize what logic alone concludes about a puzzle.
+fn explain(puzzle: &Board) -> String {
+    match puzzle.analysis() {
+        Analysis::Solved => "already solved".to_string(),
+        Analysis::Solvable { hint } => format!(
+            "can still place {} at r{}c{}",
+            hint.val.val(),
+            hint.pos.row().inner(),
+            hint.pos.col().inner()
+        ),
+        Analysis::Stuck {
+            requires_guess: true,
+        } => "stuck, a guess is needed".to_string(),
+        Analysis::Stuck {
+            requires_guess: false,
+        } => "stuck, and no guess leads to a solution".to_string(),
+        Analysis::Contradictory(reason) => format!("contradiction, {:?}", reason),
+    }
+}
+
+/// Lay out the 11 lines of art for a board, drawing each cell with `cell`.
+fn art_lines(mut cell: impl FnMut(Coord) -> char) -> Vec<String> {
+    let mut lines = Vec::with_capacity(11);
+    for r in 0..Col::HEIGHT {
+        if r > 0 && r % 3 == 0 {
+            lines.push(SEPARATOR.to_string());
+        }
+        let mut line = String::with_capacity(ROW_LEN);
+        for c in 0..Row::WIDTH {
+            if c > 0 && c % 3 == 0 {
+                line.push('|');
+            }
+            line.push(cell(Coord::new(Row::new(r), Col::new(c))));
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+fn cell_char(cell: Option<Val>) -> char {
+    cell.map_or('.', |val| (b'0' + val.val()) as char)
+}
+
+fn fail(line: usize, col: usize, msg: impl std::fmt::Display) -> ! {
+    panic!("bad board art at line {} column {}: {}", line, col, msg)
+}
+
+/// Build a board from text art given either as one string or as one string
+/// literal per line. See `board_from_art` for the format. Panics when
+/// evaluated if the art is malformed.
+///
+/// ```
+/// # use sudoku_solver::board_art;
+/// let board = board_art!(
+///     "   |1  |   ",
+///     "   | 58|6 1",
+///     "8 1|36 | 9 ",
+///     "---+---+---",
+///     "5  |   |4 3",
+///     "  3|6 1|8  ",
+///     "6 4|   |  7",
+///     "---+---+---",
+///     " 3 | 84|5 6",
+///     "1 5|72 |   ",
+///     "   |  3|   ",
+/// );
+/// assert!(board.solve().is_some());
+/// ```
+#[macro_export]
+macro_rules! board_art {
+    ($($line:literal),+ $(,)?) => {
+        $crate::testkit::board_from_art(concat!($($line, "\n"),+))
+    };
+}
+
+/// Assert that two boards, or two optional boards as returned by the
+/// solvers, are equal. On failure, the panic message draws both boards side
+/// by side and lists the differing cells, rather than dumping two lists of 81
+/// options.
+///
+/// Pass `puzzle = ...` to explain, when a solution was expected but none was
+/// found, what logic alone concludes about the puzzle. A format string and
+/// arguments may follow to add context to the message.
+///
+/// ```
+/// # use sudoku_solver::{assert_boards_eq, board_art};
+/// let puzzle = board_art!(
+///     "   |1  |   ",
+///     "   | 58|6 1",
+///     "8 1|36 | 9 ",
+///     "---+---+---",
+///     "5  |   |4 3",
+///     "  3|6 1|8  ",
+///     "6 4|   |  7",
+///     "---+---+---",
+///     " 3 | 84|5 6",
+///     "1 5|72 |   ",
+///     "   |  3|   ",
+/// );
+/// let solution = puzzle.solve().unwrap();
+/// assert_boards_eq!(puzzle.solve(), Some(solution), puzzle = puzzle);
+/// ```
+#[macro_export]
+macro_rules! assert_boards_eq {
+    ($actual:expr, $expected:expr, puzzle = $puzzle:expr $(,)?) => {
+        $crate::testkit::assert_boards_eq_impl(&$actual, &$expected, Some(&$puzzle), None)
+    };
+    ($actual:expr, $expected:expr, puzzle = $puzzle:expr, $($arg:tt)+) => {
+        $crate::testkit::assert_boards_eq_impl(
+            &$actual,
+            &$expected,
+            Some(&$puzzle),
+            Some(format_args!($($arg)+)),
+        )
+    };
+    ($actual:expr, $expected:expr $(,)?) => {
+        $crate::testkit::assert_boards_eq_impl(&$actual, &$expected, None, None)
+    };
+    ($actual:expr, $expected:expr, $($arg:tt)+) => {
+        $crate::testkit::assert_boards_eq_impl(
+            &$actual,
+            &$expected,
+            None,
+            Some(format_args!($($arg)+)),
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str = "
+           |1  |
+           | 58|6 1
+        8 1|36 | 9
+        ---+---+---
+        5  |   |4 3
+          3|6 1|8
+        6 4|   |  7
+        ---+---+---
+         3 | 84|5 6
+        1 5|72 |
+           |  3|
+    ";
+
+    const SOLUTION: &str = "
+        467|192|385
+        329|458|671
+        851|367|294
+        ---+---+---
+        518|279|463
+        273|641|859
+        694|835|127
+        ---+---+---
+        732|984|516
+        145|726|938
+        986|513|742
+    ";
+
+    #[test]
+    fn art_forms_agree() {
+        let from_string = board_from_art(PUZZLE);
+        let from_lines = board_art!(
+            "...|1..|...",
+            "...|.58|6.1",
+            "8.1|36.|.9.",
+            "---+---+---",
+            "5..|...|4.3",
+            "..3|6.1|8..",
+            "6.4|...|..7",
+            "---+---+---",
+            ".3.|.84|5.6",
+            "1.5|72.|...",
+            "...|..3|...",
+        );
+        assert_eq!(from_string, from_lines);
+        assert_eq!(
+            from_string[Coord::new(Row::new(0), Col::new(3))],
+            Some(Val::new(1))
+        );
+        assert_eq!(from_string.row_major().iter().flatten().count(), 30);
+    }
+
+    #[test]
+    fn solution_pair() {
+        let (puzzle, solution) = solution_pair_from_art(PUZZLE, SOLUTION);
+        assert_eq!(puzzle.solve(), Some(solution));
+    }
+
+    #[test]
+    #[should_panic(expected = "row 0 column 3 has 1 in the puzzle but 9 in the solution")]
+    fn solution_pair_wrong_solution() {
+        // Swap two columns of the solution so it is still solved but no longer
+        // matches the puzzle.
+        let swapped: String = SOLUTION
+            .lines()
+            .map(|line| {
+                let trimmed = line.trim_start();
+                if trimmed.len() == ROW_LEN && !trimmed.starts_with('-') {
+                    let indent = &line[..line.len() - trimmed.len()];
+                    let b = trimmed.as_bytes();
+                    format!(
+                        "{}{}{}{}{}\n",
+                        indent,
+                        &trimmed[..4],
+                        b[5] as char,
+                        b[4] as char,
+                        &trimmed[6..]
+                    )
+                } else {
+                    format!("{}\n", line)
+                }
+            })
+            .collect();
+        let _ = solution_pair_from_art(PUZZLE, &swapped);
+    }
+
+    #[test]
+    #[should_panic(expected = "solution art is not a solved board")]
+    fn solution_pair_unsolved() {
+        let _ = solution_pair_from_art(PUZZLE, PUZZLE);
+    }
+
+    #[test]
+    #[should_panic(expected = "bad board art at line 4 column 6: unsupported cell 'x'")]
+    fn bad_cell() {
+        board_from_art(&PUZZLE.replacen("36 ", "3x ", 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "bad board art at line 3 column 4: expected '|', found ' '")]
+    fn bad_separator() {
+        board_from_art(&PUZZLE.replacen("| 58|", "  58|", 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "bad board art at line 5 column 1: expected \"---+---+---\"")]
+    fn bad_row_separator() {
+        board_from_art(&PUZZLE.replacen("---+---+---", "---+---+--x", 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "bad board art at line 2 column 1: expected 11 lines, found 10")]
+    fn missing_line() {
+        board_from_art(&PUZZLE.replacen("           |  3|\n", "", 1));
+    }
+
+    #[test]
+    fn art_round_trip() {
+        let board = board_from_art(PUZZLE);
+        let art = board_to_art(&board);
+        assert_eq!(art.lines().next(), Some("...|1..|..."));
+        assert_eq!(art.lines().nth(3), Some(SEPARATOR));
+        assert_eq!(board_from_art(&art), board);
+    }
+
+    #[test]
+    fn diff_message() {
+        let expected = board_from_art(SOLUTION);
+        let mut actual = expected.clone();
+        actual[Coord::new(Row::new(0), Col::new(3))] = None;
+        actual[Coord::new(Row::new(8), Col::new(8))] = Some(Val::new(1));
+        let msg = board_diff_message(&actual, &expected);
+        let lines: Vec<&str> = msg.lines().collect();
+        assert_eq!(lines[0], "boards differ in 2 cells");
+        assert_eq!(lines[1], "actual       expected     diff");
+        assert_eq!(lines[2], "467|.92|385  467|192|385     |*  |   ");
+        assert_eq!(lines[12], "986|513|741  986|513|742     |   |  *");
+        assert_eq!(lines[13], "r0c3: actual ., expected 1");
+        assert_eq!(lines[14], "r8c8: actual 1, expected 2");
+        assert_eq!(lines.len(), 15);
+    }
+
+    #[test]
+    fn diff_message_truncates() {
+        let msg = board_diff_message(&Board::new(), &board_from_art(SOLUTION));
+        assert!(msg.starts_with("boards differ in 81 cells\n"));
+        assert!(msg.ends_with("r0c4: actual ., expected 9\n... and 76 more\n"));
+    }
+
+    #[test]
+    fn assert_boards_eq_passes() {
+        let (puzzle, solution) = solution_pair_from_art(PUZZLE, SOLUTION);
+        assert_boards_eq!(solution, solution);
+        assert_boards_eq!(puzzle.solve(), Some(solution.clone()), puzzle = puzzle);
+        assert_boards_eq!(None::<Board>, None::<Board>, "no board {}", 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "failed: solving PUZZLE\nboards differ in 51 cells")]
+    fn assert_boards_eq_context() {
+        let (puzzle, solution) = solution_pair_from_art(PUZZLE, SOLUTION);
+        assert_boards_eq!(puzzle, solution, "solving {}", "PUZZLE");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "expected a solution but got None (reduce reports: contradiction, Empty"
+    )]
+    fn assert_boards_eq_explains_none() {
+        let mut puzzle = board_from_art(PUZZLE);
+        puzzle[Coord::new(Row::new(0), Col::new(0))] = Some(Val::new(1));
+        let (_, solution) = solution_pair_from_art(PUZZLE, SOLUTION);
+        assert_boards_eq!(puzzle.solve(), Some(solution), puzzle = puzzle);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected None but got a board\nactual:\n...|1..|...")]
+    fn assert_boards_eq_unexpected_board() {
+        assert_boards_eq!(Some(board_from_art(PUZZLE)), None::<Board>);
+    }
+}
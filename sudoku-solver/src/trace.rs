@@ -1,11 +1,33 @@
 //! Tools for tracing how a solution was reached.
-use std::ops::{Index, IndexMut};
+#[cfg(feature = "serde")]
+use std::cell::Cell;
+use std::hash::{Hash, Hasher};
+#[cfg(feature = "serde")]
+use std::io::{self, Write};
+use std::ops::{Deref, Index, IndexMut};
+use std::sync::Arc;
 
+#[cfg(feature = "serde")]
+use serde::de::DeserializeSeed;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde")]
+use thiserror::Error;
 
 use crate::collections::indexed::IndexMap;
-use crate::{AvailSet, Board, Col, Coord, Row, Sector, SectorCol, SectorRow, Val};
+use crate::solve::deductive;
+use crate::solve::remaining::RemainingTracker;
+use crate::{
+    AvailSet, Board, Col, Coord, House, Row, RuleKind, RuleSet, Sector, SectorCol, SectorRow, Val,
+};
+
+mod diff;
+#[cfg(feature = "serde")]
+mod document;
+
+pub use diff::{CellDiff, RemainingDiff};
+#[cfg(feature = "serde")]
+pub use document::{TraceDocument, TraceFormatError, CURRENT_SCHEMA};
 
 /// Records steps used during solving as a tree of puzzles.
 pub trait Tracer {
@@ -27,7 +49,8 @@ pub trait Tracer {
     /// be added to the node with add_child.
     fn guess(deduction: Self::Deductive) -> Self;
 
-    /// Add a child to this node.
+    /// Add a child to this node. The solver only adds children to nodes built
+    /// with `guess`, so implementations may panic for other nodes.
     fn add_child(&mut self, child: Self);
 }
 
@@ -103,6 +126,9 @@ impl Tracer for TraceTree {
         }
     }
 
+    /// # Panics
+    ///
+    /// Panics if this is not a `Guess` node.
     fn add_child(&mut self, child: Self) {
         match self {
             TraceTree::Solution { .. } => panic!("cannot add children to solution nodes"),
@@ -114,10 +140,106 @@ impl Tracer for TraceTree {
     }
 }
 
+/// Limits applied when parsing a trace from an untrusted source.
+#[cfg(feature = "serde")]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TraceLimits {
+    /// Maximum size of the input in bytes.
+    pub max_bytes: usize,
+    /// Maximum number of nested guesses below the root of the tree.
+    pub max_depth: usize,
+}
+
+#[cfg(feature = "serde")]
+impl Default for TraceLimits {
+    /// Defaults allow 32 MiB of input nested at most 60 guesses deep, which is
+    /// deeper than the solver nests guesses even for an empty board.
+    fn default() -> Self {
+        TraceLimits {
+            max_bytes: 32 << 20,
+            max_depth: 60,
+        }
+    }
+}
+
+/// Error when parsing a trace with limits.
+#[cfg(feature = "serde")]
+#[derive(Debug, Error)]
+pub enum TraceParseError {
+    /// The input was larger than the limit.
+    #[error("trace is {len} bytes, which exceeds the limit of {max} bytes")]
+    TooLarge { len: usize, max: usize },
+    /// The guesses were nested deeper than the limit.
+    #[error("trace has guesses nested more than {max} deep")]
+    TooDeep { max: usize },
+    /// The input was not a valid trace.
+    #[error("invalid trace: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[cfg(feature = "serde")]
+impl TraceTree {
+    /// Parse a trace tree from json which may come from an untrusted source.
+    /// Rejects inputs larger than the byte limit before parsing, and stops
+    /// parsing as soon as guesses are nested deeper than the depth limit, so
+    /// hostile input cannot exhaust the stack.
+    pub fn from_json_limited(input: &str, limits: &TraceLimits) -> Result<Self, TraceParseError> {
+        if input.len() > limits.max_bytes {
+            return Err(TraceParseError::TooLarge {
+                len: input.len(),
+                max: limits.max_bytes,
+            });
+        }
+        let mut de = serde_json::Deserializer::from_str(input);
+        let tree = Self::deserialize_limited(&mut de, limits.max_depth)?;
+        de.end()?;
+        Ok(tree)
+    }
+
+    /// Deserialize a trace tree, stopping as soon as guesses are nested deeper
+    /// than `max_depth`.
+    pub(crate) fn deserialize_limited<'de, D>(
+        deserializer: D,
+        max_depth: usize,
+    ) -> Result<Self, TraceParseError>
+    where
+        D: serde::Deserializer<'de, Error = serde_json::Error>,
+    {
+        let too_deep = Cell::new(false);
+        let seed = serde_limited::TreeSeed {
+            depth: 0,
+            max_depth,
+            too_deep: &too_deep,
+        };
+        match seed.deserialize(deserializer) {
+            Ok(tree) => Ok(tree),
+            Err(_) if too_deep.get() => Err(TraceParseError::TooDeep { max: max_depth }),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
 /// Records steps used during deductive reduction.
 pub trait DeductiveTracer {
-    /// Record a deduction and the reason why the deduction happened.
+    /// Record a deduction and the reason why the deduction happened, along
+    /// with the state after the deduction's changes.
     fn deduce(&mut self, reason: DeductionReason, remaining: Remaining);
+
+    /// Whether `fail` should be given the state from before the failing step.
+    /// Snapshotting costs a copy of the candidates at the start of every step,
+    /// so it is only done for tracers which return true.
+    fn wants_pre_state(&self) -> bool {
+        false
+    }
+
+    /// Record that the board was proven unsolveable. `remaining` is the state
+    /// at the failure, including whatever the failing step eliminated before
+    /// it failed. `before` is the state when that step began, if
+    /// `wants_pre_state` returned true. Forwards to `deduce` by default.
+    fn fail(&mut self, reason: UnsolveableReason, before: Option<Remaining>, remaining: Remaining) {
+        let _ = before;
+        self.deduce(DeductionReason::Unsolveable(reason), remaining)
+    }
 }
 
 /// Deductive tracer that doesn't record anything.
@@ -128,9 +250,130 @@ impl DeductiveTracer for NopDeductiveTracer {
     fn deduce(&mut self, _: DeductionReason, _: Remaining) {}
 }
 
+impl<D: DeductiveTracer + ?Sized> DeductiveTracer for &mut D {
+    fn deduce(&mut self, reason: DeductionReason, remaining: Remaining) {
+        (**self).deduce(reason, remaining)
+    }
+
+    fn wants_pre_state(&self) -> bool {
+        (**self).wants_pre_state()
+    }
+
+    fn fail(&mut self, reason: UnsolveableReason, before: Option<Remaining>, remaining: Remaining) {
+        (**self).fail(reason, before, remaining)
+    }
+}
+
 impl DeductiveTracer for Vec<Deduction> {
     fn deduce(&mut self, reason: DeductionReason, remaining: Remaining) {
-        self.push(Deduction { reason, remaining });
+        let (remaining, prev_candidates) = share_snapshot(self, remaining);
+        self.push(Deduction::new(reason, remaining, None, prev_candidates));
+    }
+
+    fn wants_pre_state(&self) -> bool {
+        true
+    }
+
+    fn fail(&mut self, reason: UnsolveableReason, before: Option<Remaining>, remaining: Remaining) {
+        let (remaining, prev_candidates) = share_snapshot(self, remaining);
+        let before = before.map(|before| share_snapshot(self, before).0);
+        let reason = DeductionReason::Unsolveable(reason);
+        self.push(Deduction::new(reason, remaining, before, prev_candidates));
+    }
+}
+
+/// Convert a snapshot for the next deduction of the trace, sharing the last
+/// deduction's snapshot if it is the same. Also returns the number of
+/// candidates the last deduction left.
+fn share_snapshot(trace: &[Deduction], remaining: Remaining) -> (ArcRemaining, usize) {
+    match trace.last() {
+        Some(last) if last.remaining == remaining => {
+            (last.remaining.clone(), last.remaining.num_candidates())
+        }
+        Some(last) => (remaining.into(), last.remaining.num_candidates()),
+        None => (remaining.into(), 0),
+    }
+}
+
+/// Deductive tracer which streams each deduction to a writer as a single line
+/// of JSON, without buffering the whole trace.
+///
+/// Since tracing cannot fail, the first error encountered while writing is
+/// saved and all later deductions are dropped. Use `into_inner` or `error` to
+/// check whether the whole trace was written.
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+pub struct JsonLinesTracer<W> {
+    writer: W,
+    error: Option<io::Error>,
+    /// Number of candidates left after the last deduction.
+    candidates: usize,
+}
+
+#[cfg(feature = "serde")]
+impl<W: Write> JsonLinesTracer<W> {
+    /// Create a tracer which writes to the given writer.
+    pub fn new(writer: W) -> Self {
+        JsonLinesTracer {
+            writer,
+            error: None,
+            candidates: 0,
+        }
+    }
+
+    /// Get a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Get the first error that was encountered while writing, if any.
+    pub fn error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+
+    /// Flush and unwrap the underlying writer, or return the first error that
+    /// was encountered while writing.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        match self.error {
+            Some(err) => Err(err),
+            None => {
+                self.writer.flush()?;
+                Ok(self.writer)
+            }
+        }
+    }
+
+    /// Write a deduction unless writing has already failed.
+    fn record(&mut self, reason: DeductionReason, before: Option<Remaining>, remaining: Remaining) {
+        if self.error.is_none() {
+            let before = before.map(Into::into);
+            let deduction = Deduction::new(reason, remaining.into(), before, self.candidates);
+            self.candidates = deduction.remaining.num_candidates();
+            if let Err(err) = self.write_line(&deduction) {
+                self.error = Some(err);
+            }
+        }
+    }
+
+    /// Write a single deduction as a line of json.
+    fn write_line(&mut self, deduction: &Deduction) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, deduction)?;
+        self.writer.write_all(b"\n")
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<W: Write> DeductiveTracer for JsonLinesTracer<W> {
+    fn deduce(&mut self, reason: DeductionReason, remaining: Remaining) {
+        self.record(reason, None, remaining);
+    }
+
+    fn wants_pre_state(&self) -> bool {
+        true
+    }
+
+    fn fail(&mut self, reason: UnsolveableReason, before: Option<Remaining>, remaining: Remaining) {
+        self.record(DeductionReason::Unsolveable(reason), before, remaining);
     }
 }
 
@@ -141,6 +384,7 @@ pub struct Remaining(IndexMap<Coord, AvailSet>);
 
 impl Remaining {
     /// Get a Board with only the known remaining values set.
+    #[must_use]
     pub fn board(&self) -> Board {
         let mut board = Board::new();
         for (src, dest) in self.0.as_ref().iter().zip(board.as_mut()) {
@@ -148,6 +392,78 @@ impl Remaining {
         }
         board
     }
+
+    /// Iterator over each coordinate along with the values remaining there.
+    #[must_use]
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (Coord, AvailSet)> + '_ {
+        self.0.iter().map(|(coord, avail)| (coord, *avail))
+    }
+
+    /// Total number of candidates left across all cells.
+    #[must_use]
+    pub fn num_candidates(&self) -> usize {
+        self.iter().map(|(_, avail)| avail.len()).sum()
+    }
+
+    /// Get the values which were remaining in `prev` but are no longer
+    /// remaining here, in row-major order and then by value.
+    #[must_use]
+    pub fn eliminated_since(&self, prev: &Remaining) -> Vec<(Coord, Val)> {
+        RemainingDiff::between(prev, self).eliminations()
+    }
+
+    /// Returns true if no cell is empty and the value of every cell with a
+    /// single value left has been eliminated from all of its neighbors. Closed
+    /// candidates are exactly the ones `close` leaves unchanged.
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.iter().all(|(coord, avail)| match avail.get_single() {
+            Some(val) => coord.neighbors().all(|n| !self[n].contains(val)),
+            None => !avail.is_empty(),
+        })
+    }
+
+    /// Find every deduction the given rules could make from these candidates,
+    /// without applying any of them. Each rule is checked the same way the
+    /// solver checks it, so applying an opportunity eliminates exactly what it
+    /// lists. Opportunities are ordered by the default rule priority, and
+    /// rules which would find a contradiction are left out.
+    #[must_use]
+    pub fn available_deductions(&self, rules: &RuleSet) -> Vec<DeductionOpportunity> {
+        deductive::opportunities(&RemainingTracker::from_remaining(self), *rules)
+    }
+
+    /// Eliminate the value of every cell with a single value left from its
+    /// neighbors, repeating until nothing changes. No other rules are applied.
+    /// Fails with the first cell left empty if the candidates contradict.
+    pub fn close(&self) -> Result<ClosedRemaining, UnsolveableReason> {
+        if let Some((pos, _)) = self.iter().find(|(_, avail)| avail.is_empty()) {
+            return Err(UnsolveableReason::Empty { pos });
+        }
+        let mut closed = self.clone();
+        // Visit cells in row-major order, so the reported cell is predictable.
+        let mut pending: Vec<Coord> = self
+            .iter()
+            .filter(|(_, avail)| avail.is_single())
+            .map(|(coord, _)| coord)
+            .collect();
+        pending.reverse();
+        while let Some(coord) = pending.pop() {
+            let val = closed[coord].get_single().unwrap();
+            for neighbor in coord.neighbors() {
+                let cell = &mut closed[neighbor];
+                if cell.remove(val) {
+                    if cell.is_empty() {
+                        return Err(UnsolveableReason::Empty { pos: neighbor });
+                    }
+                    if cell.is_single() {
+                        pending.push(neighbor);
+                    }
+                }
+            }
+        }
+        Ok(ClosedRemaining(closed))
+    }
 }
 
 impl From<IndexMap<Coord, AvailSet>> for Remaining {
@@ -188,13 +504,238 @@ impl AsMut<[AvailSet]> for Remaining {
     }
 }
 
+/// A deduction a rule could make from the current candidates, found by
+/// `Remaining::available_deductions` without applying it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DeductionOpportunity {
+    /// The rule which would make the deduction.
+    pub rule: RuleKind,
+    /// The deduction which would be recorded. Names the cell, house, or
+    /// sector-row/sector-col the rule is based on and the values involved.
+    pub reason: DeductionReason,
+    /// The cells the deduction is based on, in row-major order.
+    pub cells: Vec<Coord>,
+    /// The candidates the deduction would eliminate, in the order the solver
+    /// eliminates them.
+    pub eliminations: Vec<(Coord, Val)>,
+}
+
+/// Remaining values which are known to be closed, as produced by
+/// `Remaining::close`. Deserializing closes the input, failing if it
+/// contradicts.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(transparent))]
+pub struct ClosedRemaining(Remaining);
+
+impl ClosedRemaining {
+    /// Get the closed remaining values.
+    #[must_use]
+    pub fn into_inner(self) -> Remaining {
+        self.0
+    }
+}
+
+impl Deref for ClosedRemaining {
+    type Target = Remaining;
+
+    fn deref(&self) -> &Remaining {
+        &self.0
+    }
+}
+
+impl From<ClosedRemaining> for Remaining {
+    fn from(closed: ClosedRemaining) -> Self {
+        closed.0
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for ClosedRemaining {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Remaining::deserialize(deserializer)?
+            .close()
+            .map_err(|reason| match reason {
+                UnsolveableReason::Empty { pos } => serde::de::Error::custom(format_args!(
+                    "candidates contradict: no values left at {}",
+                    pos
+                )),
+                other => {
+                    serde::de::Error::custom(format_args!("candidates contradict: {:?}", other))
+                }
+            })
+    }
+}
+
+/// Shared snapshot of what was remaining at each coordinate.
+///
+/// Cloning only bumps a reference count, so many traces or threads can hold
+/// the same snapshot. Mutation copies the snapshot first if it is shared.
+/// Equality and hashing are by value, with a fast path for snapshots sharing
+/// the same allocation.
+#[derive(Clone, Debug)]
+pub struct ArcRemaining(Arc<IndexMap<Coord, AvailSet>>);
+
+impl ArcRemaining {
+    /// Get a Board with only the known remaining values set.
+    #[must_use]
+    pub fn board(&self) -> Board {
+        let mut board = Board::new();
+        for (src, dest) in self.as_ref().iter().zip(board.as_mut()) {
+            *dest = src.get_single()
+        }
+        board
+    }
+
+    /// Iterator over each coordinate along with the values remaining there.
+    #[must_use]
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (Coord, AvailSet)> + '_ {
+        self.0.iter().map(|(coord, avail)| (coord, *avail))
+    }
+
+    /// Total number of candidates left across all cells.
+    #[must_use]
+    pub fn num_candidates(&self) -> usize {
+        self.iter().map(|(_, avail)| avail.len()).sum()
+    }
+
+    /// Get mutable access to the remaining values, copying them first if this
+    /// snapshot is shared.
+    pub fn make_mut(&mut self) -> &mut [AvailSet] {
+        Arc::make_mut(&mut self.0).as_mut()
+    }
+
+    /// Returns true if both snapshots share the same allocation.
+    #[must_use]
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        Arc::ptr_eq(&this.0, &other.0)
+    }
+}
+
+impl From<Remaining> for ArcRemaining {
+    fn from(rem: Remaining) -> Self {
+        Self(Arc::new(rem.0))
+    }
+}
+
+impl From<ArcRemaining> for Remaining {
+    fn from(rem: ArcRemaining) -> Self {
+        Self(Arc::try_unwrap(rem.0).unwrap_or_else(|shared| (*shared).clone()))
+    }
+}
+
+impl From<IndexMap<Coord, AvailSet>> for ArcRemaining {
+    fn from(board: IndexMap<Coord, AvailSet>) -> Self {
+        Self(Arc::new(board))
+    }
+}
+
+impl PartialEq for ArcRemaining {
+    fn eq(&self, other: &Self) -> bool {
+        Self::ptr_eq(self, other) || self.0 == other.0
+    }
+}
+
+impl Eq for ArcRemaining {}
+
+impl PartialEq<Remaining> for ArcRemaining {
+    fn eq(&self, other: &Remaining) -> bool {
+        *self.0 == other.0
+    }
+}
+
+impl Hash for ArcRemaining {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl Index<Coord> for ArcRemaining {
+    type Output = AvailSet;
+
+    fn index(&self, idx: Coord) -> &Self::Output {
+        &self.0[idx]
+    }
+}
+
+impl IndexMut<Coord> for ArcRemaining {
+    fn index_mut(&mut self, idx: Coord) -> &mut Self::Output {
+        &mut Arc::make_mut(&mut self.0)[idx]
+    }
+}
+
+impl AsRef<[AvailSet]> for ArcRemaining {
+    fn as_ref(&self) -> &[AvailSet] {
+        (*self.0).as_ref()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for ArcRemaining {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (*self.0).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for ArcRemaining {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        IndexMap::deserialize(deserializer).map(|board| Self(Arc::new(board)))
+    }
+}
+
 /// The cause and result of a single deduction.
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Deduction {
     #[cfg_attr(feature = "serde", serde(flatten))]
     pub reason: DeductionReason,
-    pub remaining: Remaining,
+    /// The candidates after this deduction's changes. For `InitialState` this
+    /// is the board before any reduction. For `Unsolveable` it is the state at
+    /// the failure, which includes whatever the failing step eliminated before
+    /// it failed, so some of its changes may be applied and others not.
+    pub remaining: ArcRemaining,
+    /// For `Unsolveable`, the candidates when the failing step began, so
+    /// `before` to `remaining` is exactly what that step changed. None for
+    /// other deductions, and for traces recorded without it.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub before: Option<ArcRemaining>,
+    /// Number of candidates this deduction removed, compared to the previous
+    /// deduction. Always 0 for `InitialState`, which starts a new reduction.
+    pub eliminated_count: usize,
+}
+
+impl Deduction {
+    /// Build a deduction, counting the candidates it removed from the
+    /// previous snapshot, which had `prev_candidates` candidates left.
+    fn new(
+        reason: DeductionReason,
+        remaining: ArcRemaining,
+        before: Option<ArcRemaining>,
+        prev_candidates: usize,
+    ) -> Self {
+        let eliminated_count = match reason {
+            DeductionReason::InitialState => 0,
+            _ => prev_candidates.saturating_sub(remaining.num_candidates()),
+        };
+        Deduction {
+            reason,
+            remaining,
+            before,
+            eliminated_count,
+        }
+    }
+
+    /// Describe the deduction in prose for screen readers, naming every cell,
+    /// house, and digit involved rather than relying on highlighting. Rows,
+    /// columns, and boxes are numbered from 1.
+    #[must_use]
+    pub fn describe_accessible(&self) -> String {
+        crate::describe::deduction(self)
+    }
 }
 
 /// Reason a deduction could be performed.
@@ -242,10 +783,87 @@ pub enum DeductionReason {
     /// the given value, so those values have been eliminated from the rest of
     /// the sector.
     ColOnlySec { pos: SectorCol, vals: AvailSet },
+    /// The given values are confined to the given two cells of the house, so
+    /// every other value has been eliminated from those cells.
+    HiddenPair {
+        pos: House,
+        cells: [Coord; 2],
+        vals: AvailSet,
+    },
+    /// The given values are confined to the given three cells of the house, so
+    /// every other value has been eliminated from those cells.
+    HiddenTriple {
+        pos: House,
+        cells: [Coord; 3],
+        vals: AvailSet,
+    },
+    /// The value's candidates in the two rows lie within the two columns, so
+    /// it must take two opposite corners of the rectangle and has been
+    /// eliminated from the rest of those columns. When `by_rows` is false the
+    /// roles are swapped: the candidates in the columns lie within the rows,
+    /// and it has been eliminated from the rest of those rows.
+    XWing {
+        rows: [Row; 2],
+        cols: [Col; 2],
+        val: Val,
+        by_rows: bool,
+    },
     /// The board was proven unsolveable for the given reason.
     Unsolveable(UnsolveableReason),
 }
 
+impl DeductionReason {
+    /// The rule which made this deduction, if it was made by a rule.
+    #[must_use]
+    pub fn rule_kind(&self) -> Option<RuleKind> {
+        match self {
+            DeductionReason::InitialState | DeductionReason::Unsolveable(_) => None,
+            DeductionReason::CoordNeighbors { .. } => Some(RuleKind::CoordNeighbors),
+            DeductionReason::UniqueInRow { .. } => Some(RuleKind::UniqueInRow),
+            DeductionReason::UniqueInCol { .. } => Some(RuleKind::UniqueInCol),
+            DeductionReason::UniqueInSector { .. } => Some(RuleKind::UniqueInSector),
+            DeductionReason::SecRowTriple { .. } => Some(RuleKind::SecRowTriple),
+            DeductionReason::SecColTriple { .. } => Some(RuleKind::SecColTriple),
+            DeductionReason::SecOnlyRow { .. } => Some(RuleKind::SecOnlyRow),
+            DeductionReason::SecOnlyCol { .. } => Some(RuleKind::SecOnlyCol),
+            DeductionReason::RowOnlySec { .. } => Some(RuleKind::RowOnlySec),
+            DeductionReason::ColOnlySec { .. } => Some(RuleKind::ColOnlySec),
+            DeductionReason::HiddenPair { .. } => Some(RuleKind::HiddenPair),
+            DeductionReason::HiddenTriple { .. } => Some(RuleKind::HiddenTriple),
+            DeductionReason::XWing { .. } => Some(RuleKind::XWing),
+        }
+    }
+
+    /// The conventional name of the technique which made this deduction, for
+    /// labeling steps with names solvers will recognize. The sector-line rules
+    /// go by several names: `SecOnlyRow` and `SecOnlyCol` are "pointing" and
+    /// `RowOnlySec` and `ColOnlySec` are "box/line reduction", also called
+    /// "claiming".
+    #[must_use]
+    pub fn technique_name(&self) -> &'static str {
+        match self {
+            DeductionReason::InitialState => "Initial State",
+            DeductionReason::CoordNeighbors { .. } => "Naked Single",
+            DeductionReason::UniqueInRow { .. }
+            | DeductionReason::UniqueInCol { .. }
+            | DeductionReason::UniqueInSector { .. } => "Hidden Single",
+            DeductionReason::SecRowTriple { .. } | DeductionReason::SecColTriple { .. } => {
+                "Locked Triple"
+            }
+            DeductionReason::SecOnlyRow { .. } | DeductionReason::SecOnlyCol { .. } => {
+                "Pointing Pair/Triple"
+            }
+            DeductionReason::RowOnlySec { .. } | DeductionReason::ColOnlySec { .. } => {
+                "Box/Line Reduction"
+            }
+            DeductionReason::HiddenPair { .. } => "Hidden Pair",
+            DeductionReason::HiddenTriple { .. } => "Hidden Triple",
+            DeductionReason::XWing { .. } => "X-Wing",
+            DeductionReason::Unsolveable(_) => "Contradiction",
+        }
+    }
+}
+
 /// Reason the board cannot be solved.
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[cfg_attr(
@@ -279,11 +897,437 @@ pub enum UnsolveableReason {
     SecColTooFewVals { pos: SectorCol },
 }
 
+/// Deserialization of `TraceTree` which tracks the depth of the tree.
+#[cfg(feature = "serde")]
+mod serde_limited {
+    use std::cell::Cell;
+    use std::fmt;
+
+    use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+    use serde::{Deserialize, Deserializer};
+
+    use super::{Deduction, TraceTree};
+
+    #[derive(Deserialize)]
+    #[serde(field_identifier, rename_all = "snake_case")]
+    enum Field {
+        Type,
+        Deduction,
+        Guesses,
+        #[serde(other)]
+        Other,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    enum Kind {
+        Solution,
+        Unsolveable,
+        Guess,
+    }
+
+    /// Deserializes a single tree node at the given depth.
+    pub(super) struct TreeSeed<'a> {
+        pub(super) depth: usize,
+        pub(super) max_depth: usize,
+        /// Set when parsing stopped because of the depth limit.
+        pub(super) too_deep: &'a Cell<bool>,
+    }
+
+    impl<'de> DeserializeSeed<'de> for TreeSeed<'_> {
+        type Value = TraceTree;
+
+        fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<TraceTree, D::Error> {
+            deserializer.deserialize_map(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for TreeSeed<'_> {
+        type Value = TraceTree;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a trace tree node")
+        }
+
+        fn visit_map<M: MapAccess<'de>>(self, mut map: M) -> Result<TraceTree, M::Error> {
+            let mut kind = None;
+            let mut deduction: Option<Vec<Deduction>> = None;
+            let mut guesses = None;
+            while let Some(field) = map.next_key()? {
+                match field {
+                    Field::Type => kind = Some(map.next_value()?),
+                    Field::Deduction => deduction = Some(map.next_value()?),
+                    Field::Guesses => {
+                        guesses = Some(map.next_value_seed(GuessesSeed {
+                            depth: self.depth + 1,
+                            max_depth: self.max_depth,
+                            too_deep: self.too_deep,
+                        })?)
+                    }
+                    Field::Other => {
+                        map.next_value::<IgnoredAny>()?;
+                    }
+                }
+            }
+            let deduction = deduction.ok_or_else(|| de::Error::missing_field("deduction"))?;
+            match kind.ok_or_else(|| de::Error::missing_field("type"))? {
+                Kind::Solution => Ok(TraceTree::Solution { deduction }),
+                Kind::Unsolveable => Ok(TraceTree::Unsolveable { deduction }),
+                Kind::Guess => Ok(TraceTree::Guess {
+                    deduction,
+                    guesses: guesses.ok_or_else(|| de::Error::missing_field("guesses"))?,
+                }),
+            }
+        }
+    }
+
+    /// Deserializes the children of a guess node, which are at the given depth.
+    struct GuessesSeed<'a> {
+        depth: usize,
+        max_depth: usize,
+        too_deep: &'a Cell<bool>,
+    }
+
+    impl<'de> DeserializeSeed<'de> for GuessesSeed<'_> {
+        type Value = Vec<TraceTree>;
+
+        fn deserialize<D: Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> Result<Vec<TraceTree>, D::Error> {
+            deserializer.deserialize_seq(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for GuessesSeed<'_> {
+        type Value = Vec<TraceTree>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a sequence of trace tree nodes")
+        }
+
+        fn visit_seq<S: SeqAccess<'de>>(self, mut seq: S) -> Result<Vec<TraceTree>, S::Error> {
+            let mut guesses = Vec::new();
+            loop {
+                if self.depth > self.max_depth {
+                    // Only an error if there is actually a child at this depth.
+                    if seq.next_element::<IgnoredAny>()?.is_some() {
+                        self.too_deep.set(true);
+                        return Err(de::Error::custom(format_args!(
+                            "guesses nested more than {} deep",
+                            self.max_depth
+                        )));
+                    }
+                    return Ok(guesses);
+                }
+                let seed = TreeSeed {
+                    depth: self.depth,
+                    max_depth: self.max_depth,
+                    too_deep: self.too_deep,
+                };
+                match seq.next_element_seed(seed)? {
+                    Some(guess) => guesses.push(guess),
+                    None => return Ok(guesses),
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::collections::hash_map::DefaultHasher;
+    use std::mem;
+
+    use super::*;
+    use crate::collections::indexed::FixedSizeIndex;
+    use crate::Zone;
+
+    /// Snapshots must be shareable across threads.
+    const _: fn() = || {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ArcRemaining>();
+        assert_send_sync::<Remaining>();
+        assert_send_sync::<Deduction>();
+        assert_send_sync::<Board>();
+    };
+
+    fn hash_of<T: Hash>(val: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        val.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn arc_remaining_eq_and_hash() {
+        crate::setup();
+
+        let rem: ArcRemaining = IndexMap::with_value(AvailSet::all()).into();
+        let shared = rem.clone();
+        assert!(ArcRemaining::ptr_eq(&rem, &shared));
+        assert_eq!(rem, shared);
+
+        let separate: ArcRemaining = IndexMap::with_value(AvailSet::all()).into();
+        assert!(!ArcRemaining::ptr_eq(&rem, &separate));
+        assert_eq!(rem, separate);
+        assert_eq!(hash_of(&rem), hash_of(&separate));
+
+        let pos = Coord::new(Row::new(3), Col::new(6));
+        let mut changed = shared.clone();
+        changed[pos] = AvailSet::only(Val::new(2));
+        assert!(!ArcRemaining::ptr_eq(&changed, &shared));
+        assert_ne!(changed, rem);
+        assert_eq!(rem[pos], AvailSet::all());
+        assert_eq!(changed.board()[pos], Some(Val::new(2)));
+
+        let back: Remaining = changed.clone().into();
+        assert_eq!(changed, back);
+        assert_eq!(
+            back.iter().collect::<Vec<_>>(),
+            changed.iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn eliminated_count() {
+        crate::setup();
+
+        let coord = Coord::new(Row::new(4), Col::new(2));
+        let mut map = IndexMap::with_value(AvailSet::all());
+        let mut trace = Vec::new();
+        trace.deduce(DeductionReason::InitialState, Remaining::from(map.clone()));
+        map[coord] -= AvailSet::only(Val::new(3)) | Val::new(7);
+        let reason = DeductionReason::UniqueInRow {
+            pos: coord.row(),
+            vals: AvailSet::only(Val::new(1)),
+        };
+        trace.deduce(reason.clone(), Remaining::from(map.clone()));
+        trace.deduce(reason, Remaining::from(map.clone()));
+        map[coord] = AvailSet::none();
+        trace.deduce(DeductionReason::InitialState, Remaining::from(map));
+        let counts: Vec<_> = trace.iter().map(|d| d.eliminated_count).collect();
+        assert_eq!(counts, [0, 2, 0, 0]);
+
+        // Over a whole reduction, the counts add up to every candidate removed.
+        let board = crate::fixtures::puzzle1();
+        let (_, trace) = board.solve_with(Vec::<Deduction>::new());
+        let first = trace.first().unwrap().remaining.num_candidates();
+        let last = trace.last().unwrap().remaining.num_candidates();
+        let total: usize = trace.iter().map(|d| d.eliminated_count).sum();
+        assert_eq!(total, first - last);
+    }
+
+    #[test]
+    fn vec_tracer_shares_unchanged_snapshots() {
+        crate::setup();
+
+        const STEPS: usize = 500;
+        const SNAPSHOT_BYTES: usize = Coord::NUM_INDEXES * mem::size_of::<AvailSet>();
+
+        // Only every tenth step changes the remaining values.
+        let mut map = IndexMap::with_value(AvailSet::all());
+        let mut trace = Vec::new();
+        for step in 0..STEPS {
+            if step % 10 == 0 {
+                let coord = Coord::all().nth(step / 10).unwrap();
+                map[coord] = AvailSet::only(Val::new(1));
+            }
+            trace.deduce(DeductionReason::InitialState, Remaining::from(map.clone()));
+        }
+
+        let allocations = 1 + trace
+            .windows(2)
+            .filter(|pair| !ArcRemaining::ptr_eq(&pair[0].remaining, &pair[1].remaining))
+            .count();
+        let unshared_bytes = STEPS * SNAPSHOT_BYTES;
+        let shared_bytes = allocations * SNAPSHOT_BYTES;
+        assert_eq!(allocations, STEPS / 10);
+        assert!(shared_bytes * 10 <= unshared_bytes);
+    }
+
+    fn coord(row: u8, col: u8) -> Coord {
+        Coord::new(Row::new(row), Col::new(col))
+    }
+
+    #[test]
+    fn eliminated_since() {
+        let prev = Remaining::from(IndexMap::with_value(AvailSet::all()));
+        let mut next = prev.clone();
+        next[coord(2, 7)] -= Val::new(5);
+        assert_eq!(
+            next.eliminated_since(&prev),
+            vec![(coord(2, 7), Val::new(5))]
+        );
+        assert_eq!(prev.eliminated_since(&next), vec![]);
+        assert_eq!(next.eliminated_since(&next), vec![]);
+    }
+
+    #[test]
+    fn close_to_fixed_point() {
+        crate::setup();
+
+        let mut rem = Remaining::from(IndexMap::with_value(AvailSet::all()));
+        rem[coord(0, 0)] = AvailSet::only(Val::new(1));
+        rem[coord(0, 1)] = AvailSet::only(Val::new(1)) | Val::new(2);
+        rem[coord(0, 2)] = AvailSet::only(Val::new(1)) | Val::new(2) | Val::new(3);
+        assert!(!rem.is_closed());
+
+        let closed = rem.close().unwrap();
+        assert!(closed.is_closed());
+        let singles = [
+            (coord(0, 0), Val::new(1)),
+            (coord(0, 1), Val::new(2)),
+            (coord(0, 2), Val::new(3)),
+        ];
+        for pos in Coord::all() {
+            let expected = match singles.iter().find(|(single, _)| *single == pos) {
+                Some(&(_, val)) => AvailSet::only(val),
+                None => singles
+                    .iter()
+                    .filter(|(single, _)| pos.neighbors().any(|n| n == *single))
+                    .fold(AvailSet::all(), |avail, &(_, val)| avail - val),
+            };
+            assert_eq!(closed[pos], expected, "at {}", pos);
+        }
+        assert_eq!(closed.close().unwrap(), closed);
+        assert!(Remaining::from(IndexMap::with_value(AvailSet::all())).is_closed());
+    }
+
+    #[test]
+    fn close_contradiction() {
+        crate::setup();
+
+        // Filling (0, 1) with 2 leaves nothing for (5, 1).
+        let mut rem = Remaining::from(IndexMap::with_value(AvailSet::all()));
+        rem[coord(0, 0)] = AvailSet::only(Val::new(1));
+        rem[coord(0, 1)] = AvailSet::only(Val::new(1)) | Val::new(2);
+        rem[coord(5, 1)] = AvailSet::only(Val::new(2));
+        assert_eq!(
+            rem.close(),
+            Err(UnsolveableReason::Empty { pos: coord(5, 1) })
+        );
+
+        let mut rem = Remaining::from(IndexMap::with_value(AvailSet::all()));
+        rem[coord(3, 3)] = AvailSet::none();
+        assert!(!rem.is_closed());
+        assert_eq!(
+            rem.close(),
+            Err(UnsolveableReason::Empty { pos: coord(3, 3) })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot add children to solution nodes")]
+    fn add_child_to_solution() {
+        let mut tree = TraceTree::solution(Vec::new());
+        tree.add_child(TraceTree::unsolveable(Vec::new()));
+    }
+
+    #[test]
+    fn technique_names() {
+        let vals = AvailSet::only(Val::new(1));
+        let srow = SectorRow::from_idx(0);
+        let scol = SectorCol::from_idx(0);
+        let cases = [
+            (DeductionReason::InitialState, "Initial State"),
+            (
+                DeductionReason::CoordNeighbors {
+                    pos: coord(0, 0),
+                    val: Val::new(1),
+                },
+                "Naked Single",
+            ),
+            (
+                DeductionReason::UniqueInRow {
+                    pos: Row::new(0),
+                    vals,
+                },
+                "Hidden Single",
+            ),
+            (
+                DeductionReason::UniqueInCol {
+                    pos: Col::new(0),
+                    vals,
+                },
+                "Hidden Single",
+            ),
+            (
+                DeductionReason::UniqueInSector {
+                    pos: Sector::from_idx(0),
+                    vals,
+                },
+                "Hidden Single",
+            ),
+            (
+                DeductionReason::SecRowTriple { pos: srow, vals },
+                "Locked Triple",
+            ),
+            (
+                DeductionReason::SecColTriple { pos: scol, vals },
+                "Locked Triple",
+            ),
+            (
+                DeductionReason::SecOnlyRow { pos: srow, vals },
+                "Pointing Pair/Triple",
+            ),
+            (
+                DeductionReason::SecOnlyCol { pos: scol, vals },
+                "Pointing Pair/Triple",
+            ),
+            (
+                DeductionReason::RowOnlySec { pos: srow, vals },
+                "Box/Line Reduction",
+            ),
+            (
+                DeductionReason::ColOnlySec { pos: scol, vals },
+                "Box/Line Reduction",
+            ),
+            (
+                DeductionReason::HiddenPair {
+                    pos: House::Row(Row::new(0)),
+                    cells: [coord(0, 0), coord(0, 1)],
+                    vals,
+                },
+                "Hidden Pair",
+            ),
+            (
+                DeductionReason::HiddenTriple {
+                    pos: House::Col(Col::new(0)),
+                    cells: [coord(0, 0), coord(1, 0), coord(2, 0)],
+                    vals,
+                },
+                "Hidden Triple",
+            ),
+            (
+                DeductionReason::XWing {
+                    rows: [Row::new(0), Row::new(4)],
+                    cols: [Col::new(2), Col::new(7)],
+                    val: Val::new(5),
+                    by_rows: true,
+                },
+                "X-Wing",
+            ),
+            (
+                DeductionReason::Unsolveable(UnsolveableReason::Empty { pos: coord(0, 0) }),
+                "Contradiction",
+            ),
+        ];
+        for (reason, name) in &cases {
+            assert_eq!(reason.technique_name(), *name, "{:?}", reason);
+        }
+        // Every rule is covered.
+        let kinds: Vec<_> = cases
+            .iter()
+            .filter_map(|(reason, _)| reason.rule_kind())
+            .collect();
+        assert_eq!(kinds, RuleKind::ALL);
+    }
+
     #[cfg(feature = "serde")]
     mod serde {
         use super::super::*;
+        use crate::board_art;
+        use crate::fixtures::inkala;
 
         use log::debug;
 
@@ -303,6 +1347,8 @@ mod tests {
                     val: Val::new(8),
                 },
                 remaining: IndexMap::with_value(AvailSet::all()).into(),
+                before: None,
+                eliminated_count: 0,
             };
             let ser = serde_json::to_string(&deduction).unwrap();
             debug!("Deduction CoordNeighbors Ser: {}", ser);
@@ -319,6 +1365,8 @@ mod tests {
                     pos: Coord::new(Row::new(3), Col::new(5)),
                 }),
                 remaining: IndexMap::with_value(AvailSet::none()).into(),
+                before: Some(IndexMap::with_value(AvailSet::all()).into()),
+                eliminated_count: 0,
             };
 
             let ser = serde_json::to_string(&deduction).unwrap();
@@ -327,6 +1375,131 @@ mod tests {
             assert_eq!(roundtrip, deduction);
         }
 
+        #[test]
+        fn json_lines_tracer() {
+            crate::setup();
+
+            let board = board_art!(
+                "   |8  | 14",
+                "1 6|4  |75 ",
+                " 47|53 |   ",
+                "---+---+---",
+                "9  | 5 | 62",
+                "   |7 9|   ",
+                "63 | 4 |  5",
+                "---+---+---",
+                "   | 87|34 ",
+                " 14|  5|6 9",
+                "89 |  4|   ",
+            );
+            let (solution, tracer) = board.solve_with(JsonLinesTracer::new(Vec::new()));
+            let expected: Vec<Deduction> = Vec::new();
+            let (_, expected) = board.solve_with(expected);
+            let buf = tracer.into_inner().unwrap();
+            let output = std::str::from_utf8(&buf).unwrap();
+            let lines: Vec<Deduction> = output
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect();
+            assert!(solution.is_some());
+            assert!(!lines.is_empty());
+            assert_eq!(lines, expected);
+            assert_eq!(lines.last().unwrap().remaining.board(), solution.unwrap());
+        }
+
+        #[test]
+        fn serialize_trace_deterministic() {
+            crate::setup();
+
+            let board = board_art!(
+                " 49|   |65 ",
+                " 5 |8 7|  3",
+                "   |46 |   ",
+                "---+---+---",
+                "27 |   |   ",
+                "  4|5 1|8  ",
+                "   |   | 32",
+                "---+---+---",
+                "   | 42|   ",
+                "9  |3 6| 2 ",
+                " 27|   |31 ",
+            );
+            // Identical requests must produce byte-identical output so
+            // responses can be cached.
+            let (_, first) = board.solve_traced::<TraceTree>();
+            let (_, second) = board.solve_traced::<TraceTree>();
+            let first = serde_json::to_string(&first).unwrap();
+            let second = serde_json::to_string(&second).unwrap();
+            assert_eq!(first, second);
+        }
+
+        #[test]
+        fn limited_roundtrip() {
+            crate::setup();
+
+            let board = inkala();
+            let (_, tree) = board.solve_traced::<TraceTree>();
+            assert!(matches!(tree, TraceTree::Guess { .. }));
+            let ser = serde_json::to_string(&tree).unwrap();
+            let roundtrip = TraceTree::from_json_limited(&ser, &TraceLimits::default()).unwrap();
+            assert_eq!(roundtrip, tree);
+
+            let limits = TraceLimits {
+                max_bytes: ser.len() - 1,
+                ..Default::default()
+            };
+            assert!(matches!(
+                TraceTree::from_json_limited(&ser, &limits),
+                Err(TraceParseError::TooLarge { .. })
+            ));
+            let limits = TraceLimits {
+                max_depth: 0,
+                ..Default::default()
+            };
+            assert!(matches!(
+                TraceTree::from_json_limited(&ser, &limits),
+                Err(TraceParseError::TooDeep { max: 0 })
+            ));
+        }
+
+        #[test]
+        fn limited_rejects_deep_nesting() {
+            const DEPTH: usize = 10_000;
+            let mut input = String::new();
+            for _ in 0..DEPTH {
+                input.push_str(r#"{"type":"guess","deduction":[],"guesses":["#);
+            }
+            input.push_str(r#"{"type":"solution","deduction":[]}"#);
+            for _ in 0..DEPTH {
+                input.push_str("]}");
+            }
+            // Run with a small stack to make sure parsing does not recurse
+            // past the limit.
+            let res = std::thread::Builder::new()
+                .stack_size(256 * 1024)
+                .spawn(move || {
+                    matches!(
+                        TraceTree::from_json_limited(&input, &TraceLimits::default()),
+                        Err(TraceParseError::TooDeep { max: 60 })
+                    )
+                })
+                .unwrap()
+                .join()
+                .unwrap();
+            assert!(res);
+        }
+
+        #[test]
+        fn limited_reports_json_errors() {
+            let res = TraceTree::from_json_limited(r#"{"type":"guess"}"#, &Default::default());
+            assert!(matches!(res, Err(TraceParseError::Json(_))));
+            let res = TraceTree::from_json_limited(
+                r#"{"type":"solution","deduction":[]} x"#,
+                &Default::default(),
+            );
+            assert!(matches!(res, Err(TraceParseError::Json(_))));
+        }
+
         #[test]
         fn serialize_tree() {
             crate::setup();
@@ -338,6 +1511,8 @@ mod tests {
                         val: Val::new(8),
                     },
                     remaining: IndexMap::with_value(AvailSet::all()).into(),
+                    before: None,
+                    eliminated_count: 0,
                 }],
             };
             let ser = serde_json::to_string(&tree).unwrap();
@@ -345,5 +1520,40 @@ mod tests {
             let roundtrip: TraceTree = serde_json::from_str(&ser).unwrap();
             assert_eq!(roundtrip, tree);
         }
+
+        #[test]
+        fn serialize_arc_remaining() {
+            let mut map = IndexMap::with_value(AvailSet::all());
+            map[Coord::new(Row::new(1), Col::new(2))] = AvailSet::only(Val::new(4));
+            let rem = Remaining::from(map);
+            let arc = ArcRemaining::from(rem.clone());
+            let ser = serde_json::to_string(&arc).unwrap();
+            assert_eq!(ser, serde_json::to_string(&rem).unwrap());
+            let roundtrip: ArcRemaining = serde_json::from_str(&ser).unwrap();
+            assert_eq!(roundtrip, arc);
+        }
+
+        #[test]
+        fn deserialize_closed_remaining() {
+            let mut map = IndexMap::with_value(AvailSet::all());
+            map[Coord::new(Row::new(0), Col::new(0))] = AvailSet::only(Val::new(4));
+            let rem = Remaining::from(map.clone());
+            let ser = serde_json::to_string(&rem).unwrap();
+            let closed: ClosedRemaining = serde_json::from_str(&ser).unwrap();
+            assert_eq!(closed, rem.close().unwrap());
+            assert_eq!(
+                serde_json::to_string(&closed).unwrap(),
+                serde_json::to_string(&*closed).unwrap()
+            );
+
+            map[Coord::new(Row::new(0), Col::new(8))] = AvailSet::only(Val::new(4));
+            let ser = serde_json::to_string(&Remaining::from(map)).unwrap();
+            let err = serde_json::from_str::<ClosedRemaining>(&ser).unwrap_err();
+            assert!(
+                err.to_string().contains("no values left at row 0 column 8"),
+                "{}",
+                err
+            );
+        }
     }
 }
@@ -0,0 +1,264 @@
+//! What changed between two snapshots of the remaining candidates.
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::Remaining;
+use crate::{AvailSet, Coord, House, Val, Zone};
+
+/// Change to the candidates of a single cell.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CellDiff {
+    /// The cell which changed.
+    pub coord: Coord,
+    /// Values which were candidates before but not after.
+    pub removed: AvailSet,
+    /// Values which were not candidates before but are after. Solving only
+    /// ever removes candidates, so any value here means the snapshots are
+    /// inconsistent.
+    pub added: AvailSet,
+    /// The candidates left in the cell afterwards.
+    pub remaining: AvailSet,
+}
+
+impl CellDiff {
+    /// The candidates in the cell before the change.
+    #[must_use]
+    pub fn before(&self) -> AvailSet {
+        (self.remaining - self.added) | self.removed
+    }
+}
+
+/// Difference between two snapshots of the remaining candidates, listing every
+/// cell which changed in row-major order. This is the one place the crate
+/// compares snapshots, so eliminations and placements are worked out the same
+/// way everywhere.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct RemainingDiff {
+    cells: Vec<CellDiff>,
+}
+
+impl RemainingDiff {
+    /// Find what changed from `before` to `after`.
+    #[must_use]
+    pub fn between(before: &Remaining, after: &Remaining) -> Self {
+        Self::between_cells(before.as_ref(), after.as_ref())
+    }
+
+    /// Find what changed between two snapshots given as cells in row-major
+    /// order, so shared snapshots can be compared without copying them.
+    pub(crate) fn between_cells(before: &[AvailSet], after: &[AvailSet]) -> Self {
+        let cells = Coord::all()
+            .zip(before.iter().zip(after))
+            .filter(|(_, (before, after))| before != after)
+            .map(|(coord, (&before, &after))| CellDiff {
+                coord,
+                removed: before - after,
+                added: after - before,
+                remaining: after,
+            })
+            .collect();
+        RemainingDiff { cells }
+    }
+
+    /// The cells which changed, in row-major order.
+    #[must_use]
+    pub fn cells(&self) -> &[CellDiff] {
+        &self.cells
+    }
+
+    /// Returns true if nothing changed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Returns true if candidates were only removed, never added, as is the
+    /// case for any two snapshots taken in order during solving.
+    #[must_use]
+    pub fn is_monotone(&self) -> bool {
+        self.cells.iter().all(|cell| cell.added.is_empty())
+    }
+
+    /// Every candidate removed, in row-major order and then by value.
+    #[must_use]
+    pub fn eliminations(&self) -> Vec<(Coord, Val)> {
+        self.cells
+            .iter()
+            .flat_map(|cell| cell.removed.into_iter().map(move |val| (cell.coord, val)))
+            .collect()
+    }
+
+    /// Every cell left with a single value which had more than one before,
+    /// with that value, in row-major order.
+    #[must_use]
+    pub fn placements(&self) -> Vec<(Coord, Val)> {
+        self.cells
+            .iter()
+            .filter(|cell| cell.before().len() > 1)
+            .filter_map(|cell| cell.remaining.get_single().map(|val| (cell.coord, val)))
+            .collect()
+    }
+
+    /// Every row, column, and sector containing a changed cell, in `House`
+    /// order.
+    #[must_use]
+    pub fn affected_houses(&self) -> Vec<House> {
+        let mut houses: Vec<_> = self
+            .cells
+            .iter()
+            .flat_map(|cell| House::containing(cell.coord))
+            .collect();
+        houses.sort_unstable();
+        houses.dedup();
+        houses
+    }
+
+    /// Apply the change to a snapshot, removing and adding the same values in
+    /// each changed cell. Applying the diff to `before` gives `after`.
+    #[must_use]
+    pub fn apply(&self, base: &Remaining) -> Remaining {
+        let mut result = base.clone();
+        for cell in &self.cells {
+            result[cell.coord] = (result[cell.coord] - cell.removed) | cell.added;
+        }
+        result
+    }
+
+    /// The diff which undoes this one, going from `after` back to `before`.
+    #[must_use]
+    pub fn invert(&self) -> Self {
+        let cells = self
+            .cells
+            .iter()
+            .map(|cell| CellDiff {
+                coord: cell.coord,
+                removed: cell.added,
+                added: cell.removed,
+                remaining: cell.before(),
+            })
+            .collect();
+        RemainingDiff { cells }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board_art;
+    use crate::collections::indexed::IndexMap;
+    use crate::trace::{Deduction, DeductionReason};
+    use crate::{Col, Row, Sector};
+
+    fn trace() -> Vec<Deduction> {
+        let board = board_art!(
+            "467|192|385",
+            "329|458|671",
+            "851|367|294",
+            "---+---+---",
+            "518|279|463",
+            "27 |6 1|859",
+            "69 |8 5|127",
+            "---+---+---",
+            "732|984|516",
+            "145|726|938",
+            "986|513|742",
+        );
+        let (_, trace) = board.solve_with(Vec::<Deduction>::new());
+        trace
+    }
+
+    #[test]
+    fn round_trips() {
+        crate::setup();
+
+        let trace = trace();
+        assert!(trace.len() > 2);
+        for pair in trace.windows(2) {
+            let before = Remaining::from(pair[0].remaining.clone());
+            let after = Remaining::from(pair[1].remaining.clone());
+            let diff = RemainingDiff::between(&before, &after);
+            let inverse = diff.invert();
+            assert_eq!(diff.apply(&before), after);
+            assert_eq!(inverse.apply(&after), before);
+            assert_eq!(inverse, RemainingDiff::between(&after, &before));
+            assert_eq!(inverse.invert(), diff);
+            if !matches!(pair[1].reason, DeductionReason::InitialState) {
+                assert!(diff.is_monotone());
+                assert_eq!(diff.eliminations().len(), pair[1].eliminated_count);
+            }
+        }
+    }
+
+    #[test]
+    fn non_monotone() {
+        let trace = trace();
+        let before = Remaining::from(trace[0].remaining.clone());
+        let after = Remaining::from(trace[1].remaining.clone());
+        let diff = RemainingDiff::between(&after, &before);
+        assert!(!diff.is_empty());
+        assert!(!diff.is_monotone());
+        assert!(diff.eliminations().is_empty());
+        assert!(diff.placements().is_empty());
+        assert!(RemainingDiff::between(&before, &before).is_empty());
+        assert!(RemainingDiff::between(&before, &before).is_monotone());
+    }
+
+    #[test]
+    fn placements_match_boards() {
+        let trace = trace();
+        for pair in trace.windows(2) {
+            let diff = RemainingDiff::between_cells(
+                pair[0].remaining.as_ref(),
+                pair[1].remaining.as_ref(),
+            );
+            let (before, after) = (pair[0].remaining.board(), pair[1].remaining.board());
+            let expected: Vec<_> = Coord::all()
+                .filter(|&coord| before[coord].is_none())
+                .filter_map(|coord| after[coord].map(|val| (coord, val)))
+                .collect();
+            assert_eq!(diff.placements(), expected);
+        }
+    }
+
+    #[test]
+    fn affected_houses() {
+        let mut before = Remaining::from(IndexMap::with_value(AvailSet::all()));
+        let at = Coord::new(Row::new(4), Col::new(7));
+        before[at] = AvailSet::only(Val::new(3)) | Val::new(5);
+        let mut after = before.clone();
+        after[at] = AvailSet::only(Val::new(5));
+        let diff = RemainingDiff::between(&before, &after);
+        assert_eq!(
+            diff.cells(),
+            [CellDiff {
+                coord: at,
+                removed: AvailSet::only(Val::new(3)),
+                added: AvailSet::none(),
+                remaining: AvailSet::only(Val::new(5)),
+            }]
+        );
+        assert_eq!(diff.placements(), [(at, Val::new(5))]);
+        assert_eq!(
+            diff.affected_houses(),
+            [
+                House::Row(Row::new(4)),
+                House::Col(Col::new(7)),
+                House::Sector(Sector::containing(at)),
+            ]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize() {
+        let trace = trace();
+        let before = Remaining::from(trace[0].remaining.clone());
+        let after = Remaining::from(trace[1].remaining.clone());
+        let diff = RemainingDiff::between(&before, &after);
+        let ser = serde_json::to_string(&diff).unwrap();
+        assert!(ser.starts_with(r#"[{"coord":{"row":"#));
+        assert_eq!(serde_json::from_str::<RemainingDiff>(&ser).unwrap(), diff);
+    }
+}
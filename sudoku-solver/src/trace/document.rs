@@ -0,0 +1,325 @@
+//! Versioned wrapper for traces which are stored and read back later.
+//!
+//! Every document records the schema its body was written with. Reading a
+//! document upgrades the body one schema at a time until it matches the current
+//! format, so traces saved by older versions keep loading as the format
+//! changes.
+use serde::Serialize;
+use serde_json::Value;
+use thiserror::Error;
+
+use super::{Deduction, TraceLimits, TraceParseError, TraceTree};
+
+/// Oldest schema which can still be read.
+const OLDEST_SCHEMA: u32 = 1;
+
+/// Upgrades a document body from one schema to the next. Bodies may be either
+/// a trace tree or a list of deductions, so a migration which changes
+/// deductions must handle both.
+type Migration = fn(&mut Value) -> Result<(), String>;
+
+/// Migrations from each supported schema to the next, starting from
+/// `OLDEST_SCHEMA`. Changing the serialized trace format means adding a
+/// migration here and a frozen fixture for the schema being replaced.
+const MIGRATIONS: &[Migration] = &[add_eliminated_count];
+
+/// The schema version of traces written by this version of the library.
+pub const CURRENT_SCHEMA: u32 = OLDEST_SCHEMA + MIGRATIONS.len() as u32;
+
+/// A trace along with the schema it was written with. Serialize this instead of
+/// the bare trace when it will be stored, and read it back with `parse`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct TraceDocument<T = TraceTree> {
+    /// Schema version of the body.
+    pub schema: u32,
+    /// The trace.
+    pub body: T,
+}
+
+impl<T> TraceDocument<T> {
+    /// Wrap a trace in a document with the current schema.
+    pub fn new(body: T) -> Self {
+        TraceDocument {
+            schema: CURRENT_SCHEMA,
+            body,
+        }
+    }
+}
+
+impl TraceDocument {
+    /// Parse a trace tree document written with any supported schema.
+    pub fn parse(input: &str) -> Result<TraceTree, TraceFormatError> {
+        Self::parse_limited(input, &Default::default())
+    }
+
+    /// Parse a trace tree document written with any supported schema, applying
+    /// the same limits as `TraceTree::from_json_limited`.
+    pub fn parse_limited(input: &str, limits: &TraceLimits) -> Result<TraceTree, TraceFormatError> {
+        let body = read_body(input, limits, MIGRATIONS)?;
+        Ok(TraceTree::deserialize_limited(body, limits.max_depth)?)
+    }
+}
+
+impl TraceDocument<Vec<Deduction>> {
+    /// Parse a document holding a list of deductions written with any
+    /// supported schema.
+    pub fn parse_deductions(input: &str) -> Result<Vec<Deduction>, TraceFormatError> {
+        let body = read_body(input, &Default::default(), MIGRATIONS)?;
+        serde_json::from_value(body).map_err(|err| TraceParseError::Json(err).into())
+    }
+}
+
+/// Error when reading a trace document.
+#[derive(Debug, Error)]
+pub enum TraceFormatError {
+    /// The document did not say which schema it was written with.
+    #[error("trace document has no schema version")]
+    MissingSchema,
+    /// The document was written by a newer version of the library.
+    #[error(
+        "trace document has schema {schema} but only schemas up to {current} are \
+         supported; it was produced by a newer version"
+    )]
+    NewerSchema { schema: u64, current: u32 },
+    /// The document was written with a schema which can no longer be read.
+    #[error("trace document schema {schema} is older than the oldest supported schema {oldest}")]
+    UnsupportedSchema { schema: u64, oldest: u32 },
+    /// The body could not be upgraded from the given schema.
+    #[error("could not upgrade trace document from schema {from}: {message}")]
+    Migration { from: u32, message: String },
+    /// The body was not a valid trace once upgraded.
+    #[error(transparent)]
+    Parse(#[from] TraceParseError),
+}
+
+/// Parse the document and upgrade its body to the current schema using the
+/// given migrations.
+fn read_body(
+    input: &str,
+    limits: &TraceLimits,
+    migrations: &[Migration],
+) -> Result<Value, TraceFormatError> {
+    if input.len() > limits.max_bytes {
+        return Err(TraceParseError::TooLarge {
+            len: input.len(),
+            max: limits.max_bytes,
+        }
+        .into());
+    }
+    let mut document: Value = serde_json::from_str(input).map_err(TraceParseError::Json)?;
+    let schema = document
+        .get("schema")
+        .and_then(Value::as_u64)
+        .ok_or(TraceFormatError::MissingSchema)?;
+    let body = document
+        .get_mut("body")
+        .map(Value::take)
+        .ok_or_else(|| TraceParseError::Json(serde::de::Error::missing_field("body")))?;
+    upgrade(body, schema, migrations)
+}
+
+/// Apply every migration needed to bring a body from the given schema to the
+/// newest schema the migrations reach.
+fn upgrade(
+    mut body: Value,
+    schema: u64,
+    migrations: &[Migration],
+) -> Result<Value, TraceFormatError> {
+    let current = OLDEST_SCHEMA + migrations.len() as u32;
+    if schema > current as u64 {
+        return Err(TraceFormatError::NewerSchema { schema, current });
+    }
+    if schema < OLDEST_SCHEMA as u64 {
+        return Err(TraceFormatError::UnsupportedSchema {
+            schema,
+            oldest: OLDEST_SCHEMA,
+        });
+    }
+    let schema = schema as u32;
+    for (from, migration) in (schema..).zip(&migrations[(schema - OLDEST_SCHEMA) as usize..]) {
+        migration(&mut body).map_err(|message| TraceFormatError::Migration { from, message })?;
+    }
+    Ok(body)
+}
+
+/// Schema 1 to 2: every deduction records how many candidates it removed.
+fn add_eliminated_count(body: &mut Value) -> Result<(), String> {
+    match body {
+        Value::Array(deductions) => count_eliminated(deductions),
+        Value::Object(node) => {
+            if let Some(deductions) = node.get_mut("deduction") {
+                let deductions = deductions
+                    .as_array_mut()
+                    .ok_or("trace node deductions are not a list")?;
+                count_eliminated(deductions)?;
+            }
+            if let Some(guesses) = node.get_mut("guesses") {
+                let guesses = guesses
+                    .as_array_mut()
+                    .ok_or("trace node guesses are not a list")?;
+                guesses.iter_mut().try_for_each(add_eliminated_count)?;
+            }
+            Ok(())
+        }
+        _ => Err("trace body is neither a trace node nor a list of deductions".into()),
+    }
+}
+
+/// Add `eliminated_count` to each deduction in a list, the same way
+/// `Deduction::new` computes it.
+fn count_eliminated(deductions: &mut [Value]) -> Result<(), String> {
+    let mut prev_candidates: usize = 0;
+    for deduction in deductions {
+        let candidates = deduction
+            .get("remaining")
+            .and_then(Value::as_array)
+            .and_then(|cells| {
+                cells
+                    .iter()
+                    .map(|cell| cell.as_array().map(Vec::len))
+                    .sum::<Option<usize>>()
+            })
+            .ok_or("deduction has no valid remaining candidates")?;
+        let eliminated = match deduction.get("kind").and_then(Value::as_str) {
+            Some("initial_state") => 0,
+            _ => prev_candidates.saturating_sub(candidates),
+        };
+        deduction
+            .as_object_mut()
+            .ok_or("deduction is not an object")?
+            .insert("eliminated_count".into(), eliminated.into());
+        prev_candidates = candidates;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::board_art;
+    use crate::Board;
+
+    const TRACE_V1: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/golden/trace_v1.json"
+    ));
+    const DEDUCTIONS_V1: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/golden/deductions_v1.json"
+    ));
+
+    /// Board the frozen fixtures were made from. A solved board with a deadly
+    /// rectangle removed, so solving it takes one guess.
+    fn fixture_board() -> Board {
+        board_art!(
+            "467|192|385",
+            "329|458|671",
+            "851|367|294",
+            "---+---+---",
+            "518|279|463",
+            "27 |6 1|859",
+            "69 |8 5|127",
+            "---+---+---",
+            "732|984|516",
+            "145|726|938",
+            "986|513|742",
+        )
+    }
+
+    #[test]
+    fn read_v1_fixtures() {
+        crate::setup();
+
+        let board = fixture_board();
+        let (_, tree) = board.solve_traced::<TraceTree>();
+        assert!(matches!(tree, TraceTree::Guess { .. }));
+        assert_eq!(TraceDocument::parse(TRACE_V1).unwrap(), tree);
+
+        let (_, deductions) = board.solve_with(Vec::<Deduction>::new());
+        assert_eq!(
+            TraceDocument::parse_deductions(DEDUCTIONS_V1).unwrap(),
+            deductions
+        );
+    }
+
+    #[test]
+    fn roundtrip_current() {
+        crate::setup();
+
+        let (_, tree) = fixture_board().solve_traced::<TraceTree>();
+        let doc = TraceDocument::new(tree);
+        assert_eq!(doc.schema, CURRENT_SCHEMA);
+        let ser = serde_json::to_string(&doc).unwrap();
+        assert!(ser.starts_with(&format!(r#"{{"schema":{},"body":"#, CURRENT_SCHEMA)));
+        assert_eq!(TraceDocument::parse(&ser).unwrap(), doc.body);
+    }
+
+    #[test]
+    fn schema_errors() {
+        let newer = format!(r#"{{"schema":{},"body":{{}}}}"#, CURRENT_SCHEMA + 1);
+        let err = TraceDocument::parse(&newer).unwrap_err();
+        assert!(matches!(err, TraceFormatError::NewerSchema { .. }));
+        assert!(err.to_string().contains("produced by a newer version"));
+
+        assert!(matches!(
+            TraceDocument::parse(r#"{"schema":0,"body":{}}"#),
+            Err(TraceFormatError::UnsupportedSchema { schema: 0, .. })
+        ));
+        assert!(matches!(
+            TraceDocument::parse(r#"{"type":"solution","deduction":[]}"#),
+            Err(TraceFormatError::MissingSchema)
+        ));
+        assert!(matches!(
+            TraceDocument::parse(r#"{"schema":1}"#),
+            Err(TraceFormatError::Parse(TraceParseError::Json(_)))
+        ));
+        assert!(matches!(
+            TraceDocument::parse(r#"{"schema":1,"body":{"type":"guess"}}"#),
+            Err(TraceFormatError::Parse(TraceParseError::Json(_)))
+        ));
+        let limits = TraceLimits {
+            max_bytes: 4,
+            ..Default::default()
+        };
+        assert!(matches!(
+            TraceDocument::parse_limited(r#"{"schema":1,"body":{}}"#, &limits),
+            Err(TraceFormatError::Parse(TraceParseError::TooLarge { .. }))
+        ));
+    }
+
+    #[test]
+    fn upgrade_applies_migrations_in_order() {
+        fn rename(body: &mut Value) -> Result<(), String> {
+            let old = body.get_mut("old").ok_or("missing old")?.take();
+            body["renamed"] = old;
+            Ok(())
+        }
+        fn add_count(body: &mut Value) -> Result<(), String> {
+            body["count"] = json!(body.as_object().map_or(0, |obj| obj.len()));
+            Ok(())
+        }
+        let migrations: &[Migration] = &[rename, add_count];
+
+        let body = upgrade(json!({"old": 5}), 1, migrations).unwrap();
+        assert_eq!(body, json!({"old": null, "renamed": 5, "count": 2}));
+        // Bodies already past a migration skip it.
+        let body = upgrade(json!({"renamed": 5}), 2, migrations).unwrap();
+        assert_eq!(body, json!({"renamed": 5, "count": 1}));
+        let body = upgrade(json!({"x": 1}), 3, migrations).unwrap();
+        assert_eq!(body, json!({"x": 1}));
+
+        assert!(matches!(
+            upgrade(json!({}), 1, migrations),
+            Err(TraceFormatError::Migration { from: 1, .. })
+        ));
+        assert!(matches!(
+            upgrade(json!({}), 4, migrations),
+            Err(TraceFormatError::NewerSchema {
+                schema: 4,
+                current: 3
+            })
+        ));
+    }
+}
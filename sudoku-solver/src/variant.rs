@@ -0,0 +1,449 @@
+//! Hooks for solving variants of Sudoku with rules beyond rows, columns, and
+//! sectors.
+//!
+//! Rules are added to `Variants`, which is set on a `Solver`. Extra peers cover
+//! rules which forbid pairs of cells from sharing a value, such as anti-knight
+//! or anti-king. Extra houses cover regions which may not repeat a value, such
+//! as diagonals or killer cages, and which must hold every value if they have
+//! nine cells. Branch filters veto guesses, for rules which are easier to check
+//! on a partial board than to deduce from, such as sandwich sums.
+//!
+//! After each round of the built-in deduction rules, the solver eliminates the
+//! value of every solved cell from its extra peers and houses, fills values
+//! which have only one place left in a nine-cell extra house, and deduces again
+//! until nothing changes.
+use std::fmt;
+use std::sync::Arc;
+
+use crate::solve::remaining::RemainingTracker;
+use crate::trace::Remaining;
+use crate::{AvailSet, Board, Coord, DynZone, Val, Zone};
+
+/// Extra pairs of cells which may not hold the same value.
+pub trait ExtraPeers {
+    /// Cells which may not hold the same value as the given cell, besides
+    /// those sharing a row, column, or sector with it. Need not be symmetric:
+    /// if `b` is a peer of `a`, `a` is treated as a peer of `b` as well.
+    fn peers(&self, coord: Coord) -> Vec<Coord>;
+}
+
+impl<F: Fn(Coord) -> Vec<Coord>> ExtraPeers for F {
+    fn peers(&self, coord: Coord) -> Vec<Coord> {
+        self(coord)
+    }
+}
+
+/// Vetoes branches while the solver is guessing.
+pub trait BranchFilter {
+    /// Returns false if no solution can be reached from the candidates of a
+    /// branch the solver is about to try, so it can be skipped. Must never
+    /// return false for a branch which leads to a solution.
+    fn allows(&self, branch: &Remaining) -> bool;
+}
+
+impl<F: Fn(&Remaining) -> bool> BranchFilter for F {
+    fn allows(&self, branch: &Remaining) -> bool {
+        self(branch)
+    }
+}
+
+/// A region of cells which may not repeat a value. A region of nine cells must
+/// hold every value.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ExtraHouse {
+    cells: Vec<Coord>,
+}
+
+impl ExtraHouse {
+    /// Build a house from the given cells. Repeated cells are ignored.
+    pub fn new(cells: impl IntoIterator<Item = Coord>) -> Self {
+        let mut cells: Vec<_> = cells.into_iter().collect();
+        cells.sort_unstable();
+        cells.dedup();
+        ExtraHouse { cells }
+    }
+
+    /// The cells of the house, in row-major order.
+    #[must_use]
+    pub fn cells(&self) -> &[Coord] {
+        &self.cells
+    }
+
+    /// Whether the house must hold every value.
+    fn is_complete(&self) -> bool {
+        self.cells.len() == AvailSet::all().len()
+    }
+}
+
+impl<Z: DynZone> From<&Z> for ExtraHouse {
+    fn from(zone: &Z) -> Self {
+        ExtraHouse::new(zone.dyn_coords())
+    }
+}
+
+/// Rules added to the classic ones. The default has none, which solves classic
+/// Sudoku.
+#[derive(Clone, Default)]
+pub struct Variants {
+    /// Extra peers of each cell, in row-major order, or empty if there are
+    /// none.
+    peers: Vec<Vec<Coord>>,
+    houses: Vec<ExtraHouse>,
+    filters: Vec<Arc<dyn BranchFilter + Send + Sync>>,
+}
+
+impl Variants {
+    /// Variants with no extra rules.
+    #[must_use]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add extra peers. The peers of every cell are collected once, here.
+    #[must_use]
+    pub fn with_peers(mut self, extra: impl ExtraPeers) -> Self {
+        if self.peers.is_empty() {
+            self.peers = vec![Vec::new(); Board::SIZE];
+        }
+        for coord in Coord::all() {
+            for peer in extra.peers(coord) {
+                if peer == coord || coord.neighbors().any(|n| n == peer) {
+                    continue;
+                }
+                for (a, b) in [(coord, peer), (peer, coord)] {
+                    let peers = &mut self.peers[a.rowmajor_idx()];
+                    if !peers.contains(&b) {
+                        peers.push(b);
+                    }
+                }
+            }
+        }
+        for peers in &mut self.peers {
+            peers.sort_unstable();
+        }
+        self
+    }
+
+    /// Add an extra house.
+    #[must_use]
+    pub fn with_house(mut self, house: impl Into<ExtraHouse>) -> Self {
+        self.houses.push(house.into());
+        self
+    }
+
+    /// Add a branch filter.
+    #[must_use]
+    pub fn with_filter(mut self, filter: impl BranchFilter + Send + Sync + 'static) -> Self {
+        self.filters.push(Arc::new(filter));
+        self
+    }
+
+    /// Returns true if there are no extra rules.
+    #[must_use]
+    pub fn is_classic(&self) -> bool {
+        self.peers.is_empty() && self.houses.is_empty() && self.filters.is_empty()
+    }
+
+    /// The extra peers of the cell, in row-major order.
+    #[must_use]
+    pub fn peers(&self, coord: Coord) -> &[Coord] {
+        self.peers
+            .get(coord.rowmajor_idx())
+            .map_or(&[], |peers| &peers[..])
+    }
+
+    /// The extra houses.
+    #[must_use]
+    pub fn houses(&self) -> &[ExtraHouse] {
+        &self.houses
+    }
+
+    /// Every pair of filled cells which break an extra rule by holding the same
+    /// value, with the value they share. Pairs are listed once, with the cells
+    /// in row-major order, sorted as in `Board::conflicts`.
+    #[must_use]
+    pub fn conflicts(&self, board: &Board) -> Vec<(Coord, Coord, Val)> {
+        let mut conflicts = Vec::new();
+        for coord in Coord::all() {
+            let Some(val) = board[coord] else { continue };
+            for &peer in self.peers(coord) {
+                if peer > coord && board[peer] == Some(val) {
+                    conflicts.push((coord, peer, val));
+                }
+            }
+        }
+        for house in &self.houses {
+            for (i, &a) in house.cells.iter().enumerate() {
+                for &b in &house.cells[i + 1..] {
+                    match (board[a], board[b]) {
+                        (Some(x), Some(y)) if x == y && !a.neighbors().any(|n| n == b) => {
+                            conflicts.push((a, b, x))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        conflicts.sort_unstable();
+        conflicts.dedup();
+        conflicts
+    }
+
+    /// Returns true if no filter vetoes the branch.
+    pub(crate) fn allows_branch(&self, branch: &RemainingTracker) -> bool {
+        if self.filters.is_empty() {
+            return true;
+        }
+        let remaining = branch.remaining();
+        self.filters.iter().all(|filter| filter.allows(&remaining))
+    }
+
+    /// Apply the extra peers and houses to the tracker until nothing changes.
+    /// Returns whether anything was eliminated, or Err if the candidates
+    /// became contradictory.
+    pub(crate) fn propagate(&self, tracker: &mut RemainingTracker) -> Result<bool, ()> {
+        if self.peers.is_empty() && self.houses.is_empty() {
+            return Ok(false);
+        }
+        let mut changed = false;
+        loop {
+            let mut pass = false;
+            for coord in Coord::all() {
+                let Some(val) = tracker[coord].get_single() else {
+                    continue;
+                };
+                for &peer in self.peers(coord) {
+                    pass |= eliminate(tracker, peer, val)?;
+                }
+            }
+            for house in &self.houses {
+                for &coord in &house.cells {
+                    let Some(val) = tracker[coord].get_single() else {
+                        continue;
+                    };
+                    for &other in &house.cells {
+                        if other != coord {
+                            pass |= eliminate(tracker, other, val)?;
+                        }
+                    }
+                }
+                if house.is_complete() {
+                    for val in AvailSet::all() {
+                        let mut places = house.cells.iter().filter(|&&c| tracker[c].contains(val));
+                        match (places.next(), places.next()) {
+                            (None, _) => return Err(()),
+                            (Some(&only), None) if !tracker[only].is_single() => {
+                                tracker.exclude(only, tracker[only] - val);
+                                pass = true;
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            if !pass {
+                break;
+            }
+            changed = true;
+        }
+        if changed && tracker.known_unsolveable() {
+            return Err(());
+        }
+        Ok(changed)
+    }
+}
+
+/// Eliminate the value from the cell, returning whether it was there, or Err
+/// if the cell is left empty.
+fn eliminate(tracker: &mut RemainingTracker, coord: Coord, val: Val) -> Result<bool, ()> {
+    if !tracker[coord].contains(val) {
+        return Ok(false);
+    }
+    tracker.exclude(coord, AvailSet::only(val));
+    if tracker[coord].is_empty() {
+        Err(())
+    } else {
+        Ok(true)
+    }
+}
+
+impl PartialEq for Variants {
+    /// Filters compare equal only if they are the same filter.
+    fn eq(&self, other: &Self) -> bool {
+        self.peers == other.peers
+            && self.houses == other.houses
+            && self.filters.len() == other.filters.len()
+            && self
+                .filters
+                .iter()
+                .zip(&other.filters)
+                .all(|(a, b)| Arc::ptr_eq(a, b))
+    }
+}
+
+impl Eq for Variants {}
+
+impl fmt::Debug for Variants {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Variants")
+            .field("peers", &self.peers.iter().map(Vec::len).sum::<usize>())
+            .field("houses", &self.houses)
+            .field("filters", &self.filters.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{board_art, Col, FixedSizeIndex, Row, SolveOutcome, Solver, Unsolveable};
+
+    /// Cells a chess knight's move away.
+    fn knight(coord: Coord) -> Vec<Coord> {
+        let (row, col) = (coord.row().idx() as i8, coord.col().idx() as i8);
+        [(1, 2), (2, 1), (2, -1), (1, -2)]
+            .iter()
+            .flat_map(|&(dr, dc)| [(row + dr, col + dc), (row - dr, col - dc)])
+            .filter(|&(r, c)| (0..9).contains(&r) && (0..9).contains(&c))
+            .map(|(r, c)| Coord::new(Row::new(r as u8), Col::new(c as u8)))
+            .collect()
+    }
+
+    fn diagonals() -> [ExtraHouse; 2] {
+        [
+            ExtraHouse::new((0..9).map(|i| Coord::new(Row::new(i), Col::new(i)))),
+            ExtraHouse::new((0..9).map(|i| Coord::new(Row::new(i), Col::new(8 - i)))),
+        ]
+    }
+
+    #[test]
+    fn anti_knight() {
+        crate::setup();
+
+        let puzzle = board_art!(
+            "1  |   |   ",
+            "  6|   |   ",
+            "7  | 23|   ",
+            "---+---+---",
+            "2  |   |8  ",
+            "   |   |  1",
+            "   |   | 6 ",
+            "---+---+---",
+            "   |6  | 7 ",
+            "   |9  |   ",
+            "   |   |  5",
+        );
+        let expected = board_art!(
+            "123|456|789",
+            "456|789|123",
+            "789|123|456",
+            "---+---+---",
+            "231|564|897",
+            "564|897|231",
+            "897|231|564",
+            "---+---+---",
+            "312|645|978",
+            "645|978|312",
+            "978|312|645",
+        );
+        // Not enough givens for a unique solution under the classic rules.
+        assert_eq!(puzzle.count_solutions_dlx(2), 2);
+
+        let variants = Variants::new().with_peers(knight);
+        // Peers sharing a sector are left to the classic rules.
+        assert!(variants
+            .peers(Coord::new(Row::new(0), Col::new(0)))
+            .is_empty());
+        assert_eq!(
+            variants.peers(Coord::new(Row::new(4), Col::new(4))).len(),
+            8
+        );
+        assert!(variants.conflicts(&expected).is_empty());
+        let solver = Solver {
+            variants: variants.clone(),
+            ..Solver::new()
+        };
+        assert_eq!(solver.run(&puzzle), SolveOutcome::Solved(expected.clone()));
+
+        // Givens a knight's move apart with the same value are rejected.
+        let mut invalid = puzzle.clone();
+        let (a, b) = (
+            Coord::new(Row::new(1), Col::new(6)),
+            Coord::new(Row::new(2), Col::new(4)),
+        );
+        invalid[a] = invalid[b];
+        assert!(!invalid.has_conflict());
+        let conflicts = vec![(a, b, puzzle[b].unwrap())];
+        assert_eq!(variants.conflicts(&invalid), conflicts);
+        assert_eq!(
+            solver.run(&invalid),
+            SolveOutcome::Unsolveable(Unsolveable {
+                reason: None,
+                conflicts,
+                excluded: Vec::new(),
+                certificate: None,
+            })
+        );
+
+        // So are classic solutions which break the extra rule.
+        let classic = Board::new().solve().unwrap();
+        let conflicts = variants.conflicts(&classic);
+        assert!(!conflicts.is_empty());
+        assert_eq!(
+            solver.run(&classic),
+            SolveOutcome::Unsolveable(Unsolveable {
+                reason: None,
+                conflicts,
+                excluded: Vec::new(),
+                certificate: None,
+            })
+        );
+    }
+
+    #[test]
+    fn diagonal_houses() {
+        crate::setup();
+
+        let [main, anti] = diagonals();
+        let variants = Variants::new().with_house(main).with_house(anti);
+        let solver = Solver {
+            variants: variants.clone(),
+            ..Solver::new()
+        };
+        let solved = match solver.run(&Board::new()) {
+            SolveOutcome::Solved(board) => board,
+            outcome => panic!("expected a solution, got {:?}", outcome),
+        };
+        assert!(solved.is_solved());
+        assert!(variants.conflicts(&solved).is_empty());
+        for house in variants.houses() {
+            let vals = house
+                .cells()
+                .iter()
+                .fold(AvailSet::none(), |vals, &c| vals | solved[c].unwrap());
+            assert_eq!(vals, AvailSet::all());
+        }
+    }
+
+    #[test]
+    fn branch_filter() {
+        crate::setup();
+
+        let corner = Coord::new(Row::new(0), Col::new(0));
+        let one = AvailSet::only(Val::new(1));
+        let solver = Solver {
+            variants: Variants::new().with_filter(move |branch: &Remaining| branch[corner] != one),
+            ..Solver::new()
+        };
+        let solved = match solver.run(&Board::new()) {
+            SolveOutcome::Solved(board) => board,
+            outcome => panic!("expected a solution, got {:?}", outcome),
+        };
+        assert!(solved.is_solved());
+        assert_ne!(solved[corner], Some(Val::new(1)));
+        assert_ne!(
+            Solver::new().run(&Board::new()),
+            SolveOutcome::Solved(solved)
+        );
+    }
+}
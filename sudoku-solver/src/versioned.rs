@@ -0,0 +1,111 @@
+//! Versioned wrapper for boards which are stored and read back later.
+//!
+//! Traces are versioned by `TraceDocument`. Boards have a much simpler format,
+//! so rather than migrating old boards, a stored board records the format it
+//! was written with and reading it back checks that the format still matches.
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::Board;
+
+/// A board along with the version of the board format it was written with.
+/// Serialize this instead of the bare board when it will be stored, for
+/// example as the key of a persisted solve cache.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct VersionedBoard {
+    /// Format version of the board.
+    pub version: u32,
+    /// The board.
+    pub board: Board,
+}
+
+impl VersionedBoard {
+    /// The board format version written by this version of the library.
+    pub const CURRENT_VERSION: u32 = 1;
+
+    /// Wrap a board with the current format version.
+    #[must_use]
+    pub fn new(board: Board) -> Self {
+        Self::with_version(Self::CURRENT_VERSION, board)
+    }
+
+    /// Wrap a board with the given format version.
+    #[must_use]
+    pub fn with_version(version: u32, board: Board) -> Self {
+        VersionedBoard { version, board }
+    }
+
+    /// Whether the board was written with the current format version.
+    #[must_use]
+    pub fn is_current(&self) -> bool {
+        self.version == Self::CURRENT_VERSION
+    }
+
+    /// Get the board, if it was written with the current format version.
+    pub fn into_board(self) -> Result<Board, BoardVersionError> {
+        if self.is_current() {
+            Ok(self.board)
+        } else {
+            Err(BoardVersionError {
+                found: self.version,
+                expected: Self::CURRENT_VERSION,
+            })
+        }
+    }
+}
+
+impl From<Board> for VersionedBoard {
+    fn from(board: Board) -> Self {
+        Self::new(board)
+    }
+}
+
+/// Returned when a stored board was written with a different format version.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Error)]
+#[error("board has format version {found} but version {expected} is required")]
+pub struct BoardVersionError {
+    /// Version the board was written with.
+    pub found: u32,
+    /// Version this library reads.
+    pub expected: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::puzzle1;
+
+    #[test]
+    fn version_in_json() {
+        let board = puzzle1();
+        let ser = serde_json::to_string(&VersionedBoard::from(board.clone())).unwrap();
+        assert!(ser.starts_with(r#"{"version":1,"board":[null,null,null,1,"#));
+
+        let de: VersionedBoard = serde_json::from_str(&ser).unwrap();
+        assert!(de.is_current());
+        assert_eq!(de.into_board(), Ok(board));
+    }
+
+    #[test]
+    fn version_mismatch() {
+        let ser = serde_json::to_string(&VersionedBoard::with_version(7, Board::new())).unwrap();
+        let de: VersionedBoard = serde_json::from_str(&ser).unwrap();
+        assert!(!de.is_current());
+        let err = de.into_board().unwrap_err();
+        assert_eq!(
+            err,
+            BoardVersionError {
+                found: 7,
+                expected: 1
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "board has format version 7 but version 1 is required"
+        );
+
+        // A bare board has no version, so it is not mistaken for a stored one.
+        let bare = serde_json::to_string(&Board::new()).unwrap();
+        assert!(serde_json::from_str::<VersionedBoard>(&bare).is_err());
+    }
+}
@@ -0,0 +1,151 @@
+//! Views of a single column of a board.
+//!
+//! Rows are contiguous in a board, so they are plain slices. The cells of a
+//! column are spread across the board, so a column is viewed through a handle
+//! which indexes the board by row.
+use std::fmt;
+use std::iter::FusedIterator;
+use std::ops::{Index, IndexMut};
+
+use crate::collections::indexed::FixedSizeIndex;
+use crate::{Board, Col, Coord, Row, Val};
+
+/// A column of a board, indexed by row.
+#[derive(Copy, Clone)]
+pub struct ColView<'a> {
+    board: &'a Board,
+    col: Col,
+}
+
+impl<'a> ColView<'a> {
+    pub(crate) fn new(board: &'a Board, col: Col) -> Self {
+        ColView { board, col }
+    }
+
+    /// The column this views.
+    #[must_use]
+    pub fn col(&self) -> Col {
+        self.col
+    }
+
+    /// Iterator over the cells of the column, from the top row down.
+    #[must_use]
+    pub fn iter(
+        &self,
+    ) -> impl 'a + DoubleEndedIterator<Item = &'a Option<Val>> + ExactSizeIterator + FusedIterator
+    {
+        let (board, col) = (self.board, self.col);
+        Row::values().map(move |row| &board[Coord::new(row, col)])
+    }
+}
+
+impl Index<Row> for ColView<'_> {
+    type Output = Option<Val>;
+
+    fn index(&self, row: Row) -> &Option<Val> {
+        &self.board[Coord::new(row, self.col)]
+    }
+}
+
+impl PartialEq for ColView<'_> {
+    /// Columns are equal if they hold the same cells, whichever column of
+    /// whichever board they come from.
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for ColView<'_> {}
+
+impl fmt::Debug for ColView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ColView")
+            .field("col", &self.col)
+            .field("cells", &self.iter().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// A mutable column of a board, indexed by row. Holds a reference to each cell
+/// of the column, so the columns of one board can be borrowed at once.
+pub struct ColViewMut<'a> {
+    cells: [&'a mut Option<Val>; Row::NUM_INDEXES],
+    col: Col,
+}
+
+impl<'a> ColViewMut<'a> {
+    pub(crate) fn new(board: &'a mut Board, col: Col) -> Self {
+        let mut cells = board
+            .row_major_mut()
+            .iter_mut()
+            .skip(col.idx())
+            .step_by(Col::NUM_INDEXES);
+        ColViewMut {
+            cells: std::array::from_fn(|_| cells.next().unwrap()),
+            col,
+        }
+    }
+
+    /// Split a board into its columns, in order.
+    pub(crate) fn split(board: &'a mut Board) -> [Self; Col::NUM_INDEXES] {
+        let mut rows: Vec<_> = board
+            .rows_arrays_mut()
+            .iter_mut()
+            .map(|row| row.iter_mut())
+            .collect();
+        let mut cols = Col::values();
+        std::array::from_fn(|_| ColViewMut {
+            cells: std::array::from_fn(|r| rows[r].next().unwrap()),
+            col: cols.next().unwrap(),
+        })
+    }
+
+    /// The column this views.
+    #[must_use]
+    pub fn col(&self) -> Col {
+        self.col
+    }
+
+    /// Iterator over the cells of the column, from the top row down.
+    #[must_use]
+    pub fn iter(
+        &self,
+    ) -> impl '_ + DoubleEndedIterator<Item = &Option<Val>> + ExactSizeIterator + FusedIterator
+    {
+        self.cells.iter().map(|cell| &**cell)
+    }
+
+    /// Iterator over mutable references to the cells of the column, from the
+    /// top row down.
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = &mut Option<Val>>
+           + ExactSizeIterator
+           + FusedIterator
+           + use<'_, 'a> {
+        self.cells.iter_mut().map(|cell| &mut **cell)
+    }
+}
+
+impl Index<Row> for ColViewMut<'_> {
+    type Output = Option<Val>;
+
+    fn index(&self, row: Row) -> &Option<Val> {
+        self.cells[row.idx()]
+    }
+}
+
+impl IndexMut<Row> for ColViewMut<'_> {
+    fn index_mut(&mut self, row: Row) -> &mut Option<Val> {
+        self.cells[row.idx()]
+    }
+}
+
+impl fmt::Debug for ColViewMut<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ColViewMut")
+            .field("col", &self.col)
+            .field("cells", &self.iter().collect::<Vec<_>>())
+            .finish()
+    }
+}
@@ -0,0 +1,30 @@
+//! Uses the public constructors the way callers should, with every warning
+//! denied, so a deprecation or an ignored `#[must_use]` result in the everyday
+//! API fails the build.
+#![deny(warnings)]
+
+use sudoku_solver::{AvailSet, Board, Budget, Exclusions, Solver, Val};
+
+#[test]
+fn defaults_are_empty() {
+    assert_eq!(Board::default(), Board::new());
+    assert!(Board::default().row_major().iter().all(Option::is_none));
+    assert_eq!(AvailSet::default(), AvailSet::none());
+    assert!(AvailSet::default().is_empty());
+    assert_eq!(Exclusions::default(), Exclusions::new());
+    assert_eq!(Solver::default(), Solver::new());
+    assert_eq!(Budget::default(), Budget::new());
+}
+
+#[test]
+fn results_are_used() {
+    let mut set = AvailSet::default();
+    assert!(set.add(Val::new(3)));
+    assert_eq!(set, AvailSet::only(Val::new(3)));
+    assert_eq!(
+        set | Val::new(4),
+        AvailSet::none() | Val::new(3) | Val::new(4)
+    );
+    let solution = Board::new().solve().expect("empty board has a solution");
+    assert!(solution.is_solved());
+}
@@ -0,0 +1,285 @@
+//! Public API surface snapshot.
+//!
+//! `registry!` below lists every public item of the crate and imports each one,
+//! so removing or renaming an item fails to compile here instead of in a
+//! downstream crate. The same list is checked against
+//! `tests/snapshots/api.txt`, which `scripts/api_items.py --write` regenerates
+//! from the source, so a new public item must be registered too.
+//!
+//! When the public API changes on purpose, rewrite the snapshot with the
+//! script, update the registry to match (`scripts/api_items.py --registry`
+//! prints it), and bless the new snapshot as described in `tests/snapshots.rs`.
+
+/// Imports each listed item and records its kind and path.
+macro_rules! registry {
+    ($($(#[cfg($cfg:meta)])? $kind:ident $($seg:ident)::+;)*) => {
+        $(
+            $(#[cfg($cfg)])?
+            #[allow(unused_imports, deprecated)]
+            use $($seg)::+ as _;
+        )*
+
+        /// Kind and path of each registered item, in the snapshot format.
+        fn registered() -> Vec<String> {
+            let mut items = Vec::new();
+            $(
+                $(#[cfg($cfg)])?
+                items.push(format!(
+                    "{} {}",
+                    stringify!($kind),
+                    [$(stringify!($seg)),+].join("::"),
+                ));
+            )*
+            items
+        }
+    };
+}
+
+registry! {
+    struct sudoku_solver::AvailSet;
+    struct sudoku_solver::Band;
+    struct sudoku_solver::Board;
+    #[cfg(feature = "serde")]
+    struct sudoku_solver::BoardVersionError;
+    struct sudoku_solver::Budget;
+    struct sudoku_solver::BudgetExceeded;
+    struct sudoku_solver::BudgetUsage;
+    struct sudoku_solver::ClueMask;
+    struct sudoku_solver::Col;
+    struct sudoku_solver::ColView;
+    struct sudoku_solver::ColViewMut;
+    struct sudoku_solver::Conflict;
+    struct sudoku_solver::Coord;
+    struct sudoku_solver::Coords;
+    struct sudoku_solver::DeadlineExceeded;
+    trait sudoku_solver::DynZone;
+    struct sudoku_solver::Exclusions;
+    enum sudoku_solver::ExhaustionKind;
+    type sudoku_solver::Guess;
+    enum sudoku_solver::House;
+    struct sudoku_solver::IncorrectSize;
+    trait sudoku_solver::Intersect;
+    enum sudoku_solver::IntersectionResult;
+    enum sudoku_solver::LimitKind;
+    struct sudoku_solver::OutOfRange;
+    enum sudoku_solver::ParseBoardError;
+    struct sudoku_solver::ReduceOptions;
+    enum sudoku_solver::Relation;
+    struct sudoku_solver::Row;
+    enum sudoku_solver::RuleKind;
+    struct sudoku_solver::RulePriority;
+    struct sudoku_solver::RuleSet;
+    struct sudoku_solver::Sector;
+    struct sudoku_solver::SectorCol;
+    struct sudoku_solver::SectorRow;
+    struct sudoku_solver::Solutions;
+    enum sudoku_solver::SolveCode;
+    enum sudoku_solver::SolveOutcome;
+    struct sudoku_solver::Solver;
+    struct sudoku_solver::Stack;
+    enum sudoku_solver::Symmetry;
+    struct sudoku_solver::UnknownRule;
+    struct sudoku_solver::Unsolveable;
+    struct sudoku_solver::Val;
+    struct sudoku_solver::Values;
+    #[cfg(feature = "serde")]
+    struct sudoku_solver::VersionedBoard;
+    trait sudoku_solver::Zone;
+    enum sudoku_solver::analysis::Analysis;
+    struct sudoku_solver::analysis::CellHint;
+    struct sudoku_solver::analysis::CellNode;
+    struct sudoku_solver::analysis::ConstraintGraph;
+    enum sudoku_solver::analysis::DisclosureLevel;
+    struct sudoku_solver::analysis::Hint;
+    struct sudoku_solver::analysis::HintEscalation;
+    struct sudoku_solver::analysis::HouseHint;
+    struct sudoku_solver::analysis::ImplicationGraph;
+    struct sudoku_solver::analysis::Log2Millibits;
+    enum sudoku_solver::analysis::PartialHint;
+    struct sudoku_solver::analysis::PeerEdge;
+    struct sudoku_solver::analysis::PuzzleStrength;
+    enum sudoku_solver::analysis::Regime;
+    struct sudoku_solver::analysis::StrengthReport;
+    const sudoku_solver::analysis::UNDERDETERMINED_ENTROPY;
+    enum sudoku_solver::analysis::Uniqueness;
+    struct sudoku_solver::analysis::ValueHint;
+    fn sudoku_solver::analysis::constraint_graph;
+    fn sudoku_solver::analysis::implication_graph;
+    fn sudoku_solver::analysis::strength_report;
+    #[cfg(feature = "testkit")]
+    macro sudoku_solver::assert_boards_eq;
+    #[cfg(feature = "testkit")]
+    macro sudoku_solver::board_art;
+    struct sudoku_solver::certificate::Branch;
+    struct sudoku_solver::certificate::CertificateStep;
+    enum sudoku_solver::certificate::Proof;
+    struct sudoku_solver::certificate::UnsolveableCertificate;
+    fn sudoku_solver::certificate::verify_certificate;
+    fn sudoku_solver::certificate::verify_solution;
+    enum sudoku_solver::describe::DescribeBy;
+    struct sudoku_solver::describe::DescribeOptions;
+    enum sudoku_solver::describe::DescribeStyle;
+    struct sudoku_solver::design::DesignSession;
+    enum sudoku_solver::design::SolutionClass;
+    struct sudoku_solver::design::Transition;
+    #[cfg(feature = "serde")]
+    struct sudoku_solver::errors::BoardVersionError;
+    struct sudoku_solver::errors::BudgetExceeded;
+    struct sudoku_solver::errors::DeadlineExceeded;
+    struct sudoku_solver::errors::IncorrectSize;
+    struct sudoku_solver::errors::OutOfRange;
+    enum sudoku_solver::errors::ParseBoardError;
+    enum sudoku_solver::errors::ParseSizedBoardError;
+    enum sudoku_solver::errors::PrecomputeError;
+    #[cfg(feature = "serde")]
+    enum sudoku_solver::errors::TraceFormatError;
+    #[cfg(feature = "serde")]
+    enum sudoku_solver::errors::TraceParseError;
+    struct sudoku_solver::errors::UnknownRule;
+    enum sudoku_solver::generate::Difficulty;
+    struct sudoku_solver::lesson::Lesson;
+    struct sudoku_solver::lesson::LessonPolicy;
+    struct sudoku_solver::lesson::LessonUnit;
+    fn sudoku_solver::lesson::build;
+    enum sudoku_solver::precompute::Artifact;
+    enum sudoku_solver::precompute::ArtifactKind;
+    struct sudoku_solver::precompute::Precompute;
+    enum sudoku_solver::precompute::PrecomputeError;
+    struct sudoku_solver::precompute::TaskSet;
+    struct sudoku_solver::prelude::AvailSet;
+    struct sudoku_solver::prelude::Board;
+    struct sudoku_solver::prelude::Col;
+    struct sudoku_solver::prelude::Coord;
+    enum sudoku_solver::prelude::Difficulty;
+    struct sudoku_solver::prelude::Hint;
+    enum sudoku_solver::prelude::House;
+    trait sudoku_solver::prelude::Intersect;
+    enum sudoku_solver::prelude::ParseBoardError;
+    struct sudoku_solver::prelude::Row;
+    struct sudoku_solver::prelude::Sector;
+    enum sudoku_solver::prelude::SolveOutcome;
+    struct sudoku_solver::prelude::Solver;
+    struct sudoku_solver::prelude::Val;
+    trait sudoku_solver::prelude::Zone;
+    #[cfg(feature = "serde")]
+    struct sudoku_solver::prelude::with_serde::BoardVersionError;
+    #[cfg(feature = "serde")]
+    struct sudoku_solver::prelude::with_serde::TraceDocument;
+    #[cfg(feature = "serde")]
+    struct sudoku_solver::prelude::with_serde::VersionedBoard;
+    trait sudoku_solver::sized::Dims;
+    struct sudoku_solver::sized::Nine;
+    enum sudoku_solver::sized::ParseSizedBoardError;
+    struct sudoku_solver::sized::Six;
+    struct sudoku_solver::sized::SizedBoard;
+    struct sudoku_solver::sized::Twelve;
+    struct sudoku_solver::solve::Budget;
+    struct sudoku_solver::solve::BudgetExceeded;
+    struct sudoku_solver::solve::BudgetUsage;
+    enum sudoku_solver::solve::ExhaustionKind;
+    enum sudoku_solver::solve::LimitKind;
+    struct sudoku_solver::solve::ReduceOptions;
+    enum sudoku_solver::solve::RuleKind;
+    struct sudoku_solver::solve::RulePriority;
+    struct sudoku_solver::solve::RuleSet;
+    struct sudoku_solver::solve::Solutions;
+    enum sudoku_solver::solve::SolveCode;
+    enum sudoku_solver::solve::SolveOutcome;
+    struct sudoku_solver::solve::Solver;
+    struct sudoku_solver::solve::UnknownRule;
+    struct sudoku_solver::solve::Unsolveable;
+    struct sudoku_solver::solve::techniques::FishPattern;
+    struct sudoku_solver::solve::techniques::Link;
+    enum sudoku_solver::solve::techniques::LinkStrength;
+    fn sudoku_solver::solve::techniques::conjugate_pairs;
+    fn sudoku_solver::solve::techniques::find_fish;
+    fn sudoku_solver::solve::techniques::find_links;
+    struct sudoku_solver::tagged::Meta;
+    struct sudoku_solver::tagged::Operation;
+    struct sudoku_solver::tagged::Tagged;
+    #[cfg(feature = "testkit")]
+    trait sudoku_solver::testkit::BoardOutcome;
+    #[cfg(feature = "testkit")]
+    fn sudoku_solver::testkit::assert_boards_eq_impl;
+    #[cfg(feature = "testkit")]
+    fn sudoku_solver::testkit::board_diff_message;
+    #[cfg(feature = "testkit")]
+    fn sudoku_solver::testkit::board_from_art;
+    #[cfg(feature = "testkit")]
+    fn sudoku_solver::testkit::board_to_art;
+    #[cfg(feature = "testkit")]
+    fn sudoku_solver::testkit::solution_pair_from_art;
+    struct sudoku_solver::trace::ArcRemaining;
+    #[cfg(feature = "serde")]
+    const sudoku_solver::trace::CURRENT_SCHEMA;
+    struct sudoku_solver::trace::CellDiff;
+    struct sudoku_solver::trace::ClosedRemaining;
+    struct sudoku_solver::trace::Deduction;
+    struct sudoku_solver::trace::DeductionOpportunity;
+    enum sudoku_solver::trace::DeductionReason;
+    trait sudoku_solver::trace::DeductiveTracer;
+    #[cfg(feature = "serde")]
+    struct sudoku_solver::trace::JsonLinesTracer;
+    struct sudoku_solver::trace::NopDeductiveTracer;
+    struct sudoku_solver::trace::NopTracer;
+    struct sudoku_solver::trace::Remaining;
+    struct sudoku_solver::trace::RemainingDiff;
+    #[cfg(feature = "serde")]
+    struct sudoku_solver::trace::TraceDocument;
+    #[cfg(feature = "serde")]
+    enum sudoku_solver::trace::TraceFormatError;
+    #[cfg(feature = "serde")]
+    struct sudoku_solver::trace::TraceLimits;
+    #[cfg(feature = "serde")]
+    enum sudoku_solver::trace::TraceParseError;
+    enum sudoku_solver::trace::TraceTree;
+    trait sudoku_solver::trace::Tracer;
+    enum sudoku_solver::trace::UnsolveableReason;
+    trait sudoku_solver::variant::BranchFilter;
+    struct sudoku_solver::variant::ExtraHouse;
+    trait sudoku_solver::variant::ExtraPeers;
+    struct sudoku_solver::variant::Variants;
+}
+
+const SNAPSHOT: &str = include_str!("snapshots/api.txt");
+
+/// Items in the snapshot which exist with the features this test was built
+/// with.
+fn snapshot() -> Vec<String> {
+    SNAPSHOT
+        .lines()
+        .filter_map(|line| match line.split_once(" (") {
+            None => Some(line.to_owned()),
+            Some((item, feature)) => {
+                let enabled = match feature.trim_end_matches(')') {
+                    "serde" => cfg!(feature = "serde"),
+                    "testkit" => cfg!(feature = "testkit"),
+                    "rand" => cfg!(feature = "rand"),
+                    other => panic!("unknown feature {:?} in api snapshot", other),
+                };
+                enabled.then(|| item.to_owned())
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn registry_matches_snapshot() {
+    let registered = registered();
+    let snapshot = snapshot();
+    let missing: Vec<_> = snapshot
+        .iter()
+        .filter(|item| !registered.contains(item))
+        .collect();
+    let extra: Vec<_> = registered
+        .iter()
+        .filter(|item| !snapshot.contains(item))
+        .collect();
+    assert!(
+        missing.is_empty() && extra.is_empty(),
+        "api registry is out of date with tests/snapshots/api.txt\n\
+         not registered: {:#?}\nnot in snapshot: {:#?}",
+        missing,
+        extra,
+    );
+}
@@ -0,0 +1,177 @@
+//! Cross-API invariants which must hold for every board.
+//!
+//! Every input is run through `check_all_invariants`, which exercises each pair
+//! of public APIs with a documented relationship. A failure names the invariant
+//! that was broken.
+use sudoku_solver::trace::TraceTree;
+use sudoku_solver::{assert_boards_eq, Board, Coord, Exclusions, Zone};
+
+/// Puzzles with known unique solutions, in the 81-character line format.
+const PUZZLES: &[&str] = &[
+    "...1.........586.18.136..9.5.....4.3..36.18..6.4.....7.3..845.61.572.........3...",
+    "...8...141.64..75..4753....9...5..62...7.9...63..4...5....8734..14..56.989...4...",
+    ".49...65..5.8.7..3...46....27.........45.18.........32....42...9..3.6.2..27...31.",
+];
+
+/// Boards with no solution.
+const UNSOLVEABLE: &[&str] = &[
+    "349...65..5.8.7..3...46....27.........45.18.........32....42...9..3.6.2..27...31.",
+    // Two 5s in the first row.
+    "55...............................................................................",
+];
+
+/// Deterministically blank out cells of a board, keeping roughly one cell in
+/// `keep_one_in`.
+fn masked(board: &Board, seed: u64, keep_one_in: u64) -> Board {
+    let mut state = seed;
+    let mut out = board.clone();
+    for cell in out.row_major_mut() {
+        // xorshift64
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        if !state.is_multiple_of(keep_one_in) {
+            *cell = None;
+        }
+    }
+    out
+}
+
+/// The solution of the board, if any, must be a filled, valid board that agrees
+/// with the clues of the input.
+fn check_solution_matches_clues(board: &Board, solution: &Board) {
+    for coord in Coord::all() {
+        assert!(
+            solution[coord].is_some(),
+            "solution-filled: solution has empty cell at {}",
+            coord
+        );
+        if let Some(val) = board[coord] {
+            assert_eq!(
+                solution[coord],
+                Some(val),
+                "solution-keeps-clues: clue at {} changed",
+                coord
+            );
+        }
+    }
+}
+
+/// A solved board must be a fixed point of every API.
+fn check_solved_fixed_point(solution: &Board) {
+    assert!(solution.is_solved(), "solution-is-solved");
+    assert!(
+        !solution.known_unsolveable(),
+        "solution-not-known-unsolveable"
+    );
+    assert_boards_eq!(
+        solution.solve(),
+        Some(solution),
+        "solve-idempotent: solving a solution must return it unchanged"
+    );
+}
+
+/// Run every invariant that applies to the given board.
+fn check_all_invariants(board: &Board) {
+    let solution = board.solve();
+
+    let (traced, tree) = board.solve_traced::<TraceTree>();
+    assert_boards_eq!(
+        traced,
+        solution,
+        "traced-agrees: solve_traced and solve differ"
+    );
+    assert_eq!(
+        trace_found_solution(&tree),
+        solution.is_some(),
+        "trace-outcome: trace tree disagrees with the solve result"
+    );
+
+    assert_boards_eq!(
+        board.solve_with_exclusions(&Exclusions::new()),
+        solution,
+        "no-exclusions-agrees: empty exclusions must not change the result"
+    );
+
+    if board.known_unsolveable() {
+        assert_boards_eq!(
+            solution,
+            None::<Board>,
+            "known-unsolveable-sound: known unsolveable board was solved"
+        );
+    }
+
+    if board.is_solved() {
+        assert_boards_eq!(
+            solution,
+            Some(board),
+            "solved-is-own-solution: solved board solved to something else"
+        );
+    }
+
+    if let Some(solution) = solution {
+        check_solution_matches_clues(board, &solution);
+        check_solved_fixed_point(&solution);
+    }
+}
+
+/// Returns true if some leaf of the trace tree is a solution.
+fn trace_found_solution(tree: &TraceTree) -> bool {
+    match tree {
+        TraceTree::Solution { .. } => true,
+        TraceTree::Unsolveable { .. } => false,
+        TraceTree::Guess { guesses, .. } => guesses.iter().any(trace_found_solution),
+    }
+}
+
+#[test]
+fn empty_board() {
+    check_all_invariants(&Board::new());
+}
+
+#[test]
+fn fixture_puzzles() {
+    for puzzle in PUZZLES {
+        let board = Board::from_line(puzzle).unwrap();
+        check_all_invariants(&board);
+        assert!(
+            board.solve().is_some(),
+            "fixture {} has no solution",
+            puzzle
+        );
+    }
+}
+
+#[test]
+fn fixture_solutions() {
+    for puzzle in PUZZLES {
+        let solution = Board::from_line(puzzle).unwrap().solve().unwrap();
+        check_all_invariants(&solution);
+    }
+}
+
+#[test]
+fn unsolveable_boards() {
+    for puzzle in UNSOLVEABLE {
+        let board = Board::from_line(puzzle).unwrap();
+        check_all_invariants(&board);
+        assert_boards_eq!(
+            board.solve(),
+            None::<Board>,
+            "{} should be unsolveable",
+            puzzle
+        );
+    }
+}
+
+#[test]
+fn masked_solutions() {
+    for puzzle in PUZZLES {
+        let solution = Board::from_line(puzzle).unwrap().solve().unwrap();
+        for seed in 1..=4 {
+            for keep_one_in in 2..=4 {
+                check_all_invariants(&masked(&solution, seed, keep_one_in));
+            }
+        }
+    }
+}
@@ -0,0 +1,236 @@
+//! Checks that the public API does not panic for any valid typed input.
+//!
+//! Functions which only take typed values (`Coord`, `Val`, `Board`, ...) have
+//! no documented panics, so every combination of those values must be handled.
+//! Each group of calls runs under a panic hook which records where the panic
+//! happened, and the test fails listing every group that panicked. Documented
+//! panics are covered by `should_panic` tests next to the functions.
+use std::convert::TryFrom;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+
+use sudoku_solver::{
+    AvailSet, Band, Board, ClueMask, Col, Coord, House, Row, Sector, SectorCol, SectorRow, Stack,
+    Symmetry, Val, Zone,
+};
+
+const SYMMETRIES: [Symmetry; 6] = [
+    Symmetry::Rotate180,
+    Symmetry::Rotate90,
+    Symmetry::MirrorRows,
+    Symmetry::MirrorCols,
+    Symmetry::Diagonal,
+    Symmetry::AntiDiagonal,
+];
+
+/// A solved board to build puzzles from.
+const SOLUTION: &str =
+    "467192385329458671851367294518279463273641859694835127732984516145726938986513742";
+
+/// Small deterministic generator so failures can be reproduced.
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn val(&mut self) -> Val {
+        Val::try_from(self.below(9) + 1).unwrap()
+    }
+
+    fn coord(&mut self) -> Coord {
+        Coord::all().nth(self.below(Board::SIZE)).unwrap()
+    }
+}
+
+fn vals() -> impl Iterator<Item = Val> {
+    (1..=9u8).map(|v| Val::try_from(v).unwrap())
+}
+
+/// Every set of values.
+fn avail_sets() -> impl Iterator<Item = AvailSet> {
+    (0..1u16 << 9).map(|bits| {
+        vals()
+            .filter(|val| bits & (1 << (val.val() - 1)) != 0)
+            .fold(AvailSet::none(), |set, val| set | val)
+    })
+}
+
+/// Boards covering valid puzzles, boards with conflicting givens, and the
+/// empty and solved boards.
+fn boards() -> Vec<Board> {
+    let solution = Board::from_line(SOLUTION).unwrap();
+    let mut rng = XorShift(0x5eed);
+    let mut boards = vec![Board::new(), solution.clone()];
+    for n in (20..=81).step_by(3) {
+        let puzzle = Board::apply_mask(&solution, &ClueMask::random(n, rng.next()));
+        let mut conflicting = puzzle.clone();
+        for _ in 0..3 {
+            conflicting[rng.coord()] = Some(rng.val());
+        }
+        boards.push(puzzle);
+        boards.push(conflicting);
+    }
+    boards
+}
+
+fn exercise_zone<Z: Zone>(zone: Z) {
+    let board = Board::from_line(SOLUTION).unwrap();
+    for coord in zone.coords() {
+        assert!(zone.contains(coord));
+        assert!(Z::containing_zone(coord) == zone);
+    }
+    assert_eq!(board.house_cells(zone).count(), Z::SIZE);
+    assert_eq!(zone.into_iter().count(), Z::SIZE);
+}
+
+fn exercise_coords() {
+    for coord in Coord::all() {
+        let _ = coord.to_string();
+        let _ = (coord.sector_row(), coord.sector_col());
+        assert_eq!(Coord::from_rowmajor_idx(coord.rowmajor_idx()), coord);
+        assert_eq!(coord.neighbors().count(), 20);
+        for other in Coord::all() {
+            let _ = coord.relation(other);
+        }
+        for sym in SYMMETRIES {
+            let _ = sym.apply(coord);
+        }
+        exercise_zone(coord);
+    }
+    for row in Row::all() {
+        assert_eq!(Row::new(row.inner()), row);
+        exercise_zone(row);
+    }
+    for col in Col::all() {
+        assert_eq!(Col::new(col.inner()), col);
+        exercise_zone(col);
+    }
+    for sector in Sector::all() {
+        let _ = (sector.base_row(), sector.base_col());
+        assert_eq!(sector.rows().count() * sector.cols().count(), Sector::SIZE);
+        exercise_zone(sector);
+    }
+    for house in House::all() {
+        assert_eq!(house.coords().count(), 9);
+    }
+    for band in Band::all() {
+        assert_eq!(band.sectors().count(), 3);
+        let _ = (band.base_row(), band.rows().count());
+    }
+    for stack in Stack::all() {
+        assert_eq!(stack.sectors().count(), 3);
+        let _ = (stack.base_col(), stack.cols().count());
+    }
+    for sector_row in SectorRow::all() {
+        let _ = (sector_row.sector(), sector_row.row(), sector_row.base_col());
+        assert_eq!(sector_row.neighbors().count(), 4);
+        exercise_zone(sector_row);
+    }
+    for sector_col in SectorCol::all() {
+        let _ = (sector_col.sector(), sector_col.col(), sector_col.base_row());
+        assert_eq!(sector_col.neighbors().count(), 4);
+        exercise_zone(sector_col);
+    }
+}
+
+fn exercise_avail_sets() {
+    for set in avail_sets() {
+        assert_eq!(set.iter().count(), set.len());
+        assert_eq!(set.iter().rev().count(), set.len());
+        let _ = (set.is_empty(), set.is_single(), set.get_single(), !set);
+        for other in avail_sets().step_by(7) {
+            let _ = (set | other, set & other, set - other);
+            let _ = (set.intersects(other), set.difference_len(other));
+            let _ = set.intersection_len(other);
+        }
+        for val in vals() {
+            let mut copy = set;
+            let _ = (copy.contains(val), copy.add(val), copy.remove(val));
+            let _ = (set | val, set & val, set - val);
+        }
+    }
+}
+
+fn exercise_masks() {
+    let mut rng = XorShift(7);
+    for n in 0..=Board::SIZE {
+        let mask = ClueMask::random(n, rng.next());
+        assert_eq!(mask.len(), n);
+        assert_eq!(ClueMask::from_hex(&mask.to_hex()), Some(mask));
+        for sym in SYMMETRIES {
+            let _ = (mask.image(sym), mask.is_symmetric(sym));
+            assert!(mask.symmetrize(sym).is_symmetric(sym));
+        }
+    }
+}
+
+fn exercise_boards() {
+    for mut board in boards() {
+        let _ = (board.is_solved(), board.known_unsolveable());
+        let _ = board.hint();
+        let solved = board.solve();
+        assert_eq!(board.count_solutions_dlx(1), usize::from(solved.is_some()));
+        for coord in Coord::all() {
+            let _ = board.peer_values(coord);
+            assert_eq!(
+                board[coord.row()][usize::from(coord.col().inner())],
+                board[coord]
+            );
+            assert_eq!(board.col(coord.col())[coord.row()], board[coord]);
+        }
+        assert!(board.rows().zip(board.cols()).all(|(r, c)| {
+            r.len() == Row::WIDTH as usize && c.iter().count() == Col::HEIGHT as usize
+        }));
+        for row in board.rows_mut() {
+            row.iter_mut().for_each(|_| {});
+        }
+        for mut col in board.cols_mut() {
+            col.iter_mut().for_each(|_| {});
+        }
+        let _ = ClueMask::from_puzzle(&board);
+        let shifted = board.map_values(|val| Val::try_from(val.val() % 9 + 1).unwrap());
+        let _ = board.cmp(&shifted);
+        board.specify_sector(Sector::all().next().unwrap(), [None; 9]);
+    }
+}
+
+#[test]
+fn public_api_does_not_panic() {
+    let failures = Arc::new(Mutex::new(Vec::new()));
+    let hook_failures = failures.clone();
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        hook_failures.lock().unwrap().push(info.to_string());
+    }));
+
+    let groups: [(&str, fn()); 4] = [
+        ("coordinates", exercise_coords),
+        ("avail sets", exercise_avail_sets),
+        ("clue masks", exercise_masks),
+        ("boards", exercise_boards),
+    ];
+    let mut panicked = Vec::new();
+    for (name, group) in groups {
+        if panic::catch_unwind(AssertUnwindSafe(group)).is_err() {
+            panicked.push(name);
+        }
+    }
+
+    panic::set_hook(default_hook);
+    let failures = failures.lock().unwrap();
+    assert!(
+        panicked.is_empty(),
+        "{:?} panicked:\n{}",
+        panicked,
+        failures.join("\n")
+    );
+}
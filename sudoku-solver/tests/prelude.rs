@@ -0,0 +1,59 @@
+//! Checks that a program using only the prelude can do the everyday tasks, and
+//! pins the names the prelude provides so removing one is caught.
+use sudoku_solver::prelude::*;
+
+fn puzzle() -> Board {
+    Board::from_rows([
+        "...1.....",
+        "....586.1",
+        "8.136..9.",
+        "5.....4.3",
+        "..36.18..",
+        "6.4.....7",
+        ".3..845.6",
+        "1.572....",
+        ".....3...",
+    ])
+    .unwrap()
+}
+
+#[test]
+fn parse_solve_hint_rate() {
+    let board = puzzle();
+    let err: ParseBoardError = Board::from_rows(["x"; 9]).unwrap_err();
+    assert!(!err.to_string().is_empty());
+
+    let outcome: SolveOutcome = Solver::new().run(&board);
+    let solution = outcome.into_solution().unwrap();
+    assert!(solution.is_solved());
+    assert_eq!(board.solve(), Some(solution.clone()));
+
+    let hint: Hint = board.hint().unwrap();
+    assert_eq!(solution[hint.pos], Some(hint.val));
+    assert_eq!(Difficulty::of(&board), Some(Difficulty::Easy));
+}
+
+#[test]
+fn prelude_names() {
+    let pos = Coord::new(Row::new(0), Col::new(0));
+    let sector: Sector = pos.sector();
+    let house = House::Sector(sector);
+    assert!(house.contains(pos));
+    assert!(Zone::contains(&pos.row(), pos));
+    assert_eq!(pos.row().intersect(pos.col()), Some(pos));
+    let vals: AvailSet = AvailSet::only(Val::new(1));
+    assert_eq!(vals.len(), 1);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_sub_prelude() {
+    use sudoku_solver::prelude::with_serde::*;
+
+    let versioned = VersionedBoard::from(puzzle());
+    let json = serde_json::to_string(&versioned).unwrap();
+    let back: VersionedBoard = serde_json::from_str(&json).unwrap();
+    let board: Result<Board, BoardVersionError> = back.into_board();
+    assert_eq!(board, Ok(puzzle()));
+    let _: Option<TraceDocument> = None;
+}
@@ -0,0 +1,272 @@
+//! Release compatibility snapshots.
+//!
+//! Two snapshots record the parts of the crate downstream users depend on:
+//!
+//! - `snapshots/api.txt`, every public item, checked by `tests/api_surface.rs`.
+//! - `snapshots/serde/*.json`, the serialized form of a representative value of
+//!   every public serializable type, checked here when the `serde` feature is
+//!   enabled.
+//!
+//! `snapshots/blessed.txt` records the crate version the snapshots were last
+//! blessed at and a digest of their contents. If either snapshot changes, the
+//! change is breaking for someone, so the crate version must be bumped before
+//! the new snapshots can be blessed.
+//!
+//! To change a snapshot on purpose:
+//!
+//! 1. Bump `version` in `Cargo.toml`.
+//! 2. Regenerate the API snapshot with `scripts/api_items.py --write` if the
+//!    public API changed.
+//! 3. Run `BLESS=1 cargo test --all-features --test snapshots`, which rewrites
+//!    the serde snapshots and records the new version and digest.
+//! 4. Review and commit the changed snapshot files.
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const SNAPSHOTS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots");
+
+/// Whether this run should rewrite snapshots rather than check them.
+fn blessing() -> bool {
+    std::env::var_os("BLESS").is_some_and(|val| !val.is_empty() && val != "0")
+}
+
+/// Every snapshot file, by path relative to the snapshot directory, except the
+/// blessing record itself.
+fn snapshot_files() -> BTreeMap<String, Vec<u8>> {
+    fn walk(root: &Path, dir: &Path, files: &mut BTreeMap<String, Vec<u8>>) {
+        for entry in fs::read_dir(dir).expect("could not read snapshot dir") {
+            let path = entry.expect("could not read snapshot dir").path();
+            if path.is_dir() {
+                walk(root, &path, files);
+            } else {
+                let name = path.strip_prefix(root).unwrap().to_string_lossy();
+                if name != "blessed.txt" {
+                    let name = name.replace('\\', "/");
+                    files.insert(name, fs::read(&path).expect("could not read snapshot"));
+                }
+            }
+        }
+    }
+    let mut files = BTreeMap::new();
+    walk(Path::new(SNAPSHOTS), Path::new(SNAPSHOTS), &mut files);
+    files
+}
+
+/// FNV-1a digest of every snapshot file's name and contents. Stable across
+/// platforms and Rust versions, unlike `DefaultHasher`.
+fn digest(files: &BTreeMap<String, Vec<u8>>) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for (name, contents) in files {
+        for &byte in name.as_bytes().iter().chain(&[0]).chain(contents) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    format!("{:016x}", hash)
+}
+
+fn parse_version(version: &str) -> (u64, u64, u64) {
+    let mut parts = version.split('.').map(|part| {
+        part.parse()
+            .unwrap_or_else(|_| panic!("unsupported version {:?}", version))
+    });
+    let mut next = || parts.next().unwrap_or(0);
+    (next(), next(), next())
+}
+
+/// Check that the snapshots match the version they were blessed at, or when
+/// blessing, record them at the current version.
+fn check_blessed() {
+    let path = PathBuf::from(SNAPSHOTS).join("blessed.txt");
+    let record = fs::read_to_string(&path).unwrap_or_default();
+    let field = |key: &str| {
+        record
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix(' '))
+            .unwrap_or_default()
+            .to_owned()
+    };
+    let (version, recorded) = (field("version"), field("digest"));
+    let current = digest(&snapshot_files());
+    if current == recorded {
+        return;
+    }
+    let crate_version = env!("CARGO_PKG_VERSION");
+    let bumped = version.is_empty() || parse_version(crate_version) > parse_version(&version);
+    assert!(
+        bumped,
+        "snapshots changed since they were blessed at version {}; bump the crate \
+         version and bless them as described in tests/snapshots.rs",
+        version,
+    );
+    assert!(
+        blessing(),
+        "snapshots changed since they were blessed at version {}; rerun with \
+         BLESS=1 to bless them at version {}",
+        version,
+        crate_version,
+    );
+    fs::write(
+        &path,
+        format!("version {}\ndigest {}\n", crate_version, current),
+    )
+    .expect("could not write blessing record");
+}
+
+#[cfg(feature = "serde")]
+mod serde_formats {
+    use super::*;
+
+    use serde::Serialize;
+    use sudoku_solver::analysis::{DisclosureLevel, Regime};
+    use sudoku_solver::generate::Difficulty;
+    use sudoku_solver::lesson::{self, LessonPolicy};
+    use sudoku_solver::trace::{Deduction, Remaining, TraceTree};
+    use sudoku_solver::{
+        board_art, AvailSet, Band, Board, Col, Conflict, Coord, Exclusions, House, Row, SectorCol,
+        SectorRow, Solver, Stack, Val, VersionedBoard, Zone,
+    };
+
+    /// A solved board with a deadly rectangle removed, so solving it takes one
+    /// guess.
+    fn guess_board() -> Board {
+        board_art!(
+            "467|192|385",
+            "329|458|671",
+            "851|367|294",
+            "---+---+---",
+            "518|279|463",
+            "27 |6 1|859",
+            "69 |8 5|127",
+            "---+---+---",
+            "732|984|516",
+            "145|726|938",
+            "986|513|742",
+        )
+    }
+
+    /// The same board with only one empty cell.
+    fn one_left() -> Board {
+        board_art!(
+            "467|192|385",
+            "329|458|671",
+            "851|367|294",
+            "---+---+---",
+            "518|279|463",
+            "273|6 1|859",
+            "694|835|127",
+            "---+---+---",
+            "732|984|516",
+            "145|726|938",
+            "986|513|742",
+        )
+    }
+
+    /// A board with conflicting givens, so it has no solution.
+    fn unsolveable() -> Board {
+        let mut board = one_left();
+        board[Coord::new(Row::new(3), Col::new(4))] = None;
+        board[Coord::new(Row::new(4), Col::new(3))] = Some(Val::new(4));
+        board
+    }
+
+    /// Serialized form of each snapshotted value, by snapshot name.
+    fn values() -> Vec<(&'static str, String)> {
+        fn ser<T: Serialize>(val: &T) -> String {
+            serde_json::to_string(val).expect("could not serialize")
+        }
+
+        let coord = Coord::new(Row::new(4), Col::new(7));
+        let deductions = guess_board().solve_with(Vec::<Deduction>::new()).1;
+        let (_, trace) = guess_board().solve_traced::<TraceTree>();
+        let hint = one_left().hint().expect("board has a hint");
+        let assured = Solver {
+            high_assurance: true,
+            ..Solver::new()
+        };
+        let mut exclusions = Exclusions::new();
+        exclusions.exclude(coord, AvailSet::only(Val::new(3)));
+
+        vec![
+            ("analysis", ser(&one_left().analysis())),
+            (
+                "avail_set",
+                ser(&(AvailSet::none() | Val::new(1) | Val::new(5))),
+            ),
+            ("band", ser(&Band::containing(coord))),
+            ("board", ser(&guess_board())),
+            ("col", ser(&coord.col())),
+            (
+                "conflict",
+                ser(&Conflict {
+                    house: House::Row(coord.row()),
+                    val: Val::new(7),
+                    cells: [Coord::new(Row::new(4), Col::new(1)), coord],
+                }),
+            ),
+            ("coord", ser(&coord)),
+            ("deduction", ser(&deductions[1])),
+            ("difficulty", ser(&Difficulty::Hard)),
+            ("disclosure_level", ser(&DisclosureLevel::Value)),
+            ("exclusions", ser(&exclusions)),
+            ("hint", ser(&hint)),
+            ("house", ser(&House::Sector(coord.sector()))),
+            (
+                "lesson",
+                ser(&lesson::build(&deductions, &LessonPolicy::default())),
+            ),
+            ("partial_hint", ser(&hint.at_level(DisclosureLevel::Value))),
+            ("regime", ser(&Regime::Underdetermined)),
+            (
+                "remaining",
+                ser(&Remaining::from(deductions[0].remaining.clone())),
+            ),
+            ("row", ser(&coord.row())),
+            ("sector", ser(&coord.sector())),
+            ("sector_col", ser(&SectorCol::containing(coord))),
+            ("sector_row", ser(&SectorRow::containing(coord))),
+            ("solve_outcome", ser(&Solver::new().run(&guess_board()))),
+            ("stack", ser(&Stack::containing(coord))),
+            ("trace_tree", ser(&trace)),
+            ("uniqueness", ser(&guess_board().uniqueness_certificate())),
+            ("unsolveable", ser(&assured.run(&unsolveable()))),
+            ("val", ser(&Val::new(7))),
+            ("versioned_board", ser(&VersionedBoard::from(one_left()))),
+        ]
+    }
+
+    /// Check every value against its snapshot, or rewrite the snapshots when
+    /// blessing.
+    pub(super) fn check() {
+        let dir = PathBuf::from(SNAPSHOTS).join("serde");
+        if blessing() {
+            fs::create_dir_all(&dir).expect("could not create serde snapshot dir");
+        }
+        let mut changed = Vec::new();
+        for (name, ser) in values() {
+            let path = dir.join(format!("{}.json", name));
+            let ser = ser + "\n";
+            if blessing() {
+                fs::write(&path, &ser).expect("could not write snapshot");
+            } else if fs::read_to_string(&path).ok().as_deref() != Some(&ser) {
+                changed.push(name);
+            }
+        }
+        assert!(
+            changed.is_empty(),
+            "serialized format changed for {:?}; if this is intended, bless the new \
+             format as described in tests/snapshots.rs",
+            changed,
+        );
+    }
+}
+
+#[test]
+fn snapshots() {
+    // Serde snapshots are written before the blessing is recorded, so this is
+    // one test rather than two racing ones.
+    #[cfg(feature = "serde")]
+    serde_formats::check();
+    check_blessed();
+}
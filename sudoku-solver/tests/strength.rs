@@ -0,0 +1,102 @@
+//! Regression test for solver strength over the bundled corpus.
+//!
+//! The committed baseline in `tests/golden/strength.csv` records how many
+//! puzzles were solved without guessing and how many guesses, deductions, and
+//! eliminations each needed. The test fails if the solver gets weaker than the
+//! baseline, or if the report differs from it at all, so the baseline can't
+//! drift from what the solver does. After an intentional change, regenerate the
+//! baseline with:
+//! `UPDATE_GOLDEN=1 cargo test --test strength`
+use std::env;
+use std::fs;
+
+use sudoku_solver::analysis::{strength_report, StrengthReport};
+use sudoku_solver::Board;
+
+const GOLDEN: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/strength.csv");
+
+/// Corpus of puzzles, in the 81-character line format.
+const CORPUS: &[&str] = &[
+    "...1.........586.18.136..9.5.....4.3..36.18..6.4.....7.3..845.61.572.........3...",
+    "...8...141.64..75..4753....9...5..62...7.9...63..4...5....8734..14..56.989...4...",
+    ".49...65..5.8.7..3...46....27.........45.18.........32....42...9..3.6.2..27...31.",
+    // Arto Inkala's "world's hardest sudoku", which requires guessing.
+    "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..",
+];
+
+/// Parse the `total` row of a report in CSV form into (puzzles solved without
+/// guessing, guesses).
+fn parse_totals(csv: &str) -> (usize, usize) {
+    let total = csv
+        .lines()
+        .find(|line| line.starts_with("total,"))
+        .expect("missing total row");
+    let fields: Vec<usize> = total
+        .split(',')
+        .skip(1)
+        .map(|field| field.parse().expect("bad number"))
+        .collect();
+    (fields[1], fields[2])
+}
+
+fn report() -> StrengthReport {
+    let corpus: Vec<_> = CORPUS
+        .iter()
+        .map(|line| Board::from_line(line).unwrap())
+        .collect();
+    strength_report(&corpus)
+}
+
+#[test]
+fn strength_matches_baseline() {
+    let report = report();
+    let csv = report.to_csv();
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(GOLDEN, &csv).expect("failed to write baseline");
+        return;
+    }
+    let baseline = fs::read_to_string(GOLDEN).expect("missing baseline, run with UPDATE_GOLDEN=1");
+    let (base_no_guess, base_guesses) = parse_totals(&baseline);
+    assert!(
+        report.solved_without_guess >= base_no_guess,
+        "solved without guessing dropped from {} to {}\n{}",
+        base_no_guess,
+        report.solved_without_guess,
+        csv,
+    );
+    assert!(
+        report.guesses <= base_guesses,
+        "guesses rose from {} to {}\n{}",
+        base_guesses,
+        report.guesses,
+        csv,
+    );
+    assert_eq!(
+        csv, baseline,
+        "strength report differs from the baseline, regenerate it with UPDATE_GOLDEN=1 if \
+         this is intended"
+    );
+}
+
+#[test]
+fn strength_report_aggregates() {
+    let report = report();
+    assert_eq!(report.puzzles.len(), CORPUS.len());
+    assert!(report.puzzles.iter().all(|p| p.solved));
+    assert_eq!(report.solved_without_guess, 3);
+    assert_eq!(report.no_guess_millipercent(), 75_000);
+    assert_eq!(
+        report.guesses,
+        report.puzzles.iter().map(|p| p.guesses).sum::<usize>()
+    );
+    assert_eq!(
+        report.deductions.values().sum::<usize>(),
+        report
+            .puzzles
+            .iter()
+            .flat_map(|p| p.deductions.values())
+            .sum::<usize>()
+    );
+    assert!(report.puzzles[3].guesses > 0);
+    assert!(report.eliminations > 0);
+}